@@ -57,6 +57,14 @@ pub fn main(input: TokenStream) -> TokenStream {
     main_derive.visit_derive_input(&input);
     let MainDerive { mem_ty, mem_name } = main_derive;
 
+    let soft_delete = CollectionAttributes::parse(&input).soft_delete.then(|| {
+        quote!(
+            fn soft_delete(&self) -> bool {
+                true
+            }
+        )
+    });
+
     let serde_derive = if cfg!(feature = "serde") {
         Some(quote!(,::claw_ql::prelude::macro_derive_collection::Deserialize))
     } else {
@@ -134,6 +142,7 @@ pub fn main(input: TokenStream) -> TokenStream {
             fn id(&self) -> &Self::Id {
                 &SingleIncremintalInt
             }
+            #soft_delete
         }
 
         impl HasHandler for #this {
@@ -168,6 +177,35 @@ pub fn main(input: TokenStream) -> TokenStream {
     return ts;
 }
 
+/// `#[collection(soft_delete)]`, keeps deleted records around, see
+/// `links::soft_delete`
+#[derive(Default)]
+struct CollectionAttributes {
+    soft_delete: bool,
+}
+
+impl CollectionAttributes {
+    fn parse(input: &syn::DeriveInput) -> Self {
+        let mut this = Self::default();
+        for attr in input.attrs.iter().filter(|e| e.path().is_ident("collection")) {
+            let parsed = attr.parse_nested_meta(|meta| {
+                if !meta.path.is_ident("soft_delete") {
+                    return Err(meta.error("expected `soft_delete`"));
+                }
+                if this.soft_delete {
+                    return Err(meta.error("declared twice"));
+                }
+                this.soft_delete = true;
+                Ok(())
+            });
+            if let Err(err) = parsed {
+                abort!(err.span(), "{}", err);
+            }
+        }
+        this
+    }
+}
+
 #[test]
 fn test_collection_derive() {
     use pretty_assertions::assert_eq;
@@ -301,3 +339,25 @@ fn test_collection_derive() {
 
     assert_eq!(expect.to_string(), tobe.to_string());
 }
+
+#[test]
+fn soft_delete_attribute() {
+    let derived = main(quote! {
+        #[collection(soft_delete)]
+        pub struct Todo {
+            pub title: String,
+        }
+    });
+    let soft_delete = quote!(
+        fn soft_delete(&self) -> bool {
+            true
+        }
+    );
+
+    assert!(derived.to_string().contains(&soft_delete.to_string()));
+    assert!(
+        !main(quote! { pub struct Todo { pub title: String } })
+            .to_string()
+            .contains("soft_delete")
+    );
+}
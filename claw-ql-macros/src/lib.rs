@@ -4,14 +4,14 @@ use proc_macro_error::proc_macro_error;
 mod utils;
 
 mod on_migrate_derive;
-#[proc_macro_derive(OnMigrate)]
+#[proc_macro_derive(OnMigrate, attributes(column))]
 #[proc_macro_error]
 pub fn on_migrate(input: TokenStream) -> TokenStream {
     on_migrate_derive::main(input.into()).into()
 }
 
 mod collection_derive;
-#[proc_macro_derive(Collection)]
+#[proc_macro_derive(Collection, attributes(collection))]
 #[proc_macro_error]
 pub fn collection(input: TokenStream) -> TokenStream {
     collection_derive::main(input.into()).into()
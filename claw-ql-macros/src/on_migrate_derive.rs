@@ -17,6 +17,7 @@ pub fn main(input: TokenStream) -> TokenStream {
     #[derive(Default)]
     struct MainDerive {
         mem_name: Vec<Ident>,
+        constraints: Vec<ColumnConstraints>,
     }
 
     impl Visit<'_> for MainDerive {
@@ -30,6 +31,7 @@ pub fn main(input: TokenStream) -> TokenStream {
             match field.ident.as_ref() {
                 Some(ident) => {
                     self.mem_name.push(ident.clone());
+                    self.constraints.push(ColumnConstraints::parse(field));
                 }
                 None => {
                     abort!(field.span(), "unamed fields are not supported");
@@ -46,7 +48,16 @@ pub fn main(input: TokenStream) -> TokenStream {
         &format!("{}_members", name.to_string().to_snake()),
         name.span(),
     );
-    let MainDerive { mem_name } = md;
+    let MainDerive {
+        mem_name,
+        constraints,
+    } = md;
+
+    let (col_def_ty, col_def): (Vec<_>, Vec<_>) = mem_name
+        .iter()
+        .zip(constraints)
+        .map(|(mem_name, constraints)| constraints.col_def(quote!(#members::#mem_name), mem_name))
+        .unzip();
 
     quote! {
         const _ : () = {
@@ -58,7 +69,7 @@ pub fn main(input: TokenStream) -> TokenStream {
                     table_as_expression<#name>,
                     (
                         <#name as Collection>::Id,
-                        #(col_def_for_collection_member<#members::#mem_name>,)*
+                        #(#col_def_ty,)*
                     ),
                 >;
 
@@ -68,7 +79,7 @@ pub fn main(input: TokenStream) -> TokenStream {
                         name: table_as_expression(#name),
                         col_defs: (
                             Collection::id(self).clone(),
-                            #(col_def_for_collection_member(#members::#mem_name),)*
+                            #(#col_def,)*
                         ),
                     }
                 }
@@ -77,6 +88,86 @@ pub fn main(input: TokenStream) -> TokenStream {
     }
 }
 
+/// `#[column(check = "<sql>", default = "<sql>")]`, both are optional
+///
+/// the check is named `check_<member>` so a failing insert reports which
+/// member it was about
+#[derive(Default)]
+struct ColumnConstraints {
+    check: Option<syn::LitStr>,
+    default: Option<syn::LitStr>,
+}
+
+impl ColumnConstraints {
+    fn parse(field: &syn::Field) -> Self {
+        let mut this = Self::default();
+        for attr in field.attrs.iter().filter(|e| e.path().is_ident("column")) {
+            let parsed = attr.parse_nested_meta(|meta| {
+                let slot = if meta.path.is_ident("check") {
+                    &mut this.check
+                } else if meta.path.is_ident("default") {
+                    &mut this.default
+                } else {
+                    return Err(meta.error("expected `check` or `default`"));
+                };
+                if slot.is_some() {
+                    return Err(meta.error("declared twice"));
+                }
+                *slot = Some(meta.value()?.parse()?);
+                Ok(())
+            });
+            if let Err(err) = parsed {
+                abort!(err.span(), "{}", err);
+            }
+        }
+        this
+    }
+
+    /// the type and the value of the column definition
+    fn col_def(self, member: TokenStream, mem_name: &Ident) -> (TokenStream, TokenStream) {
+        let cc = quote!(::claw_ql::sqlx_query_builder::column_constraints);
+        let check_name = format!("check_{}", mem_name);
+
+        let default = self.default.map(|e| {
+            (
+                quote!(#cc::Default<#cc::Sql>),
+                quote!(#cc::Default(#cc::Sql(#e))),
+            )
+        });
+        let check = self.check.map(|e| {
+            (
+                quote!(#cc::Named<&'static str, #cc::Check<#cc::Sql>>),
+                quote!(#cc::Named {
+                    name: #check_name,
+                    constraint: #cc::Check(#cc::Sql(#e)),
+                }),
+            )
+        });
+
+        let (constraints_ty, constraints) = match (default, check) {
+            (None, None) => {
+                return (
+                    quote!(col_def_for_collection_member<#member>),
+                    quote!(col_def_for_collection_member(#member)),
+                );
+            }
+            (Some(one), None) | (None, Some(one)) => one,
+            (Some((d_ty, d)), Some((c_ty, c))) => (
+                quote!(::claw_ql::sqlx_query_builder::basic_expressions::ManyFlat<(#d_ty, #c_ty)>),
+                quote!(::claw_ql::sqlx_query_builder::basic_expressions::ManyFlat((#d, #c))),
+            ),
+        };
+
+        (
+            quote!(#cc::ConstrainedColumn<col_def_for_collection_member<#member>, #constraints_ty>),
+            quote!(#cc::ConstrainedColumn {
+                col_def: col_def_for_collection_member(#member),
+                constraints: #constraints,
+            }),
+        )
+    }
+}
+
 #[test]
 fn main_test() {
     let expect = quote! {
@@ -123,3 +214,99 @@ fn main_test() {
 
     crate::utils::expect_to_eq(expect, to_be);
 }
+
+#[test]
+fn column_constraints_test() {
+    let expect = quote! {
+        pub struct Todo {
+            #[column(check = "LENGTH(\"title\") > 0")]
+            pub title: String,
+            #[column(default = "0")]
+            pub done: bool,
+            #[column(default = "'none'", check = "\"description\" <> ''")]
+            pub description: Option<String>,
+        }
+    };
+
+    let expect = main(expect);
+
+    let to_be = quote! {
+        const _ : () = {
+            use ::claw_ql::prelude::on_migrate_derive::*;
+
+            impl OnMigrate for todo {
+                type Statements = CreateTable<
+                    create_table,
+                    table_as_expression<todo>,
+                    (
+                        <todo as Collection>::Id,
+                        ::claw_ql::sqlx_query_builder::column_constraints::ConstrainedColumn<
+                            col_def_for_collection_member<todo_members::title>,
+                            ::claw_ql::sqlx_query_builder::column_constraints::Named<
+                                &'static str,
+                                ::claw_ql::sqlx_query_builder::column_constraints::Check<
+                                    ::claw_ql::sqlx_query_builder::column_constraints::Sql>>
+                        >,
+                        ::claw_ql::sqlx_query_builder::column_constraints::ConstrainedColumn<
+                            col_def_for_collection_member<todo_members::done>,
+                            ::claw_ql::sqlx_query_builder::column_constraints::Default<
+                                ::claw_ql::sqlx_query_builder::column_constraints::Sql> >,
+                        ::claw_ql::sqlx_query_builder::column_constraints::ConstrainedColumn<
+                            col_def_for_collection_member<todo_members::description>,
+                            ::claw_ql::sqlx_query_builder::basic_expressions::ManyFlat<(
+                                ::claw_ql::sqlx_query_builder::column_constraints::Default<
+                                    ::claw_ql::sqlx_query_builder::column_constraints::Sql
+                                >,
+                                ::claw_ql::sqlx_query_builder::column_constraints::Named<
+                                    &'static str,
+                                    ::claw_ql::sqlx_query_builder::column_constraints::Check<
+                                        ::claw_ql::sqlx_query_builder::column_constraints::Sql>>
+                            )>
+                        >,
+                    ),
+                >;
+
+                fn statments(&self) -> Self::Statements {
+                    CreateTable {
+                        init: create_table,
+                        name: table_as_expression(todo),
+                        col_defs: (
+                            Collection::id(self).clone(),
+                            ::claw_ql::sqlx_query_builder::column_constraints::ConstrainedColumn {
+                                col_def: col_def_for_collection_member(todo_members::title),
+                                constraints: ::claw_ql::sqlx_query_builder::column_constraints::Named {
+                                    name: "check_title",
+                                    constraint: ::claw_ql::sqlx_query_builder::column_constraints::Check(
+                                        ::claw_ql::sqlx_query_builder::column_constraints::Sql("LENGTH(\"title\") > 0")
+                                    ),
+                                },
+                            },
+                            ::claw_ql::sqlx_query_builder::column_constraints::ConstrainedColumn {
+                                col_def: col_def_for_collection_member(todo_members::done),
+                                constraints: ::claw_ql::sqlx_query_builder::column_constraints::Default(
+                                    ::claw_ql::sqlx_query_builder::column_constraints::Sql("0")
+                                ),
+                            },
+                            ::claw_ql::sqlx_query_builder::column_constraints::ConstrainedColumn {
+                                col_def: col_def_for_collection_member(todo_members::description),
+                                constraints: ::claw_ql::sqlx_query_builder::basic_expressions::ManyFlat((
+                                    ::claw_ql::sqlx_query_builder::column_constraints::Default(
+                                        ::claw_ql::sqlx_query_builder::column_constraints::Sql("'none'")
+                                    ),
+                                    ::claw_ql::sqlx_query_builder::column_constraints::Named {
+                                        name: "check_description",
+                                        constraint: ::claw_ql::sqlx_query_builder::column_constraints::Check(
+                                            ::claw_ql::sqlx_query_builder::column_constraints::Sql("\"description\" <> ''")
+                                        ),
+                                    }
+                                )),
+                            },
+                        ),
+                    }
+                }
+            }
+        };
+    };
+
+    crate::utils::expect_to_eq(expect, to_be);
+}
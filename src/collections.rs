@@ -9,6 +9,11 @@ pub trait Collection {
     type OutputData;
     type Id: CollectionId;
     fn id(&self) -> Self::Id;
+    /// records of a soft deleted collection are kept when deleted, see
+    /// `links::soft_delete`
+    fn soft_delete(&self) -> bool {
+        false
+    }
 }
 
 /// dyn-compatable parts of Collection
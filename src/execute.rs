@@ -1,14 +1,30 @@
 use sqlx::{Database, Execute};
 use std::mem;
 
+use crate::statement_cache::CachedOperation;
+
 pub struct Executable<'q, A> {
     pub string: &'q str,
     pub arguments: A,
+    /// keep the prepared statement in the statement cache of the connection
+    pub persistent: bool,
+}
+
+impl<'q, A> Executable<'q, A> {
+    /// persistent when `operation` is, counts the shape of `string` in its metrics
+    pub fn cached(operation: &CachedOperation, string: &'q str, arguments: A) -> Self {
+        operation.record(string);
+        Self {
+            persistent: operation.persistent(),
+            string,
+            arguments,
+        }
+    }
 }
 
 impl<'q, S: Database> Execute<'q, S> for Executable<'q, S::Arguments<'q>> {
     fn sql(&self) -> &'q str {
-        &self.string
+        self.string
     }
 
     fn statement(&self) -> Option<&<S as Database>::Statement<'q>> {
@@ -22,6 +38,6 @@ impl<'q, S: Database> Execute<'q, S> for Executable<'q, S::Arguments<'q>> {
     }
 
     fn persistent(&self) -> bool {
-        false
+        self.persistent
     }
 }
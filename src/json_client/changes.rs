@@ -0,0 +1,105 @@
+//! change events of dynamic collections.
+//!
+//! every write of the `op_*` functions is published on the broadcast channel
+//! of the executor once it succeeds, `subscribe` forwards the events of one
+//! collection to a client. events aren't persisted, a subscriber only sees
+//! what happens after it subscribes, and one that falls too far behind skips
+//! the events it missed.
+//!
+//! collections with an `audit_log` link also keep every event in their
+//! history table, see `publish`.
+
+use std::sync::Arc;
+
+use crate::{
+    database_extention::DatabaseExt,
+    fix_executor::ExecutorTrait,
+    gen_serde::{IntoSerializedString, json_serialize_side::JsonAsString},
+    json_client::{
+        client_interface::Caller,
+        dynamic_collection::{CollectionToSerialize, DynamicCollection},
+        sqlx_executor::SqlxExecutorData,
+    },
+    links::audit_log::{AuditAction, operations::record_version},
+};
+
+/// events kept for slow subscribers before they start to skip
+pub(crate) const CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Insert => "insert",
+            ChangeKind::Update => "update",
+            ChangeKind::Delete => "delete",
+        }
+    }
+
+    pub fn audit_action(&self) -> AuditAction {
+        match self {
+            ChangeKind::Insert => AuditAction::Insert,
+            ChangeKind::Update => AuditAction::Update,
+            ChangeKind::Delete => AuditAction::Delete,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChangeEvent {
+    pub kind: ChangeKind,
+    /// snake_case name of the collection
+    pub collection: Arc<str>,
+    pub id: i64,
+    /// the record after an insert or update, before a delete
+    pub attributes: CollectionToSerialize,
+}
+
+/// records the event as a version of the record when `base` is audited, then
+/// sends it to the subscribers.
+///
+/// reads `link_info`, so the caller can't hold a guard on it.
+pub(crate) async fn publish<S>(
+    this: &SqlxExecutorData<S>,
+    base: &DynamicCollection<S>,
+    event: ChangeEvent,
+    caller: Caller,
+    conn: &mut S::Connection,
+) where
+    S: sqlx::Database + DatabaseExt + ExecutorTrait,
+    i64: for<'q> sqlx::Encode<'q, S> + sqlx::Type<S>,
+    String: for<'q> sqlx::Encode<'q, S> + sqlx::Type<S>,
+    Option<String>: for<'q> sqlx::Encode<'q, S> + sqlx::Type<S>,
+{
+    let audited = this
+        .link_info
+        .read()
+        .await
+        .audited
+        .contains(event.collection.as_ref());
+
+    if audited {
+        let changed_by = match caller {
+            Caller::System => String::from("system"),
+            Caller::Anonymous => String::from("anonymous"),
+            Caller::User(id) => id.to_string(),
+        };
+        record_version::<S, i64>(
+            &base.collection_name.pascal_case,
+            event.id,
+            event.kind.audit_action(),
+            IntoSerializedString::<JsonAsString>::serialize_to_string(&event.attributes),
+            Some(changed_by),
+            conn,
+        )
+        .await;
+    }
+
+    let _ = this.changes.send(event);
+}
@@ -1,5 +1,3 @@
-use std::collections::BTreeMap;
-
 use crate::expressions::ColumnEqual;
 use crate::gen_serde::Serialize;
 use crate::gen_serde::json_format_side::PartialDeserialize;
@@ -7,12 +5,15 @@ use crate::gen_serde::json_serialize_side::JsonAsString;
 use crate::json_client::dynamic_collection::CollectionToSerialize;
 use crate::operations::{CollectionOutput, LinkedOutput};
 use crate::sub_arc::ArcSubStr;
+use std::sync::Arc;
 
 //*******************
 //*
 //* SupportedType
 //*
 //*******************
+/// see `supported_types` for how the newer variants are stored and ordered,
+/// nested arrays are not supported, store them as `Json`
 #[derive(Debug)]
 pub enum SupportedType {
     String,
@@ -20,6 +21,15 @@ pub enum SupportedType {
     Int,
     Float64,
     Array(Box<SupportedType>),
+    DateTime,
+    Date,
+    Uuid,
+    Json,
+    Bytes,
+    /// a string that has to be one of `variants`
+    Enum {
+        variants: Vec<ArcSubStr>,
+    },
 }
 
 //*******************
@@ -27,7 +37,7 @@ pub enum SupportedType {
 //* SupportedFilter
 //*
 //*******************
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SupportedFilter {
     ColEq(ColumnEqual<ArcSubStr, PartialDeserialize>),
     ColNe {
@@ -50,22 +60,73 @@ pub enum SupportedFilter {
         col: ArcSubStr,
         lte: PartialDeserialize,
     },
+    /// `value` is matched as is, `%` and `_` are no wildcards
     ColContains {
         col: ArcSubStr,
         value: PartialDeserialize,
     },
+    ColStartsWith {
+        col: ArcSubStr,
+        value: PartialDeserialize,
+    },
+    ColEndsWith {
+        col: ArcSubStr,
+        value: PartialDeserialize,
+    },
+    /// equality of text fields ignoring case
+    ColEqIgnoreCase {
+        col: ArcSubStr,
+        eq: PartialDeserialize,
+    },
+    /// `values` can't be empty
+    ColIn {
+        col: ArcSubStr,
+        values: Vec<PartialDeserialize>,
+    },
+    /// both `low` and `high` are included
+    ColBetween {
+        col: ArcSubStr,
+        low: PartialDeserialize,
+        high: PartialDeserialize,
+    },
     ColIsNull {
         col: ArcSubStr,
     },
     ColIsNotNull {
         col: ArcSubStr,
     },
+    /// records matching the FTS5 `query`, only for collections with
+    /// a full text search link
+    Search {
+        query: ArcSubStr,
+    },
     And {
         filters: Vec<SupportedFilter>,
     },
     Or {
         filters: Vec<SupportedFilter>,
     },
+    Not {
+        filter: Box<SupportedFilter>,
+    },
+    Exists {
+        link: SupportedLinkFilter,
+    },
+    Any {
+        link: SupportedLinkFilter,
+        filters: Vec<SupportedFilter>,
+    },
+    All {
+        link: SupportedLinkFilter,
+        filters: Vec<SupportedFilter>,
+    },
+}
+
+/// nested filters of `Exists`, `Any` and `All` refer to the columns of `to`
+#[derive(Debug, Clone)]
+pub enum SupportedLinkFilter {
+    OptionalToMany { to: ArcSubStr },
+    ManyToMany { to: ArcSubStr },
 }
 
 //*******************
@@ -77,6 +138,23 @@ pub enum SupportedFilter {
 pub struct AddCollectionInput {
     pub name: ArcSubStr,
     pub fields: Vec<DynamicFieldInput>,
+    /// created right after the table
+    pub indexes: Vec<IndexInput>,
+    /// makes the collection a read-only view over this `SELECT`, instead
+    /// of a table. it returns an `id` column and every field by name, and
+    /// the fields can't have rules or defaults, nor the view indexes
+    pub view: Option<ArcSubStr>,
+}
+
+/// an index over one or more fields of the new collection
+#[derive(Debug)]
+pub struct IndexInput {
+    pub fields: Vec<ArcSubStr>,
+    /// rejects a second record with the same values in `fields`
+    pub unique: bool,
+    /// makes it a partial index over the records where all of these
+    /// optional fields are set
+    pub where_not_null: Vec<ArcSubStr>,
 }
 
 #[derive(Debug)]
@@ -84,14 +162,42 @@ pub struct DynamicFieldInput {
     pub name: ArcSubStr,
     pub type_info: SupportedType,
     pub is_optional: bool,
+    pub rules: Vec<FieldRule>,
+    /// stored when an insert leaves the field out, a non optional field
+    /// with a default can be left out
+    pub default: Option<PartialDeserialize>,
+}
+
+/// checked by insert and update before any SQL is issued, null values of
+/// optional fields skip every rule
+#[derive(Debug)]
+pub enum FieldRule {
+    /// characters of a `String`, items of an `Array` or bytes of `Bytes`
+    MinLength(i64),
+    MaxLength(i64),
+    /// inclusive bounds of an `Int` or `Float64`
+    Range {
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    /// a regex the whole `String` has to match
+    Pattern(ArcSubStr),
+    OneOf(Vec<ArcSubStr>),
+    /// no other record has the same value
+    Unique,
+    /// a `MinLength`, `MaxLength`, `Range` or `OneOf` enforced by a `CHECK`
+    /// of the table instead, so every writer of the table is held to it.
+    /// rejected writes are reported as `InvalidDataRule::Check`
+    Check(Box<FieldRule>),
 }
 
 pub type AddCollectionOutput = ();
 
 #[derive(Debug)]
 pub enum AddCollectionError {
-    CollectionAlreadyExists,
-    InvalidCollectionInput,
+    CollectionAlreadyExists { collection: Arc<str> },
+    InvalidCollectionInput { collection: Arc<str> },
+    Forbidden { collection: Arc<str> },
 }
 
 //*******************
@@ -104,14 +210,81 @@ pub enum AddLinkInput {
     OptionalToMany { from: ArcSubStr, to: ArcSubStr },
     ManyToMany { from: ArcSubStr, to: ArcSubStr },
     Timestamp { collection: ArcSubStr },
+    SoftDelete { collection: ArcSubStr },
+    AuditLog { collection: ArcSubStr },
+    Versioned { collection: ArcSubStr },
+    FullTextSearch {
+        collection: ArcSubStr,
+        fields: Vec<ArcSubStr>,
+    },
 }
 
 pub type AddLinkOutput = ();
 
 #[derive(Debug)]
 pub enum AddLinkError {
-    LinkAlreadyExists,
-    CollectionNotFound,
+    /// `to` is `None` for `Timestamp`, `SoftDelete`, `AuditLog`, `Versioned`
+    /// and `FullTextSearch`
+    LinkAlreadyExists {
+        from: Arc<str>,
+        to: Option<Arc<str>>,
+    },
+    CollectionNotFound {
+        collection: Arc<str>,
+    },
+    Forbidden {
+        collection: Arc<str>,
+    },
+    /// `field` of a full text search isn't a text field of `collection`
+    InvalidField {
+        collection: Arc<str>,
+        field: Arc<str>,
+    },
+    /// links can't be added to views
+    ReadOnly {
+        collection: Arc<str>,
+    },
+}
+
+//*******************
+//*
+//* InvalidData
+//*
+//*******************
+/// why the data of insert or update was rejected
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidData {
+    /// `None` when the data as a whole is rejected
+    pub field: Option<Arc<str>>,
+    pub rule: InvalidDataRule,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidDataRule {
+    /// not an object, or a value listed twice
+    Malformed,
+    /// nothing to insert or update
+    Empty,
+    UnknownField,
+    /// a non optional field is missing or null
+    Required,
+    /// the value doesn't deserialize to the field's type
+    Type,
+    MinLength,
+    MaxLength,
+    Range,
+    Pattern,
+    /// `FieldRule::OneOf`, or a variant of `SupportedType::Enum`
+    OneOf,
+    Unique,
+    /// the database rejected the value with a `FieldRule::Check`
+    Check,
+}
+
+impl InvalidData {
+    pub fn of_data(rule: InvalidDataRule) -> Self {
+        Self { field: None, rule }
+    }
 }
 
 //*******************
@@ -144,12 +317,33 @@ pub struct InsertOneInput {
 pub type InsertOneOutput =
     LinkedOutput<i64, CollectionToSerialize, Vec<Box<dyn Serialize<JsonAsString> + Send>>>;
 
+/// `index` is the position of the offending entry in `links`
 #[derive(Debug)]
 pub enum InsertOneError {
-    CollectionNotFound,
-    InvalidData,
-    InvalidLink,
-    LinkNotSetUpForThisBase,
+    CollectionNotFound {
+        collection: Arc<str>,
+    },
+    InvalidData(InvalidData),
+    /// `value` of a `SetNew` link was rejected
+    InvalidLinkData {
+        index: usize,
+        invalid: InvalidData,
+    },
+    InvalidLink {
+        index: usize,
+    },
+    Forbidden {
+        collection: Arc<str>,
+    },
+    /// `collection` is a view
+    ReadOnly {
+        collection: Arc<str>,
+    },
+    /// a hook registered on the executor returned `reason`
+    Aborted {
+        collection: Arc<str>,
+        reason: Arc<str>,
+    },
 }
 
 //*******************
@@ -176,10 +370,23 @@ pub struct InsertManyOutput {
 
 #[derive(Debug)]
 pub enum InsertManyError {
-    CollectionNotFound,
-    InvalidData,
-    InvalidLink,
-    LinkNotSetUpForThisBase,
+    CollectionNotFound {
+        collection: Arc<str>,
+    },
+    /// the batch as a whole was rejected
+    InvalidData(InvalidData),
+    /// `items[index]` was rejected, nothing after it was inserted
+    Item {
+        index: usize,
+        error: InsertOneError,
+    },
+    Forbidden {
+        collection: Arc<str>,
+    },
+    /// `collection` is a view
+    ReadOnly {
+        collection: Arc<str>,
+    },
 }
 
 //*******************
@@ -192,6 +399,11 @@ pub enum SupportedLinkFetchMany {
     OptionalToMany { to: ArcSubStr },
     ManyToMany { to: ArcSubStr },
     Timestamp,
+    /// records of a soft-deleted collection are left out unless
+    /// `include_deleted` is set
+    SoftDelete { include_deleted: bool },
+    /// the current version of records of a versioned collection
+    Version,
 }
 
 #[derive(Debug)]
@@ -202,23 +414,49 @@ pub struct FetchManyInput {
     pub pagination: Pagination,
 }
 
+/// `cursor` is a `next_item` or `prev_item` token of a previous page
+/// fetched with the same base and order by, `page` starts at 0 and
+/// `count_total` fills `total` of the output
 #[derive(Debug)]
-pub struct Pagination {
-    pub limit: i64,
-    pub first_item: Option<FirstItem>,
-    pub order_by: Vec<OrderBy>,
+pub enum Pagination {
+    Cursor {
+        limit: i64,
+        cursor: Option<ArcSubStr>,
+        order_by: Vec<OrderBy>,
+    },
+    Offset {
+        page: i64,
+        page_size: i64,
+        count_total: bool,
+        order_by: Vec<OrderBy>,
+    },
 }
 
 #[derive(Debug)]
-pub struct FirstItem {
-    pub id: i64,
-    pub data: BTreeMap<ArcSubStr, PartialDeserialize>,
+pub struct OrderBy {
+    pub target: OrderByTarget,
+    pub direction: Direction,
 }
 
+/// `LinkCol` should go through a link with at most one record per base,
+/// `col` refers to a column of `to`
 #[derive(Debug)]
-pub struct OrderBy {
-    pub col: ArcSubStr,
-    pub direction: Direction,
+pub enum OrderByTarget {
+    Col {
+        col: ArcSubStr,
+    },
+    LinkCol {
+        link: SupportedLinkFilter,
+        col: ArcSubStr,
+    },
+    LinkCount {
+        link: SupportedLinkFilter,
+    },
+    /// how well records match the FTS5 `query`, ascending is best match
+    /// first, only for collections with a full text search link
+    Relevance {
+        query: ArcSubStr,
+    },
 }
 
 #[derive(Debug)]
@@ -230,17 +468,32 @@ pub enum Direction {
 pub type FetchManyItem =
     LinkedOutput<i64, CollectionToSerialize, Vec<Box<dyn Serialize<JsonAsString> + Send>>>;
 
-pub type FetchManyOutput = ManyOutput<FetchManyItem, CollectionOutput<i64, CollectionToSerialize>>;
+pub type FetchManyOutput = ManyOutput<FetchManyItem, String>;
 
+/// `index` is the position of the offending entry in `filters`, `links`
+/// or `order_by`
 #[derive(Debug)]
 pub enum FetchManyError {
-    CollectionNotFound,
-    InvalidData,
-    LinkNotSetUpForThisBase,
-    InvalidFilter,
-    InvalidLink,
-    InvalidOrderBy,
-    InvalidFirstItem,
+    CollectionNotFound {
+        collection: Arc<str>,
+    },
+    InvalidFilter {
+        index: usize,
+    },
+    InvalidLink {
+        index: usize,
+    },
+    InvalidOrderBy {
+        index: usize,
+    },
+    InvalidCursor,
+    /// page sizes are in `0..=max`
+    InvalidPageSize {
+        max: i64,
+    },
+    Forbidden {
+        collection: Arc<str>,
+    },
 }
 
 //*******************
@@ -253,6 +506,11 @@ pub enum SupportedLinkFetchOne {
     OptionalToMany { to: ArcSubStr },
     ManyToMany { to: ArcSubStr },
     Timestamp,
+    /// records of a soft-deleted collection are left out unless
+    /// `include_deleted` is set
+    SoftDelete { include_deleted: bool },
+    /// the current version of records of a versioned collection
+    Version,
 }
 
 #[derive(Debug)]
@@ -268,10 +526,11 @@ pub type FetchOneOutput =
 
 #[derive(Debug)]
 pub enum FetchOneError {
-    CollectionNotFound,
-    NotFound,
-    InvalidFilter,
-    InvalidLink,
+    CollectionNotFound { collection: Arc<str> },
+    NotFound { id: i64 },
+    InvalidFilter { index: usize },
+    InvalidLink { index: usize },
+    Forbidden { collection: Arc<str> },
 }
 
 //*******************
@@ -304,6 +563,8 @@ pub struct UpdateOneInput {
     pub id: i64,
     pub data: PartialDeserialize,
     pub links: Vec<SupportedUpdateLink>,
+    /// the version the update is based on, required by versioned collections
+    pub version: Option<i64>,
 }
 
 pub type UpdateOneOutput =
@@ -311,10 +572,43 @@ pub type UpdateOneOutput =
 
 #[derive(Debug)]
 pub enum UpdateOneError {
-    CollectionNotFound,
-    InvalidData,
-    NotFound,
-    InvalidLink,
+    CollectionNotFound {
+        collection: Arc<str>,
+    },
+    InvalidData(InvalidData),
+    /// `value` of a `SetNew` link was rejected
+    InvalidLinkData {
+        index: usize,
+        invalid: InvalidData,
+    },
+    NotFound {
+        id: i64,
+    },
+    InvalidLink {
+        index: usize,
+    },
+    Forbidden {
+        collection: Arc<str>,
+    },
+    /// `collection` is a view
+    ReadOnly {
+        collection: Arc<str>,
+    },
+    /// a hook registered on the executor returned `reason`
+    Aborted {
+        collection: Arc<str>,
+        reason: Arc<str>,
+    },
+    /// the record was updated since the given version
+    Conflict {
+        id: i64,
+        current_version: i64,
+    },
+    /// a versioned collection is updated without a version, or a version is
+    /// given for a collection that isn't versioned
+    InvalidVersion {
+        collection: Arc<str>,
+    },
 }
 
 //*******************
@@ -340,9 +634,106 @@ pub type DeleteOneOutput =
 
 #[derive(Debug)]
 pub enum DeleteOneError {
-    CollectionNotFound,
-    NotFound,
-    InvalidLink,
+    CollectionNotFound {
+        collection: Arc<str>,
+    },
+    NotFound {
+        id: i64,
+    },
+    InvalidLink {
+        index: usize,
+    },
+    Forbidden {
+        collection: Arc<str>,
+    },
+    /// `collection` is a view
+    ReadOnly {
+        collection: Arc<str>,
+    },
+    /// a hook registered on the executor returned `reason`
+    Aborted {
+        collection: Arc<str>,
+        reason: Arc<str>,
+    },
+}
+
+//*******************
+//*
+//* Restore
+//*
+//*******************
+#[derive(Debug)]
+pub struct RestoreInput {
+    pub base: ArcSubStr,
+    pub id: i64,
+}
+
+pub type RestoreOutput = LinkedOutput<i64, CollectionToSerialize, ()>;
+
+#[derive(Debug)]
+pub enum RestoreError {
+    CollectionNotFound { collection: Arc<str> },
+    /// the collection has no `soft_delete` link
+    NotSoftDeleted { collection: Arc<str> },
+    /// there is no deleted record with this id
+    NotFound { id: i64 },
+    Forbidden { collection: Arc<str> },
+}
+
+//*******************
+//*
+//* History
+//*
+//*******************
+#[derive(Debug)]
+pub struct HistoryInput {
+    pub base: ArcSubStr,
+    pub id: i64,
+}
+
+/// versions of the record, oldest first, empty for unknown ids
+pub type HistoryOutput = Vec<crate::links::audit_log::HistoryEntry>;
+
+#[derive(Debug)]
+pub enum HistoryError {
+    CollectionNotFound { collection: Arc<str> },
+    /// the collection has no `audit_log` link
+    NotAudited { collection: Arc<str> },
+    Forbidden { collection: Arc<str> },
+}
+
+//*******************
+//*
+//* Subscribe
+//*
+//*******************
+pub use super::changes::{ChangeEvent, ChangeKind};
+
+#[derive(Debug)]
+pub struct SubscribeInput {
+    pub base: ArcSubStr,
+    /// only changes of records matching every filter are sent, a record that
+    /// stops matching is sent as deleted
+    pub filters: Vec<SupportedFilter>,
+}
+
+#[derive(Debug)]
+pub struct SubscribeOutput {
+    pub(crate) events: tokio::sync::mpsc::UnboundedReceiver<ChangeEvent>,
+}
+
+impl SubscribeOutput {
+    /// `None` once the executor is dropped
+    pub async fn next(&mut self) -> Option<ChangeEvent> {
+        self.events.recv().await
+    }
+}
+
+#[derive(Debug)]
+pub enum SubscribeError {
+    CollectionNotFound { collection: Arc<str> },
+    InvalidFilter { index: usize },
+    Forbidden { collection: Arc<str> },
 }
 
 //*******************
@@ -354,9 +745,23 @@ pub use super::ops::OperationError as ClientOperationError;
 pub use super::ops::OperationInput as ClientOperationInput;
 pub use super::ops::OperationOutput as ClientOperationOutput;
 
+pub use super::policy::Caller;
+
 pub struct Client {
     pub(crate) sender: tokio::sync::mpsc::UnboundedSender<(
         ClientOperationInput,
+        Caller,
         oneshot::Sender<Result<ClientOperationOutput, ClientOperationError>>,
     )>,
+    pub(crate) caller: Caller,
+}
+
+impl Client {
+    /// a client whose operations run for `caller`, sharing the same executor
+    pub fn with_caller(&self, caller: Caller) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            caller,
+        }
+    }
 }
@@ -0,0 +1,174 @@
+//! opaque cursors handed to clients as `next_item` and `prev_item`.
+//!
+//! the payload is JSON `{"dir":..,"base":..,"id":..,"data":{..}}` signed
+//! with the executor's cursor secret, see `operations::cursor_token` for
+//! the token itself.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::gen_serde::json_format_side::{JsonFormat, PartialDeserialize};
+use crate::gen_serde::json_serialize_side::JsonAsString;
+use crate::gen_serde::{
+    Deserialize, DeserializeMap, DeserializeSpec, Deserializer, IntoSerializedString, KnownKey,
+    ObjectEncoding, Serialize, SerializedJson, deserialize,
+};
+use crate::operations::cursor_token::{sign, verify};
+use crate::sub_arc::ArcSubStr;
+
+pub use crate::operations::cursor_token::CursorTokenError;
+
+/// what a verified token carries
+#[derive(Debug)]
+pub struct CursorPayload {
+    pub before: bool,
+    pub base: ArcSubStr,
+    pub id: i64,
+    pub data: BTreeMap<ArcSubStr, PartialDeserialize>,
+}
+
+/// random secret used until one is set with `SqlxExecutor::with_cursor_secret`,
+/// tokens then don't survive a restart
+pub(crate) fn random_secret() -> Vec<u8> {
+    use std::hash::{BuildHasher, RandomState};
+
+    let state = RandomState::new();
+    (0..4u64)
+        .flat_map(|i| state.hash_one(i).to_le_bytes())
+        .collect()
+}
+
+struct Payload<'a> {
+    before: bool,
+    base: &'a str,
+    id: i64,
+    data: &'a [(Arc<str>, SerializedJson)],
+}
+
+struct Data<'a>(&'a [(Arc<str>, SerializedJson)]);
+
+impl Serialize<JsonAsString> for Data<'_> {
+    fn serialize(&self, ctx: &mut JsonAsString) {
+        let mut object = ObjectEncoding::serialize_start(ctx);
+        for (key, value) in self.0 {
+            ObjectEncoding::serialize_pair(ctx, &mut object, key.as_ref(), value);
+        }
+        ObjectEncoding::serialize_end(ctx, object);
+    }
+}
+
+impl Serialize<JsonAsString> for Payload<'_> {
+    fn serialize(&self, ctx: &mut JsonAsString) {
+        let dir = match self.before {
+            true => "before",
+            false => "after",
+        };
+        let mut object = ObjectEncoding::serialize_start(ctx);
+        ObjectEncoding::serialize_pair(ctx, &mut object, "dir", dir);
+        ObjectEncoding::serialize_pair(ctx, &mut object, "base", self.base);
+        ObjectEncoding::serialize_pair(ctx, &mut object, "id", &self.id);
+        ObjectEncoding::serialize_pair(ctx, &mut object, "data", &Data(self.data));
+        ObjectEncoding::serialize_end(ctx, object);
+    }
+}
+
+impl DeserializeSpec for CursorPayload {
+    type Handler = ();
+}
+
+impl<'de, S> Deserialize<'de, S> for CursorPayload
+where
+    S: Deserializer<'de>,
+    S: DeserializeMap<'de>,
+    i64: Deserialize<'de, S>,
+    ArcSubStr: Deserialize<'de, S>,
+    BTreeMap<ArcSubStr, PartialDeserialize>: Deserialize<'de, S>,
+    S: KnownKey<&'static str>,
+{
+    fn deserialize(_handler: Self::Handler, serialized: &mut S) -> Result<Self, S::Err> {
+        let mut map = DeserializeMap::start_map(serialized)?;
+        let dir: ArcSubStr =
+            DeserializeMap::deserialize_with_known_key(serialized, &mut map, "dir", ())?;
+        let base = DeserializeMap::deserialize_with_known_key(serialized, &mut map, "base", ())?;
+        let id = DeserializeMap::deserialize_with_known_key(serialized, &mut map, "id", ())?;
+        let data = DeserializeMap::deserialize_with_known_key(serialized, &mut map, "data", ())?;
+        DeserializeMap::finish(serialized, map)?;
+        Ok(CursorPayload {
+            before: dir.as_str() == "before",
+            base,
+            id,
+            data,
+        })
+    }
+}
+
+/// `data` is one entry per order by, keyed like `DynamicOrderBy`
+pub fn encode(
+    secret: &[u8],
+    before: bool,
+    base: &str,
+    id: i64,
+    data: &[(Arc<str>, SerializedJson)],
+) -> String {
+    let payload = IntoSerializedString::<JsonAsString>::serialize_to_string(&Payload {
+        before,
+        base,
+        id,
+        data,
+    });
+    sign(secret, payload.as_bytes())
+}
+
+pub fn decode(secret: &[u8], token: &str) -> Result<CursorPayload, CursorTokenError> {
+    let payload = verify(secret, token)?;
+    let payload = String::from_utf8(payload).map_err(|_| CursorTokenError::Malformed)?;
+
+    deserialize::<_, Arc<str>, CursorPayload>(Arc::from(payload), (), JsonFormat)
+        .map_err(|_| CursorTokenError::Malformed)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use crate::gen_serde::SerializedJson;
+
+    use super::{CursorTokenError, decode, encode};
+
+    #[test]
+    fn round_trip_and_tamper() {
+        let data = vec![(Arc::from("title"), SerializedJson::new(&String::from("a")))];
+        let token = encode(b"secret", true, "todo", 4, &data);
+
+        let payload = decode(b"secret", &token).unwrap();
+        pretty_assertions::assert_eq!(payload.before, true);
+        pretty_assertions::assert_eq!(payload.base.as_str(), "todo");
+        pretty_assertions::assert_eq!(payload.id, 4);
+        pretty_assertions::assert_eq!(
+            payload
+                .data
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.0.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("title", r#""a""#)]
+        );
+
+        pretty_assertions::assert_eq!(
+            decode(b"other_secret", &token).unwrap_err(),
+            CursorTokenError::BadSignature
+        );
+
+        let forged = encode(b"other_secret", false, "todo", 4, &data);
+        let (_, forged_signature) = forged.rsplit_once('.').unwrap();
+        let (signed, _) = token.rsplit_once('.').unwrap();
+        pretty_assertions::assert_eq!(
+            decode(b"secret", &format!("{signed}.{forged_signature}")).unwrap_err(),
+            CursorTokenError::BadSignature
+        );
+
+        pretty_assertions::assert_eq!(
+            decode(b"secret", &token.replacen("v1", "v2", 1)).unwrap_err(),
+            CursorTokenError::UnsupportedVersion
+        );
+    }
+}
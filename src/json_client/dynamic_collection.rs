@@ -7,9 +7,14 @@ use crate::{
     },
     json_client::{
         ToBind,
-        client_interface::{AddCollectionInput, SupportedType},
+        client_interface::{AddCollectionInput, FieldRule, SupportedType},
+        supported_types::{Bytes, Date, DateTime, JsonValue, Uuid},
+        validation::Rule,
+    },
+    sqlx_query_builder::{
+        basic_expressions::TypeAsSyntax, column_constraints::SqlLiteral,
+        trait_objects::BoxedExpression,
     },
-    sqlx_query_builder::{basic_expressions::TypeAsSyntax, trait_objects::BoxedExpression},
     sub_arc::ArcSubStr,
 };
 use convert_case::{Case, Casing};
@@ -27,6 +32,17 @@ where
 {
     pub(crate) collection_name: CollectionName,
     pub(crate) fields: Vec<DynamicField<S>>,
+    pub(crate) indexes: Vec<DynamicIndex>,
+    /// the `SELECT` of a view, trimmed of its `;`. views are read only
+    pub(crate) view: Option<Arc<str>>,
+}
+
+/// an `AddCollectionInput` index, created by the collection's migration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DynamicIndex {
+    pub(crate) fields: Vec<FieldName>,
+    pub(crate) unique: bool,
+    pub(crate) where_not_null: Vec<FieldName>,
 }
 
 #[derive(Debug)]
@@ -37,6 +53,71 @@ where
     pub(crate) name: FieldName,
     pub(crate) type_info: VTable<S>,
     pub(crate) is_optional: bool,
+    /// set for `SupportedType::Enum`, values outside of it are rejected
+    pub(crate) variants: Option<Arc<[Arc<str>]>>,
+    /// checked by `validation::validate`
+    pub(crate) rules: Arc<[Rule]>,
+    /// `FieldRule::Check`s, written into the table by the migration
+    pub(crate) checks: Arc<[Rule]>,
+    pub(crate) default: Option<DefaultValue>,
+}
+
+/// the `DynamicFieldInput::default` of a scalar field
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum DefaultValue {
+    Text(Arc<str>),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl DefaultValue {
+    fn new(value: &PartialDeserialize, ty: &SupportedType) -> Result<Self, ()> {
+        Ok(match ty {
+            SupportedType::String | SupportedType::Enum { .. } => Self::Text(Arc::from(
+                value.continue_deserialize::<String>().map_err(|_| ())?,
+            )),
+            SupportedType::Int => Self::Int(value.continue_deserialize().map_err(|_| ())?),
+            SupportedType::Float64 => Self::Float(value.continue_deserialize().map_err(|_| ())?),
+            SupportedType::Boolean => Self::Bool(value.continue_deserialize().map_err(|_| ())?),
+            _ => return Err(()),
+        })
+    }
+}
+
+impl SqlLiteral for DefaultValue {
+    fn push_literal(&self, into: &mut String) {
+        match self {
+            Self::Text(value) => value.push_literal(into),
+            Self::Int(value) => value.push_literal(into),
+            Self::Float(value) => value.push_literal(into),
+            Self::Bool(value) => value.push_literal(into),
+        }
+    }
+}
+
+impl<S> DynamicField<S>
+where
+    S: sqlx::Database + DatabaseExt,
+{
+    /// `type_info.to_bind` that also checks the variants of enum fields
+    pub(crate) fn to_bind(
+        &self,
+        partial: PartialDeserialize,
+    ) -> Result<Box<dyn ToBind<S> + Send>, ()> {
+        if let Some(variants) = &self.variants {
+            let value: String = partial.continue_deserialize().map_err(|_| ())?;
+            if !variants.iter().any(|variant| variant.as_ref() == value) {
+                return Err(());
+            }
+        }
+        (self.type_info.to_bind)(partial)
+    }
+
+    /// `Json` values have no meaningful order
+    pub(crate) fn is_ordered(&self) -> bool {
+        (self.type_info.type_id)() != TypeId::of::<JsonValue>()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -243,6 +324,7 @@ where
         for<'q> sqlx::Encode<'q, S> + sqlx::Type<S> + for<'d> sqlx::Decode<'d, S>,
     sqlx::types::Json<Vec<f64>>:
         for<'q> sqlx::Encode<'q, S> + sqlx::Type<S> + for<'d> sqlx::Decode<'d, S>,
+    Vec<u8>: for<'q> sqlx::Encode<'q, S> + sqlx::Type<S> + for<'d> sqlx::Decode<'d, S>,
     for<'a> &'a str: sqlx::ColumnIndex<S::Row>,
 {
     use sqlx::types::Json;
@@ -257,8 +339,14 @@ where
             SupportedType::Boolean => VTable::new_as::<Json<Vec<bool>>>(),
             SupportedType::Int => VTable::new_as::<Json<Vec<i64>>>(),
             SupportedType::Float64 => VTable::new_as::<Json<Vec<f64>>>(),
-            SupportedType::Array(_) => return Err(()),
+            _ => return Err(()),
         },
+        SupportedType::DateTime => VTable::new_as::<DateTime>(),
+        SupportedType::Date => VTable::new_as::<Date>(),
+        SupportedType::Uuid => VTable::new_as::<Uuid>(),
+        SupportedType::Json => VTable::new_as::<JsonValue>(),
+        SupportedType::Bytes => VTable::new_as::<Bytes>(),
+        SupportedType::Enum { .. } => VTable::new_as::<String>(),
     })
 }
 
@@ -277,20 +365,119 @@ where
         for<'q> sqlx::Encode<'q, S> + sqlx::Type<S> + for<'d> sqlx::Decode<'d, S>,
     sqlx::types::Json<Vec<f64>>:
         for<'q> sqlx::Encode<'q, S> + sqlx::Type<S> + for<'d> sqlx::Decode<'d, S>,
+    Vec<u8>: for<'q> sqlx::Encode<'q, S> + sqlx::Type<S> + for<'d> sqlx::Decode<'d, S>,
     for<'a> &'a str: sqlx::ColumnIndex<S::Row>,
 {
     type Error = ();
 
     fn try_from(input: AddCollectionInput) -> Result<Self, Self::Error> {
         let collection_name = CollectionName::new(&input.name)?;
+        let view = match &input.view {
+            Some(select) => {
+                // the view is the only statement of its migration
+                let select = select.trim().trim_end_matches(';').trim_end();
+                if select.is_empty() || select.contains(';') {
+                    return Err(());
+                }
+                // nothing is ever written through a view
+                let writes = input
+                    .fields
+                    .iter()
+                    .any(|field| field.rules.is_empty().not() || field.default.is_some());
+                if writes || input.indexes.is_empty().not() {
+                    return Err(());
+                }
+                Some(Arc::from(select))
+            }
+            None => None,
+        };
         let fields = input
             .fields
             .into_iter()
             .map(|f| {
+                let variants: Option<Arc<[Arc<str>]>> = match &f.type_info {
+                    SupportedType::Enum { variants } if variants.is_empty() => return Err(()),
+                    SupportedType::Enum { variants } => Some(
+                        variants
+                            .iter()
+                            .map(|variant| Arc::from(variant.as_str()))
+                            .collect(),
+                    ),
+                    _ => None,
+                };
+                let (checks, rules): (Vec<_>, Vec<_>) = f
+                    .rules
+                    .iter()
+                    .partition(|rule| matches!(rule, FieldRule::Check(_)));
+                let rules: Arc<[Rule]> = rules
+                    .into_iter()
+                    .map(|rule| Rule::new(rule, &f.type_info))
+                    .collect::<Result<_, _>>()?;
+                let checks: Arc<[Rule]> = checks
+                    .into_iter()
+                    .map(|rule| match rule {
+                        FieldRule::Check(rule) => Rule::new_check(rule, &f.type_info),
+                        _ => Err(()),
+                    })
+                    .collect::<Result<_, _>>()?;
+                let default = match &f.default {
+                    Some(value) => {
+                        // held to the same rules as a value given to insert
+                        let is_variant = variants.as_ref().is_none_or(|variants| {
+                            value.continue_deserialize::<String>().is_ok_and(|value| {
+                                variants.iter().any(|variant| variant.as_ref() == value)
+                            })
+                        });
+                        let passes = rules.iter().chain(checks.iter()).all(|e| e.passes(value));
+                        if (is_variant && passes).not() {
+                            return Err(());
+                        }
+                        Some(DefaultValue::new(value, &f.type_info)?)
+                    }
+                    None => None,
+                };
                 Ok(DynamicField {
                     name: FieldName::new(&f.name)?,
                     type_info: vtable_for_type(&f.type_info)?,
                     is_optional: f.is_optional,
+                    variants,
+                    rules,
+                    checks,
+                    default,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let indexes = input
+            .indexes
+            .iter()
+            .map(|index| {
+                let field = |name: &ArcSubStr| {
+                    fields
+                        .iter()
+                        .find(|field| field.name.as_str() == name.as_str())
+                        .ok_or(())
+                };
+                if index.fields.is_empty() {
+                    return Err(());
+                }
+                let where_not_null = index
+                    .where_not_null
+                    .iter()
+                    .map(|name| match field(name)? {
+                        field if field.is_optional => Ok(field.name.clone()),
+                        // always set, the condition would be a no-op
+                        _ => Err(()),
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(DynamicIndex {
+                    fields: index
+                        .fields
+                        .iter()
+                        .map(|name| Ok(field(name)?.name.clone()))
+                        .collect::<Result<_, _>>()?,
+                    unique: index.unique,
+                    where_not_null,
                 })
             })
             .collect::<Result<Vec<_>, _>>()?;
@@ -298,6 +485,8 @@ where
         Ok(Self {
             collection_name,
             fields,
+            indexes,
+            view,
         })
     }
 }
@@ -370,7 +559,7 @@ mod dynamic_insert_binds {
                             }
                             Box::new(()) as Box<dyn ToBind<S> + Send>
                         } else {
-                            (field.type_info.to_bind)(partial).map_err(|_| {
+                            field.to_bind(partial).map_err(|_| {
                                 format!("invalid value for {:?}", key.as_str()).into()
                             })?
                         };
@@ -385,6 +574,7 @@ mod dynamic_insert_binds {
 
                     for field in &collection.fields {
                         if !field.is_optional
+                            && field.default.is_none()
                             && !out.keys().any(|key| key.as_str() == field.name.as_ref())
                         {
                             return Err(format!(
@@ -454,7 +644,7 @@ mod dynamic_insert_binds {
                             }
                             Box::new(()) as Box<dyn ToBind<S> + Send>
                         } else {
-                            (field.type_info.to_bind)(partial).map_err(|_| {
+                            field.to_bind(partial).map_err(|_| {
                                 format!("invalid value for {:?}", key.as_str()).into()
                             })?
                         };
@@ -1253,7 +1443,10 @@ mod std_impls {
 
     impl<S: DatabaseExt> PartialEq for DynamicCollection<S> {
         fn eq(&self, other: &Self) -> bool {
-            self.collection_name == other.collection_name && self.fields == other.fields
+            self.collection_name == other.collection_name
+                && self.fields == other.fields
+                && self.indexes == other.indexes
+                && self.view == other.view
         }
     }
 
@@ -1265,6 +1458,10 @@ mod std_impls {
                 name: self.name.clone(),
                 type_info: self.type_info.clone(),
                 is_optional: self.is_optional,
+                variants: self.variants.clone(),
+                rules: self.rules.clone(),
+                checks: self.checks.clone(),
+                default: self.default.clone(),
             }
         }
     }
@@ -1274,6 +1471,10 @@ mod std_impls {
             self.name == other.name
                 && self.type_info == other.type_info
                 && self.is_optional == other.is_optional
+                && self.variants == other.variants
+                && self.rules == other.rules
+                && self.checks == other.checks
+                && self.default == other.default
         }
     }
 
@@ -1287,15 +1488,27 @@ mod impl_on_migrate {
     use super::DynamicCollection;
     use crate::{
         database_extention::DatabaseExt,
-        json_client::dynamic_collection::DynamicField,
+        json_client::{
+            dynamic_collection::{DynamicField, DynamicIndex},
+            validation::{Rule, check_name},
+        },
         on_migrate::OnMigrate,
-        sqlx_query_builder::{Expression, OpExpression, StatementBuilder},
+        sqlx_query_builder::{
+            Expression, OpExpression, StatementBuilder,
+            basic_expressions::{ColumnIsNotNull, ManyFlat},
+            column_constraints::{Check, Default, Literal, Named},
+            computed_expressions::{GreaterOrEqual, InList, Length, LessOrEqual},
+            statements::create_index_statement::CreateIndex,
+        },
     };
     use sqlx::ColumnIndex;
 
     pub struct MigrateDynamicCollection<S: DatabaseExt> {
         upper_case_name: Arc<str>,
+        snake_case_name: Arc<str>,
         fields: Vec<DynamicField<S>>,
+        indexes: Vec<DynamicIndex>,
+        view: Option<Arc<str>>,
     }
 
     impl<S> OnMigrate for DynamicCollection<S>
@@ -1310,19 +1523,73 @@ mod impl_on_migrate {
         fn statments(&self) -> Self::Statements {
             MigrateDynamicCollection {
                 upper_case_name: Arc::clone(&self.collection_name.pascal_case),
+                snake_case_name: Arc::clone(&self.collection_name.snake_case),
                 fields: self.fields.clone(),
+                indexes: self.indexes.clone(),
+                view: self.view.clone(),
             }
         }
     }
 
     impl<S: DatabaseExt> OpExpression for MigrateDynamicCollection<S> {}
 
+    /// `CONSTRAINT <check_name> CHECK (..)` of a `FieldRule::Check`, the
+    /// rules `Rule::new_check` accepts
+    fn check_constraint<'q, S: DatabaseExt>(
+        col: &str,
+        num: usize,
+        rule: &Rule,
+        ctx: &mut StatementBuilder<'q, S>,
+    ) {
+        let name = check_name(col, num);
+        let col = col.to_string();
+        match rule {
+            Rule::MinLength(_, min) => Named {
+                name,
+                constraint: Check(GreaterOrEqual(Length(col), Literal(*min))),
+            }
+            .expression(ctx),
+            Rule::MaxLength(_, max) => Named {
+                name,
+                constraint: Check(LessOrEqual(Length(col), Literal(*max))),
+            }
+            .expression(ctx),
+            Rule::Range { min, max } => Named {
+                name,
+                constraint: Check(ManyFlat((
+                    min.map(|min| GreaterOrEqual(col.clone(), Literal(min))),
+                    max.map(|max| LessOrEqual(col.clone(), Literal(max))),
+                ))),
+            }
+            .expression(ctx),
+            Rule::OneOf(values) => Named {
+                name,
+                constraint: Check(InList {
+                    expression: col,
+                    values: values.iter().cloned().map(Literal).collect::<Vec<_>>(),
+                }),
+            }
+            .expression(ctx),
+            Rule::Pattern(_) | Rule::Unique => panic!("bug: not a check rule"),
+        }
+    }
+
     impl<'q, S> Expression<'q, S> for MigrateDynamicCollection<S>
     where
         S: DatabaseExt,
         S::IdExpression: Expression<'q, S>,
     {
         fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            if let Some(select) = self.view {
+                ctx.syntax("CREATE VIEW ");
+                ctx.sanitize(self.upper_case_name.as_ref());
+                ctx.syntax(" AS ");
+                // `AddCollectionInput` is only taken from `Caller::System`
+                ctx.stmt.push_str(&select);
+                ctx.syntax(";");
+                return;
+            }
+
             ctx.syntax("CREATE TABLE ");
             ctx.sanitize(self.upper_case_name.as_ref());
             ctx.syntax(" ");
@@ -1338,9 +1605,43 @@ mod impl_on_migrate {
                 if field.is_optional.not() {
                     ctx.syntax(&" NOT NULL");
                 }
+                if field.rules.contains(&Rule::Unique) {
+                    ctx.syntax(&" UNIQUE");
+                }
+                if let Some(default) = field.default {
+                    ctx.syntax(" ");
+                    Default(Literal(default)).expression(ctx);
+                }
+                for (num, rule) in field.checks.iter().enumerate() {
+                    ctx.syntax(" ");
+                    check_constraint(field.name.as_str(), num, rule, ctx);
+                }
             }
             ctx.syntax(")");
             ctx.syntax(";");
+
+            for (num, index) in self.indexes.into_iter().enumerate() {
+                ctx.syntax(" ");
+                CreateIndex {
+                    unique: index.unique,
+                    if_not_exists: false,
+                    name: format!("idx_{}_{}", self.snake_case_name, num),
+                    table: Arc::clone(&self.upper_case_name),
+                    columns: index
+                        .fields
+                        .iter()
+                        .map(|field| field.as_str().to_string())
+                        .collect::<Vec<_>>(),
+                    wheres: index
+                        .where_not_null
+                        .iter()
+                        .map(|field| ColumnIsNotNull {
+                            col: (field.as_str().to_string(),),
+                        })
+                        .collect::<Vec<_>>(),
+                }
+                .expression(ctx);
+            }
         }
     }
 }
@@ -0,0 +1,415 @@
+//! the error half of `StringClient`'s wire format.
+//!
+//! every failed `exec` returns
+//! `{"error":{"code":..,"details":{..},"message":..,"path":[..]}}`:
+//! - `code` is a stable snake_case identifier to match on
+//! - `details` holds the context of the error, its keys depend on `code`
+//! - `message` is for humans and may change between versions
+//! - `path` points into the request `body`, keys and list indices
+
+use std::sync::Arc;
+
+use crate::json_client::client_interface::{
+    AddCollectionError, AddLinkError, DeleteOneError, FetchManyError, FetchOneError, HistoryError,
+    InsertManyError, InsertOneError, InvalidData, InvalidDataRule, RestoreError, SubscribeError,
+    UpdateOneError,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorBody {
+    pub code: &'static str,
+    pub message: String,
+    pub path: Vec<PathSegment>,
+    pub details: Vec<(&'static str, Detail)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment {
+    Key(Arc<str>),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Detail {
+    String(Arc<str>),
+    Int(i64),
+}
+
+impl ErrorBody {
+    fn new(code: &'static str, message: String) -> Self {
+        Self {
+            code,
+            message,
+            path: Vec::new(),
+            details: Vec::new(),
+        }
+    }
+
+    fn key(mut self, key: &str) -> Self {
+        self.path.push(PathSegment::Key(Arc::from(key)));
+        self
+    }
+
+    fn index(mut self, index: usize) -> Self {
+        self.path.push(PathSegment::Index(index));
+        self
+    }
+
+    /// details are written in the order they are added, keep them sorted
+    fn detail(mut self, key: &'static str, value: Detail) -> Self {
+        self.details.push((key, value));
+        self
+    }
+
+    /// nests the path of `self` under `prefix`
+    fn under(mut self, prefix: &[PathSegment]) -> Self {
+        self.path.splice(0..0, prefix.iter().cloned());
+        self
+    }
+
+    pub(crate) fn invalid_input() -> Self {
+        Self::new(
+            "invalid_input",
+            String::from(r#"the request is not {"op":..,"body":..}"#),
+        )
+    }
+
+    pub(crate) fn invalid_body(op: &str) -> Self {
+        Self::new(
+            "invalid_body",
+            format!("the body doesn't match the input of {op}"),
+        )
+        .detail("op", Detail::String(Arc::from(op)))
+    }
+
+    pub(crate) fn unsupported_operation(op: &str) -> Self {
+        Self::new(
+            "unsupported_operation",
+            format!("there is no operation {op}"),
+        )
+        .detail("op", Detail::String(Arc::from(op)))
+    }
+
+    fn collection_not_found(collection: &Arc<str>) -> Self {
+        Self::new(
+            "collection_not_found",
+            format!("collection {collection} doesn't exist"),
+        )
+        .detail("collection", Detail::String(Arc::clone(collection)))
+    }
+
+    fn forbidden(collection: &Arc<str>) -> Self {
+        Self::new("forbidden", format!("the caller can't access {collection}"))
+            .detail("collection", Detail::String(Arc::clone(collection)))
+    }
+
+    fn read_only(collection: &Arc<str>) -> Self {
+        Self::new(
+            "read_only",
+            format!("collection {collection} is a view, it can't be written"),
+        )
+        .detail("collection", Detail::String(Arc::clone(collection)))
+    }
+
+    fn aborted(collection: &Arc<str>, reason: &Arc<str>) -> Self {
+        Self::new(
+            "aborted",
+            format!("a hook of {collection} aborted: {reason}"),
+        )
+        .detail("collection", Detail::String(Arc::clone(collection)))
+        .detail("reason", Detail::String(Arc::clone(reason)))
+    }
+
+    fn not_found(id: i64) -> Self {
+        Self::new("not_found", format!("no record with id {id}"))
+            .key("id")
+            .detail("id", Detail::Int(id))
+    }
+
+    fn invalid_link(index: usize) -> Self {
+        Self::new(
+            "invalid_link",
+            format!("link {index} doesn't exist or isn't set up for this collection"),
+        )
+        .key("links")
+        .index(index)
+    }
+
+    fn invalid_filter(index: usize) -> Self {
+        Self::new("invalid_filter", format!("filter {index} can't be applied"))
+            .key("filters")
+            .index(index)
+    }
+
+    /// `path` leads to the data, the field is appended to it
+    fn invalid_data(invalid: &InvalidData, path: &[PathSegment]) -> Self {
+        let rule = rule_name(invalid.rule);
+        let body = match &invalid.field {
+            Some(field) => Self::new("invalid_data", format!("{field} breaks the rule {rule}"))
+                .key(field)
+                .detail("field", Detail::String(Arc::clone(field))),
+            None => Self::new("invalid_data", format!("the data breaks the rule {rule}")),
+        };
+        body.detail("rule", Detail::String(Arc::from(rule)))
+            .under(path)
+    }
+
+    fn invalid_link_data(index: usize, invalid: &InvalidData) -> Self {
+        Self::invalid_data(
+            invalid,
+            &[
+                PathSegment::Key(Arc::from("links")),
+                PathSegment::Index(index),
+                PathSegment::Key(Arc::from("value")),
+            ],
+        )
+    }
+}
+
+fn data_path() -> [PathSegment; 1] {
+    [PathSegment::Key(Arc::from("data"))]
+}
+
+fn rule_name(rule: InvalidDataRule) -> &'static str {
+    match rule {
+        InvalidDataRule::Malformed => "malformed",
+        InvalidDataRule::Empty => "empty",
+        InvalidDataRule::UnknownField => "unknown_field",
+        InvalidDataRule::Required => "required",
+        InvalidDataRule::Type => "type",
+        InvalidDataRule::MinLength => "min_length",
+        InvalidDataRule::MaxLength => "max_length",
+        InvalidDataRule::Range => "range",
+        InvalidDataRule::Pattern => "pattern",
+        InvalidDataRule::OneOf => "one_of",
+        InvalidDataRule::Unique => "unique",
+        InvalidDataRule::Check => "check",
+    }
+}
+
+impl From<&AddCollectionError> for ErrorBody {
+    fn from(error: &AddCollectionError) -> Self {
+        match error {
+            AddCollectionError::CollectionAlreadyExists { collection } => Self::new(
+                "collection_already_exists",
+                format!("collection {collection} already exists"),
+            )
+            .key("name")
+            .detail("collection", Detail::String(Arc::clone(collection))),
+            AddCollectionError::InvalidCollectionInput { collection } => Self::new(
+                "invalid_collection_input",
+                format!("collection {collection} has invalid fields or rules"),
+            )
+            .key("fields")
+            .detail("collection", Detail::String(Arc::clone(collection))),
+            AddCollectionError::Forbidden { collection } => Self::forbidden(collection),
+        }
+    }
+}
+
+impl From<&AddLinkError> for ErrorBody {
+    fn from(error: &AddLinkError) -> Self {
+        match error {
+            AddLinkError::LinkAlreadyExists { from, to: Some(to) } => Self::new(
+                "link_already_exists",
+                format!("link from {from} to {to} already exists"),
+            )
+            .detail("from", Detail::String(Arc::clone(from)))
+            .detail("to", Detail::String(Arc::clone(to))),
+            AddLinkError::LinkAlreadyExists { from, to: None } => Self::new(
+                "link_already_exists",
+                format!("{from} already has this link"),
+            )
+            .detail("from", Detail::String(Arc::clone(from))),
+            AddLinkError::CollectionNotFound { collection } => {
+                Self::collection_not_found(collection)
+            }
+            AddLinkError::Forbidden { collection } => Self::forbidden(collection),
+            AddLinkError::InvalidField { collection, field } => Self::new(
+                "invalid_field",
+                format!("{field} of {collection} can't be searched, it isn't a text field"),
+            )
+            .key("fields")
+            .detail("collection", Detail::String(Arc::clone(collection)))
+            .detail("field", Detail::String(Arc::clone(field))),
+            AddLinkError::ReadOnly { collection } => Self::read_only(collection),
+        }
+    }
+}
+
+impl From<&InsertOneError> for ErrorBody {
+    fn from(error: &InsertOneError) -> Self {
+        match error {
+            InsertOneError::CollectionNotFound { collection } => {
+                Self::collection_not_found(collection).key("base")
+            }
+            InsertOneError::InvalidData(invalid) => Self::invalid_data(invalid, &data_path()),
+            InsertOneError::InvalidLinkData { index, invalid } => {
+                Self::invalid_link_data(*index, invalid)
+            }
+            InsertOneError::InvalidLink { index } => Self::invalid_link(*index),
+            InsertOneError::Forbidden { collection } => Self::forbidden(collection),
+            InsertOneError::ReadOnly { collection } => Self::read_only(collection),
+            InsertOneError::Aborted { collection, reason } => Self::aborted(collection, reason),
+        }
+    }
+}
+
+impl From<&InsertManyError> for ErrorBody {
+    fn from(error: &InsertManyError) -> Self {
+        match error {
+            InsertManyError::CollectionNotFound { collection } => {
+                Self::collection_not_found(collection).key("base")
+            }
+            InsertManyError::InvalidData(invalid) => Self::invalid_data(invalid, &[]).key("items"),
+            InsertManyError::Item { index, error } => Self::from(error).under(&[
+                PathSegment::Key(Arc::from("items")),
+                PathSegment::Index(*index),
+            ]),
+            InsertManyError::Forbidden { collection } => Self::forbidden(collection),
+            InsertManyError::ReadOnly { collection } => Self::read_only(collection),
+        }
+    }
+}
+
+impl From<&FetchManyError> for ErrorBody {
+    fn from(error: &FetchManyError) -> Self {
+        match error {
+            FetchManyError::CollectionNotFound { collection } => {
+                Self::collection_not_found(collection).key("base")
+            }
+            FetchManyError::InvalidFilter { index } => Self::invalid_filter(*index),
+            FetchManyError::InvalidLink { index } => Self::invalid_link(*index),
+            FetchManyError::InvalidOrderBy { index } => Self::new(
+                "invalid_order_by",
+                format!("order by {index} can't be applied"),
+            )
+            .key("pagination")
+            .key("order_by")
+            .index(*index),
+            FetchManyError::InvalidCursor => Self::new(
+                "invalid_cursor",
+                String::from("the cursor wasn't issued for this base and order by"),
+            )
+            .key("pagination")
+            .key("cursor"),
+            FetchManyError::InvalidPageSize { max } => Self::new(
+                "invalid_page_size",
+                format!("page sizes are between 0 and {max}, pages start at 0"),
+            )
+            .key("pagination")
+            .detail("max", Detail::Int(*max)),
+            FetchManyError::Forbidden { collection } => Self::forbidden(collection),
+        }
+    }
+}
+
+impl From<&FetchOneError> for ErrorBody {
+    fn from(error: &FetchOneError) -> Self {
+        match error {
+            FetchOneError::CollectionNotFound { collection } => {
+                Self::collection_not_found(collection).key("base")
+            }
+            FetchOneError::NotFound { id } => Self::not_found(*id),
+            FetchOneError::InvalidFilter { index } => Self::invalid_filter(*index),
+            FetchOneError::InvalidLink { index } => Self::invalid_link(*index),
+            FetchOneError::Forbidden { collection } => Self::forbidden(collection),
+        }
+    }
+}
+
+impl From<&UpdateOneError> for ErrorBody {
+    fn from(error: &UpdateOneError) -> Self {
+        match error {
+            UpdateOneError::CollectionNotFound { collection } => {
+                Self::collection_not_found(collection).key("base")
+            }
+            UpdateOneError::InvalidData(invalid) => Self::invalid_data(invalid, &data_path()),
+            UpdateOneError::InvalidLinkData { index, invalid } => {
+                Self::invalid_link_data(*index, invalid)
+            }
+            UpdateOneError::NotFound { id } => Self::not_found(*id),
+            UpdateOneError::InvalidLink { index } => Self::invalid_link(*index),
+            UpdateOneError::Forbidden { collection } => Self::forbidden(collection),
+            UpdateOneError::ReadOnly { collection } => Self::read_only(collection),
+            UpdateOneError::Aborted { collection, reason } => Self::aborted(collection, reason),
+            UpdateOneError::Conflict {
+                id,
+                current_version,
+            } => Self::new(
+                "conflict",
+                format!("record {id} was updated since, it is at version {current_version}"),
+            )
+            .key("version")
+            .detail("current_version", Detail::Int(*current_version))
+            .detail("id", Detail::Int(*id)),
+            UpdateOneError::InvalidVersion { collection } => Self::new(
+                "invalid_version",
+                format!("a version is given if and only if {collection} is versioned"),
+            )
+            .key("version")
+            .detail("collection", Detail::String(Arc::clone(collection))),
+        }
+    }
+}
+
+impl From<&DeleteOneError> for ErrorBody {
+    fn from(error: &DeleteOneError) -> Self {
+        match error {
+            DeleteOneError::CollectionNotFound { collection } => {
+                Self::collection_not_found(collection).key("base")
+            }
+            DeleteOneError::NotFound { id } => Self::not_found(*id),
+            DeleteOneError::InvalidLink { index } => Self::invalid_link(*index),
+            DeleteOneError::Forbidden { collection } => Self::forbidden(collection),
+            DeleteOneError::ReadOnly { collection } => Self::read_only(collection),
+            DeleteOneError::Aborted { collection, reason } => Self::aborted(collection, reason),
+        }
+    }
+}
+
+impl From<&RestoreError> for ErrorBody {
+    fn from(error: &RestoreError) -> Self {
+        match error {
+            RestoreError::CollectionNotFound { collection } => {
+                Self::collection_not_found(collection).key("base")
+            }
+            RestoreError::NotSoftDeleted { collection } => Self::new(
+                "not_soft_deleted",
+                format!("{collection} has no soft_delete link"),
+            )
+            .key("base")
+            .detail("collection", Detail::String(Arc::clone(collection))),
+            RestoreError::NotFound { id } => Self::not_found(*id),
+            RestoreError::Forbidden { collection } => Self::forbidden(collection),
+        }
+    }
+}
+
+impl From<&HistoryError> for ErrorBody {
+    fn from(error: &HistoryError) -> Self {
+        match error {
+            HistoryError::CollectionNotFound { collection } => {
+                Self::collection_not_found(collection).key("base")
+            }
+            HistoryError::NotAudited { collection } => {
+                Self::new("not_audited", format!("{collection} has no audit_log link"))
+                    .key("base")
+                    .detail("collection", Detail::String(Arc::clone(collection)))
+            }
+            HistoryError::Forbidden { collection } => Self::forbidden(collection),
+        }
+    }
+}
+
+impl From<&SubscribeError> for ErrorBody {
+    fn from(error: &SubscribeError) -> Self {
+        match error {
+            SubscribeError::CollectionNotFound { collection } => {
+                Self::collection_not_found(collection).key("base")
+            }
+            SubscribeError::InvalidFilter { index } => Self::invalid_filter(*index),
+            SubscribeError::Forbidden { collection } => Self::forbidden(collection),
+        }
+    }
+}
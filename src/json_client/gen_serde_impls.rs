@@ -2,16 +2,20 @@ use std::collections::BTreeMap;
 
 use crate::expressions::ColumnEqual;
 use crate::gen_serde::json_format_side::{JsonAsArcCursor, PartialDeserialize};
+use crate::gen_serde::json_serialize_side::JsonAsString;
 use crate::gen_serde::{
     Deserialize, DeserializeMap, DeserializeSeq, DeserializeSpec, Deserializer, KnownKey,
-    UnknownKey,
+    ObjectEncoding, Serialize, UnknownKey,
 };
+use crate::json_client::changes::ChangeEvent;
 use crate::json_client::client_interface::{
     AddCollectionInput, AddLinkInput, DeleteOneInput, Direction, DynamicFieldInput, FetchManyInput,
-    FetchOneInput, FirstItem, InsertManyInput, InsertManyItem, InsertOneInput, OrderBy, Pagination,
+    FetchOneInput, FieldRule, HistoryInput, IndexInput, InsertManyInput, InsertManyItem,
+    InsertOneInput, OrderBy, OrderByTarget, Pagination, RestoreInput, SubscribeInput,
     SupportedDeleteLink, SupportedFilter, SupportedInsertLink, SupportedLinkFetchMany,
-    SupportedLinkFetchOne, SupportedType, SupportedUpdateLink, UpdateOneInput,
+    SupportedLinkFetchOne, SupportedLinkFilter, SupportedType, SupportedUpdateLink, UpdateOneInput,
 };
+use crate::json_client::error_body::{Detail, ErrorBody, PathSegment};
 use crate::sub_arc::{ArcSubStr, SubArc};
 
 impl Serialize<JsonAsString> for InsertManyOutput
@@ -52,6 +56,11 @@ fn deserialize_supported_type_partial(
             "Boolean" => Ok(SupportedType::Boolean),
             "Int" => Ok(SupportedType::Int),
             "Float64" => Ok(SupportedType::Float64),
+            "DateTime" => Ok(SupportedType::DateTime),
+            "Date" => Ok(SupportedType::Date),
+            "Uuid" => Ok(SupportedType::Uuid),
+            "Json" => Ok(SupportedType::Json),
+            "Bytes" => Ok(SupportedType::Bytes),
             _other => Err("unsupported SupportedType".into()),
         };
     }
@@ -62,13 +71,21 @@ fn deserialize_supported_type_partial(
     };
     let mut map = DeserializeMap::start_map(&mut cursor)?;
     let ty: String = DeserializeMap::deserialize_with_known_key(&mut cursor, &mut map, "ty", ())?;
-    if ty != "Array" {
-        return Err("unsupported SupportedType object ty".into());
-    }
-    let of: SupportedType =
-        DeserializeMap::deserialize_with_known_key(&mut cursor, &mut map, "of", ())?;
+    let supported_type = match ty.as_str() {
+        "Array" => {
+            let of: SupportedType =
+                DeserializeMap::deserialize_with_known_key(&mut cursor, &mut map, "of", ())?;
+            SupportedType::Array(Box::new(of))
+        }
+        "Enum" => {
+            let variants: Vec<ArcSubStr> =
+                DeserializeMap::deserialize_with_known_key(&mut cursor, &mut map, "variants", ())?;
+            SupportedType::Enum { variants }
+        }
+        _ => return Err("unsupported SupportedType object ty".into()),
+    };
     DeserializeMap::finish(&mut cursor, map)?;
-    Ok(SupportedType::Array(Box::new(of)))
+    Ok(supported_type)
 }
 
 impl DeserializeSpec for DynamicFieldInput {
@@ -82,6 +99,8 @@ where
     ArcSubStr: Deserialize<'de, S>,
     SupportedType: Deserialize<'de, S>,
     bool: Deserialize<'de, S>,
+    Vec<FieldRule>: Deserialize<'de, S>,
+    PartialDeserialize: Deserialize<'de, S>,
     S: KnownKey<&'static str>,
 {
     fn deserialize(_handler: Self::Handler, serialized: &mut S) -> Result<Self, S::Err> {
@@ -91,15 +110,98 @@ where
             DeserializeMap::deserialize_with_known_key(serialized, &mut map, "type_info", ())?;
         let is_optional =
             DeserializeMap::deserialize_with_known_key(serialized, &mut map, "is_optional", ())?;
+        // "rules" may be left out, it has to be given (`[]` will do) along
+        // with a "default"
+        let rules = match DeserializeMap::map_has_next(serialized, &map) {
+            true => DeserializeMap::deserialize_with_known_key(serialized, &mut map, "rules", ())?,
+            false => Vec::new(),
+        };
+        let default = match DeserializeMap::map_has_next(serialized, &map) {
+            true => Some(DeserializeMap::deserialize_with_known_key(
+                serialized,
+                &mut map,
+                "default",
+                (),
+            )?),
+            false => None,
+        };
         DeserializeMap::finish(serialized, map)?;
         Ok(DynamicFieldInput {
             name,
             type_info,
             is_optional,
+            rules,
+            default,
         })
     }
 }
 
+impl DeserializeSpec for FieldRule {
+    type Handler = ();
+}
+
+impl<'de, S> Deserialize<'de, S> for FieldRule
+where
+    S: Deserializer<'de>,
+    S: DeserializeMap<'de>,
+    i64: Deserialize<'de, S>,
+    Option<f64>: Deserialize<'de, S>,
+    ArcSubStr: Deserialize<'de, S>,
+    Vec<ArcSubStr>: Deserialize<'de, S>,
+    PartialDeserialize: Deserialize<'de, S>,
+    S: KnownKey<&'static str>,
+    S::Err: From<&'static str>,
+{
+    fn deserialize(_handler: Self::Handler, serialized: &mut S) -> Result<Self, S::Err> {
+        let mut map = DeserializeMap::start_map(serialized)?;
+        let ty: ArcSubStr =
+            DeserializeMap::deserialize_with_known_key(serialized, &mut map, "ty", ())?;
+        let rule = match ty.as_str() {
+            "min_length" => {
+                let value =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "value", ())?;
+                FieldRule::MinLength(value)
+            }
+            "max_length" => {
+                let value =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "value", ())?;
+                FieldRule::MaxLength(value)
+            }
+            "range" => {
+                let min =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "min", ())?;
+                let max =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "max", ())?;
+                FieldRule::Range { min, max }
+            }
+            "pattern" => {
+                let regex =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "regex", ())?;
+                FieldRule::Pattern(regex)
+            }
+            "one_of" => {
+                let values =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "values", ())?;
+                FieldRule::OneOf(values)
+            }
+            "unique" => FieldRule::Unique,
+            "check" => {
+                // the inner rule goes through json, a `FieldRule: Deserialize<S>`
+                // bound would be recursive
+                let rule: PartialDeserialize =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "rule", ())?;
+                let rule = rule
+                    .continue_deserialize()
+                    .map_err(|_| S::Err::from("invalid check rule"))?;
+                FieldRule::Check(Box::new(rule))
+            }
+            _ => return Err(S::Err::from("unsupported field rule ty")),
+        };
+        DeserializeMap::finish(serialized, map)?;
+        Ok(rule)
+    }
+}
+
 impl DeserializeSpec for AddCollectionInput {
     type Handler = ();
 }
@@ -110,6 +212,7 @@ where
     S: DeserializeMap<'de>,
     ArcSubStr: Deserialize<'de, S>,
     Vec<DynamicFieldInput>: Deserialize<'de, S>,
+    Vec<IndexInput>: Deserialize<'de, S>,
     S: KnownKey<&'static str>,
 {
     fn deserialize(_handler: Self::Handler, serialized: &mut S) -> Result<Self, S::Err> {
@@ -117,8 +220,67 @@ where
         let name = DeserializeMap::deserialize_with_known_key(serialized, &mut map, "name", ())?;
         let fields =
             DeserializeMap::deserialize_with_known_key(serialized, &mut map, "fields", ())?;
+        // "indexes" may be left out, it has to be given (`[]` will do)
+        // along with a "view"
+        let indexes = match DeserializeMap::map_has_next(serialized, &map) {
+            true => {
+                DeserializeMap::deserialize_with_known_key(serialized, &mut map, "indexes", ())?
+            }
+            false => Vec::new(),
+        };
+        let view = match DeserializeMap::map_has_next(serialized, &map) {
+            true => Some(DeserializeMap::deserialize_with_known_key(
+                serialized,
+                &mut map,
+                "view",
+                (),
+            )?),
+            false => None,
+        };
+        DeserializeMap::finish(serialized, map)?;
+        Ok(AddCollectionInput {
+            name,
+            fields,
+            indexes,
+            view,
+        })
+    }
+}
+
+impl DeserializeSpec for IndexInput {
+    type Handler = ();
+}
+
+impl<'de, S> Deserialize<'de, S> for IndexInput
+where
+    S: Deserializer<'de>,
+    S: DeserializeMap<'de>,
+    bool: Deserialize<'de, S>,
+    Vec<ArcSubStr>: Deserialize<'de, S>,
+    S: KnownKey<&'static str>,
+{
+    fn deserialize(_handler: Self::Handler, serialized: &mut S) -> Result<Self, S::Err> {
+        let mut map = DeserializeMap::start_map(serialized)?;
+        let fields =
+            DeserializeMap::deserialize_with_known_key(serialized, &mut map, "fields", ())?;
+        let unique =
+            DeserializeMap::deserialize_with_known_key(serialized, &mut map, "unique", ())?;
+        // "where_not_null" may be left out
+        let where_not_null = match DeserializeMap::map_has_next(serialized, &map) {
+            true => DeserializeMap::deserialize_with_known_key(
+                serialized,
+                &mut map,
+                "where_not_null",
+                (),
+            )?,
+            false => Vec::new(),
+        };
         DeserializeMap::finish(serialized, map)?;
-        Ok(AddCollectionInput { name, fields })
+        Ok(IndexInput {
+            fields,
+            unique,
+            where_not_null,
+        })
     }
 }
 
@@ -295,6 +457,7 @@ where
     S: DeserializeSeq<'de>,
     ArcSubStr: Deserialize<'de, S>,
     PartialDeserialize: Deserialize<'de, S>,
+    SupportedLinkFilter: Deserialize<'de, S>,
     S: KnownKey<&'static str>,
     S::Err: From<&'static str>,
 {
@@ -352,6 +515,43 @@ where
                     DeserializeMap::deserialize_with_known_key(serialized, &mut map, "value", ())?;
                 SupportedFilter::ColContains { col, value }
             }
+            "col_starts_with" => {
+                let col =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "col", ())?;
+                let value =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "value", ())?;
+                SupportedFilter::ColStartsWith { col, value }
+            }
+            "col_ends_with" => {
+                let col =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "col", ())?;
+                let value =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "value", ())?;
+                SupportedFilter::ColEndsWith { col, value }
+            }
+            "col_eq_ignore_case" => {
+                let col =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "col", ())?;
+                let eq =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "eq", ())?;
+                SupportedFilter::ColEqIgnoreCase { col, eq }
+            }
+            "col_in" => {
+                let col =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "col", ())?;
+                let values =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "values", ())?;
+                SupportedFilter::ColIn { col, values }
+            }
+            "col_between" => {
+                let col =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "col", ())?;
+                let low =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "low", ())?;
+                let high =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "high", ())?;
+                SupportedFilter::ColBetween { col, low, high }
+            }
             "col_is_null" => {
                 let col =
                     DeserializeMap::deserialize_with_known_key(serialized, &mut map, "col", ())?;
@@ -362,6 +562,11 @@ where
                     DeserializeMap::deserialize_with_known_key(serialized, &mut map, "col", ())?;
                 SupportedFilter::ColIsNotNull { col }
             }
+            "search" => {
+                let query =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "query", ())?;
+                SupportedFilter::Search { query }
+            }
             "and" => {
                 let filters = DeserializeMap::deserialize_with_known_key(
                     serialized,
@@ -380,6 +585,40 @@ where
                 )?;
                 SupportedFilter::Or { filters }
             }
+            "not" => {
+                let filter =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "filter", ())?;
+                SupportedFilter::Not {
+                    filter: Box::new(filter),
+                }
+            }
+            "exists" => {
+                let link =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "link", ())?;
+                SupportedFilter::Exists { link }
+            }
+            "any" => {
+                let link =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "link", ())?;
+                let filters = DeserializeMap::deserialize_with_known_key(
+                    serialized,
+                    &mut map,
+                    "filters",
+                    (),
+                )?;
+                SupportedFilter::Any { link, filters }
+            }
+            "all" => {
+                let link =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "link", ())?;
+                let filters = DeserializeMap::deserialize_with_known_key(
+                    serialized,
+                    &mut map,
+                    "filters",
+                    (),
+                )?;
+                SupportedFilter::All { link, filters }
+            }
             "group" => {
                 let filters = DeserializeMap::deserialize_with_known_key(
                     serialized,
@@ -400,11 +639,46 @@ impl DeserializeSpec for SupportedFilter {
     type Handler = ();
 }
 
+impl DeserializeSpec for SupportedLinkFilter {
+    type Handler = ();
+}
+
+impl<'de, S> Deserialize<'de, S> for SupportedLinkFilter
+where
+    S: Deserializer<'de>,
+    S: DeserializeMap<'de>,
+    ArcSubStr: Deserialize<'de, S>,
+    S: KnownKey<&'static str>,
+    S::Err: From<&'static str>,
+{
+    fn deserialize(_handler: Self::Handler, serialized: &mut S) -> Result<Self, S::Err> {
+        let mut map = DeserializeMap::start_map(serialized)?;
+        let ty: ArcSubStr =
+            DeserializeMap::deserialize_with_known_key(serialized, &mut map, "ty", ())?;
+        let out = match ty.as_str() {
+            "optional_to_many" => {
+                let to =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "to", ())?;
+                SupportedLinkFilter::OptionalToMany { to }
+            }
+            "many_to_many" => {
+                let to =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "to", ())?;
+                SupportedLinkFilter::ManyToMany { to }
+            }
+            _ => return Err(S::Err::from("unsupported filter link ty")),
+        };
+        DeserializeMap::finish(serialized, map)?;
+        Ok(out)
+    }
+}
+
 impl<'de, S> Deserialize<'de, S> for SupportedLinkFetchMany
 where
     S: Deserializer<'de>,
     S: DeserializeMap<'de>,
     ArcSubStr: Deserialize<'de, S>,
+    bool: Deserialize<'de, S>,
     S: KnownKey<&'static str>,
     S::Err: From<&'static str>,
 {
@@ -424,6 +698,16 @@ where
                 SupportedLinkFetchMany::ManyToMany { to }
             }
             "timestamp" => SupportedLinkFetchMany::Timestamp,
+            "soft_delete" => {
+                let include_deleted = DeserializeMap::deserialize_with_known_key(
+                    serialized,
+                    &mut map,
+                    "include_deleted",
+                    (),
+                )?;
+                SupportedLinkFetchMany::SoftDelete { include_deleted }
+            }
+            "version" => SupportedLinkFetchMany::Version,
             _ => return Err(S::Err::from("unsupported fetch link ty")),
         };
         DeserializeMap::finish(serialized, map)?;
@@ -439,6 +723,7 @@ impl<'de, S> Deserialize<'de, S> for AddLinkInput
 where
     S: Deserializer<'de>,
     S: DeserializeMap<'de>,
+    S: DeserializeSeq<'de>,
     ArcSubStr: Deserialize<'de, S>,
     S: KnownKey<&'static str>,
     S::Err: From<&'static str>,
@@ -471,6 +756,44 @@ where
                 )?;
                 AddLinkInput::Timestamp { collection }
             }
+            "soft_delete" => {
+                let collection = DeserializeMap::deserialize_with_known_key(
+                    serialized,
+                    &mut map,
+                    "collection",
+                    (),
+                )?;
+                AddLinkInput::SoftDelete { collection }
+            }
+            "audit_log" => {
+                let collection = DeserializeMap::deserialize_with_known_key(
+                    serialized,
+                    &mut map,
+                    "collection",
+                    (),
+                )?;
+                AddLinkInput::AuditLog { collection }
+            }
+            "versioned" => {
+                let collection = DeserializeMap::deserialize_with_known_key(
+                    serialized,
+                    &mut map,
+                    "collection",
+                    (),
+                )?;
+                AddLinkInput::Versioned { collection }
+            }
+            "full_text_search" => {
+                let collection = DeserializeMap::deserialize_with_known_key(
+                    serialized,
+                    &mut map,
+                    "collection",
+                    (),
+                )?;
+                let fields =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "fields", ())?;
+                AddLinkInput::FullTextSearch { collection, fields }
+            }
             _ => return Err(S::Err::from("unsupported add link ty")),
         };
         DeserializeMap::finish(serialized, map)?;
@@ -487,6 +810,7 @@ where
     S: Deserializer<'de>,
     S: DeserializeMap<'de>,
     ArcSubStr: Deserialize<'de, S>,
+    bool: Deserialize<'de, S>,
     S: KnownKey<&'static str>,
     S::Err: From<&'static str>,
 {
@@ -506,6 +830,16 @@ where
                 SupportedLinkFetchOne::ManyToMany { to }
             }
             "timestamp" => SupportedLinkFetchOne::Timestamp,
+            "soft_delete" => {
+                let include_deleted = DeserializeMap::deserialize_with_known_key(
+                    serialized,
+                    &mut map,
+                    "include_deleted",
+                    (),
+                )?;
+                SupportedLinkFetchOne::SoftDelete { include_deleted }
+            }
+            "version" => SupportedLinkFetchOne::Version,
             _ => return Err(S::Err::from("unsupported fetch one link ty")),
         };
         DeserializeMap::finish(serialized, map)?;
@@ -587,44 +921,67 @@ where
     S: Deserializer<'de>,
     S: DeserializeMap<'de>,
     i64: Deserialize<'de, S>,
-    Option<FirstItem>: Deserialize<'de, S>,
+    bool: Deserialize<'de, S>,
+    ArcSubStr: Deserialize<'de, S>,
+    Option<ArcSubStr>: Deserialize<'de, S>,
     Vec<OrderBy>: Deserialize<'de, S>,
     S: KnownKey<&'static str>,
+    S::Err: From<&'static str>,
 {
     fn deserialize(_handler: Self::Handler, serialized: &mut S) -> Result<Self, S::Err> {
         let mut map = DeserializeMap::start_map(serialized)?;
-        let limit = DeserializeMap::deserialize_with_known_key(serialized, &mut map, "limit", ())?;
-        let first_item =
-            DeserializeMap::deserialize_with_known_key(serialized, &mut map, "first_item", ())?;
-        let order_by =
-            DeserializeMap::deserialize_with_known_key(serialized, &mut map, "order_by", ())?;
-        DeserializeMap::finish(serialized, map)?;
-        Ok(Pagination {
-            limit,
-            first_item,
-            order_by,
-        })
-    }
-}
-
-impl DeserializeSpec for FirstItem {
-    type Handler = ();
-}
-
-impl<'de, S> Deserialize<'de, S> for FirstItem
-where
-    S: Deserializer<'de>,
-    S: DeserializeMap<'de>,
-    i64: Deserialize<'de, S>,
-    BTreeMap<ArcSubStr, PartialDeserialize>: Deserialize<'de, S>,
-    S: KnownKey<&'static str>,
-{
-    fn deserialize(_handler: Self::Handler, serialized: &mut S) -> Result<Self, S::Err> {
-        let mut map = DeserializeMap::start_map(serialized)?;
-        let id = DeserializeMap::deserialize_with_known_key(serialized, &mut map, "id", ())?;
-        let data = DeserializeMap::deserialize_with_known_key(serialized, &mut map, "data", ())?;
+        let ty: ArcSubStr =
+            DeserializeMap::deserialize_with_known_key(serialized, &mut map, "ty", ())?;
+        let pagination = match ty.as_str() {
+            "cursor" => {
+                let limit =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "limit", ())?;
+                let cursor =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "cursor", ())?;
+                let order_by = DeserializeMap::deserialize_with_known_key(
+                    serialized,
+                    &mut map,
+                    "order_by",
+                    (),
+                )?;
+                Pagination::Cursor {
+                    limit,
+                    cursor,
+                    order_by,
+                }
+            }
+            "offset" => {
+                let page =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "page", ())?;
+                let page_size = DeserializeMap::deserialize_with_known_key(
+                    serialized,
+                    &mut map,
+                    "page_size",
+                    (),
+                )?;
+                let count_total = DeserializeMap::deserialize_with_known_key(
+                    serialized,
+                    &mut map,
+                    "count_total",
+                    (),
+                )?;
+                let order_by = DeserializeMap::deserialize_with_known_key(
+                    serialized,
+                    &mut map,
+                    "order_by",
+                    (),
+                )?;
+                Pagination::Offset {
+                    page,
+                    page_size,
+                    count_total,
+                    order_by,
+                }
+            }
+            _ => return Err(S::Err::from("unsupported pagination ty")),
+        };
         DeserializeMap::finish(serialized, map)?;
-        Ok(FirstItem { id, data })
+        Ok(pagination)
     }
 }
 
@@ -662,15 +1019,43 @@ where
     S: DeserializeMap<'de>,
     ArcSubStr: Deserialize<'de, S>,
     Direction: Deserialize<'de, S>,
+    SupportedLinkFilter: Deserialize<'de, S>,
     S: KnownKey<&'static str>,
+    S::Err: From<&'static str>,
 {
     fn deserialize(_handler: Self::Handler, serialized: &mut S) -> Result<Self, S::Err> {
         let mut map = DeserializeMap::start_map(serialized)?;
-        let col = DeserializeMap::deserialize_with_known_key(serialized, &mut map, "col", ())?;
+        let ty: ArcSubStr =
+            DeserializeMap::deserialize_with_known_key(serialized, &mut map, "ty", ())?;
+        let target = match ty.as_str() {
+            "col" => {
+                let col =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "col", ())?;
+                OrderByTarget::Col { col }
+            }
+            "link_col" => {
+                let link =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "link", ())?;
+                let col =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "col", ())?;
+                OrderByTarget::LinkCol { link, col }
+            }
+            "link_count" => {
+                let link =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "link", ())?;
+                OrderByTarget::LinkCount { link }
+            }
+            "relevance" => {
+                let query =
+                    DeserializeMap::deserialize_with_known_key(serialized, &mut map, "query", ())?;
+                OrderByTarget::Relevance { query }
+            }
+            _ => return Err(S::Err::from("unsupported order by ty")),
+        };
         let direction =
             DeserializeMap::deserialize_with_known_key(serialized, &mut map, "direction", ())?;
         DeserializeMap::finish(serialized, map)?;
-        Ok(OrderBy { col, direction })
+        Ok(OrderBy { target, direction })
     }
 }
 
@@ -714,12 +1099,23 @@ where
         let id = DeserializeMap::deserialize_with_known_key(serialized, &mut map, "id", ())?;
         let data = DeserializeMap::deserialize_with_known_key(serialized, &mut map, "data", ())?;
         let links = DeserializeMap::deserialize_with_known_key(serialized, &mut map, "links", ())?;
+        // "version" may be left out
+        let version = match DeserializeMap::map_has_next(serialized, &map) {
+            true => Some(DeserializeMap::deserialize_with_known_key(
+                serialized,
+                &mut map,
+                "version",
+                (),
+            )?),
+            false => None,
+        };
         DeserializeMap::finish(serialized, map)?;
         Ok(UpdateOneInput {
             base,
             id,
             data,
             links,
+            version,
         })
     }
 }
@@ -781,3 +1177,120 @@ where
         Ok(DeleteOneInput { base, id, links })
     }
 }
+
+impl DeserializeSpec for RestoreInput {
+    type Handler = ();
+}
+
+impl<'de, S> Deserialize<'de, S> for RestoreInput
+where
+    S: Deserializer<'de>,
+    S: DeserializeMap<'de>,
+    ArcSubStr: Deserialize<'de, S>,
+    i64: Deserialize<'de, S>,
+    S: KnownKey<&'static str>,
+{
+    fn deserialize(_handler: Self::Handler, serialized: &mut S) -> Result<Self, S::Err> {
+        let mut map = DeserializeMap::start_map(serialized)?;
+        let base = DeserializeMap::deserialize_with_known_key(serialized, &mut map, "base", ())?;
+        let id = DeserializeMap::deserialize_with_known_key(serialized, &mut map, "id", ())?;
+        DeserializeMap::finish(serialized, map)?;
+        Ok(RestoreInput { base, id })
+    }
+}
+
+impl DeserializeSpec for HistoryInput {
+    type Handler = ();
+}
+
+impl<'de, S> Deserialize<'de, S> for HistoryInput
+where
+    S: Deserializer<'de>,
+    S: DeserializeMap<'de>,
+    ArcSubStr: Deserialize<'de, S>,
+    i64: Deserialize<'de, S>,
+    S: KnownKey<&'static str>,
+{
+    fn deserialize(_handler: Self::Handler, serialized: &mut S) -> Result<Self, S::Err> {
+        let mut map = DeserializeMap::start_map(serialized)?;
+        let base = DeserializeMap::deserialize_with_known_key(serialized, &mut map, "base", ())?;
+        let id = DeserializeMap::deserialize_with_known_key(serialized, &mut map, "id", ())?;
+        DeserializeMap::finish(serialized, map)?;
+        Ok(HistoryInput { base, id })
+    }
+}
+
+impl DeserializeSpec for SubscribeInput {
+    type Handler = ();
+}
+
+impl<'de, S> Deserialize<'de, S> for SubscribeInput
+where
+    S: Deserializer<'de>,
+    S: DeserializeMap<'de>,
+    S: DeserializeSeq<'de>,
+    ArcSubStr: Deserialize<'de, S>,
+    Vec<SupportedFilter>: Deserialize<'de, S>,
+    S: KnownKey<&'static str>,
+{
+    fn deserialize(_handler: Self::Handler, serialized: &mut S) -> Result<Self, S::Err> {
+        let mut map = DeserializeMap::start_map(serialized)?;
+        let base = DeserializeMap::deserialize_with_known_key(serialized, &mut map, "base", ())?;
+        let filters =
+            DeserializeMap::deserialize_with_known_key(serialized, &mut map, "filters", ())?;
+        DeserializeMap::finish(serialized, map)?;
+        Ok(SubscribeInput { base, filters })
+    }
+}
+
+impl Serialize<JsonAsString> for ChangeEvent {
+    fn serialize(&self, ctx: &mut JsonAsString) {
+        let mut object = ObjectEncoding::serialize_start(ctx);
+        ObjectEncoding::serialize_pair(ctx, &mut object, "kind", self.kind.as_str());
+        ObjectEncoding::serialize_pair(ctx, &mut object, "collection", &*self.collection);
+        ObjectEncoding::serialize_pair(ctx, &mut object, "id", &self.id);
+        ObjectEncoding::serialize_pair(ctx, &mut object, "attributes", &self.attributes);
+        ObjectEncoding::serialize_end(ctx, object);
+    }
+}
+
+impl Serialize<JsonAsString> for ErrorBody {
+    fn serialize(&self, ctx: &mut JsonAsString) {
+        let mut object = ObjectEncoding::serialize_start(ctx);
+        ObjectEncoding::serialize_pair(ctx, &mut object, "code", self.code);
+        ObjectEncoding::serialize_pair(ctx, &mut object, "details", &ErrorDetails(&self.details));
+        ObjectEncoding::serialize_pair(ctx, &mut object, "message", &self.message);
+        ObjectEncoding::serialize_pair(ctx, &mut object, "path", &self.path);
+        ObjectEncoding::serialize_end(ctx, object);
+    }
+}
+
+struct ErrorDetails<'a>(&'a [(&'static str, Detail)]);
+
+impl Serialize<JsonAsString> for ErrorDetails<'_> {
+    fn serialize(&self, ctx: &mut JsonAsString) {
+        let mut object = ObjectEncoding::serialize_start(ctx);
+        for (key, value) in self.0 {
+            ObjectEncoding::serialize_pair(ctx, &mut object, *key, value);
+        }
+        ObjectEncoding::serialize_end(ctx, object);
+    }
+}
+
+impl Serialize<JsonAsString> for Detail {
+    fn serialize(&self, ctx: &mut JsonAsString) {
+        match self {
+            Detail::String(value) => (**value).serialize(ctx),
+            Detail::Int(value) => value.serialize(ctx),
+        }
+    }
+}
+
+impl Serialize<JsonAsString> for PathSegment {
+    fn serialize(&self, ctx: &mut JsonAsString) {
+        match self {
+            PathSegment::Key(key) => (**key).serialize(ctx),
+            PathSegment::Index(index) => (*index as i64).serialize(ctx),
+        }
+    }
+}
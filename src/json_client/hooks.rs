@@ -0,0 +1,113 @@
+//! closures run around the writes of dynamic collections.
+//!
+//! hooks are registered on `SqlxExecutor` by collection name and run on the
+//! connection of the operation, for its base only: records created through
+//! `SetNew` links don't trigger the hooks of their collection. returning an
+//! error aborts the operation with `Aborted`, the reason is passed along as is.
+
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+
+use crate::json_client::dynamic_collection::{
+    CollectionToSerialize, DynamicInsertInput, DynamicUpdateInput,
+};
+
+pub type BeforeInsertHook<S> = Arc<
+    dyn for<'c> Fn(
+            &'c mut <S as sqlx::Database>::Connection,
+            &'c mut DynamicInsertInput<S>,
+        ) -> BoxFuture<'c, Result<(), String>>
+        + Send
+        + Sync,
+>;
+
+/// receives the id of the record being updated
+pub type BeforeUpdateHook<S> = Arc<
+    dyn for<'c> Fn(
+            &'c mut <S as sqlx::Database>::Connection,
+            i64,
+            &'c mut DynamicUpdateInput<S>,
+        ) -> BoxFuture<'c, Result<(), String>>
+        + Send
+        + Sync,
+>;
+
+/// receives the id and attributes of the inserted or deleted record
+pub type AfterWriteHook<S> = Arc<
+    dyn for<'c> Fn(
+            &'c mut <S as sqlx::Database>::Connection,
+            i64,
+            &'c CollectionToSerialize,
+        ) -> BoxFuture<'c, Result<(), String>>
+        + Send
+        + Sync,
+>;
+
+pub(crate) struct Hooks<S: sqlx::Database> {
+    pub(crate) before_insert: Vec<BeforeInsertHook<S>>,
+    pub(crate) after_insert: Vec<AfterWriteHook<S>>,
+    pub(crate) before_update: Vec<BeforeUpdateHook<S>>,
+    pub(crate) after_delete: Vec<AfterWriteHook<S>>,
+}
+
+impl<S: sqlx::Database> Default for Hooks<S> {
+    fn default() -> Self {
+        Self {
+            before_insert: Vec::new(),
+            after_insert: Vec::new(),
+            before_update: Vec::new(),
+            after_delete: Vec::new(),
+        }
+    }
+}
+
+// hooks run in the order they were registered, the first error stops the rest
+impl<S: sqlx::Database> Hooks<S> {
+    pub(crate) async fn before_insert(
+        &self,
+        conn: &mut S::Connection,
+        data: &mut DynamicInsertInput<S>,
+    ) -> Result<(), Arc<str>> {
+        for hook in &self.before_insert {
+            hook(&mut *conn, &mut *data).await.map_err(Arc::from)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn after_insert(
+        &self,
+        conn: &mut S::Connection,
+        id: i64,
+        attributes: &CollectionToSerialize,
+    ) -> Result<(), Arc<str>> {
+        for hook in &self.after_insert {
+            hook(&mut *conn, id, attributes).await.map_err(Arc::from)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn before_update(
+        &self,
+        conn: &mut S::Connection,
+        id: i64,
+        data: &mut DynamicUpdateInput<S>,
+    ) -> Result<(), Arc<str>> {
+        for hook in &self.before_update {
+            hook(&mut *conn, id, &mut *data).await.map_err(Arc::from)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn after_delete(
+        &self,
+        conn: &mut S::Connection,
+        id: i64,
+        attributes: &CollectionToSerialize,
+    ) -> Result<(), Arc<str>> {
+        for hook in &self.after_delete {
+            hook(&mut *conn, id, attributes).await.map_err(Arc::from)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+//! serves the subscriptions of a `StringClient` as server-sent events.
+//!
+//! `POST /subscribe` takes the body of a `subscribe` op and answers with a
+//! stream of events, one change per `data` line. an invalid subscription is
+//! answered with `400` and the serialized error instead.
+
+use std::{convert::Infallible, sync::Arc};
+
+use axum::{
+    Router,
+    extract::State,
+    http::StatusCode,
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+    routing::post,
+};
+
+use crate::json_client::string_client::StringClient;
+
+pub fn subscribe_router(client: Arc<StringClient>) -> Router {
+    Router::new()
+        .route("/subscribe", post(subscribe))
+        .with_state(client)
+}
+
+async fn subscribe(State(client): State<Arc<StringClient>>, body: String) -> Response {
+    let subscription = match client.subscribe(body).await {
+        Ok(subscription) => subscription,
+        Err(error) => return (StatusCode::BAD_REQUEST, error).into_response(),
+    };
+
+    let events = futures::stream::unfold(subscription, |mut subscription| async move {
+        let event = subscription.next().await?;
+        Some((
+            Ok::<_, Infallible>(Event::default().data(event)),
+            subscription,
+        ))
+    });
+
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
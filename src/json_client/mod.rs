@@ -48,10 +48,24 @@ pub type DynManyToMany<S> = crate::links::relation_many_to_many::ManyToMany<
 pub type DynTimestamp<S> = crate::links::timestamp::Timestamp<
     std::sync::Arc<crate::json_client::dynamic_collection::DynamicCollection<S>>,
 >;
+pub type DynSoftDelete<S> = crate::links::soft_delete::SoftDelete<
+    std::sync::Arc<crate::json_client::dynamic_collection::DynamicCollection<S>>,
+>;
+pub type DynVersioned<S> = crate::links::versioned::Versioned<
+    std::sync::Arc<crate::json_client::dynamic_collection::DynamicCollection<S>>,
+>;
+pub type DynFullTextSearch<S> = crate::links::full_text_search::FullTextSearch<
+    std::sync::Arc<crate::json_client::dynamic_collection::DynamicCollection<S>>,
+    Vec<std::sync::Arc<str>>,
+>;
 
+pub mod changes;
 pub mod client_interface;
+mod cursor_token;
 pub mod dynamic_collection;
+pub mod error_body;
 mod gen_serde_impls;
+pub mod hooks;
 mod op_add_collection;
 mod op_add_link;
 mod op_delete_one;
@@ -60,15 +74,23 @@ mod op_fetch_many;
 pub mod op_fetch_many_trait_extension;
 mod op_fetch_one;
 pub mod op_fetch_one_trait_extension;
+mod op_history;
 mod op_insert_many;
 mod op_insert_one;
 pub mod op_insert_one_trait_extension;
+mod op_restore;
+mod op_subscribe;
 mod op_update_one;
 pub mod op_update_one_trait_extension;
 mod ops;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod policy;
 mod sqlx_executor;
 mod string_client;
 mod supported_filters;
+pub mod supported_types;
+mod validation;
 
 #[cfg(test)]
 mod test_utilities;
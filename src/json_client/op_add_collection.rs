@@ -1,4 +1,4 @@
-use std::{future::Future, sync::Arc};
+use std::{future::Future, ops::Not, sync::Arc};
 
 use sqlx::IntoArguments;
 
@@ -6,17 +6,22 @@ use crate::{
     database_extention::DatabaseExt,
     fix_executor::ExecutorTrait,
     json_client::{
-        client_interface::{AddCollectionError, AddCollectionInput, AddCollectionOutput},
+        client_interface::{AddCollectionError, AddCollectionInput, AddCollectionOutput, Caller},
         dynamic_collection::{DynamicCollection, FieldName},
         sqlx_executor::SqlxExecutorData,
     },
     on_migrate::OnMigrate,
-    sqlx_query_builder::{Expression, StatementBuilder},
+    sqlx_query_builder::{
+        Expression, StatementBuilder,
+        column_constraints::Literal,
+        statements::{create_view_statement::DropView, select_statement::SelectStatement},
+    },
 };
 
 pub fn add_collection<S>(
     this: Arc<SqlxExecutorData<S>>,
     input: AddCollectionInput,
+    caller: Caller,
 ) -> impl Future<Output = Result<AddCollectionOutput, AddCollectionError>> + 'static + Send + use<S>
 where
     S: Sync + DatabaseExt + ExecutorTrait,
@@ -32,20 +37,28 @@ where
         for<'q> sqlx::Encode<'q, S> + sqlx::Type<S> + for<'d> sqlx::Decode<'d, S>,
     sqlx::types::Json<Vec<f64>>:
         for<'q> sqlx::Encode<'q, S> + sqlx::Type<S> + for<'d> sqlx::Decode<'d, S>,
+    Vec<u8>: for<'q> sqlx::Encode<'q, S> + sqlx::Type<S> + for<'d> sqlx::Decode<'d, S>,
     DynamicCollection<S>: OnMigrate<Statements: Expression<'static, S>>,
     for<'a> &'a str: sqlx::ColumnIndex<<S as sqlx::Database>::Row>,
     for<'a> S::Arguments<'a>: IntoArguments<'a, S>,
 {
     async move {
-        let dc = DynamicCollection::try_from(input)
-            .map_err(|_| AddCollectionError::InvalidCollectionInput)?;
+        let collection = input.name.detach();
+        if caller != Caller::System {
+            return Err(AddCollectionError::Forbidden { collection });
+        }
+        let dc = DynamicCollection::try_from(input).map_err(|_| {
+            AddCollectionError::InvalidCollectionInput {
+                collection: Arc::clone(&collection),
+            }
+        })?;
 
         let collection_key: Arc<str> = Arc::clone(&dc.collection_name.snake_case);
 
         {
             let collections = this.collections.read().await;
             if collections.get(collection_key.as_ref()).is_some() {
-                return Err(AddCollectionError::CollectionAlreadyExists);
+                return Err(AddCollectionError::CollectionAlreadyExists { collection });
             }
         }
 
@@ -53,9 +66,41 @@ where
 
         let mut conn = this.pool.acquire().await.expect("bug: {}");
 
-        S::execute(&mut conn, mig.as_str())
-            .await
-            .expect("bug: migration should never fail");
+        let migrated = S::execute(&mut conn, mig.as_str()).await;
+        match &dc.view {
+            Some(_) => {
+                // the select is written by the caller, it may not run or
+                // leave out some of the fields
+                let columns = StatementBuilder::<S>::new_no_data(SelectStatement {
+                    select_items: std::iter::once("id")
+                        .chain(dc.fields.iter().map(|field| field.name.as_str()))
+                        .map(String::from)
+                        .collect::<Vec<_>>(),
+                    from: Arc::clone(&dc.collection_name.pascal_case),
+                    joins: (),
+                    wheres: (),
+                    group_by: (),
+                    order: (),
+                    limit: Literal(0),
+                    offset: (),
+                })
+                .expect("bug: {}");
+                let valid =
+                    migrated.is_ok() && S::execute(&mut conn, columns.as_str()).await.is_ok();
+                if valid.not() {
+                    let drop = StatementBuilder::<S>::new_no_data(DropView {
+                        if_exists: true,
+                        name: Arc::clone(&dc.collection_name.pascal_case),
+                    })
+                    .expect("bug: {}");
+                    S::execute(&mut conn, drop.as_str()).await.unwrap();
+                    return Err(AddCollectionError::InvalidCollectionInput { collection });
+                }
+            }
+            None => {
+                migrated.expect("bug: migration should never fail");
+            }
+        }
 
         let mut collections = this.collections.write().await;
         let mut migration = this.migration.write().await;
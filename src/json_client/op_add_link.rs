@@ -4,13 +4,14 @@ use crate::{
     database_extention::DatabaseExt,
     fix_executor::ExecutorTrait,
     json_client::{
-        client_interface::{AddLinkError, AddLinkInput, AddLinkOutput},
+        client_interface::{AddLinkError, AddLinkInput, AddLinkOutput, Caller},
         dynamic_collection::DynamicCollection,
         sqlx_executor::{FromTo, SqlxExecutorData},
     },
     links::{
-        DefaultRelationKey, relation_many_to_many::ManyToMany,
-        relation_optional_to_many::OptionalToMany, timestamp::Timestamp,
+        DefaultRelationKey, audit_log::AuditLog, full_text_search::FullTextSearch,
+        relation_many_to_many::ManyToMany, relation_optional_to_many::OptionalToMany,
+        soft_delete::SoftDelete, timestamp::Timestamp, versioned::Versioned,
     },
     on_migrate::OnMigrate,
     sqlx_query_builder::{Expression, StatementBuilder},
@@ -19,6 +20,7 @@ use crate::{
 pub fn add_link<S>(
     this: Arc<SqlxExecutorData<S>>,
     input: AddLinkInput,
+    caller: Caller,
 ) -> impl Future<Output = Result<AddLinkOutput, AddLinkError>> + 'static + Send + use<S>
 where
     S: DatabaseExt + Sync + Send + ExecutorTrait,
@@ -27,8 +29,52 @@ where
     ManyToMany<DefaultRelationKey, Arc<DynamicCollection<S>>, Arc<DynamicCollection<S>>>:
         OnMigrate<Statements: for<'q> Expression<'q, S>>,
     Timestamp<Arc<DynamicCollection<S>>>: OnMigrate<Statements: for<'q> Expression<'q, S>>,
+    SoftDelete<Arc<DynamicCollection<S>>>: OnMigrate<Statements: for<'q> Expression<'q, S>>,
+    AuditLog<Arc<DynamicCollection<S>>>: OnMigrate<Statements: for<'q> Expression<'q, S>>,
+    Versioned<Arc<DynamicCollection<S>>>: OnMigrate<Statements: for<'q> Expression<'q, S>>,
+    FullTextSearch<Arc<DynamicCollection<S>>, Vec<Arc<str>>>:
+        OnMigrate<Statements: for<'q> Expression<'q, S>>,
 {
     async move {
+        if caller != Caller::System {
+            let collection = match &input {
+                AddLinkInput::OptionalToMany { from, .. }
+                | AddLinkInput::ManyToMany { from, .. } => from,
+                AddLinkInput::Timestamp { collection }
+                | AddLinkInput::SoftDelete { collection }
+                | AddLinkInput::AuditLog { collection }
+                | AddLinkInput::Versioned { collection }
+                | AddLinkInput::FullTextSearch { collection, .. } => collection,
+            };
+            return Err(AddLinkError::Forbidden {
+                collection: collection.detach(),
+            });
+        }
+
+        // every link writes to the tables of its collections
+        {
+            let linked = match &input {
+                AddLinkInput::OptionalToMany { from, to }
+                | AddLinkInput::ManyToMany { from, to } => vec![from, to],
+                AddLinkInput::Timestamp { collection }
+                | AddLinkInput::SoftDelete { collection }
+                | AddLinkInput::AuditLog { collection }
+                | AddLinkInput::Versioned { collection }
+                | AddLinkInput::FullTextSearch { collection, .. } => vec![collection],
+            };
+            let collections = this.collections.read().await;
+            for name in linked {
+                let Some(collection) = collections.get(name.as_str()) else {
+                    continue;
+                };
+                if collection.read().await.view.is_some() {
+                    return Err(AddLinkError::ReadOnly {
+                        collection: name.detach(),
+                    });
+                }
+            }
+        }
+
         match input {
             AddLinkInput::OptionalToMany { from, to } => {
                 {
@@ -37,20 +83,27 @@ where
                         from: from.detach(),
                         to: to.detach(),
                     }) {
-                        return Err(AddLinkError::LinkAlreadyExists);
+                        return Err(AddLinkError::LinkAlreadyExists {
+                            from: from.detach(),
+                            to: Some(to.detach()),
+                        });
                     }
                 }
 
                 let collections = this.collections.read().await;
                 let from_col = collections
                     .get(from.as_str())
-                    .ok_or(AddLinkError::CollectionNotFound)?
+                    .ok_or_else(|| AddLinkError::CollectionNotFound {
+                        collection: from.detach(),
+                    })?
                     .read()
                     .await
                     .clone();
                 let to_col = collections
                     .get(to.as_str())
-                    .ok_or(AddLinkError::CollectionNotFound)?
+                    .ok_or_else(|| AddLinkError::CollectionNotFound {
+                        collection: to.detach(),
+                    })?
                     .read()
                     .await
                     .clone();
@@ -84,20 +137,27 @@ where
                         from: from.detach(),
                         to: to.detach(),
                     }) {
-                        return Err(AddLinkError::LinkAlreadyExists);
+                        return Err(AddLinkError::LinkAlreadyExists {
+                            from: from.detach(),
+                            to: Some(to.detach()),
+                        });
                     }
                 }
 
                 let collections = this.collections.read().await;
                 let from_col = collections
                     .get(from.as_str())
-                    .ok_or(AddLinkError::CollectionNotFound)?
+                    .ok_or_else(|| AddLinkError::CollectionNotFound {
+                        collection: from.detach(),
+                    })?
                     .read()
                     .await
                     .clone();
                 let to_col = collections
                     .get(to.as_str())
-                    .ok_or(AddLinkError::CollectionNotFound)?
+                    .ok_or_else(|| AddLinkError::CollectionNotFound {
+                        collection: to.detach(),
+                    })?
                     .read()
                     .await
                     .clone();
@@ -127,14 +187,19 @@ where
                 {
                     let li_read = this.link_info.read().await;
                     if li_read.timestamped.contains(collection.as_str()) {
-                        return Err(AddLinkError::LinkAlreadyExists);
+                        return Err(AddLinkError::LinkAlreadyExists {
+                            from: collection.detach(),
+                            to: None,
+                        });
                     }
                 }
 
                 let collections = this.collections.read().await;
                 let col = collections
                     .get(collection.as_str())
-                    .ok_or(AddLinkError::CollectionNotFound)?
+                    .ok_or_else(|| AddLinkError::CollectionNotFound {
+                        collection: collection.detach(),
+                    })?
                     .read()
                     .await
                     .clone();
@@ -159,6 +224,199 @@ where
                 migration.push(mig);
                 link_info.timestamped.insert(collection.detach());
 
+                Ok(())
+            }
+            AddLinkInput::SoftDelete { collection } => {
+                {
+                    let li_read = this.link_info.read().await;
+                    if li_read.soft_deleted.contains(collection.as_str()) {
+                        return Err(AddLinkError::LinkAlreadyExists {
+                            from: collection.detach(),
+                            to: None,
+                        });
+                    }
+                }
+
+                let collections = this.collections.read().await;
+                let col = collections
+                    .get(collection.as_str())
+                    .ok_or_else(|| AddLinkError::CollectionNotFound {
+                        collection: collection.detach(),
+                    })?
+                    .read()
+                    .await
+                    .clone();
+                drop(collections);
+
+                let mig = StatementBuilder::<S>::new_no_data(OnMigrate::statments(&SoftDelete {
+                    collection: col,
+                    include_deleted: false,
+                }))
+                .expect("bug: soft_delete migration contains bind parameters");
+
+                let mut conn = this
+                    .pool
+                    .acquire()
+                    .await
+                    .expect("dev_ops: acquire connection");
+                S::execute(&mut *conn, mig.as_str())
+                    .await
+                    .expect("bug: soft_delete migration failed");
+
+                let mut migration = this.migration.write().await;
+                let mut link_info = this.link_info.write().await;
+                migration.push(mig);
+                link_info.soft_deleted.insert(collection.detach());
+
+                Ok(())
+            }
+            AddLinkInput::AuditLog { collection } => {
+                {
+                    let li_read = this.link_info.read().await;
+                    if li_read.audited.contains(collection.as_str()) {
+                        return Err(AddLinkError::LinkAlreadyExists {
+                            from: collection.detach(),
+                            to: None,
+                        });
+                    }
+                }
+
+                let collections = this.collections.read().await;
+                let col = collections
+                    .get(collection.as_str())
+                    .ok_or_else(|| AddLinkError::CollectionNotFound {
+                        collection: collection.detach(),
+                    })?
+                    .read()
+                    .await
+                    .clone();
+                drop(collections);
+
+                let mig = StatementBuilder::<S>::new_no_data(OnMigrate::statments(&AuditLog {
+                    collection: col,
+                }))
+                .expect("bug: audit_log migration contains bind parameters");
+
+                let mut conn = this
+                    .pool
+                    .acquire()
+                    .await
+                    .expect("dev_ops: acquire connection");
+                S::execute(&mut *conn, mig.as_str())
+                    .await
+                    .expect("bug: audit_log migration failed");
+
+                let mut migration = this.migration.write().await;
+                let mut link_info = this.link_info.write().await;
+                migration.push(mig);
+                link_info.audited.insert(collection.detach());
+
+                Ok(())
+            }
+            AddLinkInput::Versioned { collection } => {
+                {
+                    let li_read = this.link_info.read().await;
+                    if li_read.versioned.contains(collection.as_str()) {
+                        return Err(AddLinkError::LinkAlreadyExists {
+                            from: collection.detach(),
+                            to: None,
+                        });
+                    }
+                }
+
+                let collections = this.collections.read().await;
+                let col = collections
+                    .get(collection.as_str())
+                    .ok_or_else(|| AddLinkError::CollectionNotFound {
+                        collection: collection.detach(),
+                    })?
+                    .read()
+                    .await
+                    .clone();
+                drop(collections);
+
+                let mig = StatementBuilder::<S>::new_no_data(OnMigrate::statments(&Versioned {
+                    collection: col,
+                }))
+                .expect("bug: versioned migration contains bind parameters");
+
+                let mut conn = this
+                    .pool
+                    .acquire()
+                    .await
+                    .expect("dev_ops: acquire connection");
+                S::execute(&mut *conn, mig.as_str())
+                    .await
+                    .expect("bug: versioned migration failed");
+
+                let mut migration = this.migration.write().await;
+                let mut link_info = this.link_info.write().await;
+                migration.push(mig);
+                link_info.versioned.insert(collection.detach());
+
+                Ok(())
+            }
+            AddLinkInput::FullTextSearch { collection, fields } => {
+                {
+                    let li_read = this.link_info.read().await;
+                    if li_read.full_text_search.contains(collection.as_str()) {
+                        return Err(AddLinkError::LinkAlreadyExists {
+                            from: collection.detach(),
+                            to: None,
+                        });
+                    }
+                }
+
+                let collections = this.collections.read().await;
+                let col = collections
+                    .get(collection.as_str())
+                    .ok_or_else(|| AddLinkError::CollectionNotFound {
+                        collection: collection.detach(),
+                    })?
+                    .read()
+                    .await
+                    .clone();
+                drop(collections);
+
+                // only text can be indexed
+                let fields = fields
+                    .iter()
+                    .map(|field| {
+                        col.fields
+                            .iter()
+                            .find(|each| each.name.as_str() == field.as_str())
+                            .filter(|each| {
+                                (each.type_info.type_name)() == std::any::type_name::<String>()
+                            })
+                            .map(|each| Arc::clone(&each.name.snake_case))
+                            .ok_or_else(|| AddLinkError::InvalidField {
+                                collection: collection.detach(),
+                                field: field.detach(),
+                            })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let mig =
+                    StatementBuilder::<S>::new_no_data(OnMigrate::statments(&FullTextSearch {
+                        collection: col,
+                        fields,
+                    }))
+                    .expect("bug: full text search migration contains bind parameters");
+
+                let mut conn = this
+                    .pool
+                    .acquire()
+                    .await
+                    .expect("dev_ops: acquire connection");
+                S::execute(&mut *conn, mig.as_str())
+                    .await
+                    .expect("bug: full text search migration failed");
+
+                let mut migration = this.migration.write().await;
+                let mut link_info = this.link_info.write().await;
+                migration.push(mig);
+                link_info.full_text_search.insert(collection.detach());
+
                 Ok(())
             }
         }
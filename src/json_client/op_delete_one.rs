@@ -11,20 +11,26 @@ use crate::{
     from_row::FromRowAlias,
     json_client::{
         DynManyToMany, DynOptionalToMany,
-        client_interface::{DeleteOneError, DeleteOneInput, DeleteOneOutput, SupportedDeleteLink},
+        changes::{self, ChangeEvent, ChangeKind},
+        client_interface::{
+            Caller, DeleteOneError, DeleteOneInput, DeleteOneOutput, SupportedDeleteLink,
+        },
         dynamic_collection::{CollectionToSerialize, DynamicCollection},
         op_delete_one_trait_extension::{JsonDeleteOneLink, JsonDeleteOneToConsume},
+        policy::{self, Mode, owns},
         sqlx_executor::{FromTo, SqlxExecutorData},
     },
     links::{
         DefaultRelationKey,
         relation_many_to_many::{DeleteManyToManyLinked, ManyToMany},
         relation_optional_to_many::{DeleteOptionalToManyLinked, OptionalToMany},
+        soft_delete::operations::SoftDeleteRecords,
     },
     operations::{
-        Operation,
+        LinkedOutput, Operation,
         delete::{Delete, DeleteLink, DeleteLinkSplit},
     },
+    sqlx_query_builder::basic_expressions::ManyFlat,
 };
 
 type DynCollection<S> = Arc<DynamicCollection<S>>;
@@ -32,6 +38,7 @@ type DynCollection<S> = Arc<DynamicCollection<S>>;
 pub fn delete_one<S>(
     this: Arc<SqlxExecutorData<S>>,
     input: DeleteOneInput,
+    caller: Caller,
 ) -> impl Future<Output = Result<DeleteOneOutput, DeleteOneError>> + 'static + Send + use<S>
 where
     i64: sqlx::Type<S> + for<'q> sqlx::Decode<'q, S> + for<'q> sqlx::Encode<'q, S>,
@@ -66,23 +73,51 @@ where
         let cols = this.collections.read().await;
         let base_gaurd = cols
             .get(input.base.as_str())
-            .ok_or(DeleteOneError::CollectionNotFound)?
+            .ok_or_else(|| DeleteOneError::CollectionNotFound {
+                collection: input.base.detach(),
+            })?
             .read()
             .await;
         let base = base_gaurd.clone();
+        if base.view.is_some() {
+            return Err(DeleteOneError::ReadOnly {
+                collection: Arc::clone(&base.collection_name.snake_case),
+            });
+        }
         let mut all_gaurds = vec![base_gaurd];
 
+        let forbidden = |collection: &DynamicCollection<S>| DeleteOneError::Forbidden {
+            collection: Arc::clone(&collection.collection_name.snake_case),
+        };
+        let access = policy::access(&this.policies, &base, Mode::Write, caller)
+            .ok_or_else(|| forbidden(&base))?;
+        // links return the linked records
+        let readable = |collection: &DynamicCollection<S>| {
+            policy::access(&this.policies, collection, Mode::Read, caller)
+                .is_some_and(|access| access.is_all())
+        };
+
         let rel_guard = this.link_info.read().await;
+        let soft_deleted = rel_guard
+            .soft_deleted
+            .contains(base.collection_name.snake_case.as_ref());
+        // the links of a soft-deleted record stay in place for `restore`
+        if soft_deleted && !input.links.is_empty() {
+            return Err(DeleteOneError::InvalidLink { index: 0 });
+        }
         let mut links = Vec::<JsonDeleteOneToConsume<S>>::new();
 
-        for link in input.links {
+        for (index, link) in input.links.into_iter().enumerate() {
             match link {
                 SupportedDeleteLink::OptionalToMany { to } => {
                     let to_gaurd = cols
                         .get(to.as_str())
-                        .ok_or(DeleteOneError::InvalidLink)?
+                        .ok_or(DeleteOneError::InvalidLink { index })?
                         .read()
                         .await;
+                    if !readable(&to_gaurd) {
+                        return Err(forbidden(&to_gaurd));
+                    }
                     let to = to_gaurd.clone();
                     all_gaurds.push(to_gaurd);
                     links.push(JsonDeleteOneToConsume::from_split(
@@ -99,9 +134,12 @@ where
                 SupportedDeleteLink::ManyToMany { to } => {
                     let to_gaurd = cols
                         .get(to.as_str())
-                        .ok_or(DeleteOneError::InvalidLink)?
+                        .ok_or(DeleteOneError::InvalidLink { index })?
                         .read()
                         .await;
+                    if !readable(&to_gaurd) {
+                        return Err(forbidden(&to_gaurd));
+                    }
                     let to = to_gaurd.clone();
                     all_gaurds.push(to_gaurd);
 
@@ -111,7 +149,7 @@ where
                     };
 
                     if !rel_guard.many_to_many.contains(&forward) {
-                        return Err(DeleteOneError::InvalidLink);
+                        return Err(DeleteOneError::InvalidLink { index });
                     }
 
                     links.push(JsonDeleteOneToConsume::from_split(DeleteManyToManyLinked {
@@ -128,27 +166,82 @@ where
 
         let mut conn = this.pool.acquire().await.unwrap();
 
-        let out = Operation::<S>::exec_operation(
-            Delete {
-                base: Arc::clone(&base),
-                wheres: ColumnEqual {
-                    col: base.id().scoped(),
-                    eq: input.id,
-                },
-                links,
+        if !owns(&base, &access, input.id, &mut conn).await {
+            return Err(DeleteOneError::NotFound { id: input.id });
+        }
+
+        let wheres = ManyFlat((
+            ColumnEqual {
+                col: base.id().scoped(),
+                eq: input.id,
             },
-            &mut conn,
-        )
-        .await;
+            access.filter(),
+        ));
+
+        let deleted = if soft_deleted {
+            let out = Operation::<S>::exec_operation(
+                SoftDeleteRecords {
+                    base: Arc::clone(&base),
+                    wheres,
+                },
+                &mut conn,
+            )
+            .await;
+
+            out.into_iter()
+                .find(|row| row.id == input.id)
+                .map(|row| LinkedOutput {
+                    id: row.id,
+                    attributes: row.attributes,
+                    links: vec![],
+                    computed: (),
+                })
+        } else {
+            let out = Operation::<S>::exec_operation(
+                Delete {
+                    base: Arc::clone(&base),
+                    wheres,
+                    links,
+                },
+                &mut conn,
+            )
+            .await;
+
+            out.into_iter().find(|row| row.id == input.id)
+        };
 
         drop(all_gaurds);
         drop(rel_guard);
         drop(cols);
 
-        let Some(deleted) = out.into_iter().find(|row| row.id == input.id) else {
-            return Err(DeleteOneError::NotFound);
+        let Some(deleted) = deleted else {
+            return Err(DeleteOneError::NotFound { id: input.id });
         };
 
+        if let Some(hooks) = this.hooks.get(base.collection_name.snake_case.as_ref()) {
+            hooks
+                .after_delete(&mut conn, deleted.id, &deleted.attributes)
+                .await
+                .map_err(|reason| DeleteOneError::Aborted {
+                    collection: Arc::clone(&base.collection_name.snake_case),
+                    reason,
+                })?;
+        }
+
+        changes::publish(
+            &this,
+            &base,
+            ChangeEvent {
+                kind: ChangeKind::Delete,
+                collection: Arc::clone(&base.collection_name.snake_case),
+                id: deleted.id,
+                attributes: deleted.attributes.clone(),
+            },
+            caller,
+            &mut conn,
+        )
+        .await;
+
         Ok(DeleteOneOutput {
             id: deleted.id,
             attributes: deleted.attributes,
@@ -4,49 +4,47 @@ use tokio::sync::{RwLock, RwLockReadGuard};
 use crate::{
     database_extention::DatabaseExt,
     expressions::ColumnEqual,
-    extentions::named_bind::NamedBind,
     fix_executor::ExecutorTrait,
     from_row::FromRowAlias,
-    gen_serde::{Serialize, json_serialize_side::JsonAsString},
     json_client::{
-        DynOptionalToMany, DynTimestamp, ToBind,
+        DynManyToMany, DynOptionalToMany, DynOptionalToManyInverse, DynTimestamp,
         client_interface::{
-            FetchManyError, FetchManyInput, FetchManyOutput, FirstItem, InsertOneInput,
+            Caller, FetchManyError, FetchManyInput, FetchManyOutput, InsertOneInput,
             InsertOneOutput, OrderBy, Pagination, SupportedInsertLink, SupportedLinkFetchMany,
         },
+        cursor_token,
         dynamic_collection::{CollectionToSerialize, DynamicCollection, VTable},
         op_fetch_many_trait_extension::JsonLinkFetchMany,
+        policy::{self, Mode},
         sqlx_executor::{FromTo, LinkInformations, SqlxExecutorData},
-        supported_filters::parse_supported_filter,
+        supported_filters::{FilterLinks, filter_link_targets, parse_supported_filter},
     },
     links::{
         DefaultRelationKey, relation_many_to_many::ManyToMany,
         relation_optional_to_many::OptionalToMany,
-        relation_optional_to_many_inverse::OptionalToManyInverse, timestamp::Timestamp,
+        relation_optional_to_many_inverse::OptionalToManyInverse, soft_delete::SoftDelete,
+        timestamp::Timestamp, versioned::Versioned,
     },
     operations::{
-        CollectionOutput, Operation,
-        fetch_many::{FetchMany, ManyOutput},
+        Operation,
+        cursor::Cursor,
+        fetch_many::{FetchMany, ManyOutput, Pagination as OpPagination},
+        link_filter::LinkFilter,
+    },
+    sqlx_query_builder::{
+        Expression, ManyExpressions, basic_expressions::ColumnIsNull,
+        trait_objects::BoxedExpression,
     },
-    sqlx_query_builder::trait_objects::BoxedExpression,
     sub_arc::ArcSubStr,
 };
-use std::collections::{BTreeMap, HashMap};
+use dynamic_order_by_mod::DynamicCursorValue;
+use std::collections::HashMap;
 use std::sync::Arc;
 
-fn cursor_attributes_from_order_by(
-    next: BTreeMap<String, Box<dyn Serialize<JsonAsString> + Send>>,
-) -> CollectionToSerialize {
-    CollectionToSerialize(
-        next.into_iter()
-            .map(|(key, value)| (Arc::<str>::from(key.as_str()), value))
-            .collect(),
-    )
-}
-
 pub fn fetch_many<S>(
     this: Arc<SqlxExecutorData<S>>,
     input: FetchManyInput,
+    caller: Caller,
 ) -> impl Future<Output = Result<FetchManyOutput, FetchManyError>> + 'static + Send + use<S>
 where
     S: DatabaseExt + ExecutorTrait + Send + Sync,
@@ -58,7 +56,28 @@ where
     ManyToMany<DefaultRelationKey, Arc<DynamicCollection<S>>, Arc<DynamicCollection<S>>>:
         JsonLinkFetchMany<S>,
     Timestamp<Arc<DynamicCollection<S>>>: JsonLinkFetchMany<S>,
+    SoftDelete<Arc<DynamicCollection<S>>>: JsonLinkFetchMany<S>,
+    Versioned<Arc<DynamicCollection<S>>>: JsonLinkFetchMany<S>,
+    DynOptionalToMany<S>: Send
+        + LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
+    DynOptionalToManyInverse<S>: Send
+        + LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
+    DynManyToMany<S>: Send
+        + LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
     i64: for<'q> Decode<'q, S> + for<'q> Encode<'q, S> + Type<S>,
+    f64: for<'q> Decode<'q, S> + for<'q> Encode<'q, S> + Type<S>,
     String: for<'q> Encode<'q, S> + Type<S>,
     for<'s> &'s str: ColumnIndex<S::Row>,
     //connection
@@ -67,28 +86,114 @@ where
         let cols_gaurd = this.collections.read().await;
         let col_gaurd = cols_gaurd
             .get(input.base.as_str())
-            .ok_or(FetchManyError::CollectionNotFound)?
+            .ok_or_else(|| FetchManyError::CollectionNotFound {
+                collection: input.base.detach(),
+            })?
             .read()
             .await;
         let rel_gaurd = this.link_info.read().await;
 
         let base = col_gaurd.clone();
 
+        let forbidden = |collection: &DynamicCollection<S>| FetchManyError::Forbidden {
+            collection: Arc::clone(&collection.collection_name.snake_case),
+        };
+        let access = policy::access(&this.policies, &base, Mode::Read, caller)
+            .ok_or_else(|| forbidden(&base))?;
+        // linked records are not filtered, links only reach collections readable as a whole
+        let readable = |collection: &DynamicCollection<S>| {
+            policy::access(&this.policies, collection, Mode::Read, caller)
+                .is_some_and(|access| access.is_all())
+        };
+
         let mut all_gaurds = vec![col_gaurd];
 
-        let wheres = parse_supported_filter(input.filters, &base)
-            .map_err(|_| FetchManyError::InvalidFilter)?;
+        let mut filter_links = FilterLinks::new(&rel_gaurd);
+        let mut targets = vec![];
+        // index of the filter or order by each target comes from
+        let mut owners = vec![];
+        for (index, filter) in input.filters.iter().enumerate() {
+            filter_link_targets(std::slice::from_ref(filter), &mut targets);
+            owners.resize(targets.len(), index);
+        }
+        let filter_targets = targets.len();
+        let input_order_by = match &input.pagination {
+            Pagination::Cursor { order_by, .. } | Pagination::Offset { order_by, .. } => order_by,
+        };
+        for (index, each) in input_order_by.iter().enumerate() {
+            dynamic_order_by_mod::order_by_link_targets(std::slice::from_ref(each), &mut targets);
+            owners.resize(targets.len(), index);
+        }
+
+        for (position, to) in targets.into_iter().enumerate() {
+            if filter_links.collections.contains_key(to.as_str()) {
+                continue;
+            }
+            if to.as_str() == base.collection_name.snake_case.as_ref() {
+                if !readable(&base) {
+                    return Err(forbidden(&base));
+                }
+                filter_links
+                    .collections
+                    .insert(Arc::clone(&base.collection_name.snake_case), Arc::clone(&base));
+                continue;
+            }
+            let to_collection_l = cols_gaurd
+                .get(to.as_str())
+                .ok_or(if position < filter_targets {
+                    FetchManyError::InvalidFilter {
+                        index: owners[position],
+                    }
+                } else {
+                    FetchManyError::InvalidOrderBy {
+                        index: owners[position],
+                    }
+                })?
+                .read()
+                .await;
+            if !readable(&to_collection_l) {
+                return Err(forbidden(&to_collection_l));
+            }
+            filter_links.collections.insert(
+                Arc::clone(&to_collection_l.collection_name.snake_case),
+                to_collection_l.clone(),
+            );
+            all_gaurds.push(to_collection_l);
+        }
+
+        let mut wheres = parse_supported_filter(input.filters, &base, &filter_links)
+            .map_err(|index| FetchManyError::InvalidFilter { index })?;
+        if let Some(filter) = access.filter() {
+            wheres.push(Box::new(filter));
+        }
+
+        let order_by = dynamic_order_by_mod::process_order_by(
+            &base,
+            input_order_by,
+            &filter_links,
+            &VTable::new_as::<i64>(),
+            &VTable::new_as::<f64>(),
+        )
+        .map_err(|index| FetchManyError::InvalidOrderBy { index })?;
+        drop(filter_links);
 
         let mut links = Vec::<Box<dyn JsonLinkFetchMany<S> + Send>>::new();
+        let soft_deleted = rel_gaurd
+            .soft_deleted
+            .contains(base.collection_name.snake_case.as_ref());
+        let mut show_deleted = false;
 
-        for each in input.links {
+        for (index, each) in input.links.into_iter().enumerate() {
             match each {
                 SupportedLinkFetchMany::OptionalToMany { to } => {
                     let to_collection_l = cols_gaurd
                         .get(to.as_str())
-                        .ok_or(FetchManyError::InvalidLink)?
+                        .ok_or(FetchManyError::InvalidLink { index })?
                         .read()
                         .await;
+                    if !readable(&to_collection_l) {
+                        return Err(forbidden(&to_collection_l));
+                    }
 
                     let to_collection = to_collection_l.clone();
                     all_gaurds.push(to_collection_l);
@@ -115,15 +220,18 @@ where
                             to: to_collection,
                         }));
                     } else {
-                        return Err(FetchManyError::InvalidLink);
+                        return Err(FetchManyError::InvalidLink { index });
                     }
                 }
                 SupportedLinkFetchMany::ManyToMany { to } => {
                     let to_collection_l = cols_gaurd
                         .get(to.as_str())
-                        .ok_or(FetchManyError::InvalidLink)?
+                        .ok_or(FetchManyError::InvalidLink { index })?
                         .read()
                         .await;
+                    if !readable(&to_collection_l) {
+                        return Err(forbidden(&to_collection_l));
+                    }
 
                     let to_collection = to_collection_l.clone();
                     all_gaurds.push(to_collection_l);
@@ -140,7 +248,7 @@ where
                             to: to_collection,
                         }));
                     } else {
-                        return Err(FetchManyError::InvalidLink);
+                        return Err(FetchManyError::InvalidLink { index });
                     }
                 }
                 SupportedLinkFetchMany::Timestamp => {
@@ -148,23 +256,84 @@ where
                         .timestamped
                         .contains(base.collection_name.snake_case.as_ref())
                     {
-                        return Err(FetchManyError::InvalidLink);
+                        return Err(FetchManyError::InvalidLink { index });
                     }
                     links.push(Box::new(Timestamp {
                         collection: Arc::clone(&base),
                     }));
                 }
+                SupportedLinkFetchMany::SoftDelete { include_deleted } => {
+                    if !soft_deleted {
+                        return Err(FetchManyError::InvalidLink { index });
+                    }
+                    show_deleted = include_deleted;
+                    links.push(Box::new(SoftDelete {
+                        collection: Arc::clone(&base),
+                        include_deleted,
+                    }));
+                }
+                SupportedLinkFetchMany::Version => {
+                    if !rel_gaurd
+                        .versioned
+                        .contains(base.collection_name.snake_case.as_ref())
+                    {
+                        return Err(FetchManyError::InvalidLink { index });
+                    }
+                    links.push(Box::new(Versioned {
+                        collection: Arc::clone(&base),
+                    }));
+                }
             }
         }
 
-        let limit = input.pagination.limit.clamp(0, 100);
-
-        let order_by = dynamic_order_by_mod::process_order_by(&base, &input.pagination.order_by)
-            .ok_or(FetchManyError::InvalidOrderBy)?;
+        // boxed links don't add their wheres
+        if soft_deleted && !show_deleted {
+            wheres.push(Box::new(ColumnIsNull {
+                col: ("deleted_at",),
+            }));
+        }
 
-        let first_item = process_first_item(&base, &input.pagination.first_item)
-            .map_err(|_| FetchManyError::InvalidFirstItem)?;
-        let first_item = first_item.map(|item| (item.id, item.attributes));
+        let base_name = Arc::clone(&base.collection_name.snake_case);
+
+        let pagination = match &input.pagination {
+            Pagination::Cursor { limit, cursor, .. } => {
+                let cursor = match cursor {
+                    None => None,
+                    Some(token) => {
+                        let payload = cursor_token::decode(&this.cursor_secret, token.as_str())
+                            .map_err(|_| FetchManyError::InvalidCursor)?;
+                        if payload.base.as_str() != base_name.as_ref() {
+                            return Err(FetchManyError::InvalidCursor);
+                        }
+                        let values =
+                            dynamic_order_by_mod::process_cursor_data(&order_by, &payload.data)
+                                .map_err(|_| FetchManyError::InvalidCursor)?;
+                        Some(match payload.before {
+                            true => Cursor::Before((payload.id, values)),
+                            false => Cursor::After((payload.id, values)),
+                        })
+                    }
+                };
+                OpPagination::Cursor {
+                    cursor,
+                    limit: *limit,
+                }
+            }
+            Pagination::Offset {
+                page,
+                page_size,
+                count_total,
+                ..
+            } => OpPagination::offset(*page, *page_size, *count_total).map_err(|_| {
+                FetchManyError::InvalidPageSize {
+                    max: this.max_page_size,
+                }
+            })?,
+        }
+        .at_most(this.max_page_size)
+        .map_err(|_| FetchManyError::InvalidPageSize {
+            max: this.max_page_size,
+        })?;
 
         let mut conn = this.pool.acquire().await.unwrap();
 
@@ -172,24 +341,29 @@ where
             base,
             wheres,
             links,
-            limit,
             cursor_order_by: order_by,
-            cursor_first_item: first_item,
+            pagination,
+            computed: (),
         };
 
         let out = Operation::<S>::exec_operation(s, &mut conn).await;
 
-        let next_item = out.next_item.map(|(id, next)| CollectionOutput {
-            id,
-            attributes: cursor_attributes_from_order_by(next),
-        });
+        let token = |before: bool, (id, values): (i64, Vec<DynamicCursorValue<S>>)| {
+            let data = values
+                .into_iter()
+                .map(|value| (value.key, value.json))
+                .collect::<Vec<_>>();
+            cursor_token::encode(&this.cursor_secret, before, &base_name, id, &data)
+        };
 
         drop(rel_gaurd);
         drop(all_gaurds);
 
         return Ok(ManyOutput {
             items: out.items,
-            next_item,
+            next_item: out.next_item.map(|item| token(false, item)),
+            prev_item: out.prev_item.map(|item| token(true, item)),
+            total: out.total,
         });
     }
 }
@@ -202,22 +376,62 @@ pub mod dynamic_order_by_mod {
 
     use crate::{
         database_extention::DatabaseExt,
-        extentions::common_expressions::Scoped,
-        from_row::{FromRowAlias, FromRowData, FromRowError, from_row_v2::RowAliased},
-        gen_serde::{Serialize, json_serialize_side::JsonAsString},
+        from_row::{FromRowAlias, FromRowData, FromRowError},
+        gen_serde::{SerializedJson, json_format_side::PartialDeserialize},
         json_client::{
-            client_interface::{Direction, OrderBy},
+            ToBind,
+            client_interface::{Direction, OrderBy, OrderByTarget},
             dynamic_collection::{DynamicCollection, VTable},
+            supported_filters::{
+                FilterLinks, ResolvedLinkFilter, ResolvedLinkSubquery, full_text_search,
+                link_target, resolve_link,
+            },
+        },
+        links::full_text_search::Relevance,
+        operations::{
+            cursor::CursorOrder, link_order_by::CountAll,
+            operations_expressions_crossover::ExpressionsForOperation,
+        },
+        sqlx_query_builder::{
+            Expression, OpExpression, StatementBuilder, basic_expressions::ScopedColumn,
         },
-        sqlx_query_builder::{Expression, OpExpression, StatementBuilder},
+        sub_arc::ArcSubStr,
     };
 
+    pub enum DynamicOrderByTarget<S>
+    where
+        S: DatabaseExt,
+    {
+        Col,
+        LinkCol(ResolvedLinkFilter<S>),
+        LinkCount(ResolvedLinkFilter<S>),
+        Relevance(Relevance),
+    }
+
+    impl<S> Clone for DynamicOrderByTarget<S>
+    where
+        S: DatabaseExt,
+    {
+        fn clone(&self) -> Self {
+            match self {
+                Self::Col => Self::Col,
+                Self::LinkCol(link) => Self::LinkCol(link.clone()),
+                Self::LinkCount(link) => Self::LinkCount(link.clone()),
+                Self::Relevance(relevance) => Self::Relevance(relevance.clone()),
+            }
+        }
+    }
+
     pub struct DynamicOrderBy<S>
     where
         S: DatabaseExt,
     {
         table: Arc<str>,
         col: Arc<str>,
+        /// name of the value in cursor tokens, `<to>.<col>` for linked
+        /// columns and `<to>.count` for link counts
+        key: Arc<str>,
+        target: DynamicOrderByTarget<S>,
         sqlx_ident: VTable<S>,
         is_optional: bool,
         direction: Direction,
@@ -231,6 +445,8 @@ pub mod dynamic_order_by_mod {
             Self {
                 table: Arc::clone(&self.table),
                 col: Arc::clone(&self.col),
+                key: Arc::clone(&self.key),
+                target: self.target.clone(),
                 sqlx_ident: self.sqlx_ident.clone(),
                 is_optional: self.is_optional,
                 direction: match self.direction {
@@ -241,40 +457,171 @@ pub mod dynamic_order_by_mod {
         }
     }
 
-    impl<S> Scoped for Vec<DynamicOrderBy<S>>
+    impl<S> DynamicOrderBy<S>
     where
         S: DatabaseExt,
+        ResolvedLinkFilter<S>: ResolvedLinkSubquery<S>,
+        String: for<'e> sqlx::Encode<'e, S> + sqlx::Type<S>,
     {
-        type Scoped = Vec<DynamicOrderBy<S>>;
-
-        fn scoped(&self) -> Self::Scoped {
-            self.clone()
+        /// the sorted value without direction, usable in select items and cursors
+        fn target_expression<'q>(&self, ctx: &mut StatementBuilder<'q, S>) {
+            let col = ScopedColumn {
+                table: ((Arc::clone(&self.table),),),
+                col: ((Arc::clone(&self.col),),),
+            };
+            match &self.target {
+                DynamicOrderByTarget::Col => Expression::expression(col, ctx),
+                DynamicOrderByTarget::LinkCol(link) => link.subquery(col, ctx),
+                DynamicOrderByTarget::LinkCount(link) => link.subquery(CountAll, ctx),
+                DynamicOrderByTarget::Relevance(relevance) => {
+                    Expression::expression(relevance.scoped(), ctx)
+                }
+            }
         }
     }
 
-    impl<S> OpExpression for DynamicOrderBy<S> where S: DatabaseExt + Database {}
+    /// select item of an order by, decoded by `FromRowAlias` under `<alias><key>`
+    pub struct DynamicOrderByAliased<S>
+    where
+        S: DatabaseExt,
+    {
+        order_by: DynamicOrderBy<S>,
+        alias: &'static str,
+        num: Option<usize>,
+    }
+
+    impl<S> OpExpression for DynamicOrderByAliased<S> where S: DatabaseExt {}
 
-    impl<'q, S> Expression<'q, S> for DynamicOrderBy<S>
+    impl<'q, S> Expression<'q, S> for DynamicOrderByAliased<S>
     where
         S: DatabaseExt,
+        ResolvedLinkFilter<S>: ResolvedLinkSubquery<S>,
+        String: for<'e> sqlx::Encode<'e, S> + sqlx::Type<S>,
     {
         fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
-            ctx.sanitize(self.table.as_ref());
-            ctx.syntax(".");
-            ctx.sanitize(self.col.as_ref());
-            ctx.syntax(" ");
-            match self.direction {
-                Direction::Asc => ctx.syntax("ASC"),
-                Direction::Desc => ctx.syntax("DESC"),
+            self.order_by.target_expression(ctx);
+            ctx.syntax(" AS ");
+            let num = self.num.map(|n| n.to_string()).unwrap_or_default();
+            ctx.sanitize(format!("{}{}{}", self.alias, num, self.order_by.key).as_str());
+        }
+    }
+
+    /// value of one order by in a cursor, kept both as JSON for the token
+    /// and as a bind for the next query
+    pub struct DynamicCursorValue<S>
+    where
+        S: DatabaseExt,
+    {
+        pub key: Arc<str>,
+        pub json: SerializedJson,
+        bind: Box<dyn ToBind<S> + Send>,
+    }
+
+    impl<S> Clone for DynamicCursorValue<S>
+    where
+        S: DatabaseExt,
+    {
+        fn clone(&self) -> Self {
+            Self {
+                key: Arc::clone(&self.key),
+                json: self.json.clone(),
+                bind: self.bind.clone(),
             }
         }
     }
 
+    impl<S> DynamicOrderBy<S>
+    where
+        S: DatabaseExt,
+    {
+        fn cursor_value(&self, json: SerializedJson) -> Result<DynamicCursorValue<S>, ()> {
+            let partial = PartialDeserialize(ArcSubStr::new(Arc::clone(&json.0), 0..json.0.len()));
+            Ok(DynamicCursorValue {
+                key: Arc::clone(&self.key),
+                bind: (self.sqlx_ident.to_bind)(partial)?,
+                json,
+            })
+        }
+    }
+
+    /// bare `DynamicOrderBy`, the key of the cursor comparison
+    pub struct DynamicCursorColumn<S>(DynamicOrderBy<S>)
+    where
+        S: DatabaseExt;
+
+    impl<S> OpExpression for DynamicCursorColumn<S> where S: DatabaseExt {}
+
+    impl<'q, S> Expression<'q, S> for DynamicCursorColumn<S>
+    where
+        S: DatabaseExt,
+        ResolvedLinkFilter<S>: ResolvedLinkSubquery<S>,
+        String: for<'e> sqlx::Encode<'e, S> + sqlx::Type<S>,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            self.0.target_expression(ctx);
+        }
+    }
+
+    impl<S> ExpressionsForOperation for Vec<DynamicOrderBy<S>>
+    where
+        S: DatabaseExt,
+    {
+        type Identifier = Vec<Arc<str>>;
+        fn identifier(&self) -> Self::Identifier {
+            self.iter().map(|e| Arc::clone(&e.key)).collect()
+        }
+        type Scoped = Vec<DynamicCursorColumn<S>>;
+        fn scoped(&self) -> Self::Scoped {
+            self.iter().map(|e| DynamicCursorColumn(e.clone())).collect()
+        }
+        type ScopedAliased = Vec<DynamicOrderByAliased<S>>;
+        fn scoped_aliased(&self, alias: &'static str) -> Self::ScopedAliased {
+            self.iter()
+                .map(|e| DynamicOrderByAliased {
+                    order_by: e.clone(),
+                    alias,
+                    num: None,
+                })
+                .collect()
+        }
+        type NumScopedAliased = Vec<DynamicOrderByAliased<S>>;
+        fn num_scoped_aliased(&self, num: usize, alias: &'static str) -> Self::NumScopedAliased {
+            self.iter()
+                .map(|e| DynamicOrderByAliased {
+                    order_by: e.clone(),
+                    alias,
+                    num: Some(num),
+                })
+                .collect()
+        }
+    }
+
+    impl<'q, S> CursorOrder<'q, S> for Vec<DynamicOrderBy<S>>
+    where
+        S: DatabaseExt,
+        ResolvedLinkFilter<S>: ResolvedLinkSubquery<S>,
+        Box<dyn ToBind<S> + Send>: sqlx::Encode<'q, S> + sqlx::Type<S>,
+        String: for<'e> sqlx::Encode<'e, S> + sqlx::Type<S>,
+    {
+        fn keys_len(&self) -> usize {
+            self.len()
+        }
+        fn descending(&self, index: usize) -> bool {
+            matches!(self[index].direction, Direction::Desc)
+        }
+        fn key(&self, index: usize, ctx: &mut StatementBuilder<'q, S>) {
+            self[index].target_expression(ctx)
+        }
+        fn value(&self, value: &Self::RData, index: usize, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.bind(value[index].bind.clone())
+        }
+    }
+
     impl<S> FromRowData for DynamicOrderBy<S>
     where
         S: DatabaseExt,
     {
-        type RData = (String, Box<dyn Serialize<JsonAsString> + Send>);
+        type RData = DynamicCursorValue<S>;
     }
 
     impl<'r, S> FromRowAlias<'r, S::Row> for DynamicOrderBy<S>
@@ -283,10 +630,11 @@ pub mod dynamic_order_by_mod {
         for<'a> &'a str: ColumnIndex<S::Row>,
     {
         fn no_alias(&self, row: &'r S::Row) -> Result<Self::RData, FromRowError> {
-            let value = (self.sqlx_ident.decode_from_row)(self.is_optional, self.col.as_ref(), row)
+            let value = (self.sqlx_ident.decode_from_row)(self.is_optional, self.key.as_ref(), row)
                 .map_err(FromRowError::ColumnNotFound)?;
 
-            Ok((self.col.to_string(), value))
+            self.cursor_value(SerializedJson::new(&value))
+                .map_err(|_| FromRowError::ColumnNotFound(self.key.to_string()))
         }
 
         fn pre_alias(
@@ -296,12 +644,13 @@ pub mod dynamic_order_by_mod {
         where
             S::Row: Row,
         {
-            let col_name = format!("{}{}", row.alias, self.col.as_ref());
+            let col_name = format!("{}{}", row.alias, self.key.as_ref());
             let value =
                 (self.sqlx_ident.decode_from_row)(self.is_optional, col_name.as_str(), row.row)
                     .map_err(FromRowError::ColumnNotFound)?;
 
-            Ok((self.col.to_string(), value))
+            self.cursor_value(SerializedJson::new(&value))
+                .map_err(|_| FromRowError::ColumnNotFound(self.key.to_string()))
         }
 
         fn post_alias(
@@ -325,13 +674,14 @@ pub mod dynamic_order_by_mod {
                 "{}{}{}",
                 row.str_alias,
                 row.num_alias.map(|n| n.to_string()).unwrap_or_default(),
-                self.col.as_ref()
+                self.key.as_ref()
             );
             let value =
                 (self.sqlx_ident.decode_from_row)(self.is_optional, col_name.as_str(), row.row)
                     .map_err(FromRowError::ColumnNotFound)?;
 
-            Ok((self.col.to_string(), value))
+            self.cursor_value(SerializedJson::new(&value))
+                .map_err(|_| FromRowError::ColumnNotFound(self.key.to_string()))
         }
     }
 
@@ -339,7 +689,7 @@ pub mod dynamic_order_by_mod {
     where
         S: DatabaseExt,
     {
-        type RData = BTreeMap<String, Box<dyn Serialize<JsonAsString> + Send>>;
+        type RData = Vec<DynamicCursorValue<S>>;
     }
 
     impl<'r, S> FromRowAlias<'r, S::Row> for Vec<DynamicOrderBy<S>>
@@ -348,12 +698,7 @@ pub mod dynamic_order_by_mod {
         for<'a> &'a str: ColumnIndex<S::Row>,
     {
         fn no_alias(&self, row: &'r S::Row) -> Result<Self::RData, FromRowError> {
-            let mut ret = BTreeMap::new();
-            for each in self {
-                let (col, value) = each.no_alias(row)?;
-                ret.insert(col, value);
-            }
-            Ok(ret)
+            self.iter().map(|each| each.no_alias(row)).collect()
         }
 
         fn pre_alias(
@@ -363,12 +708,7 @@ pub mod dynamic_order_by_mod {
         where
             S::Row: Row,
         {
-            let mut ret = BTreeMap::new();
-            for each in self {
-                let (col, value) = each.pre_alias(row.clone())?;
-                ret.insert(col, value);
-            }
-            Ok(ret)
+            self.iter().map(|each| each.pre_alias(row.clone())).collect()
         }
 
         fn post_alias(
@@ -388,77 +728,144 @@ pub mod dynamic_order_by_mod {
         where
             S::Row: Row,
         {
-            let mut ret = BTreeMap::new();
-            for each in self {
-                let (col, value) = each.two_alias(row.clone())?;
-                ret.insert(col, value);
+            self.iter().map(|each| each.two_alias(row.clone())).collect()
+        }
+    }
+
+    /// names of all collections `order_by` reach through links
+    pub fn order_by_link_targets(order_by: &[OrderBy], out: &mut Vec<ArcSubStr>) {
+        for each in order_by {
+            match &each.target {
+                OrderByTarget::Col { .. } | OrderByTarget::Relevance { .. } => {}
+                OrderByTarget::LinkCol { link, .. } | OrderByTarget::LinkCount { link } => {
+                    out.push(link_target(link).clone())
+                }
             }
-            Ok(ret)
         }
     }
 
+    /// `Err` is the index of the first order by that can't be applied
     pub fn process_order_by<S>(
-        base: &DynamicCollection<S>,
+        base: &Arc<DynamicCollection<S>>,
         order_by: &[OrderBy],
-    ) -> Option<Vec<DynamicOrderBy<S>>>
+        links: &FilterLinks<'_, S>,
+        count_vtable: &VTable<S>,
+        relevance_vtable: &VTable<S>,
+    ) -> Result<Vec<DynamicOrderBy<S>>, usize>
     where
         S: DatabaseExt,
     {
         let mut ret = vec![];
 
-        for each in order_by {
-            let found = base
-                .fields
-                .iter()
-                .find(|field| field.name.as_str() == each.col.as_str())?;
-
-            ret.push(DynamicOrderBy {
-                table: Arc::clone(&base.collection_name.snake_case),
-                col: Arc::clone(&found.name.snake_case),
-                sqlx_ident: found.type_info.clone(),
-                is_optional: found.is_optional,
-                direction: match each.direction {
-                    Direction::Asc => Direction::Asc,
-                    Direction::Desc => Direction::Desc,
-                },
-            });
+        for (index, each) in order_by.iter().enumerate() {
+            let direction = match each.direction {
+                Direction::Asc => Direction::Asc,
+                Direction::Desc => Direction::Desc,
+            };
+
+            let order_by = match &each.target {
+                OrderByTarget::Col { col } => {
+                    let found = base
+                        .fields
+                        .iter()
+                        .find(|field| field.name.as_str() == col.as_str())
+                        .filter(|field| field.is_ordered())
+                        .ok_or(index)?;
+
+                    DynamicOrderBy {
+                        table: Arc::clone(&base.collection_name.snake_case),
+                        col: Arc::clone(&found.name.snake_case),
+                        key: Arc::clone(&found.name.snake_case),
+                        target: DynamicOrderByTarget::Col,
+                        sqlx_ident: found.type_info.clone(),
+                        is_optional: found.is_optional,
+                        direction,
+                    }
+                }
+                OrderByTarget::LinkCol { link, col } => {
+                    let (link, to) = resolve_link(link, base, links).map_err(|_| index)?;
+                    if let ResolvedLinkFilter::ManyToMany(_) = link {
+                        // more than one linked record, nothing to sort on
+                        return Err(index);
+                    }
+                    let found = to
+                        .fields
+                        .iter()
+                        .find(|field| field.name.as_str() == col.as_str())
+                        .filter(|field| field.is_ordered())
+                        .ok_or(index)?;
+
+                    DynamicOrderBy {
+                        table: Arc::clone(&to.collection_name.snake_case),
+                        col: Arc::clone(&found.name.snake_case),
+                        key: Arc::from(format!(
+                            "{}.{}",
+                            to.collection_name.snake_case, found.name.snake_case
+                        )),
+                        target: DynamicOrderByTarget::LinkCol(link),
+                        sqlx_ident: found.type_info.clone(),
+                        // no linked record sorts as null
+                        is_optional: true,
+                        direction,
+                    }
+                }
+                OrderByTarget::LinkCount { link } => {
+                    let (link, to) = resolve_link(link, base, links).map_err(|_| index)?;
+
+                    DynamicOrderBy {
+                        table: Arc::clone(&to.collection_name.snake_case),
+                        col: Arc::from("count"),
+                        key: Arc::from(format!("{}.count", to.collection_name.snake_case)),
+                        target: DynamicOrderByTarget::LinkCount(link),
+                        sqlx_ident: count_vtable.clone(),
+                        is_optional: false,
+                        direction,
+                    }
+                }
+                OrderByTarget::Relevance { query } => {
+                    let search = full_text_search(base, links).map_err(|_| index)?;
+
+                    DynamicOrderBy {
+                        table: Arc::clone(&base.collection_name.snake_case),
+                        col: Arc::from("relevance"),
+                        key: Arc::from("relevance"),
+                        target: DynamicOrderByTarget::Relevance(search.relevance(query.as_str())),
+                        sqlx_ident: relevance_vtable.clone(),
+                        // records not matching the query have no rank
+                        is_optional: true,
+                        direction,
+                    }
+                }
+            };
+
+            ret.push(order_by);
         }
 
-        Some(ret)
+        Ok(ret)
     }
-}
 
-fn process_first_item<S>(
-    base: &DynamicCollection<S>,
-    pagination: &Option<FirstItem>,
-) -> Result<
-    Option<CollectionOutput<i64, Vec<NamedBind<Arc<str>, Arc<str>, Box<dyn ToBind<S> + Send>>>>>,
-    (),
->
-where
-    S: DatabaseExt,
-{
-    if let Some(first_item) = pagination {
-        let mut attributes = vec![];
-
-        for (key, value) in first_item.data.iter() {
-            let found = base.fields.iter().find(|f| f.name.as_str() == key.as_str());
-            let found = found.ok_or(())?;
-            let bind = (found.type_info.to_bind)(value.clone()).map_err(|_| ())?;
-
-            attributes.push(NamedBind {
-                table: Arc::clone(&base.collection_name.snake_case),
-                name: key.detach(),
-                value: bind,
-            });
-        }
-
-        Ok(Some(CollectionOutput {
-            id: first_item.id,
-            attributes,
-        }))
-    } else {
-        Ok(None)
+    /// `data` of a cursor token, one entry per order by
+    pub fn process_cursor_data<S>(
+        order_by: &[DynamicOrderBy<S>],
+        data: &BTreeMap<ArcSubStr, PartialDeserialize>,
+    ) -> Result<Vec<DynamicCursorValue<S>>, ()>
+    where
+        S: DatabaseExt,
+    {
+        if data.len() != order_by.len() {
+            return Err(());
+        }
+
+        order_by
+            .iter()
+            .map(|each| {
+                let (_, value) = data
+                    .iter()
+                    .find(|(key, _)| key.as_str() == each.key.as_ref())
+                    .ok_or(())?;
+                each.cursor_value(SerializedJson(value.0.detach()))
+            })
+            .collect()
     }
 }
 
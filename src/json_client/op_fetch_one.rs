@@ -10,20 +10,28 @@ use crate::{
     fix_executor::ExecutorTrait,
     from_row::FromRowAlias,
     json_client::{
-        DynManyToMany, DynOptionalToMany, DynOptionalToManyInverse, DynTimestamp,
-        client_interface::{FetchOneError, FetchOneInput, FetchOneOutput, SupportedLinkFetchOne},
+        DynManyToMany, DynOptionalToMany, DynOptionalToManyInverse, DynSoftDelete, DynTimestamp,
+        DynVersioned,
+        client_interface::{
+            Caller, FetchOneError, FetchOneInput, FetchOneOutput, SupportedLinkFetchOne,
+        },
         dynamic_collection::{CollectionToSerialize, DynamicCollection},
         op_fetch_one_trait_extension::JsonLinkFetchOne,
+        policy::{self, Mode},
         sqlx_executor::{FromTo, SqlxExecutorData},
-        supported_filters::parse_supported_filter,
+        supported_filters::{FilterLinks, filter_link_targets, parse_supported_filter},
     },
     links::{
         DefaultRelationKey, relation_many_to_many::ManyToMany,
         relation_optional_to_many::OptionalToMany,
-        relation_optional_to_many_inverse::OptionalToManyInverse, timestamp::Timestamp,
+        relation_optional_to_many_inverse::OptionalToManyInverse, soft_delete::SoftDelete,
+        timestamp::Timestamp, versioned::Versioned,
+    },
+    operations::{Operation, fetch_one::FetchOne, link_filter::LinkFilter},
+    sqlx_query_builder::{
+        Expression, ManyExpressions,
+        basic_expressions::{ColumnIsNull, ManyFlat},
     },
-    operations::{Operation, fetch_one::FetchOne},
-    sqlx_query_builder::basic_expressions::ManyFlat,
 };
 
 type DynCollection<S> = Arc<DynamicCollection<S>>;
@@ -31,6 +39,7 @@ type DynCollection<S> = Arc<DynamicCollection<S>>;
 pub fn fetch_one<S>(
     this: Arc<SqlxExecutorData<S>>,
     input: FetchOneInput,
+    caller: Caller,
 ) -> impl Future<Output = Result<FetchOneOutput, FetchOneError>> + 'static + Send + use<S>
 where
     S: DatabaseExt + ExecutorTrait + Send + Sync,
@@ -39,6 +48,26 @@ where
     DynOptionalToManyInverse<S>: JsonLinkFetchOne<S>,
     DynManyToMany<S>: JsonLinkFetchOne<S>,
     DynTimestamp<S>: JsonLinkFetchOne<S>,
+    DynSoftDelete<S>: JsonLinkFetchOne<S>,
+    DynVersioned<S>: JsonLinkFetchOne<S>,
+    DynOptionalToMany<S>: Send
+        + LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
+    DynOptionalToManyInverse<S>: Send
+        + LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
+    DynManyToMany<S>: Send
+        + LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
     i64: sqlx::Type<S> + for<'q> sqlx::Decode<'q, S> + for<'q> sqlx::Encode<'q, S>,
     String: for<'q> sqlx::Encode<'q, S> + sqlx::Type<S>,
     for<'a> &'a str: ColumnIndex<S::Row>,
@@ -47,26 +76,90 @@ where
         let cols = this.collections.read().await;
         let base_guard = cols
             .get(input.base.as_str())
-            .ok_or(FetchOneError::CollectionNotFound)?
+            .ok_or_else(|| FetchOneError::CollectionNotFound {
+                collection: input.base.detach(),
+            })?
             .read()
             .await;
         let base = base_guard.clone();
+
+        let forbidden = |collection: &DynamicCollection<S>| FetchOneError::Forbidden {
+            collection: Arc::clone(&collection.collection_name.snake_case),
+        };
+        let access = policy::access(&this.policies, &base, Mode::Read, caller)
+            .ok_or_else(|| forbidden(&base))?;
+        let readable = |collection: &DynamicCollection<S>| {
+            policy::access(&this.policies, collection, Mode::Read, caller)
+                .is_some_and(|access| access.is_all())
+        };
+
         let rel_guard = this.link_info.read().await;
         let mut all_guards = vec![base_guard];
 
-        let filter_exprs = parse_supported_filter(input.filters, &base)
-            .map_err(|_| FetchOneError::InvalidFilter)?;
+        let mut filter_links = FilterLinks::new(&rel_guard);
+        let mut targets = vec![];
+        // index of the filter each target comes from
+        let mut owners = vec![];
+        for (index, filter) in input.filters.iter().enumerate() {
+            filter_link_targets(std::slice::from_ref(filter), &mut targets);
+            owners.resize(targets.len(), index);
+        }
+
+        for (position, to) in targets.into_iter().enumerate() {
+            if filter_links.collections.contains_key(to.as_str()) {
+                continue;
+            }
+            if to.as_str() == base.collection_name.snake_case.as_ref() {
+                if !readable(&base) {
+                    return Err(forbidden(&base));
+                }
+                filter_links.collections.insert(
+                    Arc::clone(&base.collection_name.snake_case),
+                    Arc::clone(&base),
+                );
+                continue;
+            }
+            let to_guard = cols
+                .get(to.as_str())
+                .ok_or(FetchOneError::InvalidFilter {
+                    index: owners[position],
+                })?
+                .read()
+                .await;
+            if !readable(&to_guard) {
+                return Err(forbidden(&to_guard));
+            }
+            filter_links.collections.insert(
+                Arc::clone(&to_guard.collection_name.snake_case),
+                to_guard.clone(),
+            );
+            all_guards.push(to_guard);
+        }
+
+        let mut filter_exprs = parse_supported_filter(input.filters, &base, &filter_links)
+            .map_err(|index| FetchOneError::InvalidFilter { index })?;
+        if let Some(filter) = access.filter() {
+            filter_exprs.push(Box::new(filter));
+        }
+        drop(filter_links);
 
         let mut links = Vec::<Box<dyn JsonLinkFetchOne<S> + Send>>::new();
+        let soft_deleted = rel_guard
+            .soft_deleted
+            .contains(base.collection_name.snake_case.as_ref());
+        let mut show_deleted = false;
 
-        for each in input.links {
+        for (index, each) in input.links.into_iter().enumerate() {
             match each {
                 SupportedLinkFetchOne::OptionalToMany { to } => {
                     let to_guard = cols
                         .get(to.as_str())
-                        .ok_or(FetchOneError::InvalidLink)?
+                        .ok_or(FetchOneError::InvalidLink { index })?
                         .read()
                         .await;
+                    if !readable(&to_guard) {
+                        return Err(forbidden(&to_guard));
+                    }
                     let to = to_guard.clone();
                     all_guards.push(to_guard);
 
@@ -92,15 +185,18 @@ where
                             to,
                         }));
                     } else {
-                        return Err(FetchOneError::InvalidLink);
+                        return Err(FetchOneError::InvalidLink { index });
                     }
                 }
                 SupportedLinkFetchOne::ManyToMany { to } => {
                     let to_guard = cols
                         .get(to.as_str())
-                        .ok_or(FetchOneError::InvalidLink)?
+                        .ok_or(FetchOneError::InvalidLink { index })?
                         .read()
                         .await;
+                    if !readable(&to_guard) {
+                        return Err(forbidden(&to_guard));
+                    }
                     let to = to_guard.clone();
                     all_guards.push(to_guard);
 
@@ -116,7 +212,7 @@ where
                             to,
                         }));
                     } else {
-                        return Err(FetchOneError::InvalidLink);
+                        return Err(FetchOneError::InvalidLink { index });
                     }
                 }
                 SupportedLinkFetchOne::Timestamp => {
@@ -124,15 +220,43 @@ where
                         .timestamped
                         .contains(base.collection_name.snake_case.as_ref())
                     {
-                        return Err(FetchOneError::InvalidLink);
+                        return Err(FetchOneError::InvalidLink { index });
                     }
                     links.push(Box::new(Timestamp {
                         collection: Arc::clone(&base),
                     }));
                 }
+                SupportedLinkFetchOne::SoftDelete { include_deleted } => {
+                    if !soft_deleted {
+                        return Err(FetchOneError::InvalidLink { index });
+                    }
+                    show_deleted = include_deleted;
+                    links.push(Box::new(SoftDelete {
+                        collection: Arc::clone(&base),
+                        include_deleted,
+                    }));
+                }
+                SupportedLinkFetchOne::Version => {
+                    if !rel_guard
+                        .versioned
+                        .contains(base.collection_name.snake_case.as_ref())
+                    {
+                        return Err(FetchOneError::InvalidLink { index });
+                    }
+                    links.push(Box::new(Versioned {
+                        collection: Arc::clone(&base),
+                    }));
+                }
             }
         }
 
+        // boxed links don't add their wheres
+        if soft_deleted && !show_deleted {
+            filter_exprs.push(Box::new(ColumnIsNull {
+                col: ("deleted_at",),
+            }));
+        }
+
         let wheres = ManyFlat((
             ColumnEqual {
                 col: base.id().scoped(),
@@ -157,6 +281,6 @@ where
         drop(all_guards);
         drop(cols);
 
-        out.ok_or(FetchOneError::NotFound)
+        out.ok_or(FetchOneError::NotFound { id: input.id })
     }
 }
@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use crate::{
+    database_extention::DatabaseExt,
+    fix_executor::ExecutorTrait,
+    json_client::{
+        client_interface::{Caller, HistoryError, HistoryInput, HistoryOutput},
+        policy::{self, Mode},
+        sqlx_executor::SqlxExecutorData,
+    },
+    links::audit_log::operations::FetchHistory,
+    operations::Operation,
+};
+
+pub fn history<S>(
+    this: Arc<SqlxExecutorData<S>>,
+    input: HistoryInput,
+    caller: Caller,
+) -> impl Future<Output = Result<HistoryOutput, HistoryError>> + 'static + Send + use<S>
+where
+    i64: sqlx::Type<S> + for<'q> sqlx::Decode<'q, S> + for<'q> sqlx::Encode<'q, S>,
+    String: sqlx::Type<S> + for<'q> sqlx::Decode<'q, S>,
+    Option<String>: sqlx::Type<S> + for<'q> sqlx::Decode<'q, S>,
+    for<'a> &'a str: sqlx::ColumnIndex<<S as sqlx::Database>::Row>,
+    S: sqlx::Database + DatabaseExt + ExecutorTrait + Send + Sync + 'static,
+{
+    async move {
+        let cols = this.collections.read().await;
+        let base_gaurd = cols
+            .get(input.base.as_str())
+            .ok_or_else(|| HistoryError::CollectionNotFound {
+                collection: input.base.detach(),
+            })?
+            .read()
+            .await;
+        let base = base_gaurd.clone();
+        drop(base_gaurd);
+        drop(cols);
+
+        // past versions can't be filtered by the rules of the current ones
+        if !policy::access(&this.policies, &base, Mode::Read, caller)
+            .is_some_and(|access| access.is_all())
+        {
+            return Err(HistoryError::Forbidden {
+                collection: Arc::clone(&base.collection_name.snake_case),
+            });
+        }
+
+        if !this
+            .link_info
+            .read()
+            .await
+            .audited
+            .contains(base.collection_name.snake_case.as_ref())
+        {
+            return Err(HistoryError::NotAudited {
+                collection: Arc::clone(&base.collection_name.snake_case),
+            });
+        }
+
+        let mut conn = this.pool.acquire().await.unwrap();
+
+        Ok(Operation::<S>::exec_operation(
+            FetchHistory {
+                base,
+                record_id: input.id,
+            },
+            &mut conn,
+        )
+        .await)
+    }
+}
@@ -12,11 +12,16 @@ use crate::{
     },
     json_client::{
         DynManyToMany, DynOptionalToMany,
-        client_interface::{InsertManyError, InsertManyInput, InsertManyOutput, InsertOneError},
+        client_interface::{
+            Caller, InsertManyError, InsertManyInput, InsertManyOutput, InsertOneError,
+            InvalidData, InvalidDataRule,
+        },
         dynamic_collection::{CollectionToSerialize, DynamicCollection, DynamicInsertInput},
         op_insert_one::exec_insert_one,
         op_insert_one_trait_extension::{JsonInsertOneLink, JsonInsertOneToConsume},
+        policy::{self, Mode},
         sqlx_executor::SqlxExecutorData,
+        validation::{check_unique, validate},
     },
     links::update_links::{SetId, SetNew},
     operations::insert_one::{InsertLinkConsumeData, InsertOneLink},
@@ -27,6 +32,7 @@ type DynCollection<S> = Arc<DynamicCollection<S>>;
 pub fn insert_many<S>(
     this: Arc<SqlxExecutorData<S>>,
     input: InsertManyInput,
+    caller: Caller,
 ) -> impl Future<Output = Result<InsertManyOutput, InsertManyError>> + 'static + Send + use<S>
 where
     i64: sqlx::Type<S> + for<'q> sqlx::Decode<'q, S> + for<'q> sqlx::Encode<'q, S>,
@@ -51,38 +57,88 @@ where
 {
     async move {
         if input.items.is_empty() {
-            return Err(InsertManyError::InvalidData);
+            return Err(InsertManyError::InvalidData(InvalidData::of_data(
+                InvalidDataRule::Empty,
+            )));
         }
 
         let cols = this.collections.read().await;
         let base_gaurd = cols
             .get(input.base.as_str())
-            .ok_or(InsertManyError::CollectionNotFound)?
+            .ok_or_else(|| InsertManyError::CollectionNotFound {
+                collection: input.base.detach(),
+            })?
             .read()
             .await;
         let base = base_gaurd.clone();
+        if base.view.is_some() {
+            return Err(InsertManyError::ReadOnly {
+                collection: Arc::clone(&base.collection_name.snake_case),
+            });
+        }
+
+        let forbidden = || InsertOneError::Forbidden {
+            collection: Arc::clone(&base.collection_name.snake_case),
+        };
+        let access =
+            policy::access(&this.policies, &base, Mode::Write, caller).ok_or_else(|| {
+                InsertManyError::Forbidden {
+                    collection: Arc::clone(&base.collection_name.snake_case),
+                }
+            })?;
+
+        // every item is validated before the first one is inserted
+        let uniques = input
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let unique =
+                    validate(&base, &item.data, true).map_err(|invalid| InsertManyError::Item {
+                        index,
+                        error: InsertOneError::InvalidData(invalid),
+                    })?;
+                if !access.allows(&item.data, true) {
+                    return Err(InsertManyError::Item {
+                        index,
+                        error: forbidden(),
+                    });
+                }
+                Ok(unique)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
         let mut conn = this.pool.acquire().await.unwrap();
         let mut items = Vec::with_capacity(input.items.len());
 
-        for item in input.items {
+        for (index, (item, unique)) in input.items.into_iter().zip(uniques).enumerate() {
+            let item_error = |error| InsertManyError::Item { index, error };
             let data: DynamicInsertInput<S> = deserialize(
                 Arc::from(item.data.0.as_str()),
                 Arc::clone(&base),
                 JsonFormat,
             )
-            .map_err(|_| InsertManyError::InvalidData)?;
+            .map_err(|_| {
+                item_error(InsertOneError::InvalidData(InvalidData::of_data(
+                    InvalidDataRule::Malformed,
+                )))
+            })?;
 
-            let out = exec_insert_one(&this, Arc::clone(&base), data, item.links, &mut conn)
+            // earlier items of the batch are already inserted and taken into account
+            check_unique(&base, unique, None, &mut conn)
                 .await
-                .map_err(|err| match err {
-                    InsertOneError::CollectionNotFound => InsertManyError::CollectionNotFound,
-                    InsertOneError::InvalidData => InsertManyError::InvalidData,
-                    InsertOneError::InvalidLink => InsertManyError::InvalidLink,
-                    InsertOneError::LinkNotSetUpForThisBase => {
-                        InsertManyError::LinkNotSetUpForThisBase
-                    }
-                })?;
+                .map_err(|invalid| item_error(InsertOneError::InvalidData(invalid)))?;
+
+            let out = exec_insert_one(
+                &this,
+                Arc::clone(&base),
+                data,
+                item.links,
+                caller,
+                &mut conn,
+            )
+            .await
+            .map_err(item_error)?;
 
             items.push(out);
         }
@@ -13,13 +13,17 @@ use crate::{
     },
     json_client::{
         DynManyToMany, DynOptionalToMany,
+        changes::{self, ChangeEvent, ChangeKind},
         client_interface::{
-            InsertManyError, InsertManyInput, InsertManyItem, InsertManyOutput, InsertOneError,
-            InsertOneInput, InsertOneOutput, SupportedInsertLink,
+            Caller, InsertManyError, InsertManyInput, InsertManyItem, InsertManyOutput,
+            InsertOneError, InsertOneInput, InsertOneOutput, InvalidData, InvalidDataRule,
+            SupportedInsertLink,
         },
         dynamic_collection::{CollectionToSerialize, DynamicCollection, DynamicInsertInput},
         op_insert_one_trait_extension::{JsonInsertOneLink, JsonInsertOneToConsume},
+        policy::{self, Mode},
         sqlx_executor::{FromTo, SqlxExecutorData},
+        validation::{check_unique, check_violation, validate},
     },
     links::{
         DefaultRelationKey,
@@ -38,8 +42,9 @@ type DynCollection<S> = Arc<DynamicCollection<S>>;
 pub(super) async fn exec_insert_one<S>(
     this: &SqlxExecutorData<S>,
     base: Arc<DynamicCollection<S>>,
-    data: DynamicInsertInput<S>,
+    mut data: DynamicInsertInput<S>,
     links_input: Vec<SupportedInsertLink>,
+    caller: Caller,
     conn: &mut S::Connection,
 ) -> Result<InsertOneOutput, InsertOneError>
 where
@@ -67,15 +72,23 @@ where
     let rel_guard = this.link_info.read().await;
     let mut all_gaurds = Vec::new();
     let mut links = Vec::<JsonInsertOneToConsume<S>>::new();
+    let forbidden = |collection: &DynamicCollection<S>| InsertOneError::Forbidden {
+        collection: Arc::clone(&collection.collection_name.snake_case),
+    };
 
-    for link in links_input {
+    for (index, link) in links_input.into_iter().enumerate() {
         match link {
             SupportedInsertLink::SetId { to, id } => {
                 let to_gaurd = cols
                     .get(to.as_str())
-                    .ok_or(InsertOneError::InvalidLink)?
+                    .ok_or(InsertOneError::InvalidLink { index })?
                     .read()
                     .await;
+                if !policy::access(&this.policies, &to_gaurd, Mode::Read, caller)
+                    .is_some_and(|access| access.is_all())
+                {
+                    return Err(forbidden(&to_gaurd));
+                }
                 let to = to_gaurd.clone();
                 all_gaurds.push(to_gaurd);
 
@@ -103,20 +116,32 @@ where
                         id,
                     }))
                 } else {
-                    return Err(InsertOneError::InvalidLink);
+                    return Err(InsertOneError::InvalidLink { index });
                 }
             }
             SupportedInsertLink::SetNew { to, value } => {
                 let to_gaurd = cols
                     .get(to.as_str())
-                    .ok_or(InsertOneError::InvalidLink)?
+                    .ok_or(InsertOneError::InvalidLink { index })?
                     .read()
                     .await;
                 let to = to_gaurd.clone();
                 all_gaurds.push(to_gaurd);
+                let access = policy::access(&this.policies, &to, Mode::Write, caller)
+                    .ok_or_else(|| forbidden(&to))?;
+                let invalid_link_data =
+                    |invalid| InsertOneError::InvalidLinkData { index, invalid };
+                let unique = validate(&to, &value, true).map_err(invalid_link_data)?;
+                if !access.allows(&value, true) {
+                    return Err(forbidden(&to));
+                }
                 let link_data: DynamicInsertInput<S> =
-                    deserialize(Arc::from(value.0.as_str()), Arc::clone(&to), JsonFormat)
-                        .map_err(|_| InsertOneError::InvalidData)?;
+                    deserialize(Arc::from(value.0.as_str()), Arc::clone(&to), JsonFormat).map_err(
+                        |_| invalid_link_data(InvalidData::of_data(InvalidDataRule::Malformed)),
+                    )?;
+                check_unique(&to, unique, None, &mut *conn)
+                    .await
+                    .map_err(invalid_link_data)?;
                 links.push(JsonInsertOneToConsume::new(SetNew {
                     relation: OptionalToMany {
                         fk_unique_id: DefaultRelationKey,
@@ -129,22 +154,60 @@ where
         }
     }
 
+    let collection = Arc::clone(&base.collection_name.snake_case);
+    let hooks = this.hooks.get(collection.as_ref());
+    let aborted = |reason| InsertOneError::Aborted {
+        collection: Arc::clone(&collection),
+        reason,
+    };
+    if let Some(hooks) = hooks {
+        hooks
+            .before_insert(&mut *conn, &mut data)
+            .await
+            .map_err(aborted)?;
+    }
+
     let out = Operation::<S>::exec_operation(
         InsertOne {
             id: AutoGenerate,
-            base,
+            base: Arc::clone(&base),
             data,
             links,
         },
         conn,
     )
     .await
-    .expect("bug: insert one failed");
+    // a `FieldRule::Check`, everything else is checked before
+    .map_err(|violation| match check_violation(&base, &violation) {
+        Some(invalid) => InsertOneError::InvalidData(invalid),
+        None => panic!("bug: insert one failed: {violation:?}"),
+    })?;
+
+    if let Some(hooks) = hooks {
+        hooks
+            .after_insert(&mut *conn, out.id, &out.attributes)
+            .await
+            .map_err(aborted)?;
+    }
 
     drop(all_gaurds);
     drop(rel_guard);
     drop(cols);
 
+    changes::publish(
+        this,
+        &base,
+        ChangeEvent {
+            kind: ChangeKind::Insert,
+            collection,
+            id: out.id,
+            attributes: out.attributes.clone(),
+        },
+        caller,
+        &mut *conn,
+    )
+    .await;
+
     Ok(InsertOneOutput {
         id: out.id,
         attributes: out.attributes,
@@ -155,6 +218,7 @@ where
 pub fn insert_one<S>(
     this: Arc<SqlxExecutorData<S>>,
     input: InsertOneInput,
+    caller: Caller,
 ) -> impl Future<Output = Result<InsertOneOutput, InsertOneError>> + 'static + Send + use<S>
 where
     i64: sqlx::Type<S> + for<'q> sqlx::Decode<'q, S> + for<'q> sqlx::Encode<'q, S>,
@@ -181,21 +245,45 @@ where
         let cols = this.collections.read().await;
         let base_gaurd = cols
             .get(input.base.as_str())
-            .ok_or(InsertOneError::CollectionNotFound)?
+            .ok_or_else(|| InsertOneError::CollectionNotFound {
+                collection: input.base.detach(),
+            })?
             .read()
             .await;
         let base = base_gaurd.clone();
+        if base.view.is_some() {
+            return Err(InsertOneError::ReadOnly {
+                collection: Arc::clone(&base.collection_name.snake_case),
+            });
+        }
+
+        let forbidden = || InsertOneError::Forbidden {
+            collection: Arc::clone(&base.collection_name.snake_case),
+        };
+        let access =
+            policy::access(&this.policies, &base, Mode::Write, caller).ok_or_else(forbidden)?;
+
+        let unique = validate(&base, &input.data, true).map_err(InsertOneError::InvalidData)?;
+        if !access.allows(&input.data, true) {
+            return Err(forbidden());
+        }
 
         let data: DynamicInsertInput<S> = deserialize(
             Arc::from(input.data.0.as_str()),
             Arc::clone(&base),
             JsonFormat,
         )
-        .map_err(|_| InsertOneError::InvalidData)?;
+        .map_err(|_| {
+            InsertOneError::InvalidData(InvalidData::of_data(InvalidDataRule::Malformed))
+        })?;
 
         let mut conn = this.pool.acquire().await.unwrap();
 
-        let out = exec_insert_one(&this, base, data, input.links, &mut conn).await?;
+        check_unique(&base, unique, None, &mut conn)
+            .await
+            .map_err(InsertOneError::InvalidData)?;
+
+        let out = exec_insert_one(&this, base, data, input.links, caller, &mut conn).await?;
 
         drop(base_gaurd);
         drop(cols);
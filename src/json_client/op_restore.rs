@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use crate::{
+    collections::Collection,
+    database_extention::DatabaseExt,
+    expressions::ColumnEqual,
+    extentions::common_expressions::Scoped,
+    fix_executor::ExecutorTrait,
+    from_row::FromRowAlias,
+    json_client::{
+        changes::{self, ChangeEvent, ChangeKind},
+        client_interface::{Caller, RestoreError, RestoreInput, RestoreOutput},
+        dynamic_collection::{CollectionToSerialize, DynamicCollection},
+        policy::{self, Mode},
+        sqlx_executor::SqlxExecutorData,
+    },
+    links::soft_delete::operations::Restore,
+    operations::Operation,
+    sqlx_query_builder::basic_expressions::ManyFlat,
+};
+
+type DynCollection<S> = Arc<DynamicCollection<S>>;
+
+pub fn restore<S>(
+    this: Arc<SqlxExecutorData<S>>,
+    input: RestoreInput,
+    caller: Caller,
+) -> impl Future<Output = Result<RestoreOutput, RestoreError>> + 'static + Send + use<S>
+where
+    i64: sqlx::Type<S> + for<'q> sqlx::Decode<'q, S> + for<'q> sqlx::Encode<'q, S>,
+    for<'a> &'a str: sqlx::ColumnIndex<<S as sqlx::Database>::Row>,
+    S: sqlx::Database + DatabaseExt + ExecutorTrait + Send + Sync + 'static,
+    DynCollection<S>: for<'r> FromRowAlias<'r, S::Row, RData = CollectionToSerialize>,
+{
+    async move {
+        let cols = this.collections.read().await;
+        let base_gaurd = cols
+            .get(input.base.as_str())
+            .ok_or_else(|| RestoreError::CollectionNotFound {
+                collection: input.base.detach(),
+            })?
+            .read()
+            .await;
+        let base = base_gaurd.clone();
+        drop(base_gaurd);
+        drop(cols);
+
+        let access =
+            policy::access(&this.policies, &base, Mode::Write, caller).ok_or_else(|| {
+                RestoreError::Forbidden {
+                    collection: Arc::clone(&base.collection_name.snake_case),
+                }
+            })?;
+
+        if !this
+            .link_info
+            .read()
+            .await
+            .soft_deleted
+            .contains(base.collection_name.snake_case.as_ref())
+        {
+            return Err(RestoreError::NotSoftDeleted {
+                collection: Arc::clone(&base.collection_name.snake_case),
+            });
+        }
+
+        let mut conn = this.pool.acquire().await.unwrap();
+
+        // only deleted records are restored, and only the ones the caller owns
+        let out = Operation::<S>::exec_operation(
+            Restore {
+                base: Arc::clone(&base),
+                wheres: ManyFlat((
+                    ColumnEqual {
+                        col: base.id().scoped(),
+                        eq: input.id,
+                    },
+                    access.filter(),
+                )),
+            },
+            &mut conn,
+        )
+        .await;
+
+        let Some(restored) = out.into_iter().find(|row| row.id == input.id) else {
+            return Err(RestoreError::NotFound { id: input.id });
+        };
+
+        // subscribers and the history see the record coming back
+        changes::publish(
+            &this,
+            &base,
+            ChangeEvent {
+                kind: ChangeKind::Insert,
+                collection: Arc::clone(&base.collection_name.snake_case),
+                id: restored.id,
+                attributes: restored.attributes.clone(),
+            },
+            caller,
+            &mut conn,
+        )
+        .await;
+
+        Ok(restored)
+    }
+}
@@ -0,0 +1,184 @@
+use std::{collections::HashSet, sync::Arc};
+
+use sqlx::ColumnIndex;
+use tokio::sync::{broadcast::error::RecvError, mpsc as tokio_mpsc};
+
+use crate::{
+    database_extention::DatabaseExt,
+    fix_executor::ExecutorTrait,
+    from_row::FromRowAlias,
+    json_client::{
+        DynManyToMany, DynOptionalToMany, DynOptionalToManyInverse, DynSoftDelete, DynTimestamp,
+        DynVersioned,
+        changes::{ChangeEvent, ChangeKind},
+        client_interface::{
+            Caller, FetchOneError, FetchOneInput, SubscribeError, SubscribeInput, SubscribeOutput,
+        },
+        dynamic_collection::{CollectionToSerialize, DynamicCollection},
+        op_fetch_one::fetch_one,
+        op_fetch_one_trait_extension::JsonLinkFetchOne,
+        policy::{self, Mode},
+        sqlx_executor::SqlxExecutorData,
+    },
+    operations::link_filter::LinkFilter,
+    sqlx_query_builder::{Expression, ManyExpressions},
+};
+
+type DynCollection<S> = Arc<DynamicCollection<S>>;
+
+/// when the subscription filters, or the caller only reads the records it
+/// owns, inserts and updates are checked by fetching the record with the
+/// filters of the subscription. deletes can't be checked that way, only the
+/// ones of records the subscription has seen matching are sent.
+pub fn subscribe<S>(
+    this: Arc<SqlxExecutorData<S>>,
+    input: SubscribeInput,
+    caller: Caller,
+) -> impl Future<Output = Result<SubscribeOutput, SubscribeError>> + 'static + Send + use<S>
+where
+    S: DatabaseExt + ExecutorTrait + Send + Sync + 'static,
+    DynCollection<S>: for<'r> FromRowAlias<'r, S::Row, RData = CollectionToSerialize>,
+    DynOptionalToMany<S>: JsonLinkFetchOne<S>,
+    DynOptionalToManyInverse<S>: JsonLinkFetchOne<S>,
+    DynManyToMany<S>: JsonLinkFetchOne<S>,
+    DynTimestamp<S>: JsonLinkFetchOne<S>,
+    DynSoftDelete<S>: JsonLinkFetchOne<S>,
+    DynVersioned<S>: JsonLinkFetchOne<S>,
+    DynOptionalToMany<S>: Send
+        + LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
+    DynOptionalToManyInverse<S>: Send
+        + LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
+    DynManyToMany<S>: Send
+        + LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
+    i64: sqlx::Type<S> + for<'q> sqlx::Decode<'q, S> + for<'q> sqlx::Encode<'q, S>,
+    String: for<'q> sqlx::Encode<'q, S> + sqlx::Type<S>,
+    for<'a> &'a str: ColumnIndex<S::Row>,
+{
+    async move {
+        // subscribe first so nothing written while validating is missed
+        let mut changes = this.changes.subscribe();
+
+        let (collection, unfiltered) = {
+            let cols = this.collections.read().await;
+            let base_guard = cols
+                .get(input.base.as_str())
+                .ok_or_else(|| SubscribeError::CollectionNotFound {
+                    collection: input.base.detach(),
+                })?
+                .read()
+                .await;
+            let unfiltered = input.filters.is_empty()
+                && policy::access(&this.policies, &base_guard, Mode::Read, caller)
+                    .is_some_and(|access| access.is_all());
+            (
+                Arc::clone(&base_guard.collection_name.snake_case),
+                unfiltered,
+            )
+        };
+
+        // the filters are validated the same way `fetch_one` does
+        let checked = fetch_one(
+            Arc::clone(&this),
+            FetchOneInput {
+                base: input.base.clone(),
+                id: 0,
+                filters: input.filters.clone(),
+                links: vec![],
+            },
+            caller,
+        )
+        .await;
+        match checked {
+            Ok(_) | Err(FetchOneError::NotFound { .. }) => {}
+            Err(FetchOneError::CollectionNotFound { collection }) => {
+                return Err(SubscribeError::CollectionNotFound { collection });
+            }
+            Err(FetchOneError::InvalidFilter { index }) => {
+                return Err(SubscribeError::InvalidFilter { index });
+            }
+            Err(FetchOneError::Forbidden { collection }) => {
+                return Err(SubscribeError::Forbidden { collection });
+            }
+            Err(FetchOneError::InvalidLink { .. }) => unreachable!("no links were requested"),
+        }
+
+        let (sender, events) = tokio_mpsc::unbounded_channel();
+        // the channel closes once the executor is dropped
+        let data = Arc::downgrade(&this);
+        drop(this);
+
+        tokio::spawn(async move {
+            let mut matching = HashSet::<i64>::new();
+            loop {
+                let event = tokio::select! {
+                    _ = sender.closed() => break,
+                    event = changes.recv() => event,
+                };
+                let event = match event {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                if event.collection != collection {
+                    continue;
+                }
+
+                let event = if unfiltered {
+                    Some(event)
+                } else if event.kind == ChangeKind::Delete {
+                    matching.remove(&event.id).then_some(event)
+                } else {
+                    let Some(this) = data.upgrade() else {
+                        break;
+                    };
+                    let matches = fetch_one(
+                        this,
+                        FetchOneInput {
+                            base: input.base.clone(),
+                            id: event.id,
+                            filters: input.filters.clone(),
+                            links: vec![],
+                        },
+                        caller,
+                    )
+                    .await
+                    .is_ok();
+
+                    if matches {
+                        matching.insert(event.id);
+                        Some(event)
+                    } else if matching.remove(&event.id) {
+                        // the record left the subscription
+                        Some(ChangeEvent {
+                            kind: ChangeKind::Delete,
+                            ..event
+                        })
+                    } else {
+                        None
+                    }
+                };
+
+                let Some(event) = event else {
+                    continue;
+                };
+                if sender.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(SubscribeOutput { events })
+    }
+}
@@ -15,23 +15,31 @@ use crate::{
     },
     json_client::{
         DynManyToMany, DynOptionalToMany,
-        client_interface::{SupportedUpdateLink, UpdateOneError, UpdateOneInput, UpdateOneOutput},
+        changes::{self, ChangeEvent, ChangeKind},
+        client_interface::{
+            Caller, InvalidData, InvalidDataRule, SupportedUpdateLink, UpdateOneError,
+            UpdateOneInput, UpdateOneOutput,
+        },
         dynamic_collection::{
             CollectionToSerialize, DynamicCollection, DynamicInsertInput, DynamicUpdateInput,
         },
         op_update_one_trait_extension::{JsonUpdateOneLink, JsonUpdateOneToConsume},
+        policy::{self, Mode, owns},
         sqlx_executor::{FromTo, LinkInformations, SqlxExecutorData},
+        validation::{check_unique, check_violation, validate},
     },
     links::{
         DefaultRelationKey,
         relation_many_to_many::{ManyToMany, RemoveJunctionId, SetJunctionId},
         relation_optional_to_many::OptionalToMany,
         update_links::{SetId, SetNew},
+        versioned::{ExpectVersion, Versioned, operations::current_version},
     },
     operations::{
         Operation,
         update::{Update, UpdateLink, UpdateLinkData, UpdateLinkSplit},
     },
+    sqlx_query_builder::basic_expressions::{ColumnIsNull, ManyFlat},
 };
 
 type DynCollection<S> = Arc<DynamicCollection<S>>;
@@ -66,6 +74,7 @@ fn update_link_contributes_set_clause(
 pub fn update_one<S>(
     this: Arc<SqlxExecutorData<S>>,
     input: UpdateOneInput,
+    caller: Caller,
 ) -> impl Future<Output = Result<UpdateOneOutput, UpdateOneError>> + 'static + Send + use<S>
 where
     i64: sqlx::Type<S> + for<'q> sqlx::Decode<'q, S> + for<'q> sqlx::Encode<'q, S>,
@@ -110,6 +119,15 @@ where
             InitSplitPostOp: Send + 'static,
         >,
     >,
+    ExpectVersion<DynCollection<S>>: UpdateLinkSplit<
+        Link: JsonUpdateOneLink<S>
+                  + UpdateLink<
+            InitSplitForPreOp: Send + 'static,
+            InitSplitForWheres: Send + 'static,
+            InitSplitForUpdateValues: Send + 'static,
+            InitSplitPostOp: Send + 'static,
+        >,
+    >,
     Vec<JsonUpdateOneToConsume<S>>:
         UpdateLinkSplit<Link = Vec<Box<dyn JsonUpdateOneLink<S> + Send>>>,
 {
@@ -117,18 +135,45 @@ where
         let cols = this.collections.read().await;
         let base_gaurd = cols
             .get(input.base.as_str())
-            .ok_or(UpdateOneError::CollectionNotFound)?
+            .ok_or_else(|| UpdateOneError::CollectionNotFound {
+                collection: input.base.detach(),
+            })?
             .read()
             .await;
         let base = base_gaurd.clone();
+        if base.view.is_some() {
+            return Err(UpdateOneError::ReadOnly {
+                collection: Arc::clone(&base.collection_name.snake_case),
+            });
+        }
         let mut all_gaurds = vec![base_gaurd];
 
-        let data: DynamicUpdateInput<S> = deserialize(
+        let forbidden = |collection: &DynamicCollection<S>| UpdateOneError::Forbidden {
+            collection: Arc::clone(&collection.collection_name.snake_case),
+        };
+        let access = policy::access(&this.policies, &base, Mode::Write, caller)
+            .ok_or_else(|| forbidden(&base))?;
+        // `SetId` and `RemoveId` point at existing records of `to`
+        let readable = |collection: &DynamicCollection<S>| {
+            policy::access(&this.policies, collection, Mode::Read, caller)
+                .is_some_and(|access| access.is_all())
+        };
+
+        let unique = validate(&base, &input.data, false).map_err(UpdateOneError::InvalidData)?;
+        if !access.allows(&input.data, false) {
+            return Err(forbidden(&base));
+        }
+        // records created by `SetNew` links, looked up along with `unique`
+        let mut new_uniques = Vec::new();
+
+        let mut data: DynamicUpdateInput<S> = deserialize(
             Arc::from(input.data.0.as_str()),
             Arc::clone(&base),
             JsonFormat,
         )
-        .map_err(|_| UpdateOneError::InvalidData)?;
+        .map_err(|_| {
+            UpdateOneError::InvalidData(InvalidData::of_data(InvalidDataRule::Malformed))
+        })?;
 
         let rel_guard = this.link_info.read().await;
 
@@ -141,19 +186,24 @@ where
                 )
             })
         {
-            return Err(UpdateOneError::InvalidData);
+            return Err(UpdateOneError::InvalidData(InvalidData::of_data(
+                InvalidDataRule::Empty,
+            )));
         }
 
         let mut links = Vec::<JsonUpdateOneToConsume<S>>::new();
 
-        for link in input.links {
+        for (index, link) in input.links.into_iter().enumerate() {
             match link {
                 SupportedUpdateLink::SetId { to, id } => {
                     let to_gaurd = cols
                         .get(to.as_str())
-                        .ok_or(UpdateOneError::InvalidLink)?
+                        .ok_or(UpdateOneError::InvalidLink { index })?
                         .read()
                         .await;
+                    if !readable(&to_gaurd) {
+                        return Err(forbidden(&to_gaurd));
+                    }
                     let to = to_gaurd.clone();
                     all_gaurds.push(to_gaurd);
 
@@ -202,20 +252,34 @@ where
                             },
                         });
                     } else {
-                        return Err(UpdateOneError::InvalidLink);
+                        return Err(UpdateOneError::InvalidLink { index });
                     }
                 }
                 SupportedUpdateLink::SetNew { to, value } => {
                     let to_gaurd = cols
                         .get(to.as_str())
-                        .ok_or(UpdateOneError::InvalidLink)?
+                        .ok_or(UpdateOneError::InvalidLink { index })?
                         .read()
                         .await;
                     let to = to_gaurd.clone();
                     all_gaurds.push(to_gaurd);
+                    let to_access = policy::access(&this.policies, &to, Mode::Write, caller)
+                        .ok_or_else(|| forbidden(&to))?;
+                    let invalid_link_data =
+                        |invalid| UpdateOneError::InvalidLinkData { index, invalid };
+                    new_uniques.push((
+                        index,
+                        Arc::clone(&to),
+                        validate(&to, &value, true).map_err(invalid_link_data)?,
+                    ));
+                    if !to_access.allows(&value, true) {
+                        return Err(forbidden(&to));
+                    }
                     let link_data: DynamicInsertInput<S> =
                         deserialize(Arc::from(value.0.as_str()), Arc::clone(&to), JsonFormat)
-                            .map_err(|_| UpdateOneError::InvalidData)?;
+                            .map_err(|_| {
+                                invalid_link_data(InvalidData::of_data(InvalidDataRule::Malformed))
+                            })?;
                     let (link, data) = SetNew {
                         relation: OptionalToMany {
                             fk_unique_id: DefaultRelationKey,
@@ -238,7 +302,7 @@ where
                 SupportedUpdateLink::SetNull { to } => {
                     let to_gaurd = cols
                         .get(to.as_str())
-                        .ok_or(UpdateOneError::InvalidLink)?
+                        .ok_or(UpdateOneError::InvalidLink { index })?
                         .read()
                         .await;
                     let to = to_gaurd.clone();
@@ -265,9 +329,12 @@ where
                 SupportedUpdateLink::RemoveId { to, id } => {
                     let to_gaurd = cols
                         .get(to.as_str())
-                        .ok_or(UpdateOneError::InvalidLink)?
+                        .ok_or(UpdateOneError::InvalidLink { index })?
                         .read()
                         .await;
+                    if !readable(&to_gaurd) {
+                        return Err(forbidden(&to_gaurd));
+                    }
                     let to = to_gaurd.clone();
                     all_gaurds.push(to_gaurd);
 
@@ -277,7 +344,7 @@ where
                     };
 
                     if !rel_guard.many_to_many.contains(&forward) {
-                        return Err(UpdateOneError::InvalidLink);
+                        return Err(UpdateOneError::InvalidLink { index });
                     }
 
                     let (link, data) = RemoveJunctionId {
@@ -303,31 +370,118 @@ where
             }
         }
 
+        // `version` comes after the requested links in the output
+        let versioned = rel_guard
+            .versioned
+            .contains(base.collection_name.snake_case.as_ref());
+        match (versioned, input.version) {
+            (true, Some(version)) => links.push(JsonUpdateOneToConsume::new(ExpectVersion {
+                versioned: Versioned {
+                    collection: base.clone(),
+                },
+                version,
+            })),
+            (false, None) => {}
+            _ => {
+                return Err(UpdateOneError::InvalidVersion {
+                    collection: Arc::clone(&base.collection_name.snake_case),
+                });
+            }
+        }
+
         let mut conn = this.pool.acquire().await.unwrap();
 
+        if !owns(&base, &access, input.id, &mut conn).await {
+            return Err(UpdateOneError::NotFound { id: input.id });
+        }
+
+        check_unique(&base, unique, Some(input.id), &mut conn)
+            .await
+            .map_err(UpdateOneError::InvalidData)?;
+        for (index, to, unique) in new_uniques {
+            check_unique(&to, unique, None, &mut conn)
+                .await
+                .map_err(|invalid| UpdateOneError::InvalidLinkData { index, invalid })?;
+        }
+
+        if let Some(hooks) = this.hooks.get(base.collection_name.snake_case.as_ref()) {
+            hooks
+                .before_update(&mut conn, input.id, &mut data)
+                .await
+                .map_err(|reason| UpdateOneError::Aborted {
+                    collection: Arc::clone(&base.collection_name.snake_case),
+                    reason,
+                })?;
+        }
+
+        // soft-deleted records have to be restored first
+        let not_deleted = rel_guard
+            .soft_deleted
+            .contains(base.collection_name.snake_case.as_ref())
+            .then_some(ColumnIsNull {
+                col: ("deleted_at",),
+            });
+
         let out = Operation::<S>::exec_operation(
             Update {
                 base: Arc::clone(&base),
                 partial: data,
-                wheres: ColumnEqual {
-                    col: base.id().scoped(),
-                    eq: input.id,
-                },
+                wheres: ManyFlat((
+                    ColumnEqual {
+                        col: base.id().scoped(),
+                        eq: input.id,
+                    },
+                    access.filter(),
+                    not_deleted,
+                )),
                 links,
             },
             &mut conn,
         )
         .await
-        .expect("bug: update one failed");
+        // a `FieldRule::Check`, everything else is checked before
+        .map_err(|violation| match check_violation(&base, &violation) {
+            Some(invalid) => UpdateOneError::InvalidData(invalid),
+            None => panic!("bug: update one failed: {violation:?}"),
+        })?;
 
         drop(all_gaurds);
         drop(rel_guard);
         drop(cols);
 
         let Some(updated) = out.into_iter().find(|row| row.id == input.id) else {
-            return Err(UpdateOneError::NotFound);
+            if let Some(version) = input.version {
+                let current = current_version::<S, _, _, _>(
+                    Arc::clone(&base.collection_name.pascal_case),
+                    base.id().scoped(),
+                    input.id,
+                    &mut conn,
+                )
+                .await;
+                if let Some(current_version) = current.filter(|current| *current != version) {
+                    return Err(UpdateOneError::Conflict {
+                        id: input.id,
+                        current_version,
+                    });
+                }
+            }
+            return Err(UpdateOneError::NotFound { id: input.id });
         };
 
+        changes::publish(
+            &this,
+            &base,
+            ChangeEvent {
+                kind: ChangeKind::Update,
+                collection: Arc::clone(&base.collection_name.snake_case),
+                id: updated.id,
+                attributes: updated.attributes.clone(),
+            },
+            caller,
+            &mut conn,
+        )
+        .await;
+
         Ok(UpdateOneOutput {
             id: updated.id,
             attributes: updated.attributes,
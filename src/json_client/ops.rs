@@ -2,8 +2,14 @@ use super::client_interface::*;
 use super::op_fetch_many_trait_extension::JsonLinkFetchMany;
 use super::op_fetch_one_trait_extension::JsonLinkFetchOne;
 
+// stream ops go through the executor like the others but are left out of
+// `StringClient::exec`, their output is not a single value
 macro_rules! ops {
-        ($([$snake_case:ident, $pascal_case:ident]),*) => {
+        (exec: [$($exec:tt),* $(,)?], stream: [$($stream:tt),* $(,)?]) => {
+            ops!(@all $($exec,)* $($stream,)*);
+            ops!(@string_client $($exec),*);
+        };
+        (@all $([$snake_case:ident, $pascal_case:ident],)*) => {
             paste::paste!{
                 #[derive(Debug)]
                 #[non_exhaustive]
@@ -35,7 +41,7 @@ macro_rules! ops {
                             $snake_case(&self, input: [<$pascal_case Input>])
                         -> impl Future<Output = Result<[<$pascal_case Output>], [<$pascal_case Error>]>> {
                             let (tx, rx) = oneshot::async_channel::<Result<OperationOutput, OperationError>>();
-                            self.sender.send((OperationInput::$pascal_case(input), tx)).unwrap();
+                            self.sender.send((OperationInput::$pascal_case(input), self.caller, tx)).unwrap();
                             async move {
                                 let output = rx.await.expect("disconnected_channel");
                                 let mapp = match output {
@@ -84,6 +90,10 @@ macro_rules! ops {
                         for<'r> ::sqlx::Decode<'r, S>
                         + for<'q> ::sqlx::Encode<'q, S>
                         + ::sqlx::Type<S>,
+                    Vec<u8>:
+                        for<'r> ::sqlx::Decode<'r, S>
+                        + for<'q> ::sqlx::Encode<'q, S>
+                        + ::sqlx::Type<S>,
                     $crate::links::relation_optional_to_many::OptionalToMany<
                         $crate::links::DefaultRelationKey,
                         std::sync::Arc<$crate::json_client::dynamic_collection::DynamicCollection<S>>,
@@ -102,6 +112,12 @@ macro_rules! ops {
                     $crate::links::timestamp::Timestamp<
                         std::sync::Arc<$crate::json_client::dynamic_collection::DynamicCollection<S>>,
                     >: $crate::json_client::op_fetch_many_trait_extension::JsonLinkFetchMany<S>,
+                    $crate::links::soft_delete::SoftDelete<
+                        std::sync::Arc<$crate::json_client::dynamic_collection::DynamicCollection<S>>,
+                    >: $crate::json_client::op_fetch_many_trait_extension::JsonLinkFetchMany<S>,
+                    $crate::links::versioned::Versioned<
+                        std::sync::Arc<$crate::json_client::dynamic_collection::DynamicCollection<S>>,
+                    >: $crate::json_client::op_fetch_many_trait_extension::JsonLinkFetchMany<S>,
                     $crate::links::relation_optional_to_many::OptionalToMany<
                         $crate::links::DefaultRelationKey,
                         std::sync::Arc<$crate::json_client::dynamic_collection::DynamicCollection<S>>,
@@ -120,6 +136,12 @@ macro_rules! ops {
                     $crate::links::timestamp::Timestamp<
                         std::sync::Arc<$crate::json_client::dynamic_collection::DynamicCollection<S>>,
                     >: $crate::json_client::op_fetch_one_trait_extension::JsonLinkFetchOne<S>,
+                    $crate::links::soft_delete::SoftDelete<
+                        std::sync::Arc<$crate::json_client::dynamic_collection::DynamicCollection<S>>,
+                    >: $crate::json_client::op_fetch_one_trait_extension::JsonLinkFetchOne<S>,
+                    $crate::links::versioned::Versioned<
+                        std::sync::Arc<$crate::json_client::dynamic_collection::DynamicCollection<S>>,
+                    >: $crate::json_client::op_fetch_one_trait_extension::JsonLinkFetchOne<S>,
                     $crate::links::relation_optional_to_many::OptionalToMany<
                         $crate::links::DefaultRelationKey,
                         std::sync::Arc<$crate::json_client::dynamic_collection::DynamicCollection<S>>,
@@ -139,6 +161,27 @@ macro_rules! ops {
                     >: $crate::on_migrate::OnMigrate<
                         Statements: for<'q> $crate::sqlx_query_builder::Expression<'q, S>,
                     >,
+                    $crate::links::soft_delete::SoftDelete<
+                        std::sync::Arc<$crate::json_client::dynamic_collection::DynamicCollection<S>>,
+                    >: $crate::on_migrate::OnMigrate<
+                        Statements: for<'q> $crate::sqlx_query_builder::Expression<'q, S>,
+                    >,
+                    $crate::links::audit_log::AuditLog<
+                        std::sync::Arc<$crate::json_client::dynamic_collection::DynamicCollection<S>>,
+                    >: $crate::on_migrate::OnMigrate<
+                        Statements: for<'q> $crate::sqlx_query_builder::Expression<'q, S>,
+                    >,
+                    $crate::links::versioned::Versioned<
+                        std::sync::Arc<$crate::json_client::dynamic_collection::DynamicCollection<S>>,
+                    >: $crate::on_migrate::OnMigrate<
+                        Statements: for<'q> $crate::sqlx_query_builder::Expression<'q, S>,
+                    >,
+                    $crate::links::full_text_search::FullTextSearch<
+                        std::sync::Arc<$crate::json_client::dynamic_collection::DynamicCollection<S>>,
+                        Vec<std::sync::Arc<str>>,
+                    >: $crate::on_migrate::OnMigrate<
+                        Statements: for<'q> $crate::sqlx_query_builder::Expression<'q, S>,
+                    >,
                     std::sync::Arc<$crate::json_client::dynamic_collection::DynamicCollection<S>>:
                         for<'r> $crate::from_row::FromRowAlias<
                             'r,
@@ -150,13 +193,12 @@ macro_rules! ops {
                     pub fn run(mut self) -> impl Future<Output = ::std::convert::Infallible> {
                         async move {
                             loop {
-                                let operation = self.reciever.recv().await.unwrap();
+                                let (operation, caller, operation_sender) = self.reciever.recv().await.unwrap();
 
                                 paste::paste!{
-                                match operation.0 {
+                                match operation {
                                     $(OperationInput::$pascal_case(input) => {
-                                        let future = $crate::json_client::[<op_ $snake_case>]::[<$snake_case>](self.data.clone(), input);
-                                        let operation_sender = operation.1;
+                                        let future = $crate::json_client::[<op_ $snake_case>]::[<$snake_case>](self.data.clone(), input, caller);
                                         let dispatch = ::tracing::dispatcher::Dispatch::default();
                                         let span = ::tracing::Span::current();
                                         tokio::spawn(async move {
@@ -178,7 +220,10 @@ macro_rules! ops {
                         }
                     }
                 }
-
+            }
+        };
+        (@string_client $([$snake_case:ident, $pascal_case:ident]),*) => {
+            paste::paste!{
                 impl $crate::json_client::string_client::StringClient {
                     pub fn exec(&self, input: String) -> impl Future<Output = String> {
                         use crate::gen_serde::Serialize;
@@ -191,9 +236,15 @@ macro_rules! ops {
                                 start: 0,
                             };
 
+                            let fail = |error: $crate::json_client::error_body::ErrorBody| {
+                                let mut serialized = $crate::gen_serde::json_serialize_side::JsonAsString(String::new());
+                                $crate::json_client::string_client::StringClientError { error }.serialize(&mut serialized);
+                                serialized.0
+                            };
+
                             let input = match $crate::json_client::string_client::StringClientInput::deserialize((), &mut cursor) {
                                 Ok(input) => input,
-                                Err(e) => return String::from(r#"{"error":"invalid_input"}"#),
+                                Err(e) => return fail($crate::json_client::error_body::ErrorBody::invalid_input()),
                             };
 
                             match input.op.as_str() {
@@ -201,20 +252,18 @@ macro_rules! ops {
                                     stringify!($snake_case) => {
                                         let body = match input.body.continue_deserialize() {
                                             Ok(body) => body,
-                                            Err(e) => return String::from(r#"{"error":"invalid_body"}"#),
-                                        };
-                                        let output = match self.inner.$snake_case(body).await {
-                                            Ok(output) => output,
-                                            Err(e) => return format!("{{\"error\":\"{:?}\"}}", e),
+                                            Err(e) => return fail($crate::json_client::error_body::ErrorBody::invalid_body(input.op.as_str())),
                                         };
-
                                         let mut serialized = $crate::gen_serde::json_serialize_side::JsonAsString(String::new());
-                                        $crate::json_client::string_client::StringClientOutput { output }.serialize(&mut serialized);
+                                        match self.inner.$snake_case(body).await {
+                                            Ok(output) => $crate::json_client::string_client::StringClientOutput { output }.serialize(&mut serialized),
+                                            Err(error) => return fail($crate::json_client::error_body::ErrorBody::from(&error)),
+                                        };
 
                                         return serialized.0;
                                     }
                                 )*
-                                _ => return String::from(r#"{"error":"unsupported_operation"}"#),
+                                op => return fail($crate::json_client::error_body::ErrorBody::unsupported_operation(op)),
                             }
                         }
                     }
@@ -224,12 +273,19 @@ macro_rules! ops {
     }
 
 ops!(
-    [add_collection, AddCollection],
-    [add_link, AddLink],
-    [fetch_many, FetchMany],
-    [fetch_one, FetchOne],
-    [insert_one, InsertOne],
-    [insert_many, InsertMany],
-    [update_one, UpdateOne],
-    [delete_one, DeleteOne]
+    exec: [
+        [add_collection, AddCollection],
+        [add_link, AddLink],
+        [fetch_many, FetchMany],
+        [fetch_one, FetchOne],
+        [insert_one, InsertOne],
+        [insert_many, InsertMany],
+        [update_one, UpdateOne],
+        [delete_one, DeleteOne],
+        [restore, Restore],
+        [history, History],
+    ],
+    stream: [
+        [subscribe, Subscribe],
+    ]
 );
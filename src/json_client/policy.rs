@@ -0,0 +1,205 @@
+//! row-level access control of the json client.
+//!
+//! every operation runs for a `Caller`, passed along with it by `Client`.
+//! collections get a `CollectionPolicy` on the executor, those without one
+//! are open to every caller, and `Caller::System` bypasses all of them.
+//!
+//! a `RowRule::Owner` rule limits the caller to the records whose owner
+//! column equals its id: the column is added to the filters of fetch,
+//! update and delete, and inserts have to set it to the caller's id.
+//! links only reach collections the caller can access as a whole, linked
+//! records are not filtered.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use crate::{
+    database_extention::DatabaseExt,
+    execute::Executable,
+    expressions::ColumnEqual,
+    fix_executor::ExecutorTrait,
+    gen_serde::json_format_side::PartialDeserialize,
+    json_client::dynamic_collection::DynamicCollection,
+    sqlx_query_builder::{Expression, OpExpression, StatementBuilder},
+    statement_cache::DYNAMIC,
+    sub_arc::ArcSubStr,
+};
+
+/// who an operation runs for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Caller {
+    /// trusted code, every policy is bypassed and only it can change the schema
+    #[default]
+    System,
+    Anonymous,
+    User(i64),
+}
+
+/// which records of a collection a caller can reach
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RowRule {
+    Anyone,
+    /// any `Caller::User`
+    Authenticated,
+    /// records whose `col`, an `Int` field, is the id of the `Caller::User`
+    Owner {
+        col: Arc<str>,
+    },
+    Nobody,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectionPolicy {
+    pub read: RowRule,
+    /// insert, update and delete
+    pub write: RowRule,
+}
+
+impl Default for CollectionPolicy {
+    fn default() -> Self {
+        Self {
+            read: RowRule::Anyone,
+            write: RowRule::Anyone,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Mode {
+    Read,
+    Write,
+}
+
+/// what a caller was granted on one collection
+#[derive(Debug, Clone)]
+pub(crate) enum Access {
+    All,
+    Owned { col: Arc<str>, id: i64 },
+}
+
+/// `None` when the caller can't reach the collection at all, an `Owner`
+/// column that isn't an `Int` field of the collection grants nothing
+pub(crate) fn access<S>(
+    policies: &HashMap<Arc<str>, CollectionPolicy>,
+    collection: &DynamicCollection<S>,
+    mode: Mode,
+    caller: Caller,
+) -> Option<Access>
+where
+    S: DatabaseExt,
+{
+    let Some(policy) = policies.get(collection.collection_name.snake_case.as_ref()) else {
+        return Some(Access::All);
+    };
+    let rule = match mode {
+        Mode::Read => &policy.read,
+        Mode::Write => &policy.write,
+    };
+
+    match (caller, rule) {
+        (Caller::System, _) | (_, RowRule::Anyone) | (Caller::User(_), RowRule::Authenticated) => {
+            Some(Access::All)
+        }
+        (Caller::User(id), RowRule::Owner { col }) => {
+            let is_int = collection.fields.iter().any(|field| {
+                field.name.as_ref() == col.as_ref()
+                    && (field.type_info.type_name)() == std::any::type_name::<i64>()
+            });
+            is_int.then(|| Access::Owned {
+                col: Arc::clone(col),
+                id,
+            })
+        }
+        _ => None,
+    }
+}
+
+impl Access {
+    pub(crate) fn is_all(&self) -> bool {
+        matches!(self, Access::All)
+    }
+
+    /// added to the wheres of the operation, `None` for `All`
+    pub(crate) fn filter(&self) -> Option<ColumnEqual<Arc<str>, i64>> {
+        match self {
+            Access::All => None,
+            Access::Owned { col, id } => Some(ColumnEqual {
+                col: Arc::clone(col),
+                eq: *id,
+            }),
+        }
+    }
+
+    /// inserted data has to set the owner column to the caller's id,
+    /// updated data may leave it out
+    pub(crate) fn allows(&self, data: &PartialDeserialize, require: bool) -> bool {
+        let Access::Owned { col, id } = self else {
+            return true;
+        };
+        let Ok(values) = data.continue_deserialize::<BTreeMap<ArcSubStr, PartialDeserialize>>()
+        else {
+            return false;
+        };
+        match values.iter().find(|(key, _)| key.as_str() == col.as_ref()) {
+            Some((_, value)) => value.continue_deserialize::<i64>() == Ok(*id),
+            None => !require,
+        }
+    }
+}
+
+/// `SELECT 1 FROM <table> WHERE "id" = $1 AND <col> = $2 LIMIT 1`
+struct Owns {
+    table: Arc<str>,
+    record: i64,
+    filter: ColumnEqual<Arc<str>, i64>,
+}
+
+impl OpExpression for Owns {}
+
+impl<'q, S> Expression<'q, S> for Owns
+where
+    S: DatabaseExt,
+    i64: sqlx::Encode<'q, S> + sqlx::Type<S>,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("SELECT 1 FROM ");
+        ctx.sanitize(&self.table);
+        ctx.syntax(" WHERE ");
+        ctx.sanitize("id");
+        ctx.syntax(" = ");
+        ctx.bind(self.record);
+        ctx.syntax(" AND ");
+        Expression::expression(self.filter, ctx);
+        ctx.syntax(" LIMIT 1;");
+    }
+}
+
+/// links of update and delete run before the statement filtered by
+/// `Access::filter`, the record is looked up first to keep them from
+/// touching records the caller doesn't own
+pub(crate) async fn owns<S>(
+    base: &DynamicCollection<S>,
+    access: &Access,
+    record: i64,
+    conn: &mut S::Connection,
+) -> bool
+where
+    S: DatabaseExt + ExecutorTrait,
+    i64: for<'q> sqlx::Encode<'q, S> + sqlx::Type<S>,
+{
+    let Some(filter) = access.filter() else {
+        return true;
+    };
+
+    let (stmt, arguments) = StatementBuilder::<S>::new(Owns {
+        table: Arc::clone(&base.collection_name.pascal_case),
+        record,
+        filter,
+    })
+    .unwrap();
+
+    S::fetch_optional(&mut *conn, Executable::cached(&DYNAMIC, &stmt, arguments))
+        .await
+        .expect("bug: ownership check failed")
+        .is_some()
+}
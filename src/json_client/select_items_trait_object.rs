@@ -194,7 +194,10 @@ mod test {
     use crate::{
         connect_in_memory::ConnectInMemory,
         links::{DefaultRelationKey, relation_optional_to_many::OptionalToMany},
-        operations::{Operation, fetch_many::FetchMany},
+        operations::{
+            Operation,
+            fetch_many::{FetchMany, Pagination},
+        },
         test_module::{category, todo},
     };
     use serde_json::json;
@@ -246,8 +249,11 @@ mod test {
                     fk_unique_id: DefaultRelationKey,
                 },
                 cursor_order_by: (),
-                cursor_first_item: None::<(i64, ())>,
-                limit: 10,
+                pagination: Pagination::Cursor {
+                    cursor: None,
+                    limit: 10,
+                },
+                computed: (),
             },
             &mut db,
         )
@@ -310,6 +316,8 @@ mod test {
                     }
                 ],
                 "next_item": null,
+                "prev_item": null,
+                "total": null,
             })
         );
     }
@@ -1,11 +1,18 @@
+use crate::json_client::changes::{self, ChangeEvent};
 use crate::json_client::client_interface::{
-    AddCollectionInput, Client, ClientOperationError, ClientOperationInput, ClientOperationOutput,
-    SupportedType,
+    AddCollectionInput, Caller, Client, ClientOperationError, ClientOperationInput,
+    ClientOperationOutput, SupportedType,
 };
-use crate::json_client::dynamic_collection::DynamicCollection;
+use crate::json_client::cursor_token;
+use crate::json_client::dynamic_collection::{
+    CollectionToSerialize, DynamicCollection, DynamicInsertInput, DynamicUpdateInput,
+};
+use crate::json_client::hooks::Hooks;
+use crate::json_client::policy::CollectionPolicy;
 use crate::{
     database_extention::DatabaseExt, on_migrate::OnMigrate, sqlx_query_builder::Expression,
 };
+use futures::future::BoxFuture;
 use sqlx::{IntoArguments, Pool, Sqlite};
 use std::collections::HashSet;
 use std::{
@@ -13,7 +20,7 @@ use std::{
     marker::PhantomData,
     sync::{Arc, Weak},
 };
-use tokio::sync::{RwLock as Trw, mpsc as tokio_mpsc};
+use tokio::sync::{RwLock as Trw, broadcast, mpsc as tokio_mpsc};
 
 pub struct SqlxExecutor<S>
 where
@@ -21,6 +28,7 @@ where
 {
     pub(crate) reciever: tokio::sync::mpsc::UnboundedReceiver<(
         ClientOperationInput,
+        Caller,
         oneshot::Sender<Result<ClientOperationOutput, ClientOperationError>>,
     )>,
     pub(crate) data: Arc<SqlxExecutorData<S>>,
@@ -34,6 +42,16 @@ where
     pub(crate) migration: Trw<Vec<String>>,
     pub(crate) link_info: Trw<LinkInformations>,
     pub(crate) pool: Pool<S>,
+    /// key signing the cursors of `fetch_many`
+    pub(crate) cursor_secret: Vec<u8>,
+    /// largest `limit` or `page_size` accepted by `fetch_many`
+    pub(crate) max_page_size: i64,
+    /// keyed by the snake_case name of the collection
+    pub(crate) policies: HashMap<Arc<str>, CollectionPolicy>,
+    /// keyed by the snake_case name of the collection
+    pub(crate) hooks: HashMap<Arc<str>, Hooks<S>>,
+    /// every successful write, see `changes`
+    pub(crate) changes: broadcast::Sender<ChangeEvent>,
     _s: PhantomData<S>,
 }
 #[derive(Default, Debug)]
@@ -41,6 +59,10 @@ pub struct LinkInformations {
     pub optional_to_many: HashSet<FromTo>,
     pub many_to_many: HashSet<FromTo>,
     pub timestamped: HashSet<Arc<str>>,
+    pub soft_deleted: HashSet<Arc<str>>,
+    pub audited: HashSet<Arc<str>>,
+    pub versioned: HashSet<Arc<str>>,
+    pub full_text_search: HashSet<Arc<str>>,
 }
 
 #[derive(Debug, Hash, PartialEq, Eq)]
@@ -61,6 +83,7 @@ impl Client {
     {
         let (sender, reciever) = tokio_mpsc::unbounded_channel::<(
             ClientOperationInput,
+            Caller,
             oneshot::Sender<Result<ClientOperationOutput, ClientOperationError>>,
         )>();
 
@@ -69,9 +92,121 @@ impl Client {
             migration: Trw::new(Default::default()),
             link_info: Trw::new(Default::default()),
             pool,
+            cursor_secret: cursor_token::random_secret(),
+            max_page_size: 100,
+            policies: HashMap::new(),
+            hooks: HashMap::new(),
+            changes: broadcast::channel(changes::CHANNEL_CAPACITY).0,
             _s: PhantomData,
         });
 
-        (Client { sender }, SqlxExecutor { reciever, data })
+        (
+            Client {
+                sender,
+                caller: Caller::System,
+            },
+            SqlxExecutor { reciever, data },
+        )
+    }
+}
+
+impl<S> SqlxExecutor<S>
+where
+    S: sqlx::Database + DatabaseExt,
+{
+    /// sign cursors with a fixed key so they stay valid across restarts
+    /// and between executors sharing the key
+    pub fn with_cursor_secret(mut self, secret: impl Into<Vec<u8>>) -> Self {
+        Arc::get_mut(&mut self.data)
+            .expect("executor data is not shared before it runs")
+            .cursor_secret = secret.into();
+        self
+    }
+
+    /// pages larger than this are rejected with `InvalidPageSize`, defaults to 100
+    pub fn with_max_page_size(mut self, max_page_size: i64) -> Self {
+        Arc::get_mut(&mut self.data)
+            .expect("executor data is not shared before it runs")
+            .max_page_size = max_page_size;
+        self
+    }
+
+    /// restricts what callers other than `Caller::System` can do with
+    /// `collection`, which doesn't have to be added yet
+    pub fn with_policy(mut self, collection: &str, policy: CollectionPolicy) -> Self {
+        Arc::get_mut(&mut self.data)
+            .expect("executor data is not shared before it runs")
+            .policies
+            .insert(Arc::from(collection), policy);
+        self
+    }
+
+    fn hooks_of(&mut self, collection: &str) -> &mut Hooks<S> {
+        Arc::get_mut(&mut self.data)
+            .expect("executor data is not shared before it runs")
+            .hooks
+            .entry(Arc::from(collection))
+            .or_default()
+    }
+
+    /// runs before the data is inserted into `collection` and can change it
+    pub fn before_insert<F>(mut self, collection: &str, hook: F) -> Self
+    where
+        F: for<'c> Fn(
+                &'c mut S::Connection,
+                &'c mut DynamicInsertInput<S>,
+            ) -> BoxFuture<'c, Result<(), String>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.hooks_of(collection).before_insert.push(Arc::new(hook));
+        self
+    }
+
+    pub fn after_insert<F>(mut self, collection: &str, hook: F) -> Self
+    where
+        F: for<'c> Fn(
+                &'c mut S::Connection,
+                i64,
+                &'c CollectionToSerialize,
+            ) -> BoxFuture<'c, Result<(), String>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.hooks_of(collection).after_insert.push(Arc::new(hook));
+        self
+    }
+
+    /// runs before a record of `collection` is updated and can change the data
+    pub fn before_update<F>(mut self, collection: &str, hook: F) -> Self
+    where
+        F: for<'c> Fn(
+                &'c mut S::Connection,
+                i64,
+                &'c mut DynamicUpdateInput<S>,
+            ) -> BoxFuture<'c, Result<(), String>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.hooks_of(collection).before_update.push(Arc::new(hook));
+        self
+    }
+
+    pub fn after_delete<F>(mut self, collection: &str, hook: F) -> Self
+    where
+        F: for<'c> Fn(
+                &'c mut S::Connection,
+                i64,
+                &'c CollectionToSerialize,
+            ) -> BoxFuture<'c, Result<(), String>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.hooks_of(collection).after_delete.push(Arc::new(hook));
+        self
     }
 }
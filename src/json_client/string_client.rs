@@ -3,7 +3,10 @@
             ObjectEncoding, Serialize, SerializedJson, json_format_side::PartialDeserialize,
             json_serialize_side::JsonAsString,
         },
-        json_client::client_interface::Client,
+        json_client::{
+            client_interface::{Caller, Client, SubscribeInput, SubscribeOutput},
+            error_body::ErrorBody,
+        },
         sub_arc::ArcSubStr,
     };
 
@@ -17,6 +20,60 @@
         }
     }
 
+    impl StringClient {
+        /// see `Client::with_caller`
+        pub fn with_caller(&self, caller: Caller) -> Self {
+            StringClient {
+                inner: self.inner.with_caller(caller),
+            }
+        }
+
+        /// `input` is the body of a `subscribe` op, on failure the error is
+        /// serialized the same way `exec` does
+        pub fn subscribe(
+            &self,
+            input: String,
+        ) -> impl Future<Output = Result<StringSubscription, String>> {
+            use crate::gen_serde::Deserialize;
+            use crate::gen_serde::json_format_side::JsonAsArcCursor;
+
+            let fail = |error: ErrorBody| {
+                let mut serialized = JsonAsString(String::new());
+                StringClientError { error }.serialize(&mut serialized);
+                serialized.0
+            };
+
+            async move {
+                let mut cursor = JsonAsArcCursor {
+                    inner: input.as_str().into(),
+                    start: 0,
+                };
+                let body = SubscribeInput::deserialize((), &mut cursor)
+                    .map_err(|_| fail(ErrorBody::invalid_body("subscribe")))?;
+
+                match self.inner.subscribe(body).await {
+                    Ok(inner) => Ok(StringSubscription { inner }),
+                    Err(error) => Err(fail(ErrorBody::from(&error))),
+                }
+            }
+        }
+    }
+
+    /// yields each event serialized as
+    /// `{"kind":..,"collection":..,"id":..,"attributes":{..}}`
+    pub struct StringSubscription {
+        inner: SubscribeOutput,
+    }
+
+    impl StringSubscription {
+        pub async fn next(&mut self) -> Option<String> {
+            let event = self.inner.next().await?;
+            let mut serialized = JsonAsString(String::new());
+            event.serialize(&mut serialized);
+            Some(serialized.0)
+        }
+    }
+
     pub(super) struct StringClientInput {
         pub(super) op: ArcSubStr,
         pub(super) body: PartialDeserialize,
@@ -43,6 +100,23 @@
         }
     }
 
+    pub(super) struct StringClientError<T> {
+        pub(super) error: T,
+    }
+
+    impl<F, T> Serialize<F> for StringClientError<T>
+    where
+        F: ObjectEncoding,
+        T: Serialize<F>,
+        str: Serialize<F>,
+    {
+        fn serialize(&self, ctx: &mut F) {
+            let mut object = ctx.serialize_start();
+            ctx.serialize_pair(&mut object, "error", &self.error);
+            ctx.serialize_end(object);
+        }
+    }
+
     impl<'de, S> crate::gen_serde::Deserialize<'de, S> for StringClientInput
     where
         S: crate::gen_serde::Deserializer<'de>,
@@ -3,20 +3,232 @@ use crate::{
     expressions::{
         ColumnEqual,
         filters::{
-            ColumnContains, ColumnGreaterThan, ColumnGreaterThanOrEqual, ColumnIsNotNull,
-            ColumnIsNull, ColumnLessThan, ColumnLessThanOrEqual, ColumnNotEqual, FilterAnd,
+            ColumnGreaterThan, ColumnGreaterThanOrEqual, ColumnIsNotNull, ColumnIsNull,
+            ColumnLessThan, ColumnLessThanOrEqual, ColumnNotEqual, FilterAnd,
         },
     },
     gen_serde::json_format_side::PartialDeserialize,
     json_client::{
-        ToBind, client_interface::SupportedFilter, dynamic_collection::DynamicCollection,
+        DynManyToMany, DynOptionalToMany, DynOptionalToManyInverse, ToBind,
+        client_interface::{SupportedFilter, SupportedLinkFilter},
+        dynamic_collection::DynamicCollection,
+        sqlx_executor::{FromTo, LinkInformations},
+    },
+    links::{DefaultRelationKey, full_text_search::FullTextSearch},
+    operations::{
+        link_filter::{All, Any, Exists, LinkFilter},
+        link_order_by::LinkSubquery,
     },
     sqlx_query_builder::{
-        basic_expressions::{ExpressionsWithAnd, ExpressionsWithOr},
+        Expression, ManyExpressions, StatementBuilder,
+        basic_expressions::{
+            Bind, ColumnBetween, ColumnContainsText, ColumnEndsWith, ColumnEqualIgnoreCase,
+            ColumnIn, ColumnStartsWith, ExpressionsWithAnd, ExpressionsWithOr, Not,
+            PossibleImplExpression,
+        },
         trait_objects::BoxedExpression,
     },
     sub_arc::ArcSubStr,
 };
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// collections reachable by `Exists`, `Any` and `All` filters, they are
+/// resolved before parsing because every collection sits behind an async lock
+pub struct FilterLinks<'a, S>
+where
+    S: DatabaseExt,
+{
+    pub(crate) collections: HashMap<Arc<str>, Arc<DynamicCollection<S>>>,
+    pub(crate) link_info: &'a LinkInformations,
+}
+
+impl<'a, S> FilterLinks<'a, S>
+where
+    S: DatabaseExt,
+{
+    pub fn new(link_info: &'a LinkInformations) -> Self {
+        Self {
+            collections: HashMap::new(),
+            link_info,
+        }
+    }
+}
+
+/// names of all collections `filters` reach through links, nested filters included
+pub fn filter_link_targets(filters: &[SupportedFilter], out: &mut Vec<ArcSubStr>) {
+    for filter in filters {
+        match filter {
+            SupportedFilter::And { filters } | SupportedFilter::Or { filters } => {
+                filter_link_targets(filters, out)
+            }
+            SupportedFilter::Not { filter } => {
+                filter_link_targets(std::slice::from_ref(filter.as_ref()), out)
+            }
+            SupportedFilter::Exists { link } => out.push(link_target(link).clone()),
+            SupportedFilter::Any { link, filters } | SupportedFilter::All { link, filters } => {
+                out.push(link_target(link).clone());
+                filter_link_targets(filters, out);
+            }
+            _ => {}
+        }
+    }
+}
+
+pub(crate) fn link_target(link: &SupportedLinkFilter) -> &ArcSubStr {
+    match link {
+        SupportedLinkFilter::OptionalToMany { to } => to,
+        SupportedLinkFilter::ManyToMany { to } => to,
+    }
+}
+
+pub(crate) enum ResolvedLinkFilter<S>
+where
+    S: DatabaseExt,
+{
+    OptionalToMany(DynOptionalToMany<S>),
+    OptionalToManyInverse(DynOptionalToManyInverse<S>),
+    ManyToMany(DynManyToMany<S>),
+}
+
+impl<S> Clone for ResolvedLinkFilter<S>
+where
+    S: DatabaseExt,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Self::OptionalToMany(link) => Self::OptionalToMany(link.clone()),
+            Self::OptionalToManyInverse(link) => Self::OptionalToManyInverse(link.clone()),
+            Self::ManyToMany(link) => Self::ManyToMany(link.clone()),
+        }
+    }
+}
+
+macro_rules! with_resolved_link {
+    ($resolved:expr, |$link:ident| $body:expr) => {
+        match $resolved {
+            ResolvedLinkFilter::OptionalToMany($link) => $body,
+            ResolvedLinkFilter::OptionalToManyInverse($link) => $body,
+            ResolvedLinkFilter::ManyToMany($link) => $body,
+        }
+    };
+}
+
+/// `(SELECT <select> FROM <link> WHERE <correlate>)`, used by link order by
+pub(crate) trait ResolvedLinkSubquery<S>
+where
+    S: DatabaseExt,
+{
+    fn subquery<'q, Select>(&self, select: Select, ctx: &mut StatementBuilder<'q, S>)
+    where
+        Select: Expression<'q, S> + 'q;
+}
+
+impl<S> ResolvedLinkSubquery<S> for ResolvedLinkFilter<S>
+where
+    S: DatabaseExt,
+    DynOptionalToMany<S>: LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
+    DynOptionalToManyInverse<S>: LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
+    DynManyToMany<S>: LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
+{
+    fn subquery<'q, Select>(&self, select: Select, ctx: &mut StatementBuilder<'q, S>)
+    where
+        Select: Expression<'q, S> + 'q,
+    {
+        with_resolved_link!(self.clone(), |link| Expression::expression(
+            LinkSubquery { link, select },
+            ctx
+        ))
+    }
+}
+
+pub(crate) fn resolve_link<S>(
+    link: &SupportedLinkFilter,
+    base: &Arc<DynamicCollection<S>>,
+    links: &FilterLinks<'_, S>,
+) -> Result<(ResolvedLinkFilter<S>, Arc<DynamicCollection<S>>), ()>
+where
+    S: DatabaseExt,
+{
+    let to = links
+        .collections
+        .get(link_target(link).as_str())
+        .ok_or(())?;
+
+    let forward = FromTo {
+        from: Arc::clone(&base.collection_name.snake_case),
+        to: Arc::clone(&to.collection_name.snake_case),
+    };
+
+    let resolved = match link {
+        SupportedLinkFilter::OptionalToMany { .. } => {
+            let reverse = FromTo {
+                from: Arc::clone(&to.collection_name.snake_case),
+                to: Arc::clone(&base.collection_name.snake_case),
+            };
+            if links.link_info.optional_to_many.contains(&forward) {
+                ResolvedLinkFilter::OptionalToMany(DynOptionalToMany {
+                    fk_unique_id: DefaultRelationKey,
+                    from: Arc::clone(base),
+                    to: Arc::clone(to),
+                })
+            } else if links.link_info.optional_to_many.contains(&reverse) {
+                ResolvedLinkFilter::OptionalToManyInverse(DynOptionalToManyInverse {
+                    fk_unique_id: DefaultRelationKey,
+                    from: Arc::clone(base),
+                    to: Arc::clone(to),
+                })
+            } else {
+                return Err(());
+            }
+        }
+        SupportedLinkFilter::ManyToMany { .. } => {
+            if !links.link_info.many_to_many.contains(&forward) {
+                return Err(());
+            }
+            ResolvedLinkFilter::ManyToMany(DynManyToMany {
+                relation_key: DefaultRelationKey,
+                from: Arc::clone(base),
+                to: Arc::clone(to),
+            })
+        }
+    };
+
+    Ok((resolved, Arc::clone(to)))
+}
+
+/// the full text search of `base`, `Err` when `base` has none
+pub(crate) fn full_text_search<S>(
+    base: &Arc<DynamicCollection<S>>,
+    links: &FilterLinks<'_, S>,
+) -> Result<FullTextSearch<Arc<DynamicCollection<S>>, ()>, ()>
+where
+    S: DatabaseExt,
+{
+    if !links
+        .link_info
+        .full_text_search
+        .contains(base.collection_name.snake_case.as_ref())
+    {
+        return Err(());
+    }
+    Ok(FullTextSearch {
+        collection: Arc::clone(base),
+        fields: (),
+    })
+}
 
 fn field_by_col<'a, S>(
     col: &ArcSubStr,
@@ -40,31 +252,46 @@ where
     S: DatabaseExt,
 {
     let field = field_by_col(&col, base)?;
-    let bind = (field.type_info.to_bind)(partial)?;
+    let bind = field.to_bind(partial)?;
     Ok((col, bind))
 }
 
-fn string_contains_bind<S>(
+/// `field_bind` for `>`, `>=`, `<` and `<=`, fields without an order are rejected
+fn ordered_field_bind<S>(
     col: ArcSubStr,
     partial: PartialDeserialize,
     base: &DynamicCollection<S>,
 ) -> Result<(ArcSubStr, Box<dyn ToBind<S> + Send>), ()>
 where
     S: DatabaseExt,
-    String: for<'q> sqlx::Encode<'q, S> + sqlx::Type<S>,
+{
+    if !field_by_col(&col, base)?.is_ordered() {
+        return Err(());
+    }
+    field_bind(col, partial, base)
+}
+
+/// the text `partial` holds, for filters that only apply to text fields
+fn text_bind<S>(
+    col: ArcSubStr,
+    partial: PartialDeserialize,
+    base: &DynamicCollection<S>,
+) -> Result<(ArcSubStr, String), ()>
+where
+    S: DatabaseExt,
 {
     let field = field_by_col(&col, base)?;
     if (field.type_info.type_name)() != std::any::type_name::<String>() {
         return Err(());
     }
-    let needle: String = partial.continue_deserialize().map_err(|_| ())?;
-    let pattern = format!("%{}%", needle);
-    Ok((col, Box::new(pattern)))
+    let text: String = partial.continue_deserialize().map_err(|_| ())?;
+    Ok((col, text))
 }
 
 pub fn parse_one_supported_filter<'q, S>(
     filter: SupportedFilter,
-    base: &DynamicCollection<S>,
+    base: &Arc<DynamicCollection<S>>,
+    links: &FilterLinks<'_, S>,
 ) -> Result<Box<dyn BoxedExpression<S> + Send>, ()>
 where
     S: DatabaseExt,
@@ -74,9 +301,34 @@ where
     ColumnGreaterThanOrEqual<ArcSubStr, Box<dyn ToBind<S> + Send>>: BoxedExpression<S>,
     ColumnLessThan<ArcSubStr, Box<dyn ToBind<S> + Send>>: BoxedExpression<S>,
     ColumnLessThanOrEqual<ArcSubStr, Box<dyn ToBind<S> + Send>>: BoxedExpression<S>,
-    ColumnContains<ArcSubStr, Box<dyn ToBind<S> + Send>>: BoxedExpression<S>,
+    ColumnContainsText<ArcSubStr, String>: BoxedExpression<S>,
+    ColumnStartsWith<ArcSubStr, String>: BoxedExpression<S>,
+    ColumnEndsWith<ArcSubStr, String>: BoxedExpression<S>,
+    ColumnEqualIgnoreCase<ArcSubStr, String>: BoxedExpression<S>,
+    ColumnBetween<ArcSubStr, Box<dyn ToBind<S> + Send>>: BoxedExpression<S>,
+    PossibleImplExpression<ColumnIn<ArcSubStr, Vec<Bind<Box<dyn ToBind<S> + Send>>>>>:
+        BoxedExpression<S>,
+    Not<Box<dyn BoxedExpression<S> + Send>>: BoxedExpression<S>,
     ColumnIsNull<ArcSubStr>: BoxedExpression<S>,
     ColumnIsNotNull<ArcSubStr>: BoxedExpression<S>,
+    DynOptionalToMany<S>: Send
+        + LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
+    DynOptionalToManyInverse<S>: Send
+        + LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
+    DynManyToMany<S>: Send
+        + LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
     String: for<'a> sqlx::Encode<'a, S> + sqlx::Type<S>,
 {
     Ok(match filter {
@@ -89,24 +341,49 @@ where
             Box::new(ColumnNotEqual { col, ne: bind })
         }
         SupportedFilter::ColGt { col, gt } => {
-            let (col, bind) = field_bind(col, gt, base)?;
+            let (col, bind) = ordered_field_bind(col, gt, base)?;
             Box::new(ColumnGreaterThan { col, val: bind })
         }
         SupportedFilter::ColGte { col, gte } => {
-            let (col, bind) = field_bind(col, gte, base)?;
+            let (col, bind) = ordered_field_bind(col, gte, base)?;
             Box::new(ColumnGreaterThanOrEqual { col, val: bind })
         }
         SupportedFilter::ColLt { col, lt } => {
-            let (col, bind) = field_bind(col, lt, base)?;
+            let (col, bind) = ordered_field_bind(col, lt, base)?;
             Box::new(ColumnLessThan { col, val: bind })
         }
         SupportedFilter::ColLte { col, lte } => {
-            let (col, bind) = field_bind(col, lte, base)?;
+            let (col, bind) = ordered_field_bind(col, lte, base)?;
             Box::new(ColumnLessThanOrEqual { col, val: bind })
         }
         SupportedFilter::ColContains { col, value } => {
-            let (col, bind) = string_contains_bind(col, value, base)?;
-            Box::new(ColumnContains { col, val: bind })
+            let (col, val) = text_bind(col, value, base)?;
+            Box::new(ColumnContainsText { col, val })
+        }
+        SupportedFilter::ColStartsWith { col, value } => {
+            let (col, val) = text_bind(col, value, base)?;
+            Box::new(ColumnStartsWith { col, val })
+        }
+        SupportedFilter::ColEndsWith { col, value } => {
+            let (col, val) = text_bind(col, value, base)?;
+            Box::new(ColumnEndsWith { col, val })
+        }
+        SupportedFilter::ColEqIgnoreCase { col, eq } => {
+            let (col, eq) = text_bind(col, eq, base)?;
+            Box::new(ColumnEqualIgnoreCase { col, eq })
+        }
+        SupportedFilter::ColIn { col, values } => {
+            let values = values
+                .into_iter()
+                .map(|value| Ok(Bind(field_bind(col.clone(), value, base)?.1)))
+                .collect::<Result<Vec<_>, ()>>()?;
+            // `IN ()` is not valid in every database
+            Box::new(PossibleImplExpression::new(ColumnIn { col, values }).ok_or(())?)
+        }
+        SupportedFilter::ColBetween { col, low, high } => {
+            let (_, low) = ordered_field_bind(col.clone(), low, base)?;
+            let (col, high) = ordered_field_bind(col, high, base)?;
+            Box::new(ColumnBetween { col, low, high })
         }
         SupportedFilter::ColIsNull { col } => {
             field_by_col(&col, base)?;
@@ -116,21 +393,47 @@ where
             field_by_col(&col, base)?;
             Box::new(ColumnIsNotNull { col })
         }
+        SupportedFilter::Search { query } => {
+            let search = full_text_search(base, links)?;
+            Box::new(search.search(query.as_str()))
+        }
         SupportedFilter::And { filters } => {
-            let inner = parse_supported_filter(filters, base)?;
+            let inner = parse_supported_filter(filters, base, links).map_err(|_| ())?;
             Box::new(ExpressionsWithAnd(inner))
         }
         SupportedFilter::Or { filters } => {
-            let inner = parse_supported_filter(filters, base)?;
+            let inner = parse_supported_filter(filters, base, links).map_err(|_| ())?;
             Box::new(ExpressionsWithOr(inner))
         }
+        SupportedFilter::Not { filter } => {
+            Box::new(Not(parse_one_supported_filter(*filter, base, links)?))
+        }
+        SupportedFilter::Exists { link } => {
+            let (link, _) = resolve_link(&link, base, links)?;
+            with_resolved_link!(link, |link| Box::new(Exists { link }))
+        }
+        SupportedFilter::Any { link, filters } => {
+            let (link, to) = resolve_link(&link, base, links)?;
+            let filters = parse_supported_filter(filters, &to, links).map_err(|_| ())?;
+            with_resolved_link!(link, |link| Box::new(Any { link, filters }))
+        }
+        SupportedFilter::All { link, filters } => {
+            let (link, to) = resolve_link(&link, base, links)?;
+            let filters = parse_supported_filter(filters, &to, links).map_err(|_| ())?;
+            // "all" of no filters is not a filter
+            with_resolved_link!(link, |link| Box::new(
+                PossibleImplExpression::new(All { link, filters }).ok_or(())?
+            ))
+        }
     })
 }
 
+/// `Err` is the index of the first filter of `input` that can't be applied
 pub fn parse_supported_filter<'q, S>(
     input: Vec<SupportedFilter>,
-    base: &DynamicCollection<S>,
-) -> Result<Vec<Box<dyn BoxedExpression<S> + Send>>, ()>
+    base: &Arc<DynamicCollection<S>>,
+    links: &FilterLinks<'_, S>,
+) -> Result<Vec<Box<dyn BoxedExpression<S> + Send>>, usize>
 where
     S: DatabaseExt,
     ColumnEqual<ArcSubStr, Box<dyn ToBind<S> + Send>>: BoxedExpression<S>,
@@ -139,13 +442,39 @@ where
     ColumnGreaterThanOrEqual<ArcSubStr, Box<dyn ToBind<S> + Send>>: BoxedExpression<S>,
     ColumnLessThan<ArcSubStr, Box<dyn ToBind<S> + Send>>: BoxedExpression<S>,
     ColumnLessThanOrEqual<ArcSubStr, Box<dyn ToBind<S> + Send>>: BoxedExpression<S>,
-    ColumnContains<ArcSubStr, Box<dyn ToBind<S> + Send>>: BoxedExpression<S>,
+    ColumnContainsText<ArcSubStr, String>: BoxedExpression<S>,
+    ColumnStartsWith<ArcSubStr, String>: BoxedExpression<S>,
+    ColumnEndsWith<ArcSubStr, String>: BoxedExpression<S>,
+    ColumnEqualIgnoreCase<ArcSubStr, String>: BoxedExpression<S>,
+    ColumnBetween<ArcSubStr, Box<dyn ToBind<S> + Send>>: BoxedExpression<S>,
+    PossibleImplExpression<ColumnIn<ArcSubStr, Vec<Bind<Box<dyn ToBind<S> + Send>>>>>:
+        BoxedExpression<S>,
+    Not<Box<dyn BoxedExpression<S> + Send>>: BoxedExpression<S>,
     ColumnIsNull<ArcSubStr>: BoxedExpression<S>,
     ColumnIsNotNull<ArcSubStr>: BoxedExpression<S>,
+    DynOptionalToMany<S>: Send
+        + LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
+    DynOptionalToManyInverse<S>: Send
+        + LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
+    DynManyToMany<S>: Send
+        + LinkFilter<
+            From: for<'e> Expression<'e, S>,
+            Joins: for<'e> ManyExpressions<'e, S>,
+            Correlate: for<'e> Expression<'e, S>,
+        >,
     String: for<'a> sqlx::Encode<'a, S> + sqlx::Type<S>,
 {
     input
         .into_iter()
-        .map(|filter| parse_one_supported_filter(filter, base))
+        .enumerate()
+        .map(|(index, filter)| parse_one_supported_filter(filter, base, links).map_err(|_| index))
         .collect()
 }
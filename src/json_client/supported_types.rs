@@ -0,0 +1,289 @@
+//! values of `SupportedType`s that have no sqlx primitive of their own.
+//!
+//! they are stored as TEXT (`Bytes` as BLOB) in a normalized form whose
+//! byte order is their natural order, so comparison filters and order by
+//! work on the column directly:
+//! - `DateTime` is RFC 3339 converted to UTC with microseconds,
+//!   `2024-01-31T23:05:00.000000Z`, the offset given on input is dropped
+//! - `Date` is `YYYY-MM-DD`
+//! - `Uuid` is lowercase and hyphenated
+//! - `Bytes` is base64 (standard, padded) on the wire
+//! - `JsonValue` is kept as sent, it has no meaningful order and is
+//!   rejected by comparison filters and order by
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use sqlx::{Database, Decode, Encode, Type, encode::IsNull, error::BoxDynError};
+
+use crate::gen_serde::{
+    Deserialize, DeserializeSpec, Deserializer, Serialize,
+    json_format_side::{JsonAsArcCursor, PartialDeserialize},
+    json_serialize_side::JsonAsString,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DateTime(pub String);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Date(pub String);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Uuid(pub String);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonValue(pub String);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bytes(pub Vec<u8>);
+
+impl DateTime {
+    /// `YYYY-MM-DD(T| )HH:MM:SS(.fraction)?(Z|±HH:MM)`
+    pub fn parse(input: &str) -> Option<Self> {
+        let (date, rest) = (input.get(..10)?, input.get(10..)?);
+        let (year, month, day) = parse_date(date)?;
+        let rest = rest
+            .strip_prefix('T')
+            .or_else(|| rest.strip_prefix('t'))
+            .or_else(|| rest.strip_prefix(' '))?;
+
+        let hour = digits(rest.get(0..2)?)?;
+        let minute = digits(rest.get(3..5)?)?;
+        let second = digits(rest.get(6..8)?)?;
+        if rest.get(2..3)? != ":" || rest.get(5..6)? != ":" {
+            return None;
+        }
+        if hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+
+        let mut rest = &rest[8..];
+        let mut micros = 0;
+        if let Some(fraction) = rest.strip_prefix('.') {
+            let len = fraction
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(fraction.len());
+            if len == 0 {
+                return None;
+            }
+            // digits past microseconds are truncated
+            for (index, digit) in fraction[..len].bytes().take(6).enumerate() {
+                micros += (digit - b'0') as i64 * 10_i64.pow(5 - index as u32);
+            }
+            rest = &fraction[len..];
+        }
+
+        let offset = match rest {
+            "Z" | "z" => 0,
+            _ => {
+                let sign = match rest.get(0..1)? {
+                    "+" => 1,
+                    "-" => -1,
+                    _ => return None,
+                };
+                let hours = digits(rest.get(1..3)?)?;
+                let minutes = digits(rest.get(4..6)?)?;
+                if rest.len() != 6 || rest.get(3..4)? != ":" || hours > 23 || minutes > 59 {
+                    return None;
+                }
+                sign * (hours * 3600 + minutes * 60)
+            }
+        };
+
+        let seconds =
+            days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second - offset;
+
+        let (year, month, day) = civil_from_days(seconds.div_euclid(86400));
+        if !(0..=9999).contains(&year) {
+            return None;
+        }
+        let time = seconds.rem_euclid(86400);
+        Some(Self(format!(
+            "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{micros:06}Z",
+            time / 3600,
+            time % 3600 / 60,
+            time % 60,
+        )))
+    }
+}
+
+impl Date {
+    pub fn parse(input: &str) -> Option<Self> {
+        parse_date(input)?;
+        Some(Self(input.to_string()))
+    }
+}
+
+impl Uuid {
+    /// the 8-4-4-4-12 hyphenated form, in any case
+    pub fn parse(input: &str) -> Option<Self> {
+        let valid = input.len() == 36
+            && input.char_indices().all(|(index, c)| match index {
+                8 | 13 | 18 | 23 => c == '-',
+                _ => c.is_ascii_hexdigit(),
+            });
+        valid.then(|| Self(input.to_ascii_lowercase()))
+    }
+}
+
+fn digits(input: &str) -> Option<i64> {
+    if input.is_empty() || !input.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    input.parse().ok()
+}
+
+fn parse_date(input: &str) -> Option<(i64, i64, i64)> {
+    if input.len() != 10 || input.get(4..5)? != "-" || input.get(7..8)? != "-" {
+        return None;
+    }
+    let year = digits(input.get(0..4)?)?;
+    let month = digits(input.get(5..7)?)?;
+    let day = digits(input.get(8..10)?)?;
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+    (1..=days_in_month)
+        .contains(&day)
+        .then_some((year, month, day))
+}
+
+/// days since 1970-01-01 of a proleptic gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+macro_rules! stored_as {
+    ($inner:ty: $($name:ident)*) => {$(
+        impl<S: Database> Type<S> for $name
+        where
+            $inner: Type<S>,
+        {
+            fn type_info() -> S::TypeInfo {
+                <$inner as Type<S>>::type_info()
+            }
+            fn compatible(ty: &S::TypeInfo) -> bool {
+                <$inner as Type<S>>::compatible(ty)
+            }
+        }
+
+        impl<'q, S: Database> Encode<'q, S> for $name
+        where
+            $inner: Encode<'q, S>,
+        {
+            fn encode_by_ref(
+                &self,
+                buf: &mut <S as Database>::ArgumentBuffer<'q>,
+            ) -> Result<IsNull, BoxDynError> {
+                self.0.encode_by_ref(buf)
+            }
+        }
+
+        /// stored values are trusted, they were normalized on the way in
+        impl<'r, S: Database> Decode<'r, S> for $name
+        where
+            $inner: Decode<'r, S>,
+        {
+            fn decode(value: <S as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+                Ok(Self(<$inner as Decode<'r, S>>::decode(value)?))
+            }
+        }
+
+        impl crate::expressions::is_null::IsNull for $name {
+            fn is_null() -> bool {
+                false
+            }
+        }
+
+        impl DeserializeSpec for $name {
+            type Handler = ();
+        }
+    )*};
+}
+
+stored_as!(String: DateTime Date Uuid JsonValue);
+stored_as!(Vec<u8>: Bytes);
+
+macro_rules! parsed_from_string {
+    ($($name:ident)*) => {$(
+        impl Serialize<JsonAsString> for $name {
+            fn serialize(&self, ctx: &mut JsonAsString) {
+                self.0.serialize(ctx);
+            }
+        }
+
+        impl<'de> Deserialize<'de, JsonAsArcCursor> for $name {
+            fn deserialize(
+                _handler: Self::Handler,
+                serialized: &mut JsonAsArcCursor,
+            ) -> Result<Self, <JsonAsArcCursor as Deserializer<'de>>::Err> {
+                let raw = String::deserialize((), serialized)?;
+                $name::parse(&raw)
+                    .ok_or_else(|| format!("invalid {}: {raw:?}", stringify!($name)))
+            }
+        }
+    )*};
+}
+
+parsed_from_string!(DateTime Date Uuid);
+
+impl Serialize<JsonAsString> for JsonValue {
+    fn serialize(&self, ctx: &mut JsonAsString) {
+        ctx.0.push_str(&self.0);
+    }
+}
+
+impl<'de> Deserialize<'de, JsonAsArcCursor> for JsonValue {
+    fn deserialize(
+        _handler: Self::Handler,
+        serialized: &mut JsonAsArcCursor,
+    ) -> Result<Self, <JsonAsArcCursor as Deserializer<'de>>::Err> {
+        let partial = PartialDeserialize::deserialize((), serialized)?;
+        Ok(JsonValue(partial.0.as_str().to_string()))
+    }
+}
+
+impl Serialize<JsonAsString> for Bytes {
+    fn serialize(&self, ctx: &mut JsonAsString) {
+        STANDARD.encode(&self.0).serialize(ctx);
+    }
+}
+
+impl<'de> Deserialize<'de, JsonAsArcCursor> for Bytes {
+    fn deserialize(
+        _handler: Self::Handler,
+        serialized: &mut JsonAsArcCursor,
+    ) -> Result<Self, <JsonAsArcCursor as Deserializer<'de>>::Err> {
+        let raw = String::deserialize((), serialized)?;
+        STANDARD
+            .decode(raw)
+            .map(Bytes)
+            .map_err(|e| format!("invalid base64: {e}"))
+    }
+}
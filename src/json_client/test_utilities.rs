@@ -186,7 +186,7 @@
             assert_sql_eq(
                 cache.drain(),
                 vec![
-                    r#"ALTER TABLE "Todo" ADD COLUMN "fk_category_def" INTEGER  REFERENCES "Category"("id") ON DELETE SET NULL;"#.to_string(),
+                    r#"ALTER TABLE "Todo" ADD COLUMN "fk_category_def" INTEGER  REFERENCES "Category"("id") ON DELETE SET NULL; CREATE INDEX IF NOT EXISTS "idx_todo_fk_category_def" ON "Todo" ("fk_category_def");"#.to_string(),
                 ]
             );
         })
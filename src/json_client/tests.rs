@@ -1,7 +1,14 @@
     use sqlx::Sqlite;
 
+    use std::sync::Arc;
+
     use crate::{
-        connect_in_memory::ConnectInMemory, json_client::client_interface::Client,
+        connect_in_memory::ConnectInMemory,
+        json_client::{
+            client_interface::{Caller, Client},
+            policy::{CollectionPolicy, RowRule},
+        },
+        sub_arc::ArcSubStr,
         track_sqlx_query::{assert_sql_eq, watch_sqlx_calls},
     };
 
@@ -178,7 +185,10 @@
             )
             .await;
 
-        pretty_assertions::assert_eq!(result, r#"{"error":"InvalidData"}"#);
+        pretty_assertions::assert_eq!(
+            result,
+            r#"{"error":{"code":"invalid_data","details":{"rule":"empty"},"message":"the data breaks the rule empty","path":["items"]}}"#
+        );
     }
 
     #[tokio::test(flavor = "current_thread")]
@@ -189,7 +199,10 @@
         let _executor = tokio::spawn(ex.run());
 
         let result = client.exec(r#"not json"#.to_string()).await;
-        pretty_assertions::assert_eq!(result, r#"{"error":"invalid_input"}"#);
+        pretty_assertions::assert_eq!(
+            result,
+            r#"{"error":{"code":"invalid_input","details":{},"message":"the request is not {\"op\":..,\"body\":..}","path":[]}}"#
+        );
     }
 
     #[tokio::test(flavor = "current_thread")]
@@ -210,7 +223,130 @@
                 .to_string(),
             )
             .await;
-        pretty_assertions::assert_eq!(result, r#"{"error":"invalid_body"}"#);
+        pretty_assertions::assert_eq!(
+            result,
+            r#"{"error":{"code":"invalid_body","details":{"op":"add_collection"},"message":"the body doesn't match the input of add_collection","path":[]}}"#
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn errors_point_at_the_offending_part_of_the_body() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool);
+        let client = client.into_string_client();
+        let _executor = tokio::spawn(ex.run());
+
+        add_todo_collection(&client).await;
+        add_category_collection(&client).await;
+        todo_is_one_to_many_with_category(&client).await;
+
+        let unsupported = client
+            .exec(r#"{ "op": "drop_everything", "body": {} }"#.to_string())
+            .await;
+
+        pretty_assertions::assert_eq!(
+            unsupported,
+            r#"{"error":{"code":"unsupported_operation","details":{"op":"drop_everything"},"message":"there is no operation drop_everything","path":[]}}"#
+        );
+
+        let new_link_data = client
+            .exec(
+                r#"
+{
+    "op": "insert_many",
+    "body": {
+        "base": "todo",
+        "items": [
+            {
+                "data": { "title": "first", "done": false },
+                "links": []
+            },
+            {
+                "data": { "title": "second", "done": false },
+                "links": [
+                    { "ty": "set_new", "to": "category", "value": {} }
+                ]
+            }
+        ]
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            new_link_data,
+            r#"{"error":{"code":"invalid_data","details":{"field":"title","rule":"required"},"message":"title breaks the rule required","path":["items",1,"links",0,"value","title"]}}"#
+        );
+
+        let second_filter = client
+            .exec(
+                r#"
+{
+    "op": "fetch_many",
+    "body": {
+        "base": "todo",
+        "filters": [
+            { "ty": "col_eq", "col": "done", "eq": false },
+            { "ty": "col_eq", "col": "missing", "eq": "x" }
+        ],
+        "links": [],
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            second_filter,
+            r#"{"error":{"code":"invalid_filter","details":{},"message":"filter 1 can't be applied","path":["filters",1]}}"#
+        );
+
+        let missing_record = client
+            .exec(
+                r#"
+{
+    "op": "fetch_one",
+    "body": {
+        "base": "todo",
+        "id": 9,
+        "filters": [],
+        "links": []
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            missing_record,
+            r#"{"error":{"code":"not_found","details":{"id":9},"message":"no record with id 9","path":["id"]}}"#
+        );
+
+        let missing_base = client
+            .exec(
+                r#"
+{
+    "op": "delete_one",
+    "body": {
+        "base": "note",
+        "id": 1,
+        "links": []
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            missing_base,
+            r#"{"error":{"code":"collection_not_found","details":{"collection":"note"},"message":"collection note doesn't exist","path":["base"]}}"#
+        );
     }
 
     #[tokio::test(flavor = "current_thread")]
@@ -271,7 +407,10 @@
                 .to_string(),
             )
             .await;
-        pretty_assertions::assert_eq!(result, r#"{"error":"CollectionAlreadyExists"}"#);
+        pretty_assertions::assert_eq!(
+            result,
+            r#"{"error":{"code":"collection_already_exists","details":{"collection":"todo"},"message":"collection todo already exists","path":["name"]}}"#
+        );
     }
 
     #[tokio::test(flavor = "current_thread")]
@@ -300,7 +439,10 @@
                 .to_string(),
             )
             .await;
-        pretty_assertions::assert_eq!(result, r#"{"error":"LinkAlreadyExists"}"#);
+        pretty_assertions::assert_eq!(
+            result,
+            r#"{"error":{"code":"link_already_exists","details":{"from":"todo","to":"category"},"message":"link from todo to category already exists","path":[]}}"#
+        );
     }
 
     #[tokio::test(flavor = "current_thread")]
@@ -329,7 +471,10 @@
                 .to_string(),
             )
             .await;
-        pretty_assertions::assert_eq!(result, r#"{"error":"CollectionNotFound"}"#);
+        pretty_assertions::assert_eq!(
+            result,
+            r#"{"error":{"code":"collection_not_found","details":{"collection":"missing"},"message":"collection missing doesn't exist","path":[]}}"#
+        );
     }
 
     #[tokio::test(flavor = "current_thread")]
@@ -631,7 +776,7 @@
         "links": [
             { "ty": "timestamp" }
         ],
-        "pagination": { "limit": 10, "first_item": null, "order_by": [] }
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
     }
 }
 "#
@@ -641,7 +786,7 @@
 
         pretty_assertions::assert_eq!(
             result,
-            r#"{"output":{"items":[{"id":1,"attributes":{"description":"description_1","done":true,"title":"todo_1"},"links":[{"created_at":"demo created_at","updated_at":"demo updated_at"}]}],"next_item":null}}"#
+            r#"{"output":{"items":[{"id":1,"attributes":{"description":"description_1","done":true,"title":"todo_1"},"links":[{"created_at":"demo created_at","updated_at":"demo updated_at"}]}],"next_item":null,"prev_item":null,"total":null}}"#
         );
     }
 
@@ -707,7 +852,7 @@
         "links": [
             { "ty": "timestamp" }
         ],
-        "pagination": { "limit": 10, "first_item": null, "order_by": [] }
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
     }
 }
 "#
@@ -716,7 +861,7 @@
             .await;
         pretty_assertions::assert_eq!(
             matching,
-            r#"{"output":{"items":[{"id":1,"attributes":{"description":null,"done":true,"title":"done_todo"},"links":[{"created_at":"demo created_at","updated_at":"demo updated_at"}]}],"next_item":null}}"#
+            r#"{"output":{"items":[{"id":1,"attributes":{"description":null,"done":true,"title":"done_todo"},"links":[{"created_at":"demo created_at","updated_at":"demo updated_at"}]}],"next_item":null,"prev_item":null,"total":null}}"#
         );
 
         let not_done = client
@@ -732,7 +877,7 @@
         "links": [
             { "ty": "timestamp" }
         ],
-        "pagination": { "limit": 10, "first_item": null, "order_by": [] }
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
     }
 }
 "#
@@ -741,7 +886,7 @@
             .await;
         pretty_assertions::assert_eq!(
             not_done,
-            r#"{"output":{"items":[{"id":2,"attributes":{"description":null,"done":false,"title":"open_todo"},"links":[{"created_at":"demo created_at","updated_at":"demo updated_at"}]}],"next_item":null}}"#
+            r#"{"output":{"items":[{"id":2,"attributes":{"description":null,"done":false,"title":"open_todo"},"links":[{"created_at":"demo created_at","updated_at":"demo updated_at"}]}],"next_item":null,"prev_item":null,"total":null}}"#
         );
     }
 
@@ -768,14 +913,17 @@
         "links": [
             { "ty": "timestamp" }
         ],
-        "pagination": { "limit": 10, "first_item": null, "order_by": [] }
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
     }
 }
 "#
                 .to_string(),
             )
             .await;
-        pretty_assertions::assert_eq!(result, r#"{"error":"InvalidFilter"}"#);
+        pretty_assertions::assert_eq!(
+            result,
+            r#"{"error":{"code":"invalid_filter","details":{},"message":"filter 0 can't be applied","path":["filters",0]}}"#
+        );
     }
 
     #[tokio::test(flavor = "current_thread")]
@@ -801,14 +949,17 @@
         "links": [
             { "ty": "timestamp" }
         ],
-        "pagination": { "limit": 10, "first_item": null, "order_by": [] }
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
     }
 }
 "#
                 .to_string(),
             )
             .await;
-        pretty_assertions::assert_eq!(result, r#"{"error":"InvalidFilter"}"#);
+        pretty_assertions::assert_eq!(
+            result,
+            r#"{"error":{"code":"invalid_filter","details":{},"message":"filter 0 can't be applied","path":["filters",0]}}"#
+        );
     }
 
     #[tokio::test(flavor = "current_thread")]
@@ -866,7 +1017,7 @@
             { "ty": "col_ne", "col": "done", "ne": true }
         ],
         "links": [{ "ty": "timestamp" }],
-        "pagination": { "limit": 10, "first_item": null, "order_by": [] }
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
     }
 }
 "#
@@ -875,7 +1026,7 @@
             .await;
         pretty_assertions::assert_eq!(
             not_done,
-            r#"{"output":{"items":[{"id":2,"attributes":{"description":null,"done":false,"title":"open_todo"},"links":[{"created_at":"demo created_at","updated_at":"demo updated_at"}]}],"next_item":null}}"#
+            r#"{"output":{"items":[{"id":2,"attributes":{"description":null,"done":false,"title":"open_todo"},"links":[{"created_at":"demo created_at","updated_at":"demo updated_at"}]}],"next_item":null,"prev_item":null,"total":null}}"#
         );
 
         let contains = client
@@ -889,7 +1040,7 @@
             { "ty": "col_contains", "col": "title", "value": "urgent" }
         ],
         "links": [{ "ty": "timestamp" }],
-        "pagination": { "limit": 10, "first_item": null, "order_by": [] }
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
     }
 }
 "#
@@ -898,7 +1049,7 @@
             .await;
         pretty_assertions::assert_eq!(
             contains,
-            r#"{"output":{"items":[{"id":1,"attributes":{"description":null,"done":true,"title":"urgent_todo"},"links":[{"created_at":"demo created_at","updated_at":"demo updated_at"}]}],"next_item":null}}"#
+            r#"{"output":{"items":[{"id":1,"attributes":{"description":null,"done":true,"title":"urgent_todo"},"links":[{"created_at":"demo created_at","updated_at":"demo updated_at"}]}],"next_item":null,"prev_item":null,"total":null}}"#
         );
 
         let composite = client
@@ -918,7 +1069,7 @@
             }
         ],
         "links": [{ "ty": "timestamp" }],
-        "pagination": { "limit": 10, "first_item": null, "order_by": [] }
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
     }
 }
 "#
@@ -927,7 +1078,83 @@
             .await;
         pretty_assertions::assert_eq!(
             composite,
-            r#"{"output":{"items":[{"id":1,"attributes":{"description":null,"done":true,"title":"urgent_todo"},"links":[{"created_at":"demo created_at","updated_at":"demo updated_at"}]},{"id":2,"attributes":{"description":null,"done":false,"title":"open_todo"},"links":[{"created_at":"demo created_at","updated_at":"demo updated_at"}]}],"next_item":null}}"#
+            r#"{"output":{"items":[{"id":1,"attributes":{"description":null,"done":true,"title":"urgent_todo"},"links":[{"created_at":"demo created_at","updated_at":"demo updated_at"}]},{"id":2,"attributes":{"description":null,"done":false,"title":"open_todo"},"links":[{"created_at":"demo created_at","updated_at":"demo updated_at"}]}],"next_item":null,"prev_item":null,"total":null}}"#
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn fetch_many_text_in_between_and_not_filters() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool);
+        let client = client.into_string_client();
+        let _executor = tokio::spawn(ex.run());
+
+        add_todo_collection(&client).await;
+
+        for body in [
+            r#"{ "op": "insert_one", "body": { "base": "todo", "data": { "title": "100%_done", "done": true }, "links": [] } }"#,
+            r#"{ "op": "insert_one", "body": { "base": "todo", "data": { "title": "100 done", "done": false }, "links": [] } }"#,
+            r#"{ "op": "insert_one", "body": { "base": "todo", "data": { "title": "Buy Milk", "done": false }, "links": [] } }"#,
+        ] {
+            client.exec(body.to_string()).await;
+        }
+
+        let ids = async |filter: &str| {
+            let output = client
+                .exec(format!(
+                    r#"{{ "op": "fetch_many", "body": {{ "base": "todo", "filters": [{filter}], "links": [], "pagination": {{ "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }} }} }}"#
+                ))
+                .await;
+            let output: serde_json::Value = serde_json::from_str(&output).unwrap();
+            output["output"]["items"]
+                .as_array()
+                .unwrap_or_else(|| panic!("{filter} failed: {output}"))
+                .iter()
+                .map(|item| item["id"].as_i64().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        // `%` and `_` are matched as is, not as wildcards
+        pretty_assertions::assert_eq!(
+            ids(r#"{ "ty": "col_contains", "col": "title", "value": "%_" }"#).await,
+            vec![1]
+        );
+        pretty_assertions::assert_eq!(
+            ids(r#"{ "ty": "col_starts_with", "col": "title", "value": "100" }"#).await,
+            vec![1, 2]
+        );
+        pretty_assertions::assert_eq!(
+            ids(r#"{ "ty": "col_ends_with", "col": "title", "value": "_done" }"#).await,
+            vec![1]
+        );
+        pretty_assertions::assert_eq!(
+            ids(r#"{ "ty": "col_eq_ignore_case", "col": "title", "eq": "buy milk" }"#).await,
+            vec![3]
+        );
+        pretty_assertions::assert_eq!(
+            ids(r#"{ "ty": "col_in", "col": "title", "values": ["Buy Milk", "100 done"] }"#)
+                .await,
+            vec![2, 3]
+        );
+        pretty_assertions::assert_eq!(
+            ids(r#"{ "ty": "col_between", "col": "title", "low": "1", "high": "2" }"#).await,
+            vec![1, 2]
+        );
+        pretty_assertions::assert_eq!(
+            ids(r#"{ "ty": "not", "filter": { "ty": "col_eq", "col": "done", "eq": true } }"#)
+                .await,
+            vec![2, 3]
+        );
+
+        let empty_in = client
+            .exec(
+                r#"{ "op": "fetch_many", "body": { "base": "todo", "filters": [{ "ty": "col_in", "col": "title", "values": [] }], "links": [], "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] } } }"#
+                    .to_string(),
+            )
+            .await;
+        pretty_assertions::assert_eq!(
+            empty_in,
+            r#"{"error":{"code":"invalid_filter","details":{},"message":"filter 0 can't be applied","path":["filters",0]}}"#
         );
     }
 
@@ -1017,7 +1244,7 @@
             }
         ],
         "links": [],
-        "pagination": { "limit": 10, "first_item": null, "order_by": [] }
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
     }
 }
 "#
@@ -1027,104 +1254,1720 @@
 
         pretty_assertions::assert_eq!(
             result,
-            r#"{"output":{"items":[{"id":2,"attributes":{"label":"high","priority":10,"score":9.9,"tags":["z"]},"links":[]}],"next_item":null}}"#
+            r#"{"output":{"items":[{"id":2,"attributes":{"label":"high","priority":10,"score":9.9,"tags":["z"]},"links":[]}],"next_item":null,"prev_item":null,"total":null}}"#
         );
     }
 
-    mod insert_one {
-        use sqlx::Sqlite;
-
-        use crate::{
-            connect_in_memory::ConnectInMemory, json_client::client_interface::Client,
-            track_sqlx_query::{assert_sql_eq, watch_sqlx_calls},
-        };
-
-        use crate::json_client::test_utilities::{
-            add_category_collection, add_todo_collection, setup_todo_with_category_link,
-            todo_is_one_to_many_with_category,
-        };
-
-        #[tokio::test(flavor = "current_thread")]
-        async fn set_id_links_existing_category() {
-            let pool = Sqlite::in_memory_pool().await;
-            let (client, ex) = Client::new_sqlx_db(pool);
-            let client = client.into_string_client();
-            let _executor = tokio::spawn(ex.run());
-
-            add_todo_collection(&client).await;
-            add_category_collection(&client).await;
-            todo_is_one_to_many_with_category(&client).await;
+    #[tokio::test(flavor = "current_thread")]
+    async fn add_collection_date_uuid_json_bytes_and_enum_types() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool);
+        let client = client.into_string_client();
+        let _executor = tokio::spawn(ex.run());
 
-            client
-                .exec(
-                    r#"
+        client
+            .exec(
+                r#"
 {
-    "op": "insert_one",
+    "op": "add_collection",
     "body": {
-        "base": "category",
-        "data": { "title": "existing_category" },
-        "links": []
+        "name": "event",
+        "fields": [
+            { "name": "at", "type_info": "DateTime", "is_optional": false },
+            { "name": "day", "type_info": "Date", "is_optional": false },
+            { "name": "key", "type_info": "Uuid", "is_optional": false },
+            { "name": "meta", "type_info": "Json", "is_optional": true },
+            { "name": "blob", "type_info": "Bytes", "is_optional": true },
+            { "name": "status", "type_info": { "ty": "Enum", "variants": ["open", "closed"] }, "is_optional": false }
+        ]
     }
 }
 "#
-                    .to_string(),
-                )
-                .await;
+                .to_string(),
+            )
+            .await;
 
-            let result = client
-                .exec(
-                    r#"
+        let inserted = client
+            .exec(
+                r#"
 {
     "op": "insert_one",
     "body": {
-        "base": "todo",
+        "base": "event",
         "data": {
-            "title": "todo_with_category",
-            "done": true,
-            "description": "linked"
+            "at": "2024-01-01T01:30:00+02:00",
+            "day": "2024-02-29",
+            "key": "6F9619FF-8B86-D011-B42D-00C04FC964FF",
+            "meta": {"a":[1,2]},
+            "blob": "aGVsbG8=",
+            "status": "open"
         },
-        "links": [
-            { "ty": "set_id", "to": "category", "id": 1 }
-        ]
+        "links": []
     }
 }
 "#
-                    .to_string(),
-                )
-                .await;
-
-            pretty_assertions::assert_eq!(
-                result,
-                r#"{"output":{"id":1,"attributes":{"description":"linked","done":true,"title":"todo_with_category"},"links":[{"id":1,"attributes":{"title":"existing_category"}}]}}"#
-            );
-        }
-
-        #[tokio::test(flavor = "current_thread")]
-        async fn set_new_creates_category_and_links() {
-            let pool = Sqlite::in_memory_pool().await;
-            let (client, ex) = Client::new_sqlx_db(pool);
-            let client = client.into_string_client();
-            let _executor = tokio::spawn(ex.run());
+                .to_string(),
+            )
+            .await;
 
-            add_todo_collection(&client).await;
-            add_category_collection(&client).await;
-            todo_is_one_to_many_with_category(&client).await;
+        pretty_assertions::assert_eq!(
+            inserted,
+            r#"{"output":{"id":1,"attributes":{"at":"2023-12-31T23:30:00.000000Z","blob":"aGVsbG8=","day":"2024-02-29","key":"6f9619ff-8b86-d011-b42d-00c04fc964ff","meta":{"a":[1,2]},"status":"open"},"links":[]}}"#
+        );
 
-            let result = client
-                .exec(
-                    r#"
+        client
+            .exec(
+                r#"
 {
     "op": "insert_one",
     "body": {
-        "base": "todo",
+        "base": "event",
         "data": {
-            "title": "todo_with_new_category",
-            "done": false,
-            "description": "set_new"
+            "at": "2024-01-01T00:00:00Z",
+            "day": "2024-01-01",
+            "key": "00000000-0000-0000-0000-000000000000",
+            "meta": null,
+            "blob": null,
+            "status": "closed"
         },
-        "links": [
-            { "ty": "set_new", "to": "category", "value": { "title": "new_category" } }
-        ]
+        "links": []
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        let unknown_variant = client
+            .exec(
+                r#"
+{
+    "op": "insert_one",
+    "body": {
+        "base": "event",
+        "data": {
+            "at": "2024-01-01T00:00:00Z",
+            "day": "2024-01-01",
+            "key": "00000000-0000-0000-0000-000000000000",
+            "status": "archived"
+        },
+        "links": []
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            unknown_variant,
+            r#"{"error":{"code":"invalid_data","details":{"field":"status","rule":"one_of"},"message":"status breaks the rule one_of","path":["data","status"]}}"#
+        );
+
+        let invalid_date = client
+            .exec(
+                r#"
+{
+    "op": "insert_one",
+    "body": {
+        "base": "event",
+        "data": {
+            "at": "2024-01-01T00:00:00Z",
+            "day": "2023-02-29",
+            "key": "00000000-0000-0000-0000-000000000000",
+            "status": "open"
+        },
+        "links": []
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            invalid_date,
+            r#"{"error":{"code":"invalid_data","details":{"field":"day","rule":"type"},"message":"day breaks the rule type","path":["data","day"]}}"#
+        );
+
+        // compared in UTC, the first event happened before 2024
+        let after_new_year = client
+            .exec(
+                r#"
+{
+    "op": "fetch_many",
+    "body": {
+        "base": "event",
+        "filters": [
+            { "ty": "col_gte", "col": "at", "gte": "2024-01-01T00:00:00Z" }
+        ],
+        "links": [],
+        "pagination": {
+            "ty": "cursor",
+            "limit": 10,
+            "cursor": null,
+            "order_by": [{ "ty": "col", "col": "day", "direction": "asc" }]
+        }
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            after_new_year,
+            r#"{"output":{"items":[{"id":2,"attributes":{"at":"2024-01-01T00:00:00.000000Z","blob":null,"day":"2024-01-01","key":"00000000-0000-0000-0000-000000000000","meta":null,"status":"closed"},"links":[]}],"next_item":null,"prev_item":null,"total":null}}"#
+        );
+
+        let json_gt = client
+            .exec(
+                r#"
+{
+    "op": "fetch_many",
+    "body": {
+        "base": "event",
+        "filters": [{ "ty": "col_gt", "col": "meta", "gt": 1 }],
+        "links": [],
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            json_gt,
+            r#"{"error":{"code":"invalid_filter","details":{},"message":"filter 0 can't be applied","path":["filters",0]}}"#
+        );
+
+        let json_order = client
+            .exec(
+                r#"
+{
+    "op": "fetch_many",
+    "body": {
+        "base": "event",
+        "filters": [],
+        "links": [],
+        "pagination": {
+            "ty": "cursor",
+            "limit": 10,
+            "cursor": null,
+            "order_by": [{ "ty": "col", "col": "meta", "direction": "asc" }]
+        }
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            json_order,
+            r#"{"error":{"code":"invalid_order_by","details":{},"message":"order by 0 can't be applied","path":["pagination","order_by",0]}}"#
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn field_rules_are_checked_on_insert_and_update() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool);
+        let client = client.into_string_client();
+        let _executor = tokio::spawn(ex.run());
+
+        let rule_on_wrong_type = client
+            .exec(
+                r#"
+{
+    "op": "add_collection",
+    "body": {
+        "name": "account",
+        "fields": [
+            { "name": "handle", "type_info": "String", "is_optional": false, "rules": [{ "ty": "range", "min": 0, "max": 10 }] }
+        ]
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            rule_on_wrong_type,
+            r#"{"error":{"code":"invalid_collection_input","details":{"collection":"account"},"message":"collection account has invalid fields or rules","path":["fields"]}}"#
+        );
+
+        client
+            .exec(
+                r#"
+{
+    "op": "add_collection",
+    "body": {
+        "name": "account",
+        "fields": [
+            {
+                "name": "handle",
+                "type_info": "String",
+                "is_optional": false,
+                "rules": [
+                    { "ty": "min_length", "value": 3 },
+                    { "ty": "max_length", "value": 8 },
+                    { "ty": "pattern", "regex": "[a-z_]+" },
+                    { "ty": "unique" }
+                ]
+            },
+            { "name": "age", "type_info": "Int", "is_optional": true, "rules": [{ "ty": "range", "min": 0, "max": 150 }] },
+            { "name": "role", "type_info": "String", "is_optional": false, "rules": [{ "ty": "one_of", "values": ["admin", "member"] }] }
+        ]
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        let inserted = client
+            .exec(
+                r#"{ "op": "insert_one", "body": { "base": "account", "data": { "handle": "ann", "age": 30, "role": "admin" }, "links": [] } }"#
+                    .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            inserted,
+            r#"{"output":{"id":1,"attributes":{"age":30,"handle":"ann","role":"admin"},"links":[]}}"#
+        );
+
+        client
+            .exec(
+                r#"{ "op": "insert_one", "body": { "base": "account", "data": { "handle": "bob", "age": null, "role": "member" }, "links": [] } }"#
+                    .to_string(),
+            )
+            .await;
+
+        let rejected = [
+            (r#"{ "handle": "al", "role": "admin" }"#, "handle", "min_length"),
+            (r#"{ "handle": "alexander", "role": "admin" }"#, "handle", "max_length"),
+            (r#"{ "handle": "Al-1", "role": "admin" }"#, "handle", "pattern"),
+            (r#"{ "handle": "cat", "age": 151, "role": "admin" }"#, "age", "range"),
+            (r#"{ "handle": "cat", "role": "owner" }"#, "role", "one_of"),
+            (r#"{ "handle": "cat", "role": 1 }"#, "role", "type"),
+            (r#"{ "handle": "cat", "role": "admin", "email": "" }"#, "email", "unknown_field"),
+            (r#"{ "handle": "cat" }"#, "role", "required"),
+            (r#"{ "handle": "ann", "role": "admin" }"#, "handle", "unique"),
+        ];
+
+        for (data, field, rule) in rejected {
+            let result = client
+                .exec(format!(
+                    r#"{{ "op": "insert_one", "body": {{ "base": "account", "data": {data}, "links": [] }} }}"#
+                ))
+                .await;
+
+            pretty_assertions::assert_eq!(
+                result,
+                format!(
+                    r#"{{"error":{{"code":"invalid_data","details":{{"field":"{field}","rule":"{rule}"}},"message":"{field} breaks the rule {rule}","path":["data","{field}"]}}}}"#
+                )
+            );
+        }
+
+        let taken_by_other = client
+            .exec(
+                r#"{ "op": "update_one", "body": { "base": "account", "id": 2, "data": { "handle": "ann" }, "links": [] } }"#
+                    .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            taken_by_other,
+            r#"{"error":{"code":"invalid_data","details":{"field":"handle","rule":"unique"},"message":"handle breaks the rule unique","path":["data","handle"]}}"#
+        );
+
+        let kept_own = client
+            .exec(
+                r#"{ "op": "update_one", "body": { "base": "account", "id": 1, "data": { "handle": "ann", "age": 31 }, "links": [] } }"#
+                    .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            kept_own,
+            r#"{"output":{"id":1,"attributes":{"age":31,"handle":"ann","role":"admin"},"links":[]}}"#
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn add_collection_creates_declared_indexes() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool.clone());
+        let client = client.into_string_client();
+        let _executor = tokio::spawn(ex.run());
+
+        let unknown_field = client
+            .exec(
+                r#"
+{
+    "op": "add_collection",
+    "body": {
+        "name": "member",
+        "fields": [{ "name": "email", "type_info": "String", "is_optional": false }],
+        "indexes": [{ "fields": ["handle"], "unique": false }]
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            unknown_field,
+            r#"{"error":{"code":"invalid_collection_input","details":{"collection":"member"},"message":"collection member has invalid fields or rules","path":["fields"]}}"#
+        );
+
+        let added = client
+            .exec(
+                r#"
+{
+    "op": "add_collection",
+    "body": {
+        "name": "member",
+        "fields": [
+            { "name": "email", "type_info": "String", "is_optional": false },
+            { "name": "team", "type_info": "String", "is_optional": false },
+            { "name": "nickname", "type_info": "String", "is_optional": true }
+        ],
+        "indexes": [
+            { "fields": ["team", "email"], "unique": true },
+            { "fields": ["nickname"], "unique": false, "where_not_null": ["nickname"] }
+        ]
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(added, r#"{"output":null}"#);
+
+        let indexes: Vec<(String, String)> = sqlx::query_as(
+            "SELECT name, sql FROM sqlite_master WHERE type = 'index' AND tbl_name = 'Member' ORDER BY name",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+
+        pretty_assertions::assert_eq!(
+            indexes,
+            vec![
+                (
+                    "idx_member_0".to_string(),
+                    r#"CREATE UNIQUE INDEX "idx_member_0" ON "Member" ("team", "email")"#
+                        .to_string()
+                ),
+                (
+                    "idx_member_1".to_string(),
+                    r#"CREATE INDEX "idx_member_1" ON "Member" ("nickname") WHERE "nickname" IS NOT NULL"#
+                        .to_string()
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn add_collection_creates_checks_and_defaults() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool.clone());
+        let client = client.into_string_client();
+        let _executor = tokio::spawn(ex.run());
+
+        for invalid in [
+            // a pattern can't be a `CHECK`
+            r#"{ "name": "code", "type_info": "String", "is_optional": false, "rules": [{ "ty": "check", "rule": { "ty": "pattern", "regex": "[a-z]+" } }] }"#,
+            // the default breaks a rule of the field
+            r#"{ "name": "code", "type_info": "String", "is_optional": false, "rules": [{ "ty": "min_length", "value": 3 }], "default": "a" }"#,
+            r#"{ "name": "code", "type_info": "Int", "is_optional": false, "rules": [], "default": "a" }"#,
+        ] {
+            let result = client
+                .exec(format!(
+                    r#"{{ "op": "add_collection", "body": {{ "name": "product", "fields": [{invalid}] }} }}"#
+                ))
+                .await;
+
+            pretty_assertions::assert_eq!(
+                result,
+                r#"{"error":{"code":"invalid_collection_input","details":{"collection":"product"},"message":"collection product has invalid fields or rules","path":["fields"]}}"#
+            );
+        }
+
+        let added = client
+            .exec(
+                r#"
+{
+    "op": "add_collection",
+    "body": {
+        "name": "product",
+        "fields": [
+            {
+                "name": "title",
+                "type_info": "String",
+                "is_optional": false,
+                "rules": [{ "ty": "check", "rule": { "ty": "min_length", "value": 1 } }]
+            },
+            {
+                "name": "price",
+                "type_info": "Float64",
+                "is_optional": true,
+                "rules": [{ "ty": "check", "rule": { "ty": "range", "min": 0, "max": null } }]
+            },
+            {
+                "name": "status",
+                "type_info": "String",
+                "is_optional": false,
+                "rules": [{ "ty": "check", "rule": { "ty": "one_of", "values": ["draft", "live"] } }],
+                "default": "draft"
+            },
+            { "name": "stock", "type_info": "Int", "is_optional": false, "rules": [], "default": 0 }
+        ]
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(added, r#"{"output":null}"#);
+
+        let table: String =
+            sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'Product'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+
+        pretty_assertions::assert_eq!(
+            table,
+            r#"CREATE TABLE "Product" ("id" INTEGER PRIMARY KEY AUTOINCREMENT, "title" TEXT NOT NULL CONSTRAINT "check_title_0" CHECK (LENGTH("title") >= 1), "price" REAL CONSTRAINT "check_price_0" CHECK ("price" >= 0.0), "status" TEXT NOT NULL DEFAULT ('draft') CONSTRAINT "check_status_0" CHECK ("status" IN ('draft', 'live')), "stock" INTEGER NOT NULL DEFAULT (0))"#
+        );
+
+        client
+            .exec(
+                r#"{ "op": "insert_one", "body": { "base": "product", "data": { "title": "pen" }, "links": [] } }"#
+                    .to_string(),
+            )
+            .await;
+
+        let stored: (String, Option<f64>, String, i64) =
+            sqlx::query_as("SELECT title, price, status, stock FROM Product WHERE id = 1")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        pretty_assertions::assert_eq!(stored, ("pen".to_string(), None, "draft".to_string(), 0));
+
+        let rejected = [
+            (r#"{ "title": "" }"#, "title"),
+            (r#"{ "title": "ink", "price": -1 }"#, "price"),
+            (r#"{ "title": "ink", "status": "sold" }"#, "status"),
+        ];
+
+        for (data, field) in rejected {
+            let result = client
+                .exec(format!(
+                    r#"{{ "op": "insert_one", "body": {{ "base": "product", "data": {data}, "links": [] }} }}"#
+                ))
+                .await;
+
+            pretty_assertions::assert_eq!(
+                result,
+                format!(
+                    r#"{{"error":{{"code":"invalid_data","details":{{"field":"{field}","rule":"check"}},"message":"{field} breaks the rule check","path":["data","{field}"]}}}}"#
+                )
+            );
+        }
+
+        let updated = client
+            .exec(
+                r#"{ "op": "update_one", "body": { "base": "product", "id": 1, "data": { "status": "sold" }, "links": [] } }"#
+                    .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            updated,
+            r#"{"error":{"code":"invalid_data","details":{"field":"status","rule":"check"},"message":"status breaks the rule check","path":["data","status"]}}"#
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn add_collection_creates_read_only_views() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool.clone());
+        let client = client.into_string_client();
+        let _executor = tokio::spawn(ex.run());
+
+        add_todo_collection(&client).await;
+
+        for (title, done) in [("first", false), ("second", true), ("third", false)] {
+            client
+                .exec(format!(
+                    r#"{{ "op": "insert_one", "body": {{ "base": "todo", "data": {{ "title": "{title}", "done": {done} }}, "links": [] }} }}"#
+                ))
+                .await;
+        }
+
+        let title = r#"{ "name": "title", "type_info": "String", "is_optional": false }"#;
+        for (fields, indexes, view) in [
+            // the select leaves out a field
+            (title, "[]", "SELECT id FROM Todo"),
+            // the select doesn't run
+            (title, "[]", "SELECT id, title FROM Missing"),
+            // the view is the only statement
+            (title, "[]", "SELECT id, title FROM Todo; DELETE FROM Todo"),
+            // views are never written
+            (title, r#"[{ "fields": ["title"], "unique": true }]"#, "SELECT id, title FROM Todo"),
+            (
+                r#"{ "name": "title", "type_info": "String", "is_optional": false, "rules": [{ "ty": "min_length", "value": 1 }] }"#,
+                "[]",
+                "SELECT id, title FROM Todo",
+            ),
+        ] {
+            let result = client
+                .exec(format!(
+                    r#"{{ "op": "add_collection", "body": {{ "name": "open_todo", "fields": [{fields}], "indexes": {indexes}, "view": "{view}" }} }}"#
+                ))
+                .await;
+
+            pretty_assertions::assert_eq!(
+                result,
+                r#"{"error":{"code":"invalid_collection_input","details":{"collection":"open_todo"},"message":"collection open_todo has invalid fields or rules","path":["fields"]}}"#
+            );
+        }
+
+        let views: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'view'")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        pretty_assertions::assert_eq!(views, Vec::<String>::new());
+        let todos: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM Todo")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        pretty_assertions::assert_eq!(todos, 3);
+
+        let added = client
+            .exec(
+                r#"
+{
+    "op": "add_collection",
+    "body": {
+        "name": "open_todo",
+        "fields": [{ "name": "title", "type_info": "String", "is_optional": false }],
+        "indexes": [],
+        "view": "SELECT id, title FROM Todo WHERE done = 0;"
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(added, r#"{"output":null}"#);
+
+        let view: String =
+            sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE type = 'view' AND name = 'OpenTodo'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        pretty_assertions::assert_eq!(
+            view,
+            r#"CREATE VIEW "OpenTodo" AS SELECT id, title FROM Todo WHERE done = 0"#
+        );
+
+        let open = client
+            .exec(
+                r#"
+{
+    "op": "fetch_many",
+    "body": {
+        "base": "open_todo",
+        "filters": [],
+        "links": [],
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            open,
+            r#"{"output":{"items":[{"id":1,"attributes":{"title":"first"},"links":[]},{"id":3,"attributes":{"title":"third"},"links":[]}],"next_item":null,"prev_item":null,"total":null}}"#
+        );
+
+        let writes = [
+            r#"{ "op": "insert_one", "body": { "base": "open_todo", "data": { "title": "fourth" }, "links": [] } }"#,
+            r#"{ "op": "insert_many", "body": { "base": "open_todo", "items": [{ "data": { "title": "fourth" }, "links": [] }] } }"#,
+            r#"{ "op": "update_one", "body": { "base": "open_todo", "id": 1, "data": { "title": "renamed" }, "links": [] } }"#,
+            r#"{ "op": "delete_one", "body": { "base": "open_todo", "id": 1, "links": [] } }"#,
+            r#"{ "op": "add_link", "body": { "ty": "soft_delete", "collection": "open_todo" } }"#,
+        ];
+
+        for write in writes {
+            let result = client.exec(write.to_string()).await;
+
+            pretty_assertions::assert_eq!(
+                result,
+                r#"{"error":{"code":"read_only","details":{"collection":"open_todo"},"message":"collection open_todo is a view, it can't be written","path":[]}}"#
+            );
+        }
+
+        let first: String = sqlx::query_scalar("SELECT title FROM Todo WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        pretty_assertions::assert_eq!(first, "first");
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn fetch_many_any_and_all_link_filters() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool);
+        let client = client.into_string_client();
+        let _executor = tokio::spawn(ex.run());
+
+        add_todo_collection(&client).await;
+        add_category_collection(&client).await;
+        todo_is_one_to_many_with_category(&client).await;
+
+        for category in ["work", "home"] {
+            client
+                .exec(format!(
+                    r#"{{ "op": "insert_one", "body": {{ "base": "category", "data": {{ "title": "{category}" }}, "links": [] }} }}"#
+                ))
+                .await;
+        }
+
+        for (title, category) in [("todo_1", 1), ("todo_2", 2)] {
+            client
+                .exec(format!(
+                    r#"{{ "op": "insert_one", "body": {{ "base": "todo", "data": {{ "title": "{title}", "done": false }}, "links": [{{ "ty": "set_id", "to": "category", "id": {category} }}] }} }}"#
+                ))
+                .await;
+        }
+
+        let any = client
+            .exec(
+                r#"
+{
+    "op": "fetch_many",
+    "body": {
+        "base": "todo",
+        "filters": [
+            {
+                "ty": "any",
+                "link": { "ty": "optional_to_many", "to": "category" },
+                "filters": [{ "ty": "col_eq", "col": "title", "eq": "work" }]
+            }
+        ],
+        "links": [],
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            any,
+            r#"{"output":{"items":[{"id":1,"attributes":{"description":null,"done":false,"title":"todo_1"},"links":[]}],"next_item":null,"prev_item":null,"total":null}}"#
+        );
+
+        let all = client
+            .exec(
+                r#"
+{
+    "op": "fetch_many",
+    "body": {
+        "base": "category",
+        "filters": [
+            {
+                "ty": "all",
+                "link": { "ty": "optional_to_many", "to": "todo" },
+                "filters": [{ "ty": "col_eq", "col": "title", "eq": "todo_2" }]
+            }
+        ],
+        "links": [],
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            all,
+            r#"{"output":{"items":[{"id":2,"attributes":{"title":"home"},"links":[]}],"next_item":null,"prev_item":null,"total":null}}"#
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn fetch_many_order_by_link_col_and_link_count() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool);
+        let client = client.into_string_client();
+        let _executor = tokio::spawn(ex.run());
+
+        add_todo_collection(&client).await;
+        add_category_collection(&client).await;
+        todo_is_one_to_many_with_category(&client).await;
+
+        for category in ["work", "home"] {
+            client
+                .exec(format!(
+                    r#"{{ "op": "insert_one", "body": {{ "base": "category", "data": {{ "title": "{category}" }}, "links": [] }} }}"#
+                ))
+                .await;
+        }
+
+        for (title, category) in [("todo_1", 1), ("todo_2", 2), ("todo_3", 2)] {
+            client
+                .exec(format!(
+                    r#"{{ "op": "insert_one", "body": {{ "base": "todo", "data": {{ "title": "{title}", "done": false }}, "links": [{{ "ty": "set_id", "to": "category", "id": {category} }}] }} }}"#
+                ))
+                .await;
+        }
+
+        let first_page = client
+            .exec(
+                r#"
+{
+    "op": "fetch_many",
+    "body": {
+        "base": "todo",
+        "filters": [],
+        "links": [],
+        "pagination": {
+            "ty": "cursor",
+            "limit": 2,
+            "cursor": null,
+            "order_by": [
+                {
+                    "ty": "link_col",
+                    "link": { "ty": "optional_to_many", "to": "category" },
+                    "col": "title",
+                    "direction": "asc"
+                }
+            ]
+        }
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        let next_item = first_page
+            .split(r#""next_item":""#)
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+            .unwrap()
+            .to_string();
+        assert!(next_item.starts_with("v1."));
+
+        pretty_assertions::assert_eq!(
+            first_page,
+            format!(
+                r#"{{"output":{{"items":[{{"id":2,"attributes":{{"description":null,"done":false,"title":"todo_2"}},"links":[]}},{{"id":3,"attributes":{{"description":null,"done":false,"title":"todo_3"}},"links":[]}}],"next_item":"{next_item}","prev_item":null,"total":null}}}}"#
+            )
+        );
+
+        let second_page = client
+            .exec(format!(
+                r#"
+{{
+    "op": "fetch_many",
+    "body": {{
+        "base": "todo",
+        "filters": [],
+        "links": [],
+        "pagination": {{
+            "ty": "cursor",
+            "limit": 2,
+            "cursor": "{next_item}",
+            "order_by": [
+                {{
+                    "ty": "link_col",
+                    "link": {{ "ty": "optional_to_many", "to": "category" }},
+                    "col": "title",
+                    "direction": "asc"
+                }}
+            ]
+        }}
+    }}
+}}
+"#
+            ))
+            .await;
+
+        let prev_item = second_page
+            .split(r#""prev_item":""#)
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+            .unwrap()
+            .to_string();
+
+        pretty_assertions::assert_eq!(
+            second_page,
+            format!(
+                r#"{{"output":{{"items":[{{"id":1,"attributes":{{"description":null,"done":false,"title":"todo_1"}},"links":[]}}],"next_item":null,"prev_item":"{prev_item}","total":null}}}}"#
+            )
+        );
+
+        let back_to_first_page = client
+            .exec(format!(
+                r#"
+{{
+    "op": "fetch_many",
+    "body": {{
+        "base": "todo",
+        "filters": [],
+        "links": [],
+        "pagination": {{
+            "ty": "cursor",
+            "limit": 2,
+            "cursor": "{prev_item}",
+            "order_by": [
+                {{
+                    "ty": "link_col",
+                    "link": {{ "ty": "optional_to_many", "to": "category" }},
+                    "col": "title",
+                    "direction": "asc"
+                }}
+            ]
+        }}
+    }}
+}}
+"#
+            ))
+            .await;
+
+        pretty_assertions::assert_eq!(back_to_first_page, first_page);
+
+        let tampered = next_item.replacen("v1.", "v1.e", 1);
+        let tampered_page = client
+            .exec(format!(
+                r#"
+{{
+    "op": "fetch_many",
+    "body": {{
+        "base": "todo",
+        "filters": [],
+        "links": [],
+        "pagination": {{
+            "ty": "cursor",
+            "limit": 2,
+            "cursor": "{tampered}",
+            "order_by": [
+                {{
+                    "ty": "link_col",
+                    "link": {{ "ty": "optional_to_many", "to": "category" }},
+                    "col": "title",
+                    "direction": "asc"
+                }}
+            ]
+        }}
+    }}
+}}
+"#
+            ))
+            .await;
+
+        pretty_assertions::assert_eq!(
+            tampered_page,
+            r#"{"error":{"code":"invalid_cursor","details":{},"message":"the cursor wasn't issued for this base and order by","path":["pagination","cursor"]}}"#
+        );
+
+        let by_count = client
+            .exec(
+                r#"
+{
+    "op": "fetch_many",
+    "body": {
+        "base": "category",
+        "filters": [],
+        "links": [],
+        "pagination": {
+            "ty": "cursor",
+            "limit": 10,
+            "cursor": null,
+            "order_by": [
+                {
+                    "ty": "link_count",
+                    "link": { "ty": "optional_to_many", "to": "todo" },
+                    "direction": "asc"
+                }
+            ]
+        }
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            by_count,
+            r#"{"output":{"items":[{"id":1,"attributes":{"title":"work"},"links":[]},{"id":2,"attributes":{"title":"home"},"links":[]}],"next_item":null,"prev_item":null,"total":null}}"#
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn fetch_many_offset_pagination_with_total() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool);
+        let client = client.into_string_client();
+        let _executor = tokio::spawn(ex.with_max_page_size(5).run());
+
+        add_todo_collection(&client).await;
+
+        for title in ["todo_c", "todo_a", "todo_b"] {
+            client
+                .exec(format!(
+                    r#"{{ "op": "insert_one", "body": {{ "base": "todo", "data": {{ "title": "{title}", "done": false }}, "links": [] }} }}"#
+                ))
+                .await;
+        }
+
+        let second_page = client
+            .exec(
+                r#"
+{
+    "op": "fetch_many",
+    "body": {
+        "base": "todo",
+        "filters": [],
+        "links": [],
+        "pagination": {
+            "ty": "offset",
+            "page": 1,
+            "page_size": 2,
+            "count_total": true,
+            "order_by": [
+                { "ty": "col", "col": "title", "direction": "asc" }
+            ]
+        }
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            second_page,
+            r#"{"output":{"items":[{"id":1,"attributes":{"description":null,"done":false,"title":"todo_c"},"links":[]}],"next_item":null,"prev_item":null,"total":3}}"#
+        );
+
+        let too_large = client
+            .exec(
+                r#"
+{
+    "op": "fetch_many",
+    "body": {
+        "base": "todo",
+        "filters": [],
+        "links": [],
+        "pagination": {
+            "ty": "offset",
+            "page": 0,
+            "page_size": 6,
+            "count_total": false,
+            "order_by": []
+        }
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            too_large,
+            r#"{"error":{"code":"invalid_page_size","details":{"max":5},"message":"page sizes are between 0 and 5, pages start at 0","path":["pagination"]}}"#
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn owner_policy_limits_callers_to_their_records() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool);
+        let client = client.into_string_client();
+        let owner = RowRule::Owner {
+            col: Arc::from("owner_id"),
+        };
+        let _executor = tokio::spawn(
+            ex.with_policy(
+                "note",
+                CollectionPolicy {
+                    read: owner.clone(),
+                    write: owner,
+                },
+            )
+            .run(),
+        );
+
+        client
+            .exec(
+                r#"
+{
+    "op": "add_collection",
+    "body": {
+        "name": "note",
+        "fields": [
+            { "name": "body", "type_info": "String", "is_optional": false },
+            { "name": "owner_id", "type_info": "Int", "is_optional": false }
+        ]
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+        client
+            .exec(
+                r#"{ "op": "insert_one", "body": { "base": "note", "data": { "body": "theirs", "owner_id": 2 }, "links": [] } }"#
+                    .to_string(),
+            )
+            .await;
+
+        let user = client.with_caller(Caller::User(1));
+
+        let mine = user
+            .exec(
+                r#"{ "op": "insert_one", "body": { "base": "note", "data": { "body": "mine", "owner_id": 1 }, "links": [] } }"#
+                    .to_string(),
+            )
+            .await;
+        pretty_assertions::assert_eq!(
+            mine,
+            r#"{"output":{"id":2,"attributes":{"body":"mine","owner_id":1},"links":[]}}"#
+        );
+
+        let for_someone_else = user
+            .exec(
+                r#"{ "op": "insert_one", "body": { "base": "note", "data": { "body": "forged", "owner_id": 2 }, "links": [] } }"#
+                    .to_string(),
+            )
+            .await;
+        pretty_assertions::assert_eq!(
+            for_someone_else,
+            r#"{"error":{"code":"forbidden","details":{"collection":"note"},"message":"the caller can't access note","path":[]}}"#
+        );
+
+        let fetched = user
+            .exec(
+                r#"
+{
+    "op": "fetch_many",
+    "body": {
+        "base": "note",
+        "filters": [],
+        "links": [],
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+        pretty_assertions::assert_eq!(
+            fetched,
+            r#"{"output":{"items":[{"id":2,"attributes":{"body":"mine","owner_id":1},"links":[]}],"next_item":null,"prev_item":null,"total":null}}"#
+        );
+
+        let updated = user
+            .exec(
+                r#"{ "op": "update_one", "body": { "base": "note", "id": 1, "data": { "body": "changed" }, "links": [] } }"#
+                    .to_string(),
+            )
+            .await;
+        pretty_assertions::assert_eq!(
+            updated,
+            r#"{"error":{"code":"not_found","details":{"id":1},"message":"no record with id 1","path":["id"]}}"#
+        );
+
+        let deleted = user
+            .exec(r#"{ "op": "delete_one", "body": { "base": "note", "id": 1, "links": [] } }"#.to_string())
+            .await;
+        pretty_assertions::assert_eq!(
+            deleted,
+            r#"{"error":{"code":"not_found","details":{"id":1},"message":"no record with id 1","path":["id"]}}"#
+        );
+
+        let anonymous = client
+            .with_caller(Caller::Anonymous)
+            .exec(
+                r#"{ "op": "fetch_one", "body": { "base": "note", "id": 2, "filters": [], "links": [] } }"#
+                    .to_string(),
+            )
+            .await;
+        pretty_assertions::assert_eq!(
+            anonymous,
+            r#"{"error":{"code":"forbidden","details":{"collection":"note"},"message":"the caller can't access note","path":[]}}"#
+        );
+
+        let schema_change = user
+            .exec(
+                r#"{ "op": "add_collection", "body": { "name": "secret", "fields": [] } }"#
+                    .to_string(),
+            )
+            .await;
+        pretty_assertions::assert_eq!(
+            schema_change,
+            r#"{"error":{"code":"forbidden","details":{"collection":"secret"},"message":"the caller can't access secret","path":[]}}"#
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn hooks_change_or_abort_writes() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool);
+        let client = client.into_string_client();
+        let deleted = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let deleted_by_hook = Arc::clone(&deleted);
+        let ex = ex
+            .before_insert("todo", |_, data| {
+                Box::pin(async move {
+                    data.insert(
+                        ArcSubStr::new(Arc::from("description"), 0..11),
+                        Box::new(String::from("set by hook")),
+                    );
+                    Ok(())
+                })
+            })
+            .before_update("todo", |_, _, data| {
+                Box::pin(async move {
+                    if data.0.keys().any(|key| key.as_str() == "title") {
+                        return Err(String::from("title is frozen"));
+                    }
+                    Ok(())
+                })
+            })
+            .after_delete("todo", move |_, id, _| {
+                let deleted = Arc::clone(&deleted_by_hook);
+                Box::pin(async move {
+                    deleted.lock().unwrap().push(id);
+                    Ok(())
+                })
+            });
+        let _executor = tokio::spawn(ex.run());
+
+        add_todo_collection(&client).await;
+
+        let inserted = client
+            .exec(
+                r#"{ "op": "insert_one", "body": { "base": "todo", "data": { "title": "first", "done": false }, "links": [] } }"#
+                    .to_string(),
+            )
+            .await;
+        pretty_assertions::assert_eq!(
+            inserted,
+            r#"{"output":{"id":1,"attributes":{"description":"set by hook","done":false,"title":"first"},"links":[]}}"#
+        );
+
+        let frozen = client
+            .exec(
+                r#"{ "op": "update_one", "body": { "base": "todo", "id": 1, "data": { "title": "renamed" }, "links": [] } }"#
+                    .to_string(),
+            )
+            .await;
+        pretty_assertions::assert_eq!(
+            frozen,
+            r#"{"error":{"code":"aborted","details":{"collection":"todo","reason":"title is frozen"},"message":"a hook of todo aborted: title is frozen","path":[]}}"#
+        );
+
+        client
+            .exec(r#"{ "op": "delete_one", "body": { "base": "todo", "id": 1, "links": [] } }"#.to_string())
+            .await;
+        pretty_assertions::assert_eq!(*deleted.lock().unwrap(), vec![1]);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn soft_delete_hides_and_restores_records() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool);
+        let client = client.into_string_client();
+        let _executor = tokio::spawn(ex.run());
+
+        add_todo_collection(&client).await;
+
+        client
+            .exec(
+                r#"{ "op": "add_link", "body": { "ty": "soft_delete", "collection": "todo" } }"#
+                    .to_string(),
+            )
+            .await;
+
+        for body in [
+            r#"{ "op": "insert_one", "body": { "base": "todo", "data": { "title": "first", "done": false }, "links": [] } }"#,
+            r#"{ "op": "insert_one", "body": { "base": "todo", "data": { "title": "second", "done": true }, "links": [] } }"#,
+        ] {
+            client.exec(body.to_string()).await;
+        }
+
+        let deleted = client
+            .exec(
+                r#"{ "op": "delete_one", "body": { "base": "todo", "id": 1, "links": [] } }"#
+                    .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            deleted,
+            r#"{"output":{"id":1,"attributes":{"description":null,"done":false,"title":"first"},"links":[]}}"#
+        );
+
+        let remaining = client
+            .exec(
+                r#"
+{
+    "op": "fetch_many",
+    "body": {
+        "base": "todo",
+        "filters": [],
+        "links": [],
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
+    }
+}
+"#
+                .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            remaining,
+            r#"{"output":{"items":[{"id":2,"attributes":{"description":null,"done":true,"title":"second"},"links":[]}],"next_item":null,"prev_item":null,"total":null}}"#
+        );
+
+        for body in [
+            r#"{ "op": "fetch_one", "body": { "base": "todo", "id": 1, "filters": [], "links": [] } }"#,
+            r#"{ "op": "update_one", "body": { "base": "todo", "id": 1, "data": { "done": true }, "links": [] } }"#,
+        ] {
+            pretty_assertions::assert_eq!(
+                client.exec(body.to_string()).await,
+                r#"{"error":{"code":"not_found","details":{"id":1},"message":"no record with id 1","path":["id"]}}"#
+            );
+        }
+
+        let included = client
+            .exec(
+                r#"{ "op": "fetch_one", "body": { "base": "todo", "id": 1, "filters": [], "links": [{ "ty": "soft_delete", "include_deleted": true }] } }"#
+                    .to_string(),
+            )
+            .await;
+
+        assert!(included.starts_with(
+            r#"{"output":{"id":1,"attributes":{"description":null,"done":false,"title":"first"},"links":[{"deleted_at":""#
+        ));
+
+        let restored = client
+            .exec(r#"{ "op": "restore", "body": { "base": "todo", "id": 1 } }"#.to_string())
+            .await;
+
+        pretty_assertions::assert_eq!(
+            restored,
+            r#"{"output":{"id":1,"attributes":{"description":null,"done":false,"title":"first"},"links":null}}"#
+        );
+
+        let fetched = client
+            .exec(
+                r#"{ "op": "fetch_one", "body": { "base": "todo", "id": 1, "filters": [], "links": [{ "ty": "soft_delete", "include_deleted": false }] } }"#
+                    .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            fetched,
+            r#"{"output":{"id":1,"attributes":{"description":null,"done":false,"title":"first"},"links":[{"deleted_at":null}]}}"#
+        );
+
+        // only deleted records can be restored
+        let again = client
+            .exec(r#"{ "op": "restore", "body": { "base": "todo", "id": 1 } }"#.to_string())
+            .await;
+
+        pretty_assertions::assert_eq!(
+            again,
+            r#"{"error":{"code":"not_found","details":{"id":1},"message":"no record with id 1","path":["id"]}}"#
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn audit_log_records_every_version() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool);
+        let client = client.into_string_client();
+        let _executor = tokio::spawn(ex.run());
+
+        add_todo_collection(&client).await;
+
+        let not_audited = client
+            .exec(r#"{ "op": "history", "body": { "base": "todo", "id": 1 } }"#.to_string())
+            .await;
+
+        pretty_assertions::assert_eq!(
+            not_audited,
+            r#"{"error":{"code":"not_audited","details":{"collection":"todo"},"message":"todo has no audit_log link","path":["base"]}}"#
+        );
+
+        client
+            .exec(
+                r#"{ "op": "add_link", "body": { "ty": "audit_log", "collection": "todo" } }"#
+                    .to_string(),
+            )
+            .await;
+
+        for body in [
+            r#"{ "op": "insert_one", "body": { "base": "todo", "data": { "title": "first", "done": false }, "links": [] } }"#,
+            r#"{ "op": "update_one", "body": { "base": "todo", "id": 1, "data": { "done": true }, "links": [] } }"#,
+            r#"{ "op": "delete_one", "body": { "base": "todo", "id": 1, "links": [] } }"#,
+        ] {
+            client.exec(body.to_string()).await;
+        }
+
+        let history = client
+            .exec(r#"{ "op": "history", "body": { "base": "todo", "id": 1 } }"#.to_string())
+            .await;
+
+        // `changed_at` is left out, it is the time of the write
+        for version in [
+            r#"{"version":1,"action":"insert","before":null,"after":{"description":null,"done":false,"title":"first"},"changed_by":"system","changed_at":"#,
+            r#"{"version":2,"action":"update","before":{"description":null,"done":false,"title":"first"},"after":{"description":null,"done":true,"title":"first"},"changed_by":"system","changed_at":"#,
+            r#"{"version":3,"action":"delete","before":{"description":null,"done":true,"title":"first"},"after":null,"changed_by":"system","changed_at":"#,
+        ] {
+            assert!(history.contains(version), "{history}");
+        }
+        pretty_assertions::assert_eq!(history.matches(r#""version":"#).count(), 3);
+
+        let unknown = client
+            .exec(r#"{ "op": "history", "body": { "base": "todo", "id": 2 } }"#.to_string())
+            .await;
+
+        pretty_assertions::assert_eq!(unknown, r#"{"output":[]}"#);
+    }
+
+    #[tokio::test]
+    async fn versioned_updates_conflict() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool);
+        let client = client.into_string_client();
+        let _executor = tokio::spawn(ex.run());
+
+        add_todo_collection(&client).await;
+
+        client
+            .exec(
+                r#"{ "op": "add_link", "body": { "ty": "versioned", "collection": "todo" } }"#
+                    .to_string(),
+            )
+            .await;
+        client
+            .exec(r#"{ "op": "insert_one", "body": { "base": "todo", "data": { "title": "first", "done": false }, "links": [] } }"#.to_string())
+            .await;
+
+        let fetched = client
+            .exec(r#"{ "op": "fetch_one", "body": { "base": "todo", "id": 1, "filters": [], "links": [{ "ty": "version" }] } }"#.to_string())
+            .await;
+
+        pretty_assertions::assert_eq!(
+            fetched,
+            r#"{"output":{"id":1,"attributes":{"description":null,"done":false,"title":"first"},"links":[{"version":0}]}}"#
+        );
+
+        let without_version = client
+            .exec(r#"{ "op": "update_one", "body": { "base": "todo", "id": 1, "data": { "done": true }, "links": [] } }"#.to_string())
+            .await;
+
+        pretty_assertions::assert_eq!(
+            without_version,
+            r#"{"error":{"code":"invalid_version","details":{"collection":"todo"},"message":"a version is given if and only if todo is versioned","path":["version"]}}"#
+        );
+
+        let updated = client
+            .exec(r#"{ "op": "update_one", "body": { "base": "todo", "id": 1, "data": { "done": true }, "links": [], "version": 0 } }"#.to_string())
+            .await;
+
+        pretty_assertions::assert_eq!(
+            updated,
+            r#"{"output":{"id":1,"attributes":{"description":null,"done":true,"title":"first"},"links":[{"version":1}]}}"#
+        );
+
+        // a second client still holding version 0
+        let stale = client
+            .exec(r#"{ "op": "update_one", "body": { "base": "todo", "id": 1, "data": { "title": "second" }, "links": [], "version": 0 } }"#.to_string())
+            .await;
+
+        pretty_assertions::assert_eq!(
+            stale,
+            r#"{"error":{"code":"conflict","details":{"current_version":1,"id":1},"message":"record 1 was updated since, it is at version 1","path":["version"]}}"#
+        );
+
+        let missing = client
+            .exec(r#"{ "op": "update_one", "body": { "base": "todo", "id": 2, "data": { "done": true }, "links": [], "version": 0 } }"#.to_string())
+            .await;
+
+        pretty_assertions::assert_eq!(
+            missing,
+            r#"{"error":{"code":"not_found","details":{"id":2},"message":"no record with id 2","path":["id"]}}"#
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn full_text_search_ranks_matches() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool);
+        let client = client.into_string_client();
+        let _executor = tokio::spawn(ex.run());
+
+        add_todo_collection(&client).await;
+
+        let search = r#"
+{
+    "op": "fetch_many",
+    "body": {
+        "base": "todo",
+        "filters": [{ "ty": "search", "query": "dog" }],
+        "links": [],
+        "pagination": {
+            "ty": "offset",
+            "page": 0,
+            "page_size": 5,
+            "count_total": false,
+            "order_by": [
+                { "ty": "relevance", "query": "dog", "direction": "asc" }
+            ]
+        }
+    }
+}
+"#;
+
+        let not_indexed = client.exec(search.to_string()).await;
+
+        pretty_assertions::assert_eq!(
+            not_indexed,
+            r#"{"error":{"code":"invalid_filter","details":{},"message":"filter 0 can't be applied","path":["filters",0]}}"#
+        );
+
+        let not_text = client
+            .exec(
+                r#"{ "op": "add_link", "body": { "ty": "full_text_search", "collection": "todo", "fields": ["title", "done"] } }"#
+                    .to_string(),
+            )
+            .await;
+
+        pretty_assertions::assert_eq!(
+            not_text,
+            r#"{"error":{"code":"invalid_field","details":{"collection":"todo","field":"done"},"message":"done of todo can't be searched, it isn't a text field","path":["fields"]}}"#
+        );
+
+        client
+            .exec(r#"{ "op": "insert_one", "body": { "base": "todo", "data": { "title": "walk the dog", "done": false }, "links": [] } }"#.to_string())
+            .await;
+
+        // records inserted before the link are indexed too
+        client
+            .exec(
+                r#"{ "op": "add_link", "body": { "ty": "full_text_search", "collection": "todo", "fields": ["title", "description"] } }"#
+                    .to_string(),
+            )
+            .await;
+
+        client
+            .exec(r#"{ "op": "insert_one", "body": { "base": "todo", "data": { "title": "buy milk", "done": false }, "links": [] } }"#.to_string())
+            .await;
+        client
+            .exec(r#"{ "op": "insert_one", "body": { "base": "todo", "data": { "title": "dog bed", "done": false, "description": "for the dog" }, "links": [] } }"#.to_string())
+            .await;
+
+        let found = client.exec(search.to_string()).await;
+
+        pretty_assertions::assert_eq!(
+            found,
+            r#"{"output":{"items":[{"id":3,"attributes":{"description":"for the dog","done":false,"title":"dog bed"},"links":[]},{"id":1,"attributes":{"description":null,"done":false,"title":"walk the dog"},"links":[]}],"next_item":null,"prev_item":null,"total":null}}"#
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn subscribe_streams_changes() {
+        let pool = Sqlite::in_memory_pool().await;
+        let (client, ex) = Client::new_sqlx_db(pool);
+        let client = client.into_string_client();
+        let _executor = tokio::spawn(ex.run());
+
+        add_todo_collection(&client).await;
+
+        let mut all = client
+            .subscribe(r#"{ "base": "todo", "filters": [] }"#.to_string())
+            .await
+            .unwrap();
+        let mut pending = client
+            .subscribe(
+                r#"{ "base": "todo", "filters": [{ "ty": "col_eq", "col": "done", "eq": false }] }"#
+                    .to_string(),
+            )
+            .await
+            .unwrap();
+
+        for body in [
+            r#"{ "op": "insert_one", "body": { "base": "todo", "data": { "title": "first", "done": false }, "links": [] } }"#,
+            r#"{ "op": "insert_one", "body": { "base": "todo", "data": { "title": "second", "done": true }, "links": [] } }"#,
+            r#"{ "op": "update_one", "body": { "base": "todo", "id": 1, "data": { "done": true }, "links": [] } }"#,
+            r#"{ "op": "delete_one", "body": { "base": "todo", "id": 2, "links": [] } }"#,
+        ] {
+            client.exec(body.to_string()).await;
+        }
+
+        let mut events = vec![];
+        for _ in 0..4 {
+            events.push(all.next().await.unwrap());
+        }
+        pretty_assertions::assert_eq!(
+            events,
+            vec![
+                r#"{"kind":"insert","collection":"todo","id":1,"attributes":{"description":null,"done":false,"title":"first"}}"#,
+                r#"{"kind":"insert","collection":"todo","id":2,"attributes":{"description":null,"done":true,"title":"second"}}"#,
+                r#"{"kind":"update","collection":"todo","id":1,"attributes":{"description":null,"done":true,"title":"first"}}"#,
+                r#"{"kind":"delete","collection":"todo","id":2,"attributes":{"description":null,"done":true,"title":"second"}}"#,
+            ]
+        );
+
+        // the update took the first todo out of the subscription
+        let mut events = vec![];
+        for _ in 0..2 {
+            events.push(pending.next().await.unwrap());
+        }
+        pretty_assertions::assert_eq!(
+            events,
+            vec![
+                r#"{"kind":"insert","collection":"todo","id":1,"attributes":{"description":null,"done":false,"title":"first"}}"#,
+                r#"{"kind":"delete","collection":"todo","id":1,"attributes":{"description":null,"done":true,"title":"first"}}"#,
+            ]
+        );
+
+        let invalid = client
+            .subscribe(
+                r#"{ "base": "todo", "filters": [{ "ty": "col_eq", "col": "missing", "eq": 1 }] }"#
+                    .to_string(),
+            )
+            .await
+            .err();
+        pretty_assertions::assert_eq!(
+            invalid.as_deref(),
+            Some(
+                r#"{"error":{"code":"invalid_filter","details":{},"message":"filter 0 can't be applied","path":["filters",0]}}"#
+            )
+        );
+    }
+
+    mod insert_one {
+        use sqlx::Sqlite;
+
+        use crate::{
+            connect_in_memory::ConnectInMemory, json_client::client_interface::Client,
+            track_sqlx_query::{assert_sql_eq, watch_sqlx_calls},
+        };
+
+        use crate::json_client::test_utilities::{
+            add_category_collection, add_todo_collection, setup_todo_with_category_link,
+            todo_is_one_to_many_with_category,
+        };
+
+        #[tokio::test(flavor = "current_thread")]
+        async fn set_id_links_existing_category() {
+            let pool = Sqlite::in_memory_pool().await;
+            let (client, ex) = Client::new_sqlx_db(pool);
+            let client = client.into_string_client();
+            let _executor = tokio::spawn(ex.run());
+
+            add_todo_collection(&client).await;
+            add_category_collection(&client).await;
+            todo_is_one_to_many_with_category(&client).await;
+
+            client
+                .exec(
+                    r#"
+{
+    "op": "insert_one",
+    "body": {
+        "base": "category",
+        "data": { "title": "existing_category" },
+        "links": []
+    }
+}
+"#
+                    .to_string(),
+                )
+                .await;
+
+            let result = client
+                .exec(
+                    r#"
+{
+    "op": "insert_one",
+    "body": {
+        "base": "todo",
+        "data": {
+            "title": "todo_with_category",
+            "done": true,
+            "description": "linked"
+        },
+        "links": [
+            { "ty": "set_id", "to": "category", "id": 1 }
+        ]
+    }
+}
+"#
+                    .to_string(),
+                )
+                .await;
+
+            pretty_assertions::assert_eq!(
+                result,
+                r#"{"output":{"id":1,"attributes":{"description":"linked","done":true,"title":"todo_with_category"},"links":[{"id":1,"attributes":{"title":"existing_category"}}]}}"#
+            );
+        }
+
+        #[tokio::test(flavor = "current_thread")]
+        async fn set_new_creates_category_and_links() {
+            let pool = Sqlite::in_memory_pool().await;
+            let (client, ex) = Client::new_sqlx_db(pool);
+            let client = client.into_string_client();
+            let _executor = tokio::spawn(ex.run());
+
+            add_todo_collection(&client).await;
+            add_category_collection(&client).await;
+            todo_is_one_to_many_with_category(&client).await;
+
+            let result = client
+                .exec(
+                    r#"
+{
+    "op": "insert_one",
+    "body": {
+        "base": "todo",
+        "data": {
+            "title": "todo_with_new_category",
+            "done": false,
+            "description": "set_new"
+        },
+        "links": [
+            { "ty": "set_new", "to": "category", "value": { "title": "new_category" } }
+        ]
     }
 }
 "#
@@ -1450,7 +3293,10 @@
                 )
                 .await;
 
-            pretty_assertions::assert_eq!(result, r#"{"error":"InvalidData"}"#);
+            pretty_assertions::assert_eq!(
+                result,
+                r#"{"error":{"code":"invalid_data","details":{"rule":"empty"},"message":"the data breaks the rule empty","path":["data"]}}"#
+            );
         }
 
         #[tokio::test(flavor = "current_thread")]
@@ -2224,7 +4070,7 @@
         "links": [
             { "ty": "many_to_many", "to": "tag" }
         ],
-        "pagination": { "limit": 10, "first_item": null, "order_by": [] }
+        "pagination": { "ty": "cursor", "limit": 10, "cursor": null, "order_by": [] }
     }
 }
 "#
@@ -2234,7 +4080,7 @@
 
             pretty_assertions::assert_eq!(
                 result,
-                r#"{"output":{"items":[{"id":1,"attributes":{"description":"a","done":true,"title":"todo_a"},"links":[{"many_output":[{"id":1,"attributes":{"title":"urgent"}}]}]}],"next_item":null}}"#
+                r#"{"output":{"items":[{"id":1,"attributes":{"description":"a","done":true,"title":"todo_a"},"links":[{"many_output":[{"id":1,"attributes":{"title":"urgent"}}]}]}],"next_item":null,"prev_item":null,"total":null}}"#
             );
         }
 
@@ -0,0 +1,345 @@
+//! `FieldRule`s of dynamic fields.
+//!
+//! `validate` runs every check that doesn't need the database on the data
+//! sent to insert or update, values of `FieldRule::Unique` fields are then
+//! looked up by `check_unique` before the statement that writes them.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use regex::Regex;
+
+use crate::{
+    database_extention::DatabaseExt,
+    execute::Executable,
+    fix_executor::ExecutorTrait,
+    gen_serde::json_format_side::PartialDeserialize,
+    json_client::{
+        ToBind,
+        client_interface::{FieldRule, InvalidData, InvalidDataRule, SupportedType},
+        dynamic_collection::DynamicCollection,
+        supported_types::Bytes,
+    },
+    operations::insert::{ConstraintKind, ConstraintViolation},
+    sqlx_query_builder::{Expression, OpExpression, StatementBuilder},
+    statement_cache::DYNAMIC,
+    sub_arc::ArcSubStr,
+};
+
+/// what `MinLength` and `MaxLength` count, decided by the field's type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Length {
+    Chars,
+    Items,
+    Bytes,
+}
+
+/// a `FieldRule` checked against the type of its field
+#[derive(Debug, Clone)]
+pub(crate) enum Rule {
+    MinLength(Length, usize),
+    MaxLength(Length, usize),
+    Range {
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    /// anchored, the whole string has to match
+    Pattern(Regex),
+    OneOf(Arc<[Arc<str>]>),
+    Unique,
+}
+
+impl PartialEq for Rule {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::MinLength(a, x), Self::MinLength(b, y)) => a == b && x == y,
+            (Self::MaxLength(a, x), Self::MaxLength(b, y)) => a == b && x == y,
+            (
+                Self::Range { min, max },
+                Self::Range {
+                    min: other_min,
+                    max: other_max,
+                },
+            ) => min == other_min && max == other_max,
+            (Self::Pattern(a), Self::Pattern(b)) => a.as_str() == b.as_str(),
+            (Self::OneOf(a), Self::OneOf(b)) => a == b,
+            (Self::Unique, Self::Unique) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Rule {
+    /// rules that can't apply to `ty` are rejected along with the collection
+    pub(crate) fn new(rule: &FieldRule, ty: &SupportedType) -> Result<Self, ()> {
+        let length = || match ty {
+            SupportedType::String | SupportedType::Enum { .. } => Ok(Length::Chars),
+            SupportedType::Array(_) => Ok(Length::Items),
+            SupportedType::Bytes => Ok(Length::Bytes),
+            _ => Err(()),
+        };
+        let is_string = matches!(ty, SupportedType::String);
+
+        Ok(match rule {
+            FieldRule::MinLength(min) => {
+                Rule::MinLength(length()?, usize::try_from(*min).map_err(|_| ())?)
+            }
+            FieldRule::MaxLength(max) => {
+                Rule::MaxLength(length()?, usize::try_from(*max).map_err(|_| ())?)
+            }
+            FieldRule::Range { min, max } => {
+                if !matches!(ty, SupportedType::Int | SupportedType::Float64) {
+                    return Err(());
+                }
+                if min.zip(*max).is_some_and(|(min, max)| min > max) {
+                    return Err(());
+                }
+                Rule::Range {
+                    min: *min,
+                    max: *max,
+                }
+            }
+            FieldRule::Pattern(pattern) if is_string => {
+                Rule::Pattern(Regex::new(&format!("^(?:{})$", pattern.as_str())).map_err(|_| ())?)
+            }
+            FieldRule::OneOf(values) if is_string && !values.is_empty() => {
+                Rule::OneOf(values.iter().map(|value| value.detach()).collect())
+            }
+            FieldRule::Unique => match ty {
+                SupportedType::Array(_) | SupportedType::Json => return Err(()),
+                _ => Rule::Unique,
+            },
+            _ => return Err(()),
+        })
+    }
+
+    fn kind(&self) -> InvalidDataRule {
+        match self {
+            Rule::MinLength(..) => InvalidDataRule::MinLength,
+            Rule::MaxLength(..) => InvalidDataRule::MaxLength,
+            Rule::Range { .. } => InvalidDataRule::Range,
+            Rule::Pattern(_) => InvalidDataRule::Pattern,
+            Rule::OneOf(_) => InvalidDataRule::OneOf,
+            Rule::Unique => InvalidDataRule::Unique,
+        }
+    }
+
+    /// a `FieldRule::Check`, only rules that can be written as a `CHECK`
+    /// of the column are accepted
+    pub(crate) fn new_check(rule: &FieldRule, ty: &SupportedType) -> Result<Self, ()> {
+        match Rule::new(rule, ty)? {
+            // sqlite's `LENGTH` counts the characters of a text and the
+            // bytes of a blob, not the items of an array
+            Rule::MinLength(Length::Items, _) | Rule::MaxLength(Length::Items, _) => Err(()),
+            Rule::Pattern(_) | Rule::Unique => Err(()),
+            Rule::Range {
+                min: None,
+                max: None,
+            } => Err(()),
+            rule => Ok(rule),
+        }
+    }
+
+    /// `value` already deserialized to the field's type
+    pub(crate) fn passes(&self, value: &PartialDeserialize) -> bool {
+        match self {
+            Rule::MinLength(of, min) => length(*of, value).is_some_and(|len| len >= *min),
+            Rule::MaxLength(of, max) => length(*of, value).is_some_and(|len| len <= *max),
+            Rule::Range { min, max } => value.continue_deserialize::<f64>().is_ok_and(|value| {
+                min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+            }),
+            Rule::Pattern(regex) => value
+                .continue_deserialize::<String>()
+                .is_ok_and(|value| regex.is_match(&value)),
+            Rule::OneOf(values) => value
+                .continue_deserialize::<String>()
+                .is_ok_and(|value| values.iter().any(|each| each.as_ref() == value)),
+            Rule::Unique => true,
+        }
+    }
+}
+
+fn length(of: Length, value: &PartialDeserialize) -> Option<usize> {
+    match of {
+        Length::Chars => value
+            .continue_deserialize::<String>()
+            .ok()
+            .map(|value| value.chars().count()),
+        Length::Items => value
+            .continue_deserialize::<Vec<PartialDeserialize>>()
+            .ok()
+            .map(|items| items.len()),
+        Length::Bytes => value
+            .continue_deserialize::<Bytes>()
+            .ok()
+            .map(|bytes| bytes.0.len()),
+    }
+}
+
+/// values of `FieldRule::Unique` fields that `check_unique` looks up
+pub(crate) struct UniqueValues<S: DatabaseExt>(Vec<(Arc<str>, Box<dyn ToBind<S> + Send>)>);
+
+/// `require_all` is set for inserts, where every non optional field is given
+pub(crate) fn validate<S>(
+    base: &DynamicCollection<S>,
+    data: &PartialDeserialize,
+    require_all: bool,
+) -> Result<UniqueValues<S>, InvalidData>
+where
+    S: DatabaseExt,
+{
+    let values: BTreeMap<ArcSubStr, PartialDeserialize> = data
+        .continue_deserialize()
+        .map_err(|_| InvalidData::of_data(InvalidDataRule::Malformed))?;
+
+    let mut unique = Vec::new();
+
+    for (key, value) in &values {
+        let invalid = |rule| InvalidData {
+            field: Some(key.detach()),
+            rule,
+        };
+        let field = base
+            .fields
+            .iter()
+            .find(|field| field.name.as_ref() == key.as_str())
+            .ok_or_else(|| invalid(InvalidDataRule::UnknownField))?;
+
+        if value.0.as_str().trim() == "null" {
+            if !field.is_optional {
+                return Err(invalid(InvalidDataRule::Required));
+            }
+            continue;
+        }
+
+        if let Some(variants) = &field.variants {
+            let member = value
+                .continue_deserialize::<String>()
+                .is_ok_and(|value| variants.iter().any(|variant| variant.as_ref() == value));
+            if !member {
+                return Err(invalid(InvalidDataRule::OneOf));
+            }
+        }
+
+        let bind = field
+            .to_bind(value.clone())
+            .map_err(|_| invalid(InvalidDataRule::Type))?;
+
+        for rule in field.rules.iter() {
+            if !rule.passes(value) {
+                return Err(invalid(rule.kind()));
+            }
+        }
+
+        if field.rules.contains(&Rule::Unique) {
+            unique.push((key.detach(), bind));
+        }
+    }
+
+    let missing = base.fields.iter().find(|field| {
+        !field.is_optional
+            && field.default.is_none()
+            && !values.keys().any(|key| key.as_str() == field.name.as_ref())
+    });
+    if let (true, Some(missing)) = (require_all, missing) {
+        return Err(InvalidData {
+            field: Some(Arc::clone(&missing.name.snake_case)),
+            rule: InvalidDataRule::Required,
+        });
+    }
+
+    Ok(UniqueValues(unique))
+}
+
+/// name of the `num`th `FieldRule::Check` of `field` in the table
+pub(crate) fn check_name(field: &str, num: usize) -> String {
+    format!("check_{field}_{num}")
+}
+
+/// the field of a `FieldRule::Check` the database rejected a write with,
+/// `None` for any other violation
+pub(crate) fn check_violation<S>(
+    base: &DynamicCollection<S>,
+    violation: &ConstraintViolation,
+) -> Option<InvalidData>
+where
+    S: DatabaseExt,
+{
+    if violation.kind != ConstraintKind::Check {
+        return None;
+    }
+    let name = violation.constraint.as_deref()?;
+    let field = base.fields.iter().find(|field| {
+        (0..field.checks.len()).any(|num| check_name(field.name.as_str(), num) == name)
+    })?;
+    Some(InvalidData {
+        field: Some(Arc::clone(&field.name.snake_case)),
+        rule: InvalidDataRule::Check,
+    })
+}
+
+/// `SELECT 1 FROM <table> WHERE <col> = $1 [AND "id" <> $2] LIMIT 1`
+struct TakenBy<S: DatabaseExt> {
+    table: Arc<str>,
+    col: Arc<str>,
+    value: Box<dyn ToBind<S> + Send>,
+    except: Option<i64>,
+}
+
+impl<S: DatabaseExt> OpExpression for TakenBy<S> {}
+
+impl<'q, S> Expression<'q, S> for TakenBy<S>
+where
+    S: DatabaseExt,
+    i64: sqlx::Encode<'q, S> + sqlx::Type<S>,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("SELECT 1 FROM ");
+        ctx.sanitize(&self.table);
+        ctx.syntax(" WHERE ");
+        ctx.sanitize(&self.col);
+        ctx.syntax(" = ");
+        ctx.bind(self.value);
+        if let Some(except) = self.except {
+            ctx.syntax(" AND ");
+            ctx.sanitize("id");
+            ctx.syntax(" <> ");
+            ctx.bind(except);
+        }
+        ctx.syntax(" LIMIT 1;");
+    }
+}
+
+/// `except` is the id of the record being updated, its own value is not taken
+pub(crate) async fn check_unique<S>(
+    base: &DynamicCollection<S>,
+    unique: UniqueValues<S>,
+    except: Option<i64>,
+    conn: &mut S::Connection,
+) -> Result<(), InvalidData>
+where
+    S: DatabaseExt + ExecutorTrait,
+    i64: for<'q> sqlx::Encode<'q, S> + sqlx::Type<S>,
+{
+    for (col, value) in unique.0 {
+        let (stmt, arguments) = StatementBuilder::<S>::new(TakenBy {
+            table: Arc::clone(&base.collection_name.pascal_case),
+            col: Arc::clone(&col),
+            value,
+            except,
+        })
+        .unwrap();
+
+        let taken = S::fetch_optional(&mut *conn, Executable::cached(&DYNAMIC, &stmt, arguments))
+            .await
+            .expect("bug: unique check failed");
+
+        if taken.is_some() {
+            return Err(InvalidData {
+                field: Some(col),
+                rule: InvalidDataRule::Unique,
+            });
+        }
+    }
+    Ok(())
+}
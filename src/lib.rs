@@ -39,9 +39,11 @@ pub mod row_utils;
 pub mod schema;
 pub mod singleton;
 pub mod sqlx_query_builder;
+pub mod statement_cache;
 pub mod test_module;
 pub mod tuple_trait;
 pub mod update_mod;
+pub mod view;
 pub mod macros {
     pub use claw_ql_macros::*;
 }
@@ -229,6 +231,7 @@ pub mod liquidify {
 
 pub mod fix_executor {
     use futures::future::BoxFuture;
+    use futures::stream::BoxStream;
     use sqlx::{Database, Execute};
 
     /// this trait collects the implementation I'm interested in
@@ -263,6 +266,12 @@ pub mod fix_executor {
             conn: &mut Self::Connection,
             execute: E,
         ) -> BoxFuture<'e, Result<<Self as Database>::QueryResult, sqlx::Error>>;
+        /// rows are decoded as the stream is polled, unlike `fetch_all`
+        /// the connection stays borrowed until the stream is dropped
+        fn fetch_stream<'e, E: 'e + Execute<'e, Self>>(
+            conn: &'e mut Self::Connection,
+            execute: E,
+        ) -> BoxStream<'e, Result<Self::Row, sqlx::Error>>;
     }
 
     #[cfg(not(feature = "fix_executor"))]
@@ -295,6 +304,12 @@ pub mod fix_executor {
             let break_executor = unsafe { &mut *(conn as *mut Self::Connection) };
             sqlx::Executor::execute(break_executor, execute)
         }
+        fn fetch_stream<'e, E: 'e + Execute<'e, Self>>(
+            conn: &'e mut Self::Connection,
+            execute: E,
+        ) -> BoxStream<'e, Result<Self::Row, sqlx::Error>> {
+            sqlx::Executor::fetch(conn, execute)
+        }
     }
 
     #[cfg(feature = "fix_executor")]
@@ -324,6 +339,12 @@ pub mod fix_executor {
             let keep_conn_out = sqlx::executor_2::Executor2::execute(conn, execute);
             Box::pin(async move { keep_conn_out.await })
         }
+        fn fetch_stream<'e, E: 'e + Execute<'e, Self>>(
+            conn: &'e mut Self::Connection,
+            execute: E,
+        ) -> BoxStream<'e, Result<Self::Row, sqlx::Error>> {
+            sqlx::executor_2::Executor2::fetch(conn, execute)
+        }
     }
 }
 
@@ -0,0 +1,905 @@
+//! keeps every version of the records of a collection in a
+//! `<table>_history` table.
+//!
+//! `AuditLog` migrates triggers that record a version for every insert, update
+//! and delete on the table, so writes can't skip the history whether they go
+//! through the operations of the crate or not. a version holds `json_object`
+//! snapshots of the stored columns before and after the change.
+//!
+//! triggers can't know who made a change, writes wrapped in `Audited` set
+//! `changed_by` of the versions they recorded. `FetchHistory` lists the
+//! versions of one record.
+
+/// creates the `<table>_history` table of a collection and the triggers
+/// filling it on migrate
+#[derive(Debug, Clone)]
+pub struct AuditLog<C> {
+    pub collection: C,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AuditAction {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Insert => "insert",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+        }
+    }
+
+    pub fn parse(str: &str) -> Option<Self> {
+        match str {
+            "insert" => Some(AuditAction::Insert),
+            "update" => Some(AuditAction::Update),
+            "delete" => Some(AuditAction::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// one version of a record, `before` and `after` are json objects of its
+/// attributes, `before` is `None` for inserts and `after` for deletes.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HistoryEntry {
+    pub version: i64,
+    pub action: AuditAction,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub changed_by: Option<String>,
+    pub changed_at: String,
+}
+
+impl crate::gen_serde::Serialize<crate::gen_serde::json_serialize_side::JsonAsString>
+    for HistoryEntry
+{
+    fn serialize(&self, ctx: &mut crate::gen_serde::json_serialize_side::JsonAsString) {
+        use crate::gen_serde::Serialize;
+
+        ctx.0.push('{');
+        Serialize::serialize("version", ctx);
+        ctx.0.push(':');
+        self.version.serialize(ctx);
+        ctx.0.push(',');
+        Serialize::serialize("action", ctx);
+        ctx.0.push(':');
+        self.action.as_str().serialize(ctx);
+        // snapshots are already json, they are embeded as they are
+        for (key, snapshot) in [("before", &self.before), ("after", &self.after)] {
+            ctx.0.push(',');
+            Serialize::serialize(key, ctx);
+            ctx.0.push(':');
+            match snapshot {
+                Some(snapshot) => ctx.0.push_str(snapshot),
+                None => ctx.0.push_str("null"),
+            }
+        }
+        ctx.0.push(',');
+        Serialize::serialize("changed_by", ctx);
+        ctx.0.push(':');
+        match &self.changed_by {
+            Some(changed_by) => changed_by.serialize(ctx),
+            None => ctx.0.push_str("null"),
+        }
+        ctx.0.push(',');
+        Serialize::serialize("changed_at", ctx);
+        ctx.0.push(':');
+        self.changed_at.serialize(ctx);
+        ctx.0.push('}');
+    }
+}
+
+pub mod expressions {
+    use std::marker::PhantomData;
+
+    use crate::{
+        database_extention::DatabaseExt,
+        links::audit_log::AuditAction,
+        sqlx_query_builder::{Expression, OpExpression, StatementBuilder},
+    };
+
+    /// `"<table>_history"`
+    #[derive(Debug, Clone)]
+    pub struct HistoryTable(pub String);
+
+    impl HistoryTable {
+        pub fn new(table_name: &str) -> Self {
+            HistoryTable(format!("{table_name}_history"))
+        }
+    }
+
+    impl OpExpression for HistoryTable {}
+
+    impl<'q, S> Expression<'q, S> for HistoryTable
+    where
+        S: DatabaseExt,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.sanitize(&self.0);
+        }
+    }
+
+    /// the columns of the history table, `"id"` is the version and
+    /// `"record_id"` has the type of the id of the collection
+    pub struct HistoryColumns<Id>(pub PhantomData<Id>);
+
+    impl<Id> OpExpression for HistoryColumns<Id> {}
+
+    impl<'q, S, Id> Expression<'q, S> for HistoryColumns<Id>
+    where
+        S: DatabaseExt,
+        S::IdExpression: Expression<'q, S>,
+        Id: sqlx::Type<S> + 'q,
+        String: sqlx::Type<S>,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            S::id_on_create_table_expression().expression(ctx);
+            ctx.syntax(", ");
+            ctx.sanitize("record_id");
+            ctx.syntax(" ");
+            ctx.type_as_syntax::<Id>();
+            ctx.syntax(" NOT NULL, ");
+            ctx.sanitize("action");
+            ctx.syntax(" ");
+            ctx.type_as_syntax::<String>();
+            ctx.syntax(" NOT NULL");
+            for col in ["before", "after", "changed_by"] {
+                ctx.syntax(", ");
+                ctx.sanitize(col);
+                ctx.syntax(" ");
+                ctx.type_as_syntax::<String>();
+            }
+            ctx.syntax(", ");
+            ctx.sanitize("changed_at");
+            ctx.syntax(" ");
+            ctx.type_as_syntax::<String>();
+            ctx.syntax(" NOT NULL DEFAULT CURRENT_TIMESTAMP");
+        }
+    }
+
+    /// body of the triggers of `AuditLog`, inserts a version with `json_object`
+    /// snapshots of the `fields` of the `old` and `new` rows, `id_column` being
+    /// the id column of the collection
+    pub struct RecordVersion {
+        pub table: HistoryTable,
+        pub action: AuditAction,
+        pub id_column: String,
+        pub fields: &'static [&'static str],
+    }
+
+    impl RecordVersion {
+        fn snapshot<'q, S>(&self, row: &'static str, ctx: &mut StatementBuilder<'q, S>)
+        where
+            S: DatabaseExt,
+        {
+            ctx.syntax("json_object(");
+            for (i, field) in self.fields.iter().enumerate() {
+                if i != 0 {
+                    ctx.syntax(", ");
+                }
+                // fields are names of members, they never contain a quote
+                ctx.syntax("'");
+                ctx.syntax(field);
+                ctx.syntax("', ");
+                ctx.syntax(row);
+                ctx.sanitize(field);
+            }
+            ctx.syntax(")");
+        }
+    }
+
+    impl OpExpression for RecordVersion {}
+
+    impl<'q, S> Expression<'q, S> for RecordVersion
+    where
+        S: DatabaseExt,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.syntax("INSERT INTO ");
+            self.table.clone().expression(ctx);
+            ctx.syntax(" (");
+            ctx.sanitize("record_id");
+            for col in ["action", "before", "after"] {
+                ctx.syntax(", ");
+                ctx.sanitize(col);
+            }
+            ctx.syntax(") VALUES (");
+            match self.action {
+                AuditAction::Insert => {
+                    ctx.syntax("new.");
+                    ctx.sanitize(&self.id_column);
+                    ctx.syntax(", 'insert', NULL, ");
+                    self.snapshot("new.", ctx);
+                }
+                AuditAction::Update => {
+                    ctx.syntax("new.");
+                    ctx.sanitize(&self.id_column);
+                    ctx.syntax(", 'update', ");
+                    self.snapshot("old.", ctx);
+                    ctx.syntax(", ");
+                    self.snapshot("new.", ctx);
+                }
+                AuditAction::Delete => {
+                    ctx.syntax("old.");
+                    ctx.sanitize(&self.id_column);
+                    ctx.syntax(", 'delete', ");
+                    self.snapshot("old.", ctx);
+                    ctx.syntax(", NULL");
+                }
+            }
+            ctx.syntax(");");
+        }
+    }
+
+    /// the history table followed by its triggers, separated by spaces
+    pub struct AuditMigration<CreateTable, OnInsert, OnUpdate, OnDelete> {
+        pub create_table: CreateTable,
+        pub on_insert: OnInsert,
+        pub on_update: OnUpdate,
+        pub on_delete: OnDelete,
+    }
+
+    impl<C, I, U, D> OpExpression for AuditMigration<C, I, U, D> {}
+
+    impl<'q, S, CreateTable, OnInsert, OnUpdate, OnDelete> Expression<'q, S>
+        for AuditMigration<CreateTable, OnInsert, OnUpdate, OnDelete>
+    where
+        S: DatabaseExt,
+        CreateTable: Expression<'q, S> + 'q,
+        OnInsert: Expression<'q, S> + 'q,
+        OnUpdate: Expression<'q, S> + 'q,
+        OnDelete: Expression<'q, S> + 'q,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            self.create_table.expression(ctx);
+            ctx.syntax(" ");
+            self.on_insert.expression(ctx);
+            ctx.syntax(" ");
+            self.on_update.expression(ctx);
+            ctx.syntax(" ");
+            self.on_delete.expression(ctx);
+        }
+    }
+
+    /// sets `changed_by` of the last version of a record
+    pub struct StampVersion<Id> {
+        pub table: HistoryTable,
+        pub record_id: Id,
+        pub changed_by: Option<String>,
+    }
+
+    impl<Id> OpExpression for StampVersion<Id> {}
+
+    impl<'q, S, Id> Expression<'q, S> for StampVersion<Id>
+    where
+        S: DatabaseExt,
+        Id: sqlx::Encode<'q, S> + sqlx::Type<S> + 'q,
+        Option<String>: sqlx::Encode<'q, S> + sqlx::Type<S>,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.syntax("UPDATE ");
+            self.table.clone().expression(ctx);
+            ctx.syntax(" SET ");
+            ctx.sanitize("changed_by");
+            ctx.syntax(" = ");
+            ctx.bind(self.changed_by);
+            ctx.syntax(" WHERE ");
+            ctx.sanitize("id");
+            ctx.syntax(" = (SELECT MAX(");
+            ctx.sanitize("id");
+            ctx.syntax(") FROM ");
+            self.table.expression(ctx);
+            ctx.syntax(" WHERE ");
+            ctx.sanitize("record_id");
+            ctx.syntax(" = ");
+            ctx.bind(self.record_id);
+            ctx.syntax(");");
+        }
+    }
+
+    /// the versions of a record, oldest first
+    pub struct SelectVersions<Id> {
+        pub table: HistoryTable,
+        pub record_id: Id,
+    }
+
+    impl<Id> OpExpression for SelectVersions<Id> {}
+
+    impl<'q, S, Id> Expression<'q, S> for SelectVersions<Id>
+    where
+        S: DatabaseExt,
+        Id: sqlx::Encode<'q, S> + sqlx::Type<S> + 'q,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.syntax("SELECT ");
+            ctx.sanitize("id");
+            for col in ["action", "before", "after", "changed_by", "changed_at"] {
+                ctx.syntax(", ");
+                ctx.sanitize(col);
+            }
+            ctx.syntax(" FROM ");
+            self.table.expression(ctx);
+            ctx.syntax(" WHERE ");
+            ctx.sanitize("record_id");
+            ctx.syntax(" = ");
+            ctx.bind(self.record_id);
+            ctx.syntax(" ORDER BY ");
+            ctx.sanitize("id");
+            ctx.syntax(";");
+        }
+    }
+}
+
+mod impl_on_migrate {
+    use std::marker::PhantomData;
+
+    use crate::{
+        collections::{Collection, CollectionId, SingleColumnId},
+        links::{
+            audit_log::{
+                AuditAction, AuditLog,
+                expressions::{AuditMigration, HistoryColumns, HistoryTable, RecordVersion},
+            },
+            timestamp::expressions::create_trigger::{
+                CreateTrigger, TriggerLifetimeAfter, TriggerOperationNameDelete,
+                TriggerOperationNameInsert, TriggerOperationNameUpdate,
+            },
+        },
+        on_migrate::OnMigrate,
+        sqlx_query_builder::statements::create_table_statement::{
+            CreateTable, expressions::create_if_not_exist,
+        },
+        tuple_trait::AsTuple,
+    };
+
+    type HistoryTrigger<OperationName> =
+        CreateTrigger<String, TriggerLifetimeAfter, OperationName, String, (), RecordVersion>;
+
+    impl<C> AuditLog<C>
+    where
+        C: Collection<Id: SingleColumnId>,
+        C::OutputData: AsTuple,
+    {
+        fn trigger<OperationName>(
+            &self,
+            action: AuditAction,
+            operation_name: OperationName,
+        ) -> HistoryTrigger<OperationName> {
+            let table = HistoryTable::new(self.collection.table_name());
+            CreateTrigger {
+                trigger_name: format!("{}_{}", table.0, action.as_str()),
+                temp: false,
+                if_not_exists: true,
+                lifetime: TriggerLifetimeAfter,
+                operation_name,
+                on_table: self.collection.table_name().to_string(),
+                for_each_row: false,
+                when_expression: (),
+                statements: RecordVersion {
+                    table,
+                    action,
+                    id_column: self.collection.id().as_ref().to_string(),
+                    fields: <C::OutputData as AsTuple>::NAMES,
+                },
+            }
+        }
+    }
+
+    impl<C> OnMigrate for AuditLog<C>
+    where
+        C: Collection<Id: SingleColumnId>,
+        C::OutputData: AsTuple,
+    {
+        type Statements = AuditMigration<
+            CreateTable<
+                create_if_not_exist,
+                HistoryTable,
+                HistoryColumns<<C::Id as CollectionId>::IdData>,
+            >,
+            HistoryTrigger<TriggerOperationNameInsert>,
+            HistoryTrigger<TriggerOperationNameUpdate<()>>,
+            HistoryTrigger<TriggerOperationNameDelete>,
+        >;
+
+        fn statments(&self) -> Self::Statements {
+            AuditMigration {
+                create_table: CreateTable {
+                    init: create_if_not_exist,
+                    name: HistoryTable::new(self.collection.table_name()),
+                    col_defs: HistoryColumns(PhantomData),
+                },
+                on_insert: self.trigger(AuditAction::Insert, TriggerOperationNameInsert),
+                on_update: self.trigger(AuditAction::Update, TriggerOperationNameUpdate(())),
+                on_delete: self.trigger(AuditAction::Delete, TriggerOperationNameDelete),
+            }
+        }
+    }
+}
+
+/// `Audited` wraps an `InsertOne`, `Update` or `Delete` and sets `changed_by`
+/// of the versions recorded for each record it wrote, `FetchHistory` reads
+/// them back.
+pub mod operations {
+    use sqlx::{ColumnIndex, Connection, Decode, Encode, Row, Type};
+
+    use crate::{
+        collections::{Collection, CollectionId},
+        database_extention::DatabaseExt,
+        execute::Executable,
+        fix_executor::ExecutorTrait,
+        links::audit_log::{
+            AuditAction, HistoryEntry,
+            expressions::{HistoryTable, SelectVersions, StampVersion},
+        },
+        operations::{
+            LinkedOutput, Operation, OperationOutput,
+            delete::{Delete, DeleteLink, DeleteLinkSplit},
+            insert::{ConstraintViolation, InsertLinkConsumeData, InsertOne, InsertOneLink},
+            update::{Update, UpdateLink, UpdateLinkSplit},
+        },
+        sqlx_query_builder::{StatementBuilder, statements::insert_statement::One},
+        statement_cache::AUDIT_LOG,
+    };
+
+    /// runs in a transaction, so no other write lands between the operation
+    /// and its `changed_by`
+    pub struct Audited<Op> {
+        pub op: Op,
+        /// stored as it is in `changed_by`
+        pub changed_by: Option<String>,
+    }
+
+    impl<Op: OperationOutput> OperationOutput for Audited<Op> {
+        type Output = Op::Output;
+    }
+
+    /// sets `changed_by` of the last version of a record
+    pub async fn stamp_version<S, Id>(
+        table_name: &str,
+        record_id: Id,
+        changed_by: Option<String>,
+        pool: &mut S::Connection,
+    ) where
+        S: DatabaseExt + ExecutorTrait,
+        Id: for<'q> Encode<'q, S> + Type<S> + 'static,
+        Option<String>: for<'q> Encode<'q, S> + Type<S>,
+    {
+        let (stmt, args) = StatementBuilder::<S>::new(StampVersion {
+            table: HistoryTable::new(table_name),
+            record_id,
+            changed_by,
+        })
+        .unwrap();
+
+        S::execute(&mut *pool, Executable::cached(&AUDIT_LOG, &stmt, args))
+            .await
+            .unwrap();
+    }
+
+    impl<S, Id, Base, PreL, L> Operation<S> for Audited<InsertOne<Id, Base, One<Base::InputData>, PreL>>
+    where
+        S: DatabaseExt + ExecutorTrait,
+        PreL: InsertLinkConsumeData<Link = L>,
+        L: InsertOneLink<Output: Send>,
+        Base: Collection + Clone + Send + Sync,
+        Base::InputData: Send,
+        Base::OutputData: Send,
+        <Base::Id as CollectionId>::IdData:
+            for<'q> Encode<'q, S> + Type<S> + Clone + Send + 'static,
+        Option<String>: for<'q> Encode<'q, S> + Type<S>,
+        InsertOne<Id, Base, One<Base::InputData>, PreL>: Operation<
+                S,
+                Output = Result<
+                    LinkedOutput<<Base::Id as CollectionId>::IdData, Base::OutputData, L::Output>,
+                    ConstraintViolation,
+                >,
+            >,
+    {
+        fn exec_operation(
+            self,
+            pool: &mut S::Connection,
+        ) -> impl Future<Output = Self::Output> + Send
+        where
+            S: sqlx::Database,
+            Self: Sized,
+        {
+            async move {
+                let base = self.op.handler.clone();
+                let mut tx = pool.begin().await.unwrap();
+
+                let output = self.op.exec_operation(&mut tx).await;
+                match &output {
+                    Ok(inserted) => {
+                        stamp_version::<S, _>(
+                            base.table_name(),
+                            inserted.id.clone(),
+                            self.changed_by,
+                            &mut tx,
+                        )
+                        .await;
+                        tx.commit().await.unwrap();
+                    }
+                    Err(_) => tx.rollback().await.unwrap(),
+                }
+                output
+            }
+        }
+    }
+
+    impl<S, Base, Wheres, PreL, L> Operation<S>
+        for Audited<Update<Base, Base::UpdateData, Wheres, PreL>>
+    where
+        S: DatabaseExt + ExecutorTrait,
+        PreL: UpdateLinkSplit<Link = L>,
+        L: UpdateLink<Output: Send + Sync>,
+        Base: Collection + Clone + Send + Sync,
+        Base::UpdateData: Send,
+        Base::OutputData: Send + Sync,
+        <Base::Id as CollectionId>::IdData:
+            for<'q> Encode<'q, S> + Type<S> + Clone + Send + Sync + 'static,
+        Option<String>: for<'q> Encode<'q, S> + Type<S>,
+        Update<Base, Base::UpdateData, Wheres, PreL>: Operation<
+                S,
+                Output = Result<
+                    Vec<
+                        LinkedOutput<
+                            <Base::Id as CollectionId>::IdData,
+                            Base::OutputData,
+                            L::Output,
+                        >,
+                    >,
+                    ConstraintViolation,
+                >,
+            >,
+    {
+        fn exec_operation(
+            self,
+            pool: &mut S::Connection,
+        ) -> impl Future<Output = Self::Output> + Send
+        where
+            S: sqlx::Database,
+            Self: Sized,
+        {
+            async move {
+                let base = self.op.base.clone();
+                let mut tx = pool.begin().await.unwrap();
+
+                let output = self.op.exec_operation(&mut tx).await;
+                match &output {
+                    Ok(updated) => {
+                        for updated in updated.iter() {
+                            stamp_version::<S, _>(
+                                base.table_name(),
+                                updated.id.clone(),
+                                self.changed_by.clone(),
+                                &mut tx,
+                            )
+                            .await;
+                        }
+                        tx.commit().await.unwrap();
+                    }
+                    Err(_) => tx.rollback().await.unwrap(),
+                }
+                output
+            }
+        }
+    }
+
+    impl<S, Base, Wheres, PreL, L> Operation<S> for Audited<Delete<Base, Wheres, PreL>>
+    where
+        S: DatabaseExt + ExecutorTrait,
+        PreL: DeleteLinkSplit<Link = L>,
+        L: DeleteLink<Output: Send + Sync>,
+        Base: Collection + Clone + Send + Sync,
+        Base::OutputData: Send + Sync,
+        <Base::Id as CollectionId>::IdData:
+            for<'q> Encode<'q, S> + Type<S> + Clone + Send + Sync + 'static,
+        Option<String>: for<'q> Encode<'q, S> + Type<S>,
+        Delete<Base, Wheres, PreL>: Operation<
+                S,
+                Output = Vec<
+                    LinkedOutput<<Base::Id as CollectionId>::IdData, Base::OutputData, L::Output>,
+                >,
+            >,
+    {
+        fn exec_operation(
+            self,
+            pool: &mut S::Connection,
+        ) -> impl Future<Output = Self::Output> + Send
+        where
+            S: sqlx::Database,
+            Self: Sized,
+        {
+            async move {
+                let base = self.op.base.clone();
+                let mut tx = pool.begin().await.unwrap();
+
+                let output = self.op.exec_operation(&mut tx).await;
+                for deleted in output.iter() {
+                    stamp_version::<S, _>(
+                        base.table_name(),
+                        deleted.id.clone(),
+                        self.changed_by.clone(),
+                        &mut tx,
+                    )
+                    .await;
+                }
+
+                tx.commit().await.unwrap();
+                output
+            }
+        }
+    }
+
+    /// the versions of one record, oldest first
+    pub struct FetchHistory<Base, Id> {
+        pub base: Base,
+        pub record_id: Id,
+    }
+
+    impl<Base, Id> OperationOutput for FetchHistory<Base, Id> {
+        type Output = Vec<HistoryEntry>;
+    }
+
+    impl<S, Base, Id> Operation<S> for FetchHistory<Base, Id>
+    where
+        S: DatabaseExt + ExecutorTrait,
+        Base: Collection + Send,
+        Id: for<'q> Encode<'q, S> + Type<S> + Send + 'static,
+        for<'s> &'s str: ColumnIndex<S::Row>,
+        i64: for<'r> Decode<'r, S> + Type<S>,
+        String: for<'r> Decode<'r, S> + Type<S>,
+        Option<String>: for<'r> Decode<'r, S> + Type<S>,
+    {
+        fn exec_operation(
+            self,
+            pool: &mut S::Connection,
+        ) -> impl Future<Output = Self::Output> + Send
+        where
+            S: sqlx::Database,
+            Self: Sized,
+        {
+            async move {
+                let (stmt, args) = StatementBuilder::<S>::new(SelectVersions {
+                    table: HistoryTable::new(self.base.table_name()),
+                    record_id: self.record_id,
+                })
+                .unwrap();
+
+                S::fetch_all_mapped(
+                    &mut *pool,
+                    Executable::cached(&AUDIT_LOG, &stmt, args),
+                    |row| HistoryEntry {
+                        version: row.get("id"),
+                        action: AuditAction::parse(&row.get::<String, _>("action"))
+                            .expect("bug: unknown action in a history table"),
+                        before: row.get("before"),
+                        after: row.get("after"),
+                        changed_by: row.get("changed_by"),
+                        changed_at: row.get("changed_at"),
+                    },
+                )
+                .await
+                .unwrap()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sqlx::Sqlite;
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        links::audit_log::{
+            AuditAction, AuditLog,
+            operations::{Audited, FetchHistory},
+        },
+        on_migrate::OnMigrate,
+        operations::{
+            Operation, delete::Delete, insert::InsertOne, insert_id_mode::AutoGenerate,
+            update::Update as UpdateOp,
+        },
+        sqlx_query_builder::{
+            StatementBuilder,
+            basic_expressions::{ColumnEqual, ScopedColumn},
+            statements::insert_statement::One,
+        },
+        test_module::{Todo, TodoHandler, TodoPartial},
+        update_mod::Update,
+    };
+
+    #[tokio::test]
+    async fn main() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        sqlx::query(
+            "
+        CREATE TABLE Todo (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            done BOOLEAN NOT NULL,
+            description TEXT
+        );
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let migration = StatementBuilder::<Sqlite>::new_no_data(OnMigrate::statments(&AuditLog {
+            collection: TodoHandler,
+        }))
+        .unwrap();
+
+        pretty_assertions::assert_eq!(
+            migration.split("; ").collect::<Vec<_>>(),
+            vec![
+                r#"CREATE TABLE IF NOT EXISTS "Todo_history" ("id" INTEGER PRIMARY KEY AUTOINCREMENT, "record_id" INTEGER NOT NULL, "action" TEXT NOT NULL, "before" TEXT, "after" TEXT, "changed_by" TEXT, "changed_at" TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP)"#,
+                r#"CREATE TRIGGER IF NOT EXISTS "Todo_history_insert" AFTER INSERT ON "Todo" BEGIN INSERT INTO "Todo_history" ("record_id", "action", "before", "after") VALUES (new."id", 'insert', NULL, json_object('title', new."title", 'done', new."done", 'description', new."description"))"#,
+                r#"END"#,
+                r#"CREATE TRIGGER IF NOT EXISTS "Todo_history_update" AFTER UPDATE ON "Todo" BEGIN INSERT INTO "Todo_history" ("record_id", "action", "before", "after") VALUES (new."id", 'update', json_object('title', old."title", 'done', old."done", 'description', old."description"), json_object('title', new."title", 'done', new."done", 'description', new."description"))"#,
+                r#"END"#,
+                r#"CREATE TRIGGER IF NOT EXISTS "Todo_history_delete" AFTER DELETE ON "Todo" BEGIN INSERT INTO "Todo_history" ("record_id", "action", "before", "after") VALUES (old."id", 'delete', json_object('title', old."title", 'done', old."done", 'description', old."description"), NULL)"#,
+                r#"END;"#,
+            ]
+        );
+
+        sqlx::query(&migration).execute(&mut conn).await.unwrap();
+
+        let first = || ColumnEqual {
+            col: ScopedColumn {
+                table: ("Todo",),
+                col: ("id",),
+            },
+            eq: 1,
+        };
+
+        Operation::<Sqlite>::exec_operation(
+            Audited {
+                op: InsertOne {
+                    id: AutoGenerate,
+                    data: One(Todo {
+                        title: String::from("todo"),
+                        done: false,
+                        description: None,
+                    }),
+                    handler: TodoHandler,
+                    links: (),
+                },
+                changed_by: Some(String::from("user_1")),
+            },
+            &mut conn,
+        )
+        .await
+        .unwrap();
+
+        // writes that are not `Audited` are recorded all the same
+        Operation::<Sqlite>::exec_operation(
+            UpdateOp {
+                base: TodoHandler,
+                partial: TodoPartial {
+                    title: Update::Keep,
+                    done: Update::Set(true),
+                    description: Update::Keep,
+                },
+                wheres: first(),
+                links: (),
+            },
+            &mut conn,
+        )
+        .await
+        .unwrap();
+
+        Operation::<Sqlite>::exec_operation(
+            Audited {
+                op: Delete {
+                    base: TodoHandler,
+                    wheres: first(),
+                    links: (),
+                },
+                changed_by: Some(String::from("user_2")),
+            },
+            &mut conn,
+        )
+        .await;
+
+        let history = Operation::<Sqlite>::exec_operation(
+            FetchHistory {
+                base: TodoHandler,
+                record_id: 1,
+            },
+            &mut conn,
+        )
+        .await;
+
+        let pending = r#"{"title":"todo","done":0,"description":null}"#;
+        let done = r#"{"title":"todo","done":1,"description":null}"#;
+
+        pretty_assertions::assert_eq!(
+            history
+                .iter()
+                .map(|entry| (
+                    entry.version,
+                    entry.action,
+                    entry.before.as_deref(),
+                    entry.after.as_deref(),
+                    entry.changed_by.as_deref(),
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, AuditAction::Insert, None, Some(pending), Some("user_1")),
+                (2, AuditAction::Update, Some(pending), Some(done), None),
+                (3, AuditAction::Delete, Some(done), None, Some("user_2")),
+            ]
+        );
+
+        // other records have their own history, even for raw writes
+        Operation::<Sqlite>::exec_operation(
+            InsertOne {
+                id: AutoGenerate,
+                data: One(Todo {
+                    title: String::from("other"),
+                    done: false,
+                    description: None,
+                }),
+                handler: TodoHandler,
+                links: (),
+            },
+            &mut conn,
+        )
+        .await
+        .unwrap();
+
+        sqlx::query("UPDATE Todo SET description = 'raw' WHERE id = 2")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let other = Operation::<Sqlite>::exec_operation(
+            FetchHistory {
+                base: TodoHandler,
+                record_id: 2,
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            other
+                .iter()
+                .map(|entry| (
+                    entry.version,
+                    entry.action,
+                    entry.before.as_deref(),
+                    entry.after.as_deref(),
+                    entry.changed_by.as_deref(),
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    4,
+                    AuditAction::Insert,
+                    None,
+                    Some(r#"{"title":"other","done":0,"description":null}"#),
+                    None
+                ),
+                (
+                    5,
+                    AuditAction::Update,
+                    Some(r#"{"title":"other","done":0,"description":null}"#),
+                    Some(r#"{"title":"other","done":0,"description":"raw"}"#),
+                    None
+                ),
+            ]
+        );
+    }
+}
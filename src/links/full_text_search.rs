@@ -0,0 +1,591 @@
+use crate::collections::Collection;
+
+/// indexes `fields` of `collection` in an FTS5 virtual table named
+/// `<collection>_fts`, triggers keep it in sync with the collection.
+///
+/// unlike `ColumnContainsText`, `Search` goes through the index and
+/// `Relevance` ranks the results.
+#[derive(Debug, Clone)]
+pub struct FullTextSearch<C, Fields> {
+    pub collection: C,
+    pub fields: Fields,
+}
+
+impl<C, Fields> FullTextSearch<C, Fields>
+where
+    C: Collection,
+{
+    fn table(&self) -> String {
+        self.collection.table_name().to_string()
+    }
+
+    /// the table a `FullTextSearch` migrates
+    pub fn fts_table(&self) -> String {
+        format!("{}_fts", self.collection.table_name())
+    }
+
+    /// records of the collection matching every word of `text`
+    pub fn search(&self, text: &str) -> Search {
+        Search {
+            table: self.table(),
+            fts_table: self.fts_table(),
+            query: phrases(text),
+        }
+    }
+
+    /// order by how well records match the words of `text`, best match first
+    pub fn relevance(&self, text: &str) -> Relevance {
+        Relevance {
+            table: self.table(),
+            fts_table: self.fts_table(),
+            query: phrases(text),
+        }
+    }
+}
+
+/// `text` as an FTS5 query where each word is a quoted phrase, so text from
+/// users can't use, or break, the FTS5 query syntax
+pub fn phrases(text: &str) -> String {
+    let phrases = text
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>();
+    match phrases.is_empty() {
+        // an empty phrase matches nothing
+        true => String::from("\"\""),
+        false => phrases.join(" "),
+    }
+}
+
+/// `<table>."id" IN (SELECT rowid FROM <fts_table> WHERE <fts_table> MATCH <query>)`,
+/// `query` is bound as is, see `phrases`
+#[derive(Debug, Clone)]
+pub struct Search {
+    pub table: String,
+    pub fts_table: String,
+    pub query: String,
+}
+
+/// the bm25 rank of a record, lower is a better match.
+///
+/// records not matching `query` rank as `NULL`, and row value comparison
+/// with `NULL` is never true, so it is meant to be used along `Search`
+#[derive(Debug, Clone)]
+pub struct Relevance {
+    pub table: String,
+    pub fts_table: String,
+    pub query: String,
+}
+
+pub mod expressions {
+    use crate::{
+        database_extention::DatabaseExt,
+        sqlx_query_builder::{Expression, OpExpression, StatementBuilder},
+    };
+
+    /// `CREATE VIRTUAL TABLE IF NOT EXISTS <fts_table> USING fts5(<fields>, content=<table>, content_rowid="id");`
+    pub struct CreateFtsTable {
+        pub fts_table: String,
+        pub table: String,
+        pub fields: Vec<String>,
+    }
+
+    impl OpExpression for CreateFtsTable {}
+
+    impl<'q, S> Expression<'q, S> for CreateFtsTable
+    where
+        S: DatabaseExt,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.syntax("CREATE VIRTUAL TABLE IF NOT EXISTS ");
+            ctx.sanitize(&self.fts_table);
+            ctx.syntax(" USING fts5(");
+            for field in self.fields.iter() {
+                ctx.sanitize(field);
+                ctx.syntax(", ");
+            }
+            ctx.syntax("content=");
+            ctx.sanitize(&self.table);
+            ctx.syntax(", content_rowid=");
+            ctx.sanitize("id");
+            ctx.syntax(");");
+        }
+    }
+
+    /// body of the triggers keeping `fts_table` in sync, removes the `old`
+    /// row from the index then adds the `new` one
+    pub struct FtsSync {
+        pub fts_table: String,
+        pub fields: Vec<String>,
+        pub remove_old: bool,
+        pub add_new: bool,
+    }
+
+    impl FtsSync {
+        fn row<'q, S>(&self, row: &'static str, ctx: &mut StatementBuilder<'q, S>)
+        where
+            S: DatabaseExt,
+        {
+            ctx.syntax(row);
+            ctx.sanitize("id");
+            for field in self.fields.iter() {
+                ctx.syntax(", ");
+                ctx.syntax(row);
+                ctx.sanitize(field);
+            }
+        }
+
+        fn columns<'q, S>(&self, ctx: &mut StatementBuilder<'q, S>)
+        where
+            S: DatabaseExt,
+        {
+            ctx.syntax("rowid");
+            for field in self.fields.iter() {
+                ctx.syntax(", ");
+                ctx.sanitize(field);
+            }
+        }
+    }
+
+    impl OpExpression for FtsSync {}
+
+    impl<'q, S> Expression<'q, S> for FtsSync
+    where
+        S: DatabaseExt,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            if self.remove_old {
+                // external content tables are told what to remove from the index
+                ctx.syntax("INSERT INTO ");
+                ctx.sanitize(&self.fts_table);
+                ctx.syntax("(");
+                ctx.sanitize(&self.fts_table);
+                ctx.syntax(", ");
+                self.columns(ctx);
+                ctx.syntax(") VALUES ('delete', ");
+                self.row("old.", ctx);
+                ctx.syntax(");");
+            }
+            if self.remove_old && self.add_new {
+                ctx.syntax(" ");
+            }
+            if self.add_new {
+                ctx.syntax("INSERT INTO ");
+                ctx.sanitize(&self.fts_table);
+                ctx.syntax("(");
+                self.columns(ctx);
+                ctx.syntax(") VALUES (");
+                self.row("new.", ctx);
+                ctx.syntax(");");
+            }
+        }
+    }
+
+    /// indexes the records that exist before the triggers do
+    pub struct FtsRebuild {
+        pub fts_table: String,
+    }
+
+    impl OpExpression for FtsRebuild {}
+
+    impl<'q, S> Expression<'q, S> for FtsRebuild
+    where
+        S: DatabaseExt,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.syntax("INSERT INTO ");
+            ctx.sanitize(&self.fts_table);
+            ctx.syntax("(");
+            ctx.sanitize(&self.fts_table);
+            ctx.syntax(") VALUES ('rebuild');");
+        }
+    }
+
+    /// every statement of the migration, separated by spaces
+    pub struct FtsMigration<OnInsert, OnDelete, OnUpdate> {
+        pub create_table: CreateFtsTable,
+        pub on_insert: OnInsert,
+        pub on_delete: OnDelete,
+        pub on_update: OnUpdate,
+        pub rebuild: FtsRebuild,
+    }
+
+    impl<I, D, U> OpExpression for FtsMigration<I, D, U> {}
+
+    impl<'q, S, OnInsert, OnDelete, OnUpdate> Expression<'q, S>
+        for FtsMigration<OnInsert, OnDelete, OnUpdate>
+    where
+        S: DatabaseExt,
+        OnInsert: Expression<'q, S> + 'q,
+        OnDelete: Expression<'q, S> + 'q,
+        OnUpdate: Expression<'q, S> + 'q,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            self.create_table.expression(ctx);
+            ctx.syntax(" ");
+            self.on_insert.expression(ctx);
+            ctx.syntax(" ");
+            self.on_delete.expression(ctx);
+            ctx.syntax(" ");
+            self.on_update.expression(ctx);
+            ctx.syntax(" ");
+            self.rebuild.expression(ctx);
+        }
+    }
+
+    /// `(SELECT bm25(<fts_table>) FROM <fts_table> WHERE <fts_table> MATCH <query> AND <fts_table>.rowid = <table>."id")`
+    pub struct RelevanceScore {
+        pub table: String,
+        pub fts_table: String,
+        pub query: String,
+    }
+
+    impl OpExpression for RelevanceScore {}
+
+    impl<'q, S> Expression<'q, S> for RelevanceScore
+    where
+        S: DatabaseExt,
+        String: sqlx::Encode<'q, S> + sqlx::Type<S>,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.syntax("(SELECT bm25(");
+            ctx.sanitize(&self.fts_table);
+            ctx.syntax(") FROM ");
+            ctx.sanitize(&self.fts_table);
+            ctx.syntax(" WHERE ");
+            ctx.sanitize(&self.fts_table);
+            ctx.syntax(" MATCH ");
+            ctx.bind(self.query);
+            ctx.syntax(" AND ");
+            ctx.sanitize(&self.fts_table);
+            ctx.syntax(".rowid = ");
+            ctx.sanitize(&self.table);
+            ctx.syntax(".");
+            ctx.sanitize("id");
+            ctx.syntax(")");
+        }
+    }
+}
+
+mod impl_on_migrate {
+    use crate::{
+        collections::Collection,
+        links::{
+            full_text_search::{
+                FullTextSearch,
+                expressions::{CreateFtsTable, FtsMigration, FtsRebuild, FtsSync},
+            },
+            timestamp::expressions::create_trigger::{
+                CreateTrigger, TriggerLifetimeAfter, TriggerOperationNameDelete,
+                TriggerOperationNameInsert, TriggerOperationNameUpdate,
+            },
+        },
+        on_migrate::OnMigrate,
+    };
+
+    type FtsTrigger<OperationName> =
+        CreateTrigger<String, TriggerLifetimeAfter, OperationName, String, (), FtsSync>;
+
+    impl<C, Fields> FullTextSearch<C, Fields>
+    where
+        C: Collection,
+        Fields: Clone + IntoIterator<Item: AsRef<str>>,
+    {
+        fn trigger<OperationName>(
+            &self,
+            name: &str,
+            operation_name: OperationName,
+            remove_old: bool,
+            add_new: bool,
+        ) -> FtsTrigger<OperationName> {
+            CreateTrigger {
+                trigger_name: format!("{}_{}", self.fts_table(), name),
+                temp: false,
+                if_not_exists: true,
+                lifetime: TriggerLifetimeAfter,
+                operation_name,
+                on_table: self.table(),
+                for_each_row: false,
+                when_expression: (),
+                statements: FtsSync {
+                    fts_table: self.fts_table(),
+                    fields: self.field_names(),
+                    remove_old,
+                    add_new,
+                },
+            }
+        }
+
+        fn field_names(&self) -> Vec<String> {
+            self.fields
+                .clone()
+                .into_iter()
+                .map(|field| field.as_ref().to_string())
+                .collect()
+        }
+    }
+
+    impl<C, Fields> OnMigrate for FullTextSearch<C, Fields>
+    where
+        C: Collection,
+        Fields: Clone + IntoIterator<Item: AsRef<str>>,
+    {
+        type Statements = FtsMigration<
+            FtsTrigger<TriggerOperationNameInsert>,
+            FtsTrigger<TriggerOperationNameDelete>,
+            FtsTrigger<TriggerOperationNameUpdate<()>>,
+        >;
+
+        fn statments(&self) -> Self::Statements {
+            FtsMigration {
+                create_table: CreateFtsTable {
+                    fts_table: self.fts_table(),
+                    table: self.table(),
+                    fields: self.field_names(),
+                },
+                on_insert: self.trigger("insert", TriggerOperationNameInsert, false, true),
+                on_delete: self.trigger("delete", TriggerOperationNameDelete, true, false),
+                on_update: self.trigger("update", TriggerOperationNameUpdate(()), true, true),
+                rebuild: FtsRebuild {
+                    fts_table: self.fts_table(),
+                },
+            }
+        }
+    }
+}
+
+mod impl_search {
+    use crate::{
+        database_extention::DatabaseExt,
+        links::full_text_search::Search,
+        sqlx_query_builder::{Expression, OpExpression, StatementBuilder},
+    };
+
+    impl OpExpression for Search {}
+
+    impl<'q, S> Expression<'q, S> for Search
+    where
+        S: DatabaseExt,
+        String: sqlx::Encode<'q, S> + sqlx::Type<S>,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.sanitize(&self.table);
+            ctx.syntax(".");
+            ctx.sanitize("id");
+            ctx.syntax(" IN (SELECT rowid FROM ");
+            ctx.sanitize(&self.fts_table);
+            ctx.syntax(" WHERE ");
+            ctx.sanitize(&self.fts_table);
+            ctx.syntax(" MATCH ");
+            ctx.bind(self.query);
+            ctx.syntax(")");
+        }
+    }
+}
+
+mod impl_relevance {
+    use sqlx::{ColumnIndex, Decode, Encode, Row, Type};
+
+    use crate::{
+        database_extention::DatabaseExt,
+        from_row::{
+            FromRowAlias, FromRowData, FromRowError, RowPostAliased, RowPreAliased, RowTwoAliased,
+        },
+        links::full_text_search::{Relevance, expressions::RelevanceScore},
+        operations::{
+            cursor::CursorOrder, link_order_by::AliasedExpression,
+            operations_expressions_crossover::ExpressionsForOperation,
+        },
+        sqlx_query_builder::{Expression, StatementBuilder},
+    };
+
+    impl ExpressionsForOperation for Relevance {
+        type Identifier = &'static str;
+        fn identifier(&self) -> Self::Identifier {
+            "relevance"
+        }
+
+        type Scoped = RelevanceScore;
+        fn scoped(&self) -> Self::Scoped {
+            RelevanceScore {
+                table: self.table.clone(),
+                fts_table: self.fts_table.clone(),
+                query: self.query.clone(),
+            }
+        }
+
+        type ScopedAliased = AliasedExpression<Self::Scoped, (&'static str, &'static str)>;
+        fn scoped_aliased(&self, alias: &'static str) -> Self::ScopedAliased {
+            AliasedExpression {
+                expression: self.scoped(),
+                alias: (alias, "relevance"),
+            }
+        }
+
+        type NumScopedAliased =
+            AliasedExpression<Self::Scoped, (&'static str, usize, &'static str)>;
+        fn num_scoped_aliased(&self, num: usize, alias: &'static str) -> Self::NumScopedAliased {
+            AliasedExpression {
+                expression: self.scoped(),
+                alias: (alias, num, "relevance"),
+            }
+        }
+    }
+
+    impl<'q, S> CursorOrder<'q, S> for Relevance
+    where
+        S: DatabaseExt,
+        String: Encode<'q, S> + Type<S>,
+        f64: Encode<'q, S> + Type<S>,
+    {
+        fn keys_len(&self) -> usize {
+            1
+        }
+        fn descending(&self, _: usize) -> bool {
+            false
+        }
+        fn nullable(&self, _: usize) -> bool {
+            false
+        }
+        fn key(&self, _: usize, ctx: &mut StatementBuilder<'q, S>) {
+            Expression::expression(self.scoped(), ctx)
+        }
+        fn value(&self, value: &Self::RData, _: usize, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.bind(*value)
+        }
+    }
+
+    impl FromRowData for Relevance {
+        type RData = f64;
+    }
+
+    impl<'r, R> FromRowAlias<'r, R> for Relevance
+    where
+        R: Row + 'r,
+        f64: Type<R::Database> + Decode<'r, R::Database>,
+        for<'q> &'q str: ColumnIndex<R>,
+    {
+        fn no_alias(&self, row: &'r R) -> Result<Self::RData, FromRowError> {
+            Ok(row.try_get("relevance")?)
+        }
+        fn pre_alias(&self, row: RowPreAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+            Ok(row.try_get("relevance")?)
+        }
+        fn post_alias(&self, row: RowPostAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+            Ok(row.try_get("relevance")?)
+        }
+        fn two_alias(&self, row: RowTwoAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+            Ok(row.try_get("relevance")?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sqlx::Sqlite;
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        links::full_text_search::FullTextSearch,
+        on_migrate::OnMigrate,
+        operations::{
+            Operation,
+            fetch_many::{FetchMany, Pagination},
+        },
+        sqlx_query_builder::StatementBuilder,
+        test_module::TodoHandler,
+    };
+
+    #[tokio::test]
+    async fn main() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        sqlx::query(
+            "
+        CREATE TABLE Todo (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            done BOOLEAN NOT NULL,
+            description TEXT
+        );
+        INSERT INTO Todo (title, done, description) VALUES
+            ('buy milk', false, 'and bread'),
+            ('walk the dog', false, NULL);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let fts = FullTextSearch {
+            collection: TodoHandler,
+            fields: ["title", "description"],
+        };
+
+        let migration = StatementBuilder::<Sqlite>::new_no_data(fts.statments()).unwrap();
+
+        pretty_assertions::assert_eq!(
+            migration.split("; ").collect::<Vec<_>>(),
+            vec![
+                r#"CREATE VIRTUAL TABLE IF NOT EXISTS "Todo_fts" USING fts5("title", "description", content="Todo", content_rowid="id")"#,
+                r#"CREATE TRIGGER IF NOT EXISTS "Todo_fts_insert" AFTER INSERT ON "Todo" BEGIN INSERT INTO "Todo_fts"(rowid, "title", "description") VALUES (new."id", new."title", new."description")"#,
+                r#"END"#,
+                r#"CREATE TRIGGER IF NOT EXISTS "Todo_fts_delete" AFTER DELETE ON "Todo" BEGIN INSERT INTO "Todo_fts"("Todo_fts", rowid, "title", "description") VALUES ('delete', old."id", old."title", old."description")"#,
+                r#"END"#,
+                r#"CREATE TRIGGER IF NOT EXISTS "Todo_fts_update" AFTER UPDATE ON "Todo" BEGIN INSERT INTO "Todo_fts"("Todo_fts", rowid, "title", "description") VALUES ('delete', old."id", old."title", old."description")"#,
+                r#"INSERT INTO "Todo_fts"(rowid, "title", "description") VALUES (new."id", new."title", new."description")"#,
+                r#"END"#,
+                r#"INSERT INTO "Todo_fts"("Todo_fts") VALUES ('rebuild');"#,
+            ]
+        );
+
+        sqlx::query(&migration).execute(&mut conn).await.unwrap();
+
+        sqlx::query(
+            "
+        INSERT INTO Todo (title, done, description) VALUES
+            ('buy a dog bed', false, 'for the dog');
+        UPDATE Todo SET title = 'buy cheese' WHERE id = 1;
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let search = |query: &str| FetchMany {
+            base: TodoHandler,
+            wheres: fts.search(query),
+            links: (),
+            cursor_order_by: fts.relevance(query),
+            pagination: Pagination::offset(0, 10, false).unwrap(),
+            computed: (),
+        };
+
+        let output = Operation::<Sqlite>::exec_operation(search("dog"), &mut conn).await;
+
+        // "dog" is mentioned twice in the third todo
+        pretty_assertions::assert_eq!(
+            output.items.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![3, 2]
+        );
+
+        let output = Operation::<Sqlite>::exec_operation(search("milk"), &mut conn).await;
+
+        // the update removed "milk" from the index
+        pretty_assertions::assert_eq!(output.items.len(), 0);
+
+        // FTS5 syntax is matched as words
+        for query in ["buy  cheese", "\"cheese", "cheese*"] {
+            let output = Operation::<Sqlite>::exec_operation(search(query), &mut conn).await;
+            pretty_assertions::assert_eq!(
+                output.items.iter().map(|e| e.id).collect::<Vec<_>>(),
+                vec![1],
+                "{query}"
+            );
+        }
+        for query in ["cheese OR dog", "title: (", "NEAR(", "", "  "] {
+            let output = Operation::<Sqlite>::exec_operation(search(query), &mut conn).await;
+            pretty_assertions::assert_eq!(output.items.len(), 0, "{query}");
+        }
+    }
+}
@@ -1,8 +1,12 @@
 pub mod relation_many_to_many;
 pub mod relation_optional_to_many;
 pub mod relation_optional_to_many_inverse;
+pub mod audit_log;
+pub mod full_text_search;
+pub mod soft_delete;
 pub mod timestamp;
 pub mod update_links;
+pub mod versioned;
 
 pub trait Link<Base> {
     type Spec;
@@ -17,9 +17,8 @@ pub mod junction_names {
     use core::fmt;
 
     use crate::{
+        collections::Collection,
         database_extention::DatabaseExt,
-        expressions::standard_naming_conventions::ConjuctionTableName,
-        extentions::common_expressions::TableNameExpression,
         links::relation_many_to_many::ManyToMany,
         sqlx_query_builder::{Expression, OpExpression, StatementBuilder},
     };
@@ -46,44 +45,35 @@ pub mod junction_names {
         TableLower: AsRef<str> + 'q,
     {
         fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
-            ctx.sanitize_many((self.table_lower.as_ref(), "_id"));
+            ctx.sanitize(&self.to_string());
         }
     }
 
     impl<Key, From, To> ManyToMany<Key, From, To>
     where
-        Key: Clone,
-        From: Clone + TableNameExpression,
-        To: Clone + TableNameExpression,
+        Key: AsRef<str>,
+        From: Collection,
+        To: Collection,
     {
-        pub fn junction_table_name(
-            &self,
-        ) -> ConjuctionTableName<
-            From::LowerCaseTableNameExpression,
-            To::LowerCaseTableNameExpression,
-            Key,
-        >
-        where
-            Key: Clone,
-        {
-            ConjuctionTableName {
-                first: self.from.lower_case_table_name_expression(),
-                second: self.to.lower_case_table_name_expression(),
-                key: self.relation_key.clone(),
-            }
+        /// `ct_<from><to><key>`
+        pub fn junction_table_name(&self) -> String {
+            format!(
+                "ct_{}{}{}",
+                self.from.table_name_lower_case(),
+                self.to.table_name_lower_case(),
+                self.relation_key.as_ref()
+            )
         }
 
-        pub fn from_junction_column(
-            &self,
-        ) -> JunctionSideColumn<From::LowerCaseTableNameExpression> {
+        pub fn from_junction_column(&self) -> JunctionSideColumn<String> {
             JunctionSideColumn {
-                table_lower: self.from.lower_case_table_name_expression(),
+                table_lower: self.from.table_name_lower_case().to_string(),
             }
         }
 
-        pub fn to_junction_column(&self) -> JunctionSideColumn<To::LowerCaseTableNameExpression> {
+        pub fn to_junction_column(&self) -> JunctionSideColumn<String> {
             JunctionSideColumn {
-                table_lower: self.to.lower_case_table_name_expression(),
+                table_lower: self.to.table_name_lower_case().to_string(),
             }
         }
     }
@@ -95,19 +85,6 @@ mod migration_expressions {
         sqlx_query_builder::{Expression, ManyExpressions, OpExpression, StatementBuilder},
     };
 
-    pub struct OnDeleteCascade;
-
-    impl OpExpression for OnDeleteCascade {}
-
-    impl<'q, S> Expression<'q, S> for OnDeleteCascade
-    where
-        S: DatabaseExt,
-    {
-        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
-            ctx.syntax("ON DELETE CASCADE");
-        }
-    }
-
     pub struct CompositePrimaryKey<Cols>(pub Cols);
 
     impl<Cols> OpExpression for CompositePrimaryKey<Cols> {}
@@ -130,85 +107,166 @@ mod impl_on_migrate {
 
     use crate::{
         collections::{Collection, SingleColumnId},
-        extentions::common_expressions::TableNameExpression,
         links::relation_many_to_many::{
-            ManyToMany,
-            junction_names::JunctionSideColumn,
-            migration_expressions::{CompositePrimaryKey, OnDeleteCascade},
+            ManyToMany, junction_names::JunctionSideColumn,
+            migration_expressions::CompositePrimaryKey,
         },
         on_migrate::OnMigrate,
-        sqlx_query_builder::basic_expressions::{ColumnDefinition, ManyFlat, foriegn_key},
-        sqlx_query_builder::statements::create_table_statement::{
-            CreateTable, expressions::create_table,
+        sqlx_query_builder::{
+            basic_expressions::{ColumnDefinition, ManyFlat},
+            column_constraints::{OnDelete, References},
+            statements::{
+                Batch,
+                create_index_statement::CreateIndex,
+                create_table_statement::{CreateTable, expressions::create_table},
+            },
         },
     };
 
+    type JunctionColumn =
+        ColumnDefinition<JunctionSideColumn<String>, i64, References<String, String, OnDelete>>;
+
     impl<Key, From, To> OnMigrate for ManyToMany<Key, From, To>
     where
-        Key: AsRef<str> + Clone,
-        From: Collection<Id: SingleColumnId> + Clone + TableNameExpression,
-        To: Collection<Id: SingleColumnId> + Clone + TableNameExpression,
+        Key: AsRef<str>,
+        From: Collection<Id: SingleColumnId>,
+        To: Collection<Id: SingleColumnId>,
     {
-        type Statements = CreateTable<
-            create_table,
-            ConjuctionTableName<
-                From::LowerCaseTableNameExpression,
-                To::LowerCaseTableNameExpression,
-                Key,
-            >,
-            ManyPossible<(
-                ColumnDefinition<
-                    JunctionSideColumn<From::LowerCaseTableNameExpression>,
-                    i64,
-                    foriegn_key<ManyPossible<(OnDeleteCascade,)>>,
-                >,
-                ColumnDefinition<
-                    JunctionSideColumn<To::LowerCaseTableNameExpression>,
-                    i64,
-                    foriegn_key<ManyPossible<(OnDeleteCascade,)>>,
-                >,
-                CompositePrimaryKey<
+        type Statements = Batch<
+            ManyFlat<(
+                CreateTable<
+                    create_table,
+                    String,
                     ManyFlat<(
-                        JunctionSideColumn<From::LowerCaseTableNameExpression>,
-                        JunctionSideColumn<To::LowerCaseTableNameExpression>,
+                        JunctionColumn,
+                        JunctionColumn,
+                        CompositePrimaryKey<
+                            ManyFlat<(JunctionSideColumn<String>, JunctionSideColumn<String>)>,
+                        >,
                     )>,
                 >,
+                CreateIndex<String, String, JunctionSideColumn<String>, ()>,
             )>,
         >;
 
         fn statments(&self) -> Self::Statements {
-            CreateTable {
-                init: create_table,
-                name: self.junction_table_name(),
-                col_defs: ManyPossible((
-                    ColumnDefinition {
-                        name: self.from_junction_column(),
-                        ty: PhantomData::<i64>,
-                        constraints: foriegn_key {
-                            references_table: self.from.table_name().to_string(),
-                            references_col: self.from.id().as_ref().to_string(),
-                            ons: ManyPossible((OnDeleteCascade,)),
+            Batch(ManyFlat((
+                CreateTable {
+                    init: create_table,
+                    name: self.junction_table_name(),
+                    col_defs: ManyFlat((
+                        ColumnDefinition {
+                            name: self.from_junction_column(),
+                            ty: PhantomData::<i64>,
+                            constraints: References {
+                                table: self.from.table_name().to_string(),
+                                column: self.from.id().as_ref().to_string(),
+                                ons: OnDelete("CASCADE"),
+                            },
                         },
-                    },
-                    ColumnDefinition {
-                        name: self.to_junction_column(),
-                        ty: PhantomData::<i64>,
-                        constraints: foriegn_key {
-                            references_table: self.to.table_name().to_string(),
-                            references_col: self.to.id().as_ref().to_string(),
-                            ons: ManyPossible((OnDeleteCascade,)),
+                        ColumnDefinition {
+                            name: self.to_junction_column(),
+                            ty: PhantomData::<i64>,
+                            constraints: References {
+                                table: self.to.table_name().to_string(),
+                                column: self.to.id().as_ref().to_string(),
+                                ons: OnDelete("CASCADE"),
+                            },
                         },
-                    },
-                    CompositePrimaryKey(ManyFlat((
-                        self.from_junction_column(),
+                        CompositePrimaryKey(ManyFlat((
+                            self.from_junction_column(),
+                            self.to_junction_column(),
+                        ))),
+                    )),
+                },
+                // the composite primary key already covers lookups
+                // from the `From` side, this one covers the reverse
+                CreateIndex {
+                    unique: false,
+                    if_not_exists: true,
+                    name: format!(
+                        "idx_{}_{}",
+                        self.junction_table_name(),
                         self.to_junction_column(),
-                    ))),
-                )),
-            }
+                    ),
+                    table: self.junction_table_name(),
+                    columns: self.to_junction_column(),
+                    wheres: (),
+                },
+            )))
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use sqlx::{Sqlite, query, query_as};
+
+        use crate::{
+            connect_in_memory::ConnectInMemory,
+            links::{DefaultRelationKey, relation_many_to_many::ManyToMany},
+            on_migrate::OnMigrate,
+            sqlx_query_builder::StatementBuilder,
+            test_module::{TagHandler, TodoHandler},
+        };
+
+        #[tokio::test]
+        async fn migrate() {
+            let mut conn = Sqlite::in_memory_connection().await;
+
+            let link = ManyToMany {
+                relation_key: DefaultRelationKey,
+                from: TodoHandler,
+                to: TagHandler,
+            };
+
+            let stmt = StatementBuilder::<Sqlite>::new_no_data(link.statments()).unwrap();
+            pretty_assertions::assert_eq!(
+                stmt,
+                r#"CREATE TABLE "ct_todotag_def" ("todo_id" INTEGER NOT NULL REFERENCES "Todo"("id") ON DELETE CASCADE, "tag_id" INTEGER NOT NULL REFERENCES "Tag"("id") ON DELETE CASCADE, PRIMARY KEY ("todo_id", "tag_id")); CREATE INDEX IF NOT EXISTS "idx_ct_todotag_def_tag_id" ON "ct_todotag_def" ("tag_id");"#
+            );
+
+            query(
+                "
+            CREATE TABLE Tag (id INTEGER PRIMARY KEY AUTOINCREMENT, title TEXT NOT NULL);
+            CREATE TABLE Todo (id INTEGER PRIMARY KEY AUTOINCREMENT, title TEXT NOT NULL);
+            ",
+            )
+            .execute(&mut conn)
+            .await
+            .unwrap();
+            query(&stmt).execute(&mut conn).await.unwrap();
+
+            let index: Vec<(String,)> =
+                query_as("SELECT name FROM pragma_index_info('idx_ct_todotag_def_tag_id')")
+                    .fetch_all(&mut conn)
+                    .await
+                    .unwrap();
+            pretty_assertions::assert_eq!(index, vec![("tag_id".to_string(),)]);
+
+            // deleting a tag drops its junction rows
+            query(
+                "
+            PRAGMA foreign_keys = ON;
+            INSERT INTO Tag (title) VALUES ('urgent'), ('home');
+            INSERT INTO Todo (title) VALUES ('first_todo');
+            INSERT INTO ct_todotag_def (todo_id, tag_id) VALUES (1, 1), (1, 2);
+            DELETE FROM Tag WHERE id = 1;
+            ",
+            )
+            .execute(&mut conn)
+            .await
+            .unwrap();
+            let rows: Vec<(i64, i64)> = query_as("SELECT todo_id, tag_id FROM ct_todotag_def")
+                .fetch_all(&mut conn)
+                .await
+                .unwrap();
+            pretty_assertions::assert_eq!(rows, vec![(1, 2)]);
         }
     }
 }
 
+#[claw_ql_macros::skip]
+// to be refactored
 mod many_to_many_items {
     use crate::{
         collections::{Collection, CollectionId},
@@ -377,6 +435,63 @@ mod many_to_many_joins {
     }
 }
 
+mod impl_link_filter {
+    use crate::{
+        collections::{Collection, SingleColumnId},
+        links::{
+            relation_many_to_many::{ManyToMany, junction_names::JunctionSideColumn},
+            relation_optional_to_many::join_expression::{JoinExpression, TableColumn},
+        },
+        operations::link_filter::LinkFilter,
+        sqlx_query_builder::basic_expressions::ColumnEqualColumn,
+    };
+
+    impl<Key, From, To> LinkFilter for ManyToMany<Key, From, To>
+    where
+        Key: AsRef<str>,
+        From: Collection<Id: SingleColumnId>,
+        To: Collection<Id: SingleColumnId>,
+    {
+        type From = String;
+
+        fn filter_from(&self) -> Self::From {
+            self.junction_table_name()
+        }
+
+        type Joins = JoinExpression<String, String, String, JunctionSideColumn<String>>;
+
+        fn filter_joins(&self) -> Self::Joins {
+            JoinExpression {
+                join_type: "INNER JOIN",
+                foreign_table: self.to.table_name().to_string(),
+                foreign_column: self.to.id().as_ref().to_string(),
+                local_table: self.junction_table_name(),
+                local_column: self.to_junction_column(),
+            }
+        }
+
+        type Correlate = ColumnEqualColumn<
+            TableColumn<String, JunctionSideColumn<String>>,
+            TableColumn<String, String>,
+        >;
+
+        fn filter_correlate(&self) -> Self::Correlate {
+            ColumnEqualColumn {
+                col: TableColumn {
+                    table: self.junction_table_name(),
+                    column: self.from_junction_column(),
+                },
+                other: TableColumn {
+                    table: self.from.table_name().to_string(),
+                    column: self.from.id().as_ref().to_string(),
+                },
+            }
+        }
+    }
+}
+
+#[claw_ql_macros::skip]
+// to be refactored
 mod impl_link_fetch_many {
     use std::collections::HashSet;
 
@@ -471,6 +586,8 @@ mod impl_link_fetch_many {
 
 pub type ManyToManyFetchOne<Key, From, To> = ManyToMany<Key, From, To>;
 
+#[claw_ql_macros::skip]
+// to be refactored
 mod impl_mutate_links {
     use std::marker::PhantomData;
 
@@ -974,9 +1091,11 @@ mod impl_mutate_links {
     }
 }
 
-pub use crate::operations::fetch_linked_records::FetchManyToManyLinked;
-pub use impl_mutate_links::{DeleteManyToManyLinked, RemoveJunctionId, SetJunctionId};
+// pub use crate::operations::fetch_linked_records::FetchManyToManyLinked;
+// pub use impl_mutate_links::{DeleteManyToManyLinked, RemoveJunctionId, SetJunctionId};
 
+#[claw_ql_macros::skip]
+// to be refactored
 #[cfg(test)]
 mod test {
     use sqlx::Sqlite;
@@ -990,7 +1109,7 @@ mod test {
         on_migrate::OnMigrate,
         operations::{
             CollectionOutput, LinkedOutput, ManyLinkOutput, Operation,
-            fetch_many::{FetchMany, ManyOutput},
+            fetch_many::{FetchMany, ManyOutput, Pagination},
             fetch_one::FetchOne,
         },
         sqlx_query_builder::{Expression, StatementBuilder},
@@ -1052,18 +1171,6 @@ mod test {
         sqlx::query(&qb.stmt).execute(&mut *conn).await.unwrap();
     }
 
-    #[test]
-    fn migrate_statement_creates_junction_table() {
-        let link = todo_to_tag_link();
-        let mut qb = StatementBuilder::<Sqlite>::default();
-        link.statments().expression(&mut qb);
-
-        pretty_assertions::assert_eq!(
-            qb.stmt,
-            r#"CREATE TABLE "ct_todotag_def" ("todo_id" INTEGER NOT NULL  REFERENCES "Todo"("id") ON DELETE CASCADE, "tag_id" INTEGER NOT NULL  REFERENCES "Tag"("id") ON DELETE CASCADE, PRIMARY KEY ("todo_id", "tag_id"));"#
-        );
-    }
-
     #[tokio::test]
     async fn fetch_many_returns_one_row_per_todo_with_all_tags() {
         let mut conn = Sqlite::in_memory_connection().await;
@@ -1093,8 +1200,11 @@ mod test {
                 wheres: (),
                 links: link,
                 cursor_order_by: test_module::todo_members::id,
-                cursor_first_item: None::<(i64, ())>,
-                limit: 10,
+                pagination: Pagination::Cursor {
+                    cursor: None,
+                    limit: 10,
+                },
+                computed: (),
             },
             &mut conn,
         )
@@ -1127,6 +1237,7 @@ mod test {
                                 },
                             ],
                         },
+                        computed: (),
                     },
                     LinkedOutput {
                         id: 2,
@@ -1143,9 +1254,12 @@ mod test {
                                 },
                             },],
                         },
+                        computed: (),
                     },
                 ],
                 next_item: None,
+                prev_item: None,
+                total: None,
             }
         );
     }
@@ -1211,6 +1325,7 @@ mod test {
                         },
                     ],
                 },
+                computed: (),
             })
         );
     }
@@ -1242,8 +1357,11 @@ mod test {
                 wheres: (),
                 links: link,
                 cursor_order_by: test_module::category_members::id,
-                cursor_first_item: None::<(i64, ())>,
-                limit: 10,
+                pagination: Pagination::Cursor {
+                    cursor: None,
+                    limit: 10,
+                },
+                computed: (),
             },
             &mut conn,
         )
@@ -1274,6 +1392,7 @@ mod test {
                                 },
                             ],
                         },
+                        computed: (),
                     },
                     LinkedOutput {
                         id: 2,
@@ -1288,9 +1407,12 @@ mod test {
                                 },
                             },],
                         },
+                        computed: (),
                     },
                 ],
                 next_item: None,
+                prev_item: None,
+                total: None,
             }
         );
     }
@@ -1317,8 +1439,11 @@ mod test {
                 wheres: (),
                 links: link,
                 cursor_order_by: test_module::todo_members::id,
-                cursor_first_item: None::<(i64, ())>,
-                limit: 10,
+                pagination: Pagination::Cursor {
+                    cursor: None,
+                    limit: 10,
+                },
+                computed: (),
             },
             &mut conn,
         )
@@ -1337,8 +1462,11 @@ mod test {
                     links: ManyLinkOutput {
                         many_output: vec![]
                     },
+                    computed: (),
                 },],
                 next_item: None,
+                prev_item: None,
+                total: None,
             }
         );
     }
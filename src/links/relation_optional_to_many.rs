@@ -13,7 +13,6 @@ pub mod fk_name {
     use crate::{
         collections::Collection,
         database_extention::DatabaseExt,
-        extentions::common_expressions::TableNameExpression,
         links::relation_optional_to_many::OptionalToMany,
         sqlx_query_builder::{Expression, OpExpression, StatementBuilder},
     };
@@ -58,18 +57,16 @@ pub mod fk_name {
         S: DatabaseExt,
         Id: 'q + AsRef<str>,
         F: 'q,
-        T: 'q + TableNameExpression<LowerCaseTableNameExpression: AsRef<str>>,
+        T: 'q + Collection,
     {
         fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
-            ctx.sanitize_many((
-                "fk_",
-                self.relation.to.lower_case_table_name_expression().as_ref(),
-                self.relation.fk_unique_id.as_ref(),
-            ));
+            ctx.sanitize(&self.to_string());
         }
     }
 }
 
+#[claw_ql_macros::skip]
+// to be refactored
 pub mod find_place_for_this {
     use std::marker::PhantomData;
 
@@ -142,50 +139,130 @@ mod impl_on_migrate {
 
     use crate::{
         collections::{Collection, SingleColumnId},
-        expressions::{
-            ColumnDefinition, foriegn_key, on_delete_set_null,
-            standard_naming_conventions::ForeignKeyName,
-        },
-        extentions::common_expressions::TableNameExpression,
-        links::relation_optional_to_many::OptionalToMany,
+        links::relation_optional_to_many::{OptionalToMany, fk_name::AsIdentifier},
         on_migrate::OnMigrate,
-        sqlx_query_builder::functional_expr::ManyPossible,
-        statements::AddColumn,
+        sqlx_query_builder::{
+            basic_expressions::{ColumnDefinition, ManyFlat},
+            column_constraints::{OnDelete, References},
+            statements::{
+                Batch, add_column_statement::AddColumn, create_index_statement::CreateIndex,
+            },
+        },
     };
 
     impl<Key, F, T> OnMigrate for OptionalToMany<Key, F, T>
     where
-        Key: AsRef<str>,
+        Key: AsRef<str> + Clone,
         F: Collection + Clone,
         T: Collection<Id: SingleColumnId> + Clone,
-        F: TableNameExpression,
-        T: TableNameExpression,
-        Key: Clone,
     {
-        type Statements = AddColumn<
-            F::TableNameExpression,
-            ColumnDefinition<
-                ForeignKeyName<Key, T::LowerCaseTableNameExpression>,
-                Option<i64>,
-                foriegn_key<ManyPossible<(on_delete_set_null,)>>,
-            >,
+        type Statements = Batch<
+            ManyFlat<(
+                AddColumn<
+                    String,
+                    ColumnDefinition<
+                        AsIdentifier<Self>,
+                        Option<i64>,
+                        References<String, String, OnDelete>,
+                    >,
+                >,
+                CreateIndex<String, String, AsIdentifier<Self>, ()>,
+            )>,
         >;
         fn statments(&self) -> Self::Statements {
-            AddColumn {
-                table: self.from.table_name_expression(),
-                col_def: ColumnDefinition {
-                    name: ForeignKeyName {
-                        key: self.fk_unique_id.clone(),
-                        to: self.to.lower_case_table_name_expression(),
-                    },
-                    ty: PhantomData,
-                    constraints: foriegn_key {
-                        references_table: self.to.table_name().to_string(),
-                        references_col: self.to.id().as_ref().to_string(),
-                        ons: ManyPossible((on_delete_set_null,)),
+            Batch(ManyFlat((
+                AddColumn {
+                    table: self.from.table_name().to_string(),
+                    col_def: ColumnDefinition {
+                        name: self.fk_name(),
+                        ty: PhantomData,
+                        constraints: References {
+                            table: self.to.table_name().to_string(),
+                            column: self.to.id().as_ref().to_string(),
+                            ons: OnDelete("SET NULL"),
+                        },
                     },
                 },
-            }
+                // fetching the many side filters on the foreign key
+                CreateIndex {
+                    unique: false,
+                    if_not_exists: true,
+                    name: format!(
+                        "idx_{}_{}",
+                        self.from.table_name_lower_case(),
+                        self.fk_name(),
+                    ),
+                    table: self.from.table_name().to_string(),
+                    columns: self.fk_name(),
+                    wheres: (),
+                },
+            )))
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use sqlx::{Sqlite, query, query_as};
+
+        use crate::{
+            connect_in_memory::ConnectInMemory,
+            links::{DefaultRelationKey, relation_optional_to_many::OptionalToMany},
+            on_migrate::OnMigrate,
+            sqlx_query_builder::StatementBuilder,
+            test_module::{CategoryHandler, TodoHandler},
+        };
+
+        #[tokio::test]
+        async fn migrate() {
+            let mut conn = Sqlite::in_memory_connection().await;
+
+            let link = OptionalToMany {
+                fk_unique_id: DefaultRelationKey,
+                from: TodoHandler,
+                to: CategoryHandler,
+            };
+
+            let stmt = StatementBuilder::<Sqlite>::new_no_data(link.statments()).unwrap();
+            pretty_assertions::assert_eq!(
+                stmt,
+                r#"ALTER TABLE "Todo" ADD COLUMN "fk_category_def" INTEGER REFERENCES "Category"("id") ON DELETE SET NULL; CREATE INDEX IF NOT EXISTS "idx_todo_fk_category_def" ON "Todo" ("fk_category_def");"#
+            );
+
+            query(
+                "
+            CREATE TABLE Category (id INTEGER PRIMARY KEY AUTOINCREMENT, title TEXT NOT NULL);
+            CREATE TABLE Todo (id INTEGER PRIMARY KEY AUTOINCREMENT, title TEXT NOT NULL);
+            ",
+            )
+            .execute(&mut conn)
+            .await
+            .unwrap();
+            query(&stmt).execute(&mut conn).await.unwrap();
+
+            let index: Vec<(String,)> =
+                query_as("SELECT name FROM pragma_index_info('idx_todo_fk_category_def')")
+                    .fetch_all(&mut conn)
+                    .await
+                    .unwrap();
+            pretty_assertions::assert_eq!(index, vec![("fk_category_def".to_string(),)]);
+
+            // deleting a category unlinks its todos
+            query(
+                "
+            PRAGMA foreign_keys = ON;
+            INSERT INTO Category (title) VALUES ('work');
+            INSERT INTO Todo (title, fk_category_def) VALUES ('first_todo', 1);
+            DELETE FROM Category;
+            ",
+            )
+            .execute(&mut conn)
+            .await
+            .unwrap();
+            let fk: (Option<i64>,) = query_as("SELECT fk_category_def FROM Todo")
+                .fetch_one(&mut conn)
+                .await
+                .unwrap();
+            pretty_assertions::assert_eq!(fk, (None,));
         }
     }
 }
@@ -292,6 +369,8 @@ mod impl_fetch_one {
     }
 }
 
+#[claw_ql_macros::skip]
+// to be refactored
 mod optional_to_many_items_names {
     use core::fmt;
 
@@ -473,7 +552,7 @@ pub mod join_expression {
         Lc: Expression<'q, S>,
     {
         fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
-            ctx.syntax(&self.join_type);
+            ctx.syntax(self.join_type);
             ctx.syntax(" ");
             self.foreign_table.clone().expression(ctx);
             ctx.syntax(" ON ");
@@ -486,8 +565,75 @@ pub mod join_expression {
             self.foreign_column.expression(ctx);
         }
     }
+
+    #[derive(Clone)]
+    pub struct TableColumn<Table, Column> {
+        pub table: Table,
+        pub column: Column,
+    }
+
+    impl<Table, Column> OpExpression for TableColumn<Table, Column> {}
+
+    impl<'q, S, Table, Column> Expression<'q, S> for TableColumn<Table, Column>
+    where
+        S: DatabaseExt,
+        Table: Expression<'q, S>,
+        Column: Expression<'q, S>,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            self.table.expression(ctx);
+            ctx.syntax(".");
+            self.column.expression(ctx);
+        }
+    }
 }
 
+mod impl_link_filter {
+    use crate::{
+        collections::{Collection, SingleColumnId},
+        links::relation_optional_to_many::{
+            OptionalToMany, fk_name::AsIdentifier, join_expression::TableColumn,
+        },
+        operations::link_filter::LinkFilter,
+        sqlx_query_builder::basic_expressions::ColumnEqualColumn,
+    };
+
+    impl<Key, F, T> LinkFilter for OptionalToMany<Key, F, T>
+    where
+        Key: Clone,
+        F: Collection + Clone,
+        T: Collection<Id: SingleColumnId> + Clone,
+    {
+        type From = String;
+
+        fn filter_from(&self) -> Self::From {
+            self.to.table_name().to_string()
+        }
+
+        type Joins = ();
+
+        fn filter_joins(&self) -> Self::Joins {}
+
+        type Correlate =
+            ColumnEqualColumn<TableColumn<String, String>, TableColumn<String, AsIdentifier<Self>>>;
+
+        fn filter_correlate(&self) -> Self::Correlate {
+            ColumnEqualColumn {
+                col: TableColumn {
+                    table: self.to.table_name().to_string(),
+                    column: self.to.id().as_ref().to_string(),
+                },
+                other: TableColumn {
+                    table: self.from.table_name().to_string(),
+                    column: self.fk_name(),
+                },
+            }
+        }
+    }
+}
+
+#[claw_ql_macros::skip]
+// to be refactored
 mod impl_link_fetch_many {
     use crate::{
         collections::{Collection, CollectionId, SingleColumnId},
@@ -650,6 +796,8 @@ mod impl_link_fetch_many {
     // }
 }
 
+#[claw_ql_macros::skip]
+// to be refactored
 mod impl_set_new_for_insert {
     use std::marker::PhantomData;
 
@@ -887,6 +1035,7 @@ mod impl_set_new_for_insert {
                                 title: "category_1".to_string(),
                             },
                         },
+                        computed: (),
                     })
                 );
             })
@@ -895,6 +1044,8 @@ mod impl_set_new_for_insert {
     }
 }
 
+#[claw_ql_macros::skip]
+// to be refactored
 mod impl_set_id_for_insert {
     use std::marker::PhantomData;
 
@@ -1167,6 +1318,7 @@ mod impl_set_id_for_insert {
                                 title: "category_1".to_string(),
                             },
                         },
+                        computed: (),
                     })
                 );
             })
@@ -1175,6 +1327,8 @@ mod impl_set_id_for_insert {
     }
 }
 
+#[claw_ql_macros::skip]
+// to be refactored
 mod impl_set_id_for_insert_v0 {
     use crate::{
         collections::{Collection, CollectionId},
@@ -1320,6 +1474,8 @@ mod impl_set_id_for_insert_v0 {
     }
 }
 
+#[claw_ql_macros::skip]
+// to be refactored
 mod impl_set_id_for_update {
     use std::marker::PhantomData;
 
@@ -1823,6 +1979,7 @@ WHERE Todo.id = 2;
                             title: "category_3".to_string(),
                         },
                     },
+                    computed: (),
                 }
             );
 
@@ -1871,6 +2028,7 @@ WHERE Todo.id = 2;
                             title: "category_4".to_string(),
                         },
                     },
+                    computed: (),
                 }
             );
 
@@ -1979,6 +2137,8 @@ LEFT JOIN Category ON Todo.fk_category_def = Category.id;
     }
 }
 
+#[claw_ql_macros::skip]
+// to be refactored
 mod impl_set_new_for_update {
     use std::marker::PhantomData;
 
@@ -2154,6 +2314,8 @@ mod impl_set_new_for_update {
     }
 }
 
+#[claw_ql_macros::skip]
+// to be refactored
 mod impl_for_delete {
     use std::marker::PhantomData;
 
@@ -2498,6 +2660,7 @@ mod impl_for_delete {
                                 title: "category_1".to_string(),
                             },
                         }),
+                        computed: (),
                     },]
                 );
             })
@@ -2506,4 +2669,4 @@ mod impl_for_delete {
     }
 }
 
-pub use impl_for_delete::DeleteOptionalToManyLinked;
+// pub use impl_for_delete::DeleteOptionalToManyLinked;
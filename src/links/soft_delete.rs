@@ -0,0 +1,526 @@
+/// marks records as deleted in a `deleted_at` column instead of removing them.
+///
+/// the collection itself opts in with `Collection::soft_delete`, or with
+/// `#[collection(soft_delete)]` when deriving `Collection`: `Delete`
+/// then sets `deleted_at` and `FetchOne`, `FetchMany` and `FetchStream` skip
+/// deleted records. as a fetch link it returns when each record was deleted,
+/// and brings deleted records back when `include_deleted` is set.
+#[derive(Debug, Clone)]
+pub struct SoftDelete<C> {
+    pub collection: C,
+    pub include_deleted: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SoftDeleteOutput {
+    pub deleted_at: Option<String>,
+}
+
+impl crate::gen_serde::Serialize<crate::gen_serde::json_serialize_side::JsonAsString>
+    for SoftDeleteOutput
+{
+    fn serialize(&self, ctx: &mut crate::gen_serde::json_serialize_side::JsonAsString) {
+        use crate::gen_serde::Serialize;
+
+        ctx.0.push('{');
+        Serialize::serialize("deleted_at", ctx);
+        ctx.0.push(':');
+        match &self.deleted_at {
+            Some(deleted_at) => deleted_at.serialize(ctx),
+            None => ctx.0.push_str("null"),
+        }
+        ctx.0.push('}');
+    }
+}
+
+pub mod expressions {
+    use crate::{
+        collections::Collection,
+        database_extention::DatabaseExt,
+        operations::fetch_many::LinkFetch,
+        sqlx_query_builder::{Expression, OpExpression, StatementBuilder},
+    };
+
+    /// `"deleted_at" TEXT`, nullable
+    pub struct DeletedAtColumn;
+
+    impl OpExpression for DeletedAtColumn {}
+
+    impl<'q, S> Expression<'q, S> for DeletedAtColumn
+    where
+        S: DatabaseExt,
+        String: sqlx::Type<S>,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.sanitize("deleted_at");
+            ctx.syntax(" ");
+            ctx.type_as_syntax::<String>();
+        }
+    }
+
+    /// `"Table"."deleted_at" IS NULL`
+    pub struct NotDeleted<Table> {
+        pub table: Table,
+    }
+
+    impl<Table> NotDeleted<Table> {
+        /// the filter fetches of `base` add, unless `links` include the
+        /// deleted records
+        pub fn of<C: Collection, L: LinkFetch>(base: &C, links: &L, table: Table) -> Option<Self> {
+            (base.soft_delete() && !links.include_deleted()).then_some(NotDeleted { table })
+        }
+    }
+
+    impl<Table> OpExpression for NotDeleted<Table> {}
+
+    impl<'q, S, Table> Expression<'q, S> for NotDeleted<Table>
+    where
+        S: DatabaseExt,
+        Table: 'q + Expression<'q, S>,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            self.table.expression(ctx);
+            ctx.syntax(".");
+            ctx.sanitize("deleted_at");
+            ctx.syntax(" IS NULL");
+        }
+    }
+
+    /// `"deleted_at" = CURRENT_TIMESTAMP` or `"deleted_at" = NULL`
+    pub struct SetDeletedAt {
+        pub deleted: bool,
+    }
+
+    impl OpExpression for SetDeletedAt {}
+
+    impl<'q, S> Expression<'q, S> for SetDeletedAt
+    where
+        S: DatabaseExt,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.sanitize("deleted_at");
+            if self.deleted {
+                ctx.syntax(" = CURRENT_TIMESTAMP");
+            } else {
+                ctx.syntax(" = NULL");
+            }
+        }
+    }
+}
+
+mod impl_on_migrate {
+    use crate::{
+        links::soft_delete::{SoftDelete, expressions::DeletedAtColumn},
+        on_migrate::OnMigrate,
+        operations::operations_expressions_crossover::TableExpressions,
+        sqlx_query_builder::statements::add_column_statement::AddColumn,
+    };
+
+    impl<C> OnMigrate for SoftDelete<C>
+    where
+        C: TableExpressions,
+    {
+        type Statements = AddColumn<C::PascalCase, DeletedAtColumn>;
+
+        fn statments(&self) -> Self::Statements {
+            AddColumn {
+                table: self.collection.table_name_pascal_case(),
+                col_def: DeletedAtColumn,
+            }
+        }
+    }
+}
+
+pub mod impl_fetch_many {
+    use sqlx::{ColumnIndex, Decode, Row, Type};
+
+    use crate::{
+        from_row::{
+            FromRowAlias, FromRowData, FromRowError, RowPostAliased, RowPreAliased, RowTwoAliased,
+        },
+        links::soft_delete::{SoftDelete, SoftDeleteOutput},
+        operations::{
+            fetch_many::LinkFetch,
+            operations_expressions_crossover::{ExpressionsForOperation, TableExpressions},
+        },
+        sqlx_query_builder::basic_expressions::{AliasedScopedColumn, ScopedColumn},
+    };
+
+    #[derive(Debug, Clone)]
+    pub struct SoftDeleteSelectItems<TableName>(pub TableName);
+
+    impl<T: Clone> ExpressionsForOperation for SoftDeleteSelectItems<T> {
+        type Identifier = &'static str;
+        fn identifier(&self) -> Self::Identifier {
+            "deleted_at"
+        }
+
+        type Scoped = ScopedColumn<((T,),), (&'static str,)>;
+        fn scoped(&self) -> Self::Scoped {
+            ScopedColumn {
+                table: ((self.0.clone(),),),
+                col: ("deleted_at",),
+            }
+        }
+
+        type ScopedAliased =
+            AliasedScopedColumn<((T,),), (&'static str,), (&'static str, &'static str)>;
+        fn scoped_aliased(&self, alias: &'static str) -> Self::ScopedAliased {
+            AliasedScopedColumn {
+                table: ((self.0.clone(),),),
+                column: ("deleted_at",),
+                alias: (alias, "deleted_at"),
+            }
+        }
+
+        type NumScopedAliased =
+            AliasedScopedColumn<((T,),), (&'static str,), (&'static str, usize, &'static str)>;
+        fn num_scoped_aliased(&self, num: usize, alias: &'static str) -> Self::NumScopedAliased {
+            AliasedScopedColumn {
+                table: ((self.0.clone(),),),
+                column: ("deleted_at",),
+                alias: (alias, num, "deleted_at"),
+            }
+        }
+    }
+
+    impl<T> FromRowData for SoftDeleteSelectItems<T> {
+        type RData = SoftDeleteOutput;
+    }
+
+    impl<'r, R, T> FromRowAlias<'r, R> for SoftDeleteSelectItems<T>
+    where
+        R: Row + 'r,
+        for<'s> &'s str: ColumnIndex<R>,
+        Option<String>: Decode<'r, R::Database> + Type<R::Database>,
+    {
+        fn no_alias(&self, row: &'r R) -> Result<Self::RData, FromRowError> {
+            Ok(SoftDeleteOutput {
+                deleted_at: row.try_get("deleted_at")?,
+            })
+        }
+
+        fn pre_alias(&self, row: RowPreAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+            Ok(SoftDeleteOutput {
+                deleted_at: row.try_get("deleted_at")?,
+            })
+        }
+
+        fn post_alias(&self, _: RowPostAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+            panic!("to depricate");
+        }
+
+        fn two_alias(&self, row: RowTwoAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+            Ok(SoftDeleteOutput {
+                deleted_at: row.try_get("deleted_at")?,
+            })
+        }
+    }
+
+    impl<C> LinkFetch for SoftDelete<C>
+    where
+        C: TableExpressions<PascalCase: Clone>,
+    {
+        type Output = SoftDeleteOutput;
+
+        type SelectItems = SoftDeleteSelectItems<C::PascalCase>;
+
+        fn non_aggregating_select_items(&self) -> Self::SelectItems {
+            SoftDeleteSelectItems(self.collection.table_name_pascal_case())
+        }
+
+        type Join = ();
+
+        fn non_duplicating_join_expressions(&self) -> Self::Join {}
+
+        type Wheres = ();
+
+        fn where_expressions(&self) -> Self::Wheres {}
+
+        fn include_deleted(&self) -> bool {
+            self.include_deleted
+        }
+
+        type OpInput = ();
+
+        fn operation_initialize_input(&self) -> Self::OpInput {}
+
+        type Op = ();
+
+        fn operation_construct(&self, _: Self::OpInput) -> Self::Op
+        where
+            Self::SelectItems: FromRowData,
+        {
+        }
+
+        fn operation_fix_on_many(
+            &self,
+            _: &<Self::SelectItems as FromRowData>::RData,
+            _: &mut Self::OpInput,
+        ) where
+            Self::SelectItems: FromRowData,
+        {
+        }
+
+        fn take_many(
+            &self,
+            item: <Self::SelectItems as FromRowData>::RData,
+            _: &mut <Self::Op as crate::operations::OperationOutput>::Output,
+        ) -> Self::Output {
+            item
+        }
+    }
+}
+
+/// `Restore` brings back soft deleted records, `Delete` soft deletes them.
+pub mod operations {
+    use crate::{
+        collections::{Collection, CollectionId},
+        database_extention::DatabaseExt,
+        execute::Executable,
+        fix_executor::ExecutorTrait,
+        from_row::FromRowAlias,
+        links::soft_delete::expressions::SetDeletedAt,
+        operations::{
+            LinkedOutput, Operation, OperationOutput,
+            operations_expressions_crossover::{ExpressionsForOperation, TableExpressions},
+        },
+        sqlx_query_builder::{
+            Expression, ManyExpressions, StatementBuilder,
+            basic_expressions::{ColumnIsNotNull, ManyFlat},
+            statements::update_statement::UpdateStatement,
+        },
+        statement_cache::SOFT_DELETE,
+    };
+
+    /// clears `deleted_at` of the matching deleted records and returns them
+    pub struct Restore<Base, Wheres> {
+        pub base: Base,
+        pub wheres: Wheres,
+    }
+
+    impl<Base: Collection, Wheres> OperationOutput for Restore<Base, Wheres> {
+        type Output = Vec<LinkedOutput<<Base::Id as CollectionId>::IdData, Base::OutputData, ()>>;
+    }
+
+    impl<S, Base, Wheres> Operation<S> for Restore<Base, Wheres>
+    where
+        S: DatabaseExt + ExecutorTrait,
+        Base: Send + Sync,
+        Base: Collection,
+        Base: TableExpressions<
+                Identifier: for<'q> ManyExpressions<'q, S>,
+                PascalCase: for<'q> Expression<'q, S>,
+            >,
+        Base: for<'r> FromRowAlias<'r, S::Row, RData = Base::OutputData>,
+        Base::OutputData: Send,
+        Base::Id: Send + Sync + CollectionId<IdData: Send>,
+        Base::Id: ExpressionsForOperation<Identifier: for<'q> ManyExpressions<'q, S>>,
+        Base::Id: for<'r> FromRowAlias<'r, S::Row, RData = <Base::Id as CollectionId>::IdData>,
+        Wheres: Send,
+        Wheres: for<'q> ManyExpressions<'q, S>,
+    {
+        async fn exec_operation(self, pool: &mut S::Connection) -> Self::Output {
+            let id = self.base.id();
+
+            let (stmt, args) = StatementBuilder::<S>::new(UpdateStatement {
+                table_name: self.base.table_name_pascal_case(),
+                values: SetDeletedAt { deleted: false },
+                wheres: ManyFlat((
+                    self.wheres,
+                    ColumnIsNotNull {
+                        col: ("deleted_at",),
+                    },
+                )),
+                returning: ManyFlat((id.identifier(), self.base.identifier())),
+            })
+            .unwrap();
+
+            S::fetch_all_mapped(
+                &mut *pool,
+                Executable::cached(&SOFT_DELETE, &stmt, args),
+                |row| LinkedOutput {
+                    id: id.no_alias(&row).unwrap(),
+                    attributes: self.base.no_alias(&row).unwrap(),
+                    links: (),
+                    computed: (),
+                },
+            )
+            .await
+            .unwrap()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sqlx::Sqlite;
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        links::soft_delete::{SoftDelete, operations::Restore},
+        on_migrate::OnMigrate,
+        operations::{
+            LinkedOutput, Operation,
+            delete::Delete,
+            fetch_many::{FetchMany, Pagination},
+            fetch_one::FetchOne,
+        },
+        sqlx_query_builder::{
+            StatementBuilder,
+            basic_expressions::{ColumnEqual, ScopedColumn},
+        },
+        test_module::{Note, NoteHandler},
+    };
+
+    #[tokio::test]
+    async fn main() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        sqlx::query(
+            "
+        CREATE TABLE Note (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL
+        );
+        INSERT INTO Note (title) VALUES ('note_1'), ('note_2');
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let migration =
+            StatementBuilder::<Sqlite>::new_no_data(OnMigrate::statments(&SoftDelete {
+                collection: NoteHandler,
+                include_deleted: false,
+            }))
+            .unwrap();
+
+        pretty_assertions::assert_eq!(
+            migration,
+            r#"ALTER TABLE "Note" ADD COLUMN "deleted_at" TEXT;"#
+        );
+
+        sqlx::query(&migration).execute(&mut conn).await.unwrap();
+
+        let first = || ColumnEqual {
+            col: ScopedColumn {
+                table: ("Note",),
+                col: ("id",),
+            },
+            eq: 1,
+        };
+        let note_1 = || LinkedOutput {
+            id: 1,
+            attributes: Note {
+                title: String::from("note_1"),
+            },
+            links: (),
+            computed: (),
+        };
+
+        let deleted = Operation::<Sqlite>::exec_operation(
+            Delete {
+                base: NoteHandler,
+                wheres: first(),
+                links: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(deleted, vec![note_1()]);
+
+        // already deleted records are left as they are
+        let deleted_again = Operation::<Sqlite>::exec_operation(
+            Delete {
+                base: NoteHandler,
+                wheres: first(),
+                links: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(deleted_again, vec![]);
+
+        let kept: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM Note;")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+
+        pretty_assertions::assert_eq!(kept, 2);
+
+        let hidden = Operation::<Sqlite>::exec_operation(
+            FetchOne {
+                base: NoteHandler,
+                links: (),
+                wheres: first(),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(hidden, None);
+
+        let remaining = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: NoteHandler,
+                wheres: (),
+                links: (),
+                cursor_order_by: (),
+                pagination: Pagination::offset(0, 10, true).unwrap(),
+                computed: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            remaining.items.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![2]
+        );
+        pretty_assertions::assert_eq!(remaining.total, Some(1));
+
+        let included = Operation::<Sqlite>::exec_operation(
+            FetchOne {
+                base: NoteHandler,
+                links: SoftDelete {
+                    collection: NoteHandler,
+                    include_deleted: true,
+                },
+                wheres: first(),
+            },
+            &mut conn,
+        )
+        .await
+        .unwrap();
+
+        assert!(included.links.deleted_at.is_some());
+
+        let restored = Operation::<Sqlite>::exec_operation(
+            Restore {
+                base: NoteHandler,
+                wheres: ColumnEqual { col: "id", eq: 1 },
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(restored, vec![note_1()]);
+
+        let visible = Operation::<Sqlite>::exec_operation(
+            FetchOne {
+                base: NoteHandler,
+                links: (),
+                wheres: first(),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(visible, Some(note_1()));
+    }
+}
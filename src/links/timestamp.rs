@@ -161,10 +161,10 @@ pub mod expressions {
                 if self.temp {
                     ctx.syntax("TEMP ");
                 }
+                ctx.syntax("TRIGGER ");
                 if self.if_not_exists {
                     ctx.syntax("IF NOT EXISTS ");
                 }
-                ctx.syntax("TRIGGER ");
                 self.trigger_name.expression(ctx);
                 ctx.syntax(" ");
                 self.lifetime.expression(ctx);
@@ -237,6 +237,19 @@ pub mod expressions {
             }
         }
 
+        pub struct TriggerOperationNameDelete;
+
+        impl OpExpression for TriggerOperationNameDelete {}
+
+        impl<'q, S> Expression<'q, S> for TriggerOperationNameDelete
+        where
+            S: DatabaseExt,
+        {
+            fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+                ctx.syntax("DELETE");
+            }
+        }
+
         pub struct TriggerOperationNameUpdate<OfColumns>(pub OfColumns);
 
         impl<OfColumns> OpExpression for TriggerOperationNameUpdate<OfColumns> {}
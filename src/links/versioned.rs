@@ -0,0 +1,769 @@
+/// keeps a `version` column that every update through `ExpectVersion` bumps.
+///
+/// an update only goes through when the caller still has the latest version
+/// of the record, as a fetch link it returns the current version. the
+/// migration adds a trigger that rejects updates of the members that don't
+/// bump the version, so `UpdateById` and `Update` have to carry an
+/// `ExpectVersion` link on versioned collections.
+#[derive(Debug, Clone)]
+pub struct Versioned<C> {
+    pub collection: C,
+}
+
+/// the update link of `Versioned`, `version` is the version the caller
+/// based its changes on
+#[derive(Debug, Clone)]
+pub struct ExpectVersion<C> {
+    pub versioned: Versioned<C>,
+    pub version: i64,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VersionOutput {
+    pub version: i64,
+}
+
+impl crate::gen_serde::Serialize<crate::gen_serde::json_serialize_side::JsonAsString>
+    for VersionOutput
+{
+    fn serialize(&self, ctx: &mut crate::gen_serde::json_serialize_side::JsonAsString) {
+        use crate::gen_serde::Serialize;
+
+        ctx.0.push('{');
+        Serialize::serialize("version", ctx);
+        ctx.0.push(':');
+        ctx.0.push_str(&self.version.to_string());
+        ctx.0.push('}');
+    }
+}
+
+pub mod expressions {
+    use crate::{
+        database_extention::DatabaseExt,
+        sqlx_query_builder::{Expression, OpExpression, StatementBuilder},
+    };
+
+    /// `"version" INTEGER NOT NULL DEFAULT 0`
+    pub struct VersionColumn;
+
+    impl OpExpression for VersionColumn {}
+
+    impl<'q, S> Expression<'q, S> for VersionColumn
+    where
+        S: DatabaseExt,
+        i64: sqlx::Type<S>,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.sanitize("version");
+            ctx.syntax(" ");
+            ctx.type_as_syntax::<i64>();
+            ctx.syntax(" NOT NULL DEFAULT 0");
+        }
+    }
+
+    /// `"version" = "version" + 1`
+    pub struct BumpVersion;
+
+    impl OpExpression for BumpVersion {}
+
+    impl<'q, S> Expression<'q, S> for BumpVersion
+    where
+        S: DatabaseExt,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.sanitize("version");
+            ctx.syntax(" = ");
+            ctx.sanitize("version");
+            ctx.syntax(" + 1");
+        }
+    }
+
+    /// `new."version" IS NOT old."version" + 1`
+    pub struct VersionNotBumped;
+
+    impl OpExpression for VersionNotBumped {}
+
+    impl<'q, S> Expression<'q, S> for VersionNotBumped
+    where
+        S: DatabaseExt,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.syntax("new.");
+            ctx.sanitize("version");
+            ctx.syntax(" IS NOT old.");
+            ctx.sanitize("version");
+            ctx.syntax(" + 1");
+        }
+    }
+
+    /// body of the trigger guarding the version, surfaces as a
+    /// `ConstraintKind::Trigger` violation
+    pub struct RejectUnversioned;
+
+    impl OpExpression for RejectUnversioned {}
+
+    impl<'q, S> Expression<'q, S> for RejectUnversioned
+    where
+        S: DatabaseExt,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.syntax("SELECT RAISE(ABORT, 'version not bumped');");
+        }
+    }
+
+    /// the version column followed by its trigger, separated by a space
+    pub struct VersionMigration<AddColumn, Trigger> {
+        pub add_column: AddColumn,
+        pub trigger: Trigger,
+    }
+
+    impl<A, T> OpExpression for VersionMigration<A, T> {}
+
+    impl<'q, S, AddColumn, Trigger> Expression<'q, S> for VersionMigration<AddColumn, Trigger>
+    where
+        S: DatabaseExt,
+        AddColumn: Expression<'q, S> + 'q,
+        Trigger: Expression<'q, S> + 'q,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            self.add_column.expression(ctx);
+            ctx.syntax(" ");
+            self.trigger.expression(ctx);
+        }
+    }
+}
+
+mod impl_on_migrate {
+    use crate::{
+        collections::Collection,
+        links::{
+            timestamp::expressions::create_trigger::{
+                CreateTrigger, TriggerLifetimeBefore, TriggerOperationNameUpdate,
+            },
+            versioned::{
+                Versioned,
+                expressions::{
+                    RejectUnversioned, VersionColumn, VersionMigration, VersionNotBumped,
+                },
+            },
+        },
+        on_migrate::OnMigrate,
+        operations::operations_expressions_crossover::TableExpressions,
+        sqlx_query_builder::{
+            basic_expressions::ManyFlat, statements::add_column_statement::AddColumn,
+        },
+        tuple_trait::AsTuple,
+    };
+
+    impl<C> OnMigrate for Versioned<C>
+    where
+        C: TableExpressions + Collection,
+        C::OutputData: AsTuple,
+    {
+        type Statements = VersionMigration<
+            AddColumn<C::PascalCase, VersionColumn>,
+            CreateTrigger<
+                String,
+                TriggerLifetimeBefore,
+                TriggerOperationNameUpdate<ManyFlat<Vec<&'static str>>>,
+                C::PascalCase,
+                VersionNotBumped,
+                RejectUnversioned,
+            >,
+        >;
+
+        fn statments(&self) -> Self::Statements {
+            VersionMigration {
+                add_column: AddColumn {
+                    table: self.collection.table_name_pascal_case(),
+                    col_def: VersionColumn,
+                },
+                // only updates of the members, so links like `Timestamp` and
+                // `SoftDelete` can still write their own columns
+                trigger: CreateTrigger {
+                    trigger_name: format!("{}_version", Collection::table_name(&self.collection)),
+                    temp: false,
+                    if_not_exists: true,
+                    lifetime: TriggerLifetimeBefore,
+                    operation_name: TriggerOperationNameUpdate(ManyFlat(
+                        <C::OutputData as AsTuple>::NAMES.to_vec(),
+                    )),
+                    on_table: self.collection.table_name_pascal_case(),
+                    for_each_row: false,
+                    when_expression: VersionNotBumped,
+                    statements: RejectUnversioned,
+                },
+            }
+        }
+    }
+}
+
+pub mod impl_fetch_many {
+    use sqlx::{ColumnIndex, Decode, Row, Type};
+
+    use crate::{
+        from_row::{
+            FromRowAlias, FromRowData, FromRowError, RowPostAliased, RowPreAliased, RowTwoAliased,
+        },
+        links::versioned::{VersionOutput, Versioned},
+        operations::{
+            fetch_many::LinkFetch,
+            operations_expressions_crossover::{ExpressionsForOperation, TableExpressions},
+        },
+        sqlx_query_builder::basic_expressions::{AliasedScopedColumn, ScopedColumn},
+    };
+
+    #[derive(Debug, Clone)]
+    pub struct VersionSelectItems<TableName>(pub TableName);
+
+    impl<T: Clone> ExpressionsForOperation for VersionSelectItems<T> {
+        type Identifier = &'static str;
+        fn identifier(&self) -> Self::Identifier {
+            "version"
+        }
+
+        type Scoped = ScopedColumn<((T,),), (&'static str,)>;
+        fn scoped(&self) -> Self::Scoped {
+            ScopedColumn {
+                table: ((self.0.clone(),),),
+                col: ("version",),
+            }
+        }
+
+        type ScopedAliased =
+            AliasedScopedColumn<((T,),), (&'static str,), (&'static str, &'static str)>;
+        fn scoped_aliased(&self, alias: &'static str) -> Self::ScopedAliased {
+            AliasedScopedColumn {
+                table: ((self.0.clone(),),),
+                column: ("version",),
+                alias: (alias, "version"),
+            }
+        }
+
+        type NumScopedAliased =
+            AliasedScopedColumn<((T,),), (&'static str,), (&'static str, usize, &'static str)>;
+        fn num_scoped_aliased(&self, num: usize, alias: &'static str) -> Self::NumScopedAliased {
+            AliasedScopedColumn {
+                table: ((self.0.clone(),),),
+                column: ("version",),
+                alias: (alias, num, "version"),
+            }
+        }
+    }
+
+    impl<T> FromRowData for VersionSelectItems<T> {
+        type RData = VersionOutput;
+    }
+
+    impl<'r, R, T> FromRowAlias<'r, R> for VersionSelectItems<T>
+    where
+        R: Row + 'r,
+        for<'s> &'s str: ColumnIndex<R>,
+        i64: Decode<'r, R::Database> + Type<R::Database>,
+    {
+        fn no_alias(&self, row: &'r R) -> Result<Self::RData, FromRowError> {
+            Ok(VersionOutput {
+                version: row.try_get("version")?,
+            })
+        }
+
+        fn pre_alias(&self, row: RowPreAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+            Ok(VersionOutput {
+                version: row.try_get("version")?,
+            })
+        }
+
+        fn post_alias(&self, _: RowPostAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+            panic!("to depricate");
+        }
+
+        fn two_alias(&self, row: RowTwoAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+            Ok(VersionOutput {
+                version: row.try_get("version")?,
+            })
+        }
+    }
+
+    impl<C> LinkFetch for Versioned<C>
+    where
+        C: TableExpressions<PascalCase: Clone>,
+    {
+        type Output = VersionOutput;
+
+        type SelectItems = VersionSelectItems<C::PascalCase>;
+
+        fn non_aggregating_select_items(&self) -> Self::SelectItems {
+            VersionSelectItems(self.collection.table_name_pascal_case())
+        }
+
+        type Join = ();
+
+        fn non_duplicating_join_expressions(&self) -> Self::Join {}
+
+        type Wheres = ();
+
+        fn where_expressions(&self) -> Self::Wheres {}
+
+        type OpInput = ();
+
+        fn operation_initialize_input(&self) -> Self::OpInput {}
+
+        type Op = ();
+
+        fn operation_construct(&self, _: Self::OpInput) -> Self::Op
+        where
+            Self::SelectItems: FromRowData,
+        {
+        }
+
+        fn operation_fix_on_many(
+            &self,
+            _: &<Self::SelectItems as FromRowData>::RData,
+            _: &mut Self::OpInput,
+        ) where
+            Self::SelectItems: FromRowData,
+        {
+        }
+
+        fn take_many(
+            &self,
+            item: <Self::SelectItems as FromRowData>::RData,
+            _: &mut <Self::Op as crate::operations::OperationOutput>::Output,
+        ) -> Self::Output {
+            item
+        }
+    }
+}
+
+mod impl_update {
+    use crate::{
+        from_row::FromRowData,
+        links::versioned::{
+            ExpectVersion, VersionOutput, Versioned, expressions::BumpVersion,
+            impl_fetch_many::VersionSelectItems,
+        },
+        operations::{
+            OperationOutput,
+            insert::ConstraintViolation,
+            operations_expressions_crossover::TableExpressions,
+            update::{UpdateLink, UpdateLinkData, UpdateLinkSplit},
+        },
+        sqlx_query_builder::basic_expressions::ColumnEqual,
+    };
+
+    impl<C> UpdateLinkSplit for ExpectVersion<C>
+    where
+        C: TableExpressions,
+    {
+        type Link = Versioned<C>;
+        fn init_split(
+            self,
+        ) -> (
+            Self::Link,
+            UpdateLinkData<
+                <Self::Link as UpdateLink>::InitSplitForWheres,
+                <Self::Link as UpdateLink>::InitSplitForUpdateValues,
+                <Self::Link as UpdateLink>::InitSplitForPreOp,
+                <Self::Link as UpdateLink>::InitSplitPostOp,
+            >,
+        ) {
+            (
+                self.versioned,
+                UpdateLinkData {
+                    wheres: self.version,
+                    update_values: (),
+                    pre_op: (),
+                    post_op: (),
+                },
+            )
+        }
+    }
+
+    impl<C> UpdateLink for Versioned<C>
+    where
+        C: TableExpressions,
+    {
+        type InitSplitForPreOp = ();
+        type PreOpSplitWheres = ();
+        type PreOpSplitValues = ();
+        type PreOpSplitPostOp = ();
+        type PreOpSplitTake = ();
+        type PreOp = ();
+        fn pre_op(&self, _: Self::InitSplitForPreOp) -> Self::PreOp {}
+        fn split_pre_op(
+            &self,
+            _: <Self::PreOp as OperationOutput>::Output,
+        ) -> Result<
+            (
+                Self::PreOpSplitWheres,
+                Self::PreOpSplitValues,
+                Self::PreOpSplitPostOp,
+                Self::PreOpSplitTake,
+            ),
+            ConstraintViolation,
+        > {
+            Ok(((), (), (), ()))
+        }
+        type InitSplitForWheres = i64;
+        type UpdateWhere = ColumnEqual<&'static str, i64>;
+        fn wheres(&self, version: Self::InitSplitForWheres) -> Self::UpdateWhere {
+            ColumnEqual {
+                col: "version",
+                eq: version,
+            }
+        }
+        type UpdateNames = &'static str;
+        fn update_names(&self) -> Self::UpdateNames {
+            "version"
+        }
+        type InitSplitForUpdateValues = ();
+        type UpdateValues = BumpVersion;
+        fn update_values(
+            &self,
+            _: Self::InitSplitForUpdateValues,
+            _: Self::PreOpSplitValues,
+        ) -> Self::UpdateValues {
+            BumpVersion
+        }
+        type FromRow = VersionSelectItems<C::PascalCase>;
+        fn from_row(&self) -> Self::FromRow {
+            VersionSelectItems(self.collection.table_name_pascal_case())
+        }
+        type PostOp = ();
+        type InitSplitPostOp = ();
+        fn post_op(&self, _: Self::InitSplitPostOp, _: Self::PreOpSplitPostOp) -> Self::PostOp {}
+        fn from_row_result(&self, _: &<Self::FromRow as FromRowData>::RData, _: &mut Self::PostOp) {
+        }
+        type Output = VersionOutput;
+        type PostOpOutput = ();
+        fn post_op_output(
+            &self,
+            _: <Self::PostOp as OperationOutput>::Output,
+        ) -> Result<Self::PostOpOutput, ConstraintViolation> {
+            Ok(())
+        }
+        fn take(
+            &self,
+            from_row: <Self::FromRow as FromRowData>::RData,
+            _: &mut Self::PostOpOutput,
+            _: &mut Self::PreOpSplitTake,
+        ) -> Self::Output {
+            from_row
+        }
+    }
+}
+
+/// `UpdateById` for versioned collections, it tells a stale version apart
+/// from a missing record.
+pub mod operations {
+    use sqlx::{ColumnIndex, Decode, Encode, Row, Type};
+
+    use crate::{
+        collections::{Collection, CollectionId},
+        database_extention::DatabaseExt,
+        execute::Executable,
+        fix_executor::ExecutorTrait,
+        links::versioned::{ExpectVersion, VersionOutput, Versioned},
+        operations::{
+            LinkedOutput, Operation, OperationOutput,
+            by_id::UpdateById,
+            insert::ConstraintViolation,
+            operations_expressions_crossover::{ExpressionsForOperation, TableExpressions},
+        },
+        sqlx_query_builder::{
+            Expression, StatementBuilder, basic_expressions::ColumnEqual,
+            statements::select_statement::SelectStatement,
+        },
+        statement_cache::VERSIONED,
+    };
+
+    #[derive(Debug, PartialEq, Eq, Clone)]
+    pub enum VersionedUpdateError {
+        NotFound,
+        /// the record was updated since `version`
+        Conflict {
+            current_version: i64,
+        },
+        Constraint(ConstraintViolation),
+    }
+
+    impl From<ConstraintViolation> for VersionedUpdateError {
+        fn from(value: ConstraintViolation) -> Self {
+            VersionedUpdateError::Constraint(value)
+        }
+    }
+
+    pub struct UpdateVersionedById<Base: Collection, Partial, Wheres> {
+        pub base: Base,
+        pub id: <Base::Id as CollectionId>::IdData,
+        /// the version the update is based on
+        pub version: i64,
+        pub partial: Partial,
+        pub wheres: Wheres,
+    }
+
+    type VersionedRecord<Base> = LinkedOutput<
+        <<Base as Collection>::Id as CollectionId>::IdData,
+        <Base as Collection>::OutputData,
+        VersionOutput,
+    >;
+
+    impl<Base: Collection, Partial, Wheres> OperationOutput
+        for UpdateVersionedById<Base, Partial, Wheres>
+    {
+        type Output = Result<VersionedRecord<Base>, VersionedUpdateError>;
+    }
+
+    /// the version a record is at, `None` when there is no such record
+    pub async fn current_version<S, Table, IdCol, Id>(
+        table: Table,
+        id_col: IdCol,
+        id: Id,
+        pool: &mut S::Connection,
+    ) -> Option<i64>
+    where
+        S: DatabaseExt + ExecutorTrait,
+        Table: for<'q> Expression<'q, S>,
+        IdCol: for<'q> Expression<'q, S>,
+        Id: for<'q> Encode<'q, S> + Type<S> + 'static,
+        for<'s> &'s str: ColumnIndex<S::Row>,
+        i64: for<'r> Decode<'r, S> + Type<S>,
+    {
+        let (stmt, args) = StatementBuilder::<S>::new(SelectStatement {
+            select_items: "version",
+            from: table,
+            joins: (),
+            wheres: ColumnEqual {
+                col: id_col,
+                eq: id,
+            },
+            group_by: (),
+            order: (),
+            limit: (),
+            offset: (),
+        })
+        .unwrap();
+
+        S::fetch_optional(&mut *pool, Executable::cached(&VERSIONED, &stmt, args))
+            .await
+            .unwrap()
+            .map(|row| row.get("version"))
+    }
+
+    impl<S, Base, Partial, Wheres> Operation<S> for UpdateVersionedById<Base, Partial, Wheres>
+    where
+        S: DatabaseExt + ExecutorTrait,
+        Base: Collection + Clone + Send + Sync,
+        Base: TableExpressions<PascalCase: Send + for<'q> Expression<'q, S>>,
+        Base::Id: ExpressionsForOperation<Scoped: Send + for<'q> Expression<'q, S>>,
+        <Base::Id as CollectionId>::IdData:
+            for<'q> Encode<'q, S> + Type<S> + Clone + Send + 'static,
+        Base::OutputData: Send,
+        Partial: Send,
+        Wheres: Send,
+        for<'s> &'s str: ColumnIndex<S::Row>,
+        i64: for<'r> Decode<'r, S> + Type<S>,
+        UpdateById<Base, Partial, Wheres, ExpectVersion<Base>>:
+            Operation<S, Output = Result<Option<VersionedRecord<Base>>, ConstraintViolation>>,
+    {
+        fn exec_operation(
+            self,
+            pool: &mut S::Connection,
+        ) -> impl Future<Output = Self::Output> + Send
+        where
+            S: sqlx::Database,
+            Self: Sized,
+        {
+            async move {
+                let base = self.base.clone();
+                let id = self.id.clone();
+                let version = self.version;
+
+                let updated = UpdateById {
+                    links: ExpectVersion {
+                        versioned: Versioned {
+                            collection: self.base.clone(),
+                        },
+                        version,
+                    },
+                    base: self.base,
+                    id: self.id,
+                    partial: self.partial,
+                    wheres: self.wheres,
+                }
+                .exec_operation(&mut *pool)
+                .await?;
+
+                if let Some(updated) = updated {
+                    return Ok(updated);
+                }
+
+                // the record can also be left out by `wheres`
+                let table = base.table_name_pascal_case();
+                let id_col = base.id().scoped();
+                let current = current_version::<S, _, _, _>(table, id_col, id, &mut *pool).await;
+
+                match current {
+                    Some(current_version) if current_version != version => {
+                        Err(VersionedUpdateError::Conflict { current_version })
+                    }
+                    _ => Err(VersionedUpdateError::NotFound),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sqlx::Sqlite;
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        links::versioned::{
+            VersionOutput, Versioned,
+            operations::{UpdateVersionedById, VersionedUpdateError},
+        },
+        on_migrate::OnMigrate,
+        operations::{
+            LinkedOutput, Operation,
+            fetch_one::FetchOne,
+            insert::{ConstraintKind, ConstraintViolation},
+            update::Update as UpdateOp,
+        },
+        sqlx_query_builder::{
+            StatementBuilder,
+            basic_expressions::{ColumnEqual, ScopedColumn},
+        },
+        test_module::{Todo, TodoHandler, TodoPartial},
+        update_mod::Update,
+    };
+
+    #[tokio::test]
+    async fn main() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        sqlx::query(
+            "
+        CREATE TABLE Todo (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            done BOOLEAN NOT NULL,
+            description TEXT
+        );
+        INSERT INTO Todo (title, done, description) VALUES ('todo_1', false, NULL);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let migration = StatementBuilder::<Sqlite>::new_no_data(OnMigrate::statments(&Versioned {
+            collection: TodoHandler,
+        }))
+        .unwrap();
+
+        pretty_assertions::assert_eq!(
+            migration,
+            r#"ALTER TABLE "Todo" ADD COLUMN "version" INTEGER NOT NULL DEFAULT 0; CREATE TRIGGER IF NOT EXISTS "Todo_version" BEFORE UPDATE OF "title", "done", "description" ON "Todo" WHEN new."version" IS NOT old."version" + 1 BEGIN SELECT RAISE(ABORT, 'version not bumped'); END;"#
+        );
+
+        sqlx::query(&migration).execute(&mut conn).await.unwrap();
+
+        let first = || ColumnEqual {
+            col: ScopedColumn {
+                table: ("Todo",),
+                col: ("id",),
+            },
+            eq: 1,
+        };
+
+        let rename = |version, title: &str| UpdateVersionedById {
+            base: TodoHandler,
+            id: 1,
+            version,
+            partial: TodoPartial {
+                title: Update::Set(title.to_string()),
+                done: Update::Keep,
+                description: Update::Keep,
+            },
+            wheres: (),
+        };
+
+        let updated = Operation::<Sqlite>::exec_operation(rename(0, "first"), &mut conn).await;
+
+        pretty_assertions::assert_eq!(
+            updated,
+            Ok(LinkedOutput {
+                id: 1,
+                attributes: Todo {
+                    title: String::from("first"),
+                    done: false,
+                    description: None,
+                },
+                links: VersionOutput { version: 1 },
+                computed: (),
+            })
+        );
+
+        // based on the version before the first update
+        let stale = Operation::<Sqlite>::exec_operation(rename(0, "second"), &mut conn).await;
+
+        pretty_assertions::assert_eq!(
+            stale,
+            Err(VersionedUpdateError::Conflict { current_version: 1 })
+        );
+
+        let missing = Operation::<Sqlite>::exec_operation(
+            UpdateVersionedById {
+                id: 2,
+                ..rename(1, "second")
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(missing, Err(VersionedUpdateError::NotFound));
+
+        // updates without `ExpectVersion` can't overwrite the record
+        let unversioned = Operation::<Sqlite>::exec_operation(
+            UpdateOp {
+                base: TodoHandler,
+                partial: TodoPartial {
+                    title: Update::Set(String::from("overwrite")),
+                    done: Update::Keep,
+                    description: Update::Keep,
+                },
+                wheres: first(),
+                links: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            unversioned,
+            Err(ConstraintViolation {
+                kind: ConstraintKind::Trigger,
+                constraint: Some(String::from("version not bumped")),
+            })
+        );
+
+        let fetched = Operation::<Sqlite>::exec_operation(
+            FetchOne {
+                base: TodoHandler,
+                links: Versioned {
+                    collection: TodoHandler,
+                },
+                wheres: first(),
+            },
+            &mut conn,
+        )
+        .await
+        .unwrap();
+
+        pretty_assertions::assert_eq!(fetched.attributes.title, "first");
+        pretty_assertions::assert_eq!(fetched.links, VersionOutput { version: 1 });
+    }
+}
@@ -0,0 +1,257 @@
+use std::marker::PhantomData;
+
+use sqlx::{ColumnIndex, Decode, Row, Type};
+
+use crate::{
+    from_row::{
+        FromRowAlias, FromRowData, FromRowError, RowPostAliased, RowPreAliased, RowTwoAliased,
+    },
+    operations::{
+        link_order_by::AliasedExpression, operations_expressions_crossover::ExpressionsForOperation,
+    },
+};
+
+/// an extra select item of `FetchMany`, like a window function or a
+/// `CASE` from `sqlx_query_builder::computed_expressions`, decoded as `T`
+/// into `LinkedOutput::computed`
+///
+/// the expression is evaluated over the filtered records before the page is
+/// cut, so `ROW_NUMBER() OVER (..)` ranks among every matching record
+pub struct Computed<E, T> {
+    pub name: &'static str,
+    pub expression: E,
+    _output: PhantomData<fn() -> T>,
+}
+
+impl<E, T> Computed<E, T> {
+    pub fn new(name: &'static str, expression: E) -> Self {
+        Self {
+            name,
+            expression,
+            _output: PhantomData,
+        }
+    }
+}
+
+impl<E: Clone, T> Clone for Computed<E, T> {
+    fn clone(&self) -> Self {
+        Self::new(self.name, self.expression.clone())
+    }
+}
+
+impl<E, T> ExpressionsForOperation for Computed<E, T>
+where
+    E: Clone,
+{
+    type Identifier = &'static str;
+    fn identifier(&self) -> Self::Identifier {
+        self.name
+    }
+
+    type Scoped = E;
+    fn scoped(&self) -> Self::Scoped {
+        self.expression.clone()
+    }
+
+    type ScopedAliased = AliasedExpression<E, (&'static str, &'static str)>;
+    fn scoped_aliased(&self, alias: &'static str) -> Self::ScopedAliased {
+        AliasedExpression {
+            expression: self.scoped(),
+            alias: (alias, self.name),
+        }
+    }
+
+    type NumScopedAliased = AliasedExpression<E, (&'static str, usize, &'static str)>;
+    fn num_scoped_aliased(&self, num: usize, alias: &'static str) -> Self::NumScopedAliased {
+        AliasedExpression {
+            expression: self.scoped(),
+            alias: (alias, num, self.name),
+        }
+    }
+}
+
+impl<E, T> FromRowData for Computed<E, T> {
+    type RData = T;
+}
+
+impl<'r, R, E, T> FromRowAlias<'r, R> for Computed<E, T>
+where
+    R: Row + 'r,
+    T: Type<R::Database> + Decode<'r, R::Database>,
+    for<'q> &'q str: ColumnIndex<R>,
+{
+    fn no_alias(&self, row: &'r R) -> Result<Self::RData, FromRowError> {
+        Ok(row.try_get(self.name)?)
+    }
+    fn pre_alias(&self, row: RowPreAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+        Ok(row.try_get(self.name)?)
+    }
+    fn post_alias(&self, row: RowPostAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+        Ok(row.try_get(self.name)?)
+    }
+    fn two_alias(&self, row: RowTwoAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+        Ok(row.try_get(self.name)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sqlx::{Sqlite, query};
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        operations::{
+            Operation,
+            computed::Computed,
+            fetch_many::{FetchMany, Pagination},
+        },
+        sqlx_query_builder::{
+            basic_expressions::{Bind, ColumnEqual},
+            computed_expressions::{
+                Add, Case, Descending, Lag, Multiply, Over, Rank, RowNumber, When,
+            },
+        },
+        test_module::{TodoHandler, todo_members},
+    };
+
+    #[tokio::test]
+    async fn row_number_per_group() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        query(
+            "
+        CREATE TABLE Todo (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            done BOOLEAN NOT NULL,
+            description TEXT
+        );
+
+        INSERT INTO Todo (title, done, description) VALUES
+            ('a', true, NULL),
+            ('b', false, NULL),
+            ('c', true, NULL),
+            ('d', false, NULL),
+            ('e', true, NULL);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let output = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: TodoHandler,
+                wheres: (),
+                links: (),
+                cursor_order_by: todo_members::title,
+                pagination: Pagination::Cursor {
+                    cursor: None,
+                    limit: 10,
+                },
+                computed: Computed::<_, i64>::new(
+                    "latest_in_group",
+                    Over {
+                        function: RowNumber,
+                        partition_by: "done",
+                        order_by: Descending("id"),
+                    },
+                ),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            output
+                .items
+                .iter()
+                .map(|e| (e.attributes.title.as_str(), e.computed))
+                .collect::<Vec<_>>(),
+            vec![("a", 3), ("b", 2), ("c", 2), ("d", 1), ("e", 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn rank_lag_and_case() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        query(
+            "
+        CREATE TABLE Todo (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            done BOOLEAN NOT NULL,
+            description TEXT
+        );
+
+        INSERT INTO Todo (title, done, description) VALUES
+            ('a', true, NULL),
+            ('b', false, NULL),
+            ('c', true, NULL);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        macro_rules! fetch_computed {
+            ($computed:expr) => {
+                Operation::<Sqlite>::exec_operation(
+                    FetchMany {
+                        base: TodoHandler,
+                        wheres: (),
+                        links: (),
+                        cursor_order_by: todo_members::title,
+                        pagination: Pagination::offset(0, 10, false).unwrap(),
+                        computed: $computed,
+                    },
+                    &mut conn,
+                )
+                .await
+                .items
+                .into_iter()
+                .map(|e| e.computed)
+                .collect::<Vec<_>>()
+            };
+        }
+
+        let rank = Computed::<_, i64>::new(
+            "rank",
+            Over {
+                function: Rank,
+                partition_by: (),
+                order_by: "done",
+            },
+        );
+        pretty_assertions::assert_eq!(fetch_computed!(rank), vec![2, 1, 2]);
+
+        let previous_id = Computed::<_, Option<i64>>::new(
+            "previous_id",
+            Over {
+                function: Lag {
+                    expression: "id",
+                    offset: 1,
+                },
+                partition_by: (),
+                order_by: "id",
+            },
+        );
+        pretty_assertions::assert_eq!(fetch_computed!(previous_id), vec![None, Some(1), Some(2)]);
+
+        let score = Computed::<_, i64>::new(
+            "score",
+            Case {
+                whens: vec![When {
+                    condition: ColumnEqual {
+                        col: "done",
+                        eq: true,
+                    },
+                    then: Add(Multiply("id", Bind(10)), Bind(1)),
+                }],
+                otherwise: Bind(0),
+            },
+        );
+        pretty_assertions::assert_eq!(fetch_computed!(score), vec![11, 0, 31]);
+    }
+}
@@ -0,0 +1,452 @@
+use sqlx::{Encode, Row, Type};
+
+use crate::{
+    database_extention::DatabaseExt,
+    from_row::{
+        FromRowAlias, FromRowData, FromRowError, RowPostAliased, RowPreAliased, RowTwoAliased,
+    },
+    operations::operations_expressions_crossover::ExpressionsForOperation,
+    sqlx_query_builder::{Expression, OpExpression, StatementBuilder},
+};
+
+/// where a page of `FetchMany` starts, `Item` is the id of a record
+/// along with the values it is ordered by
+///
+/// it is plain data, hand it to clients as a token, see `cursor_token`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Cursor<Item> {
+    /// page starts at `Item`, `Item` included, this is what `next_item` points to
+    After(Item),
+    /// page ends right before `Item`, this is what `prev_item` points to
+    Before(Item),
+}
+
+/// keys a `FetchMany` can be ordered and paginated by, the id of the base
+/// is appended as a tie breaker with the direction of the last key
+pub trait CursorOrder<'q, S: DatabaseExt>: FromRowData {
+    fn keys_len(&self) -> usize;
+    fn descending(&self, index: usize) -> bool;
+    /// the key at `index` can be `NULL`, `NULL` sorts before every value
+    fn nullable(&self, index: usize) -> bool;
+    fn key(&self, index: usize, ctx: &mut StatementBuilder<'q, S>);
+    /// bind the part of `value` that corresponds to the key at `index`
+    fn value(&self, value: &Self::RData, index: usize, ctx: &mut StatementBuilder<'q, S>);
+}
+
+/// order by the id alone
+impl<'q, S> CursorOrder<'q, S> for ()
+where
+    S: DatabaseExt,
+{
+    fn keys_len(&self) -> usize {
+        0
+    }
+    fn descending(&self, _: usize) -> bool {
+        false
+    }
+    fn nullable(&self, _: usize) -> bool {
+        false
+    }
+    fn key(&self, _: usize, _: &mut StatementBuilder<'q, S>) {}
+    fn value(&self, _: &Self::RData, _: usize, _: &mut StatementBuilder<'q, S>) {}
+}
+
+/// order by `T` descending
+#[derive(Debug, Clone)]
+pub struct Desc<T>(pub T);
+
+impl<'q, S, T> CursorOrder<'q, S> for Desc<T>
+where
+    S: DatabaseExt,
+    T: CursorOrder<'q, S>,
+{
+    fn keys_len(&self) -> usize {
+        self.0.keys_len()
+    }
+    fn descending(&self, index: usize) -> bool {
+        !self.0.descending(index)
+    }
+    fn nullable(&self, index: usize) -> bool {
+        self.0.nullable(index)
+    }
+    fn key(&self, index: usize, ctx: &mut StatementBuilder<'q, S>) {
+        self.0.key(index, ctx)
+    }
+    fn value(&self, value: &Self::RData, index: usize, ctx: &mut StatementBuilder<'q, S>) {
+        self.0.value(value, index, ctx)
+    }
+}
+
+impl<T> ExpressionsForOperation for Desc<T>
+where
+    T: ExpressionsForOperation,
+{
+    type Identifier = T::Identifier;
+    fn identifier(&self) -> Self::Identifier {
+        self.0.identifier()
+    }
+    type Scoped = T::Scoped;
+    fn scoped(&self) -> Self::Scoped {
+        self.0.scoped()
+    }
+    type ScopedAliased = T::ScopedAliased;
+    fn scoped_aliased(&self, alias: &'static str) -> Self::ScopedAliased {
+        self.0.scoped_aliased(alias)
+    }
+    type NumScopedAliased = T::NumScopedAliased;
+    fn num_scoped_aliased(&self, num: usize, alias: &'static str) -> Self::NumScopedAliased {
+        self.0.num_scoped_aliased(num, alias)
+    }
+}
+
+impl<T> FromRowData for Desc<T>
+where
+    T: FromRowData,
+{
+    type RData = T::RData;
+}
+
+impl<'r, R, T> FromRowAlias<'r, R> for Desc<T>
+where
+    T: FromRowAlias<'r, R>,
+{
+    fn no_alias(&self, row: &'r R) -> Result<Self::RData, FromRowError> {
+        self.0.no_alias(row)
+    }
+    fn pre_alias(&self, row: RowPreAliased<'r, R>) -> Result<Self::RData, FromRowError>
+    where
+        R: Row,
+    {
+        self.0.pre_alias(row)
+    }
+    fn post_alias(&self, row: RowPostAliased<'r, R>) -> Result<Self::RData, FromRowError>
+    where
+        R: Row,
+    {
+        self.0.post_alias(row)
+    }
+    fn two_alias(&self, row: RowTwoAliased<'r, R>) -> Result<Self::RData, FromRowError>
+    where
+        R: Row,
+    {
+        self.0.two_alias(row)
+    }
+}
+
+fn id_descending<'q, S, O>(order: &O) -> bool
+where
+    S: DatabaseExt,
+    O: CursorOrder<'q, S>,
+{
+    match order.keys_len() {
+        0 => false,
+        len => order.descending(len - 1),
+    }
+}
+
+/// `ORDER BY` items of a cursor, every direction flips when paginating backward
+///
+/// nullable keys spell out where `NULL` goes, first when ascending and last
+/// when descending
+pub struct CursorOrderBy<O, IdCol> {
+    pub order: O,
+    pub id_col: IdCol,
+    pub reverse: bool,
+}
+
+impl<O, IdCol> OpExpression for CursorOrderBy<O, IdCol> {}
+
+impl<'q, S, O, IdCol> Expression<'q, S> for CursorOrderBy<O, IdCol>
+where
+    S: DatabaseExt,
+    O: CursorOrder<'q, S> + 'q,
+    IdCol: ExpressionsForOperation<Scoped: Expression<'q, S>> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        for index in 0..self.order.keys_len() {
+            self.order.key(index, ctx);
+            match (
+                self.order.descending(index) != self.reverse,
+                self.order.nullable(index),
+            ) {
+                (true, true) => ctx.syntax(" DESC NULLS LAST, "),
+                (true, false) => ctx.syntax(" DESC, "),
+                (false, true) => ctx.syntax(" ASC NULLS FIRST, "),
+                (false, false) => ctx.syntax(" ASC, "),
+            }
+        }
+        let id_descending = id_descending(&self.order);
+        self.id_col.scoped().expression(ctx);
+        if id_descending != self.reverse {
+            ctx.syntax(" DESC");
+        } else {
+            ctx.syntax(" ASC");
+        }
+    }
+}
+
+/// records that come at or after `values` (or strictly before them when
+/// `before` is set) in the order of `order`.
+///
+/// when all keys share one direction this is a single row value comparison,
+/// otherwise it expands into an OR chain:
+/// `(k1 > v1) OR (k1 = v1 AND k2 < v2) OR (k1 = v1 AND k2 = v2 AND id >= id)`
+///
+/// a comparison with `NULL` is never true, so nullable keys always take the
+/// OR chain, where they compare with `IS` and `NULL` checks:
+/// `k IS v` and `(k > v OR (k IS NOT NULL AND v IS NULL))`
+pub struct CursorCondition<O, IdCol, IdData, Values> {
+    pub order: O,
+    pub id_col: IdCol,
+    pub id: IdData,
+    pub values: Values,
+    pub before: bool,
+}
+
+impl<O, IdCol, IdData, Values> OpExpression for CursorCondition<O, IdCol, IdData, Values> {}
+
+impl<'q, S, O, IdCol, IdData> Expression<'q, S> for CursorCondition<O, IdCol, IdData, O::RData>
+where
+    S: DatabaseExt,
+    O: CursorOrder<'q, S> + 'q,
+    O::RData: 'q,
+    IdCol: ExpressionsForOperation<Scoped: Expression<'q, S>> + 'q,
+    IdData: Encode<'q, S> + Type<S> + Clone + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        let len = self.order.keys_len();
+        let id_descending = id_descending(&self.order);
+        let descending = |index: usize| {
+            if index < len {
+                self.order.descending(index)
+            } else {
+                id_descending
+            }
+        };
+        let nullable = |index: usize| index < len && self.order.nullable(index);
+        let key = |index: usize, ctx: &mut StatementBuilder<'q, S>| {
+            if index < len {
+                self.order.key(index, ctx)
+            } else {
+                self.id_col.scoped().expression(ctx)
+            }
+        };
+        let value = |index: usize, ctx: &mut StatementBuilder<'q, S>| {
+            if index < len {
+                self.order.value(&self.values, index, ctx)
+            } else {
+                ctx.bind(self.id.clone())
+            }
+        };
+        // comparison of one key, `inclusive` only applies to the id
+        let op = |descending: bool, inclusive: bool| match (descending != self.before, inclusive) {
+            (false, false) => " > ",
+            (false, true) => " >= ",
+            (true, false) => " < ",
+            (true, true) => " <= ",
+        };
+
+        if (0..len).all(|index| descending(index) == id_descending && !nullable(index)) {
+            ctx.syntax("(");
+            for index in 0..=len {
+                if index != 0 {
+                    ctx.syntax(", ");
+                }
+                key(index, ctx);
+            }
+            ctx.syntax(")");
+            ctx.syntax(op(id_descending, !self.before));
+            ctx.syntax("(");
+            for index in 0..=len {
+                if index != 0 {
+                    ctx.syntax(", ");
+                }
+                value(index, ctx);
+            }
+            ctx.syntax(")");
+            return;
+        }
+
+        ctx.syntax("(");
+        for index in 0..=len {
+            if index != 0 {
+                ctx.syntax(" OR ");
+            }
+            ctx.syntax("(");
+            for equal in 0..index {
+                key(equal, ctx);
+                if nullable(equal) {
+                    ctx.syntax(" IS ");
+                } else {
+                    ctx.syntax(" = ");
+                }
+                value(equal, ctx);
+                ctx.syntax(" AND ");
+            }
+            if nullable(index) {
+                // `NULL` comes first, so it's less than any value
+                let less = descending(index) != self.before;
+                ctx.syntax("(");
+                key(index, ctx);
+                ctx.syntax(op(descending(index), false));
+                value(index, ctx);
+                ctx.syntax(" OR (");
+                key(index, ctx);
+                ctx.syntax(if less {
+                    " IS NULL AND "
+                } else {
+                    " IS NOT NULL AND "
+                });
+                value(index, ctx);
+                ctx.syntax(if less { " IS NOT NULL))" } else { " IS NULL))" });
+            } else {
+                key(index, ctx);
+                ctx.syntax(op(descending(index), index == len && !self.before));
+                value(index, ctx);
+            }
+            ctx.syntax(")");
+        }
+        ctx.syntax(")");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sqlx::Sqlite;
+
+    use crate::{
+        from_row::FromRowData,
+        operations::cursor::{CursorCondition, CursorOrder, CursorOrderBy, Desc},
+        sqlx_query_builder::{Expression, StatementBuilder},
+        test_module::todo_members,
+    };
+
+    /// two keys, ordered by `done` descending then `title`
+    struct DoneThenTitle;
+
+    impl FromRowData for DoneThenTitle {
+        type RData = (bool, String);
+    }
+
+    impl<'q> CursorOrder<'q, Sqlite> for DoneThenTitle {
+        fn keys_len(&self) -> usize {
+            2
+        }
+        fn descending(&self, index: usize) -> bool {
+            index == 0
+        }
+        fn nullable(&self, _: usize) -> bool {
+            false
+        }
+        fn key(&self, index: usize, ctx: &mut StatementBuilder<'q, Sqlite>) {
+            match index {
+                0 => ctx.sanitize("done"),
+                _ => ctx.sanitize("title"),
+            }
+        }
+        fn value(&self, value: &Self::RData, index: usize, ctx: &mut StatementBuilder<'q, Sqlite>) {
+            match index {
+                0 => ctx.bind(value.0),
+                _ => ctx.bind(value.1.clone()),
+            }
+        }
+    }
+
+    fn condition<O: for<'q> CursorOrder<'q, Sqlite> + 'static>(
+        order: O,
+        values: O::RData,
+        before: bool,
+    ) -> String
+    where
+        O::RData: 'static,
+    {
+        let mut stmt = StatementBuilder::<Sqlite>::default();
+        CursorCondition {
+            order,
+            id_col: todo_members::id,
+            id: 3,
+            values,
+            before,
+        }
+        .expression(&mut stmt);
+        stmt.stmt().replace("\"", "'")
+    }
+
+    #[test]
+    fn uniform_directions() {
+        pretty_assertions::assert_eq!(
+            condition(todo_members::title, String::from("a"), false),
+            r#"('Todo'.'title', 'Todo'.'id') >= ($1, $2)"#
+        );
+        pretty_assertions::assert_eq!(
+            condition(todo_members::title, String::from("a"), true),
+            r#"('Todo'.'title', 'Todo'.'id') < ($1, $2)"#
+        );
+        pretty_assertions::assert_eq!(
+            condition(Desc(todo_members::title), String::from("a"), false),
+            r#"('Todo'.'title', 'Todo'.'id') <= ($1, $2)"#
+        );
+        pretty_assertions::assert_eq!(
+            condition(Desc(todo_members::title), String::from("a"), true),
+            r#"('Todo'.'title', 'Todo'.'id') > ($1, $2)"#
+        );
+    }
+
+    #[test]
+    fn mixed_directions() {
+        pretty_assertions::assert_eq!(
+            condition(DoneThenTitle, (true, String::from("a")), false),
+            r#"(('done' < $1) OR ('done' = $2 AND 'title' > $3) OR ('done' = $4 AND 'title' = $5 AND 'Todo'.'id' >= $6))"#
+        );
+        pretty_assertions::assert_eq!(
+            condition(DoneThenTitle, (true, String::from("a")), true),
+            r#"(('done' > $1) OR ('done' = $2 AND 'title' < $3) OR ('done' = $4 AND 'title' = $5 AND 'Todo'.'id' < $6))"#
+        );
+    }
+
+    #[test]
+    fn nullable_keys() {
+        pretty_assertions::assert_eq!(
+            condition(todo_members::description, Some(String::from("a")), false),
+            r#"((('Todo'.'description' > $1 OR ('Todo'.'description' IS NOT NULL AND $2 IS NULL))) OR ('Todo'.'description' IS $3 AND 'Todo'.'id' >= $4))"#
+        );
+        pretty_assertions::assert_eq!(
+            condition(Desc(todo_members::description), None, false),
+            r#"((('Todo'.'description' < $1 OR ('Todo'.'description' IS NULL AND $2 IS NOT NULL))) OR ('Todo'.'description' IS $3 AND 'Todo'.'id' <= $4))"#
+        );
+
+        let stmt = StatementBuilder::<Sqlite>::new(CursorOrderBy {
+            order: Desc(todo_members::description),
+            id_col: todo_members::id,
+            reverse: false,
+        });
+        pretty_assertions::assert_eq!(
+            stmt.stmt().replace("\"", "'"),
+            r#"'Todo'.'description' DESC NULLS LAST, 'Todo'.'id' DESC"#
+        );
+    }
+
+    #[test]
+    fn order_by() {
+        let stmt = StatementBuilder::<Sqlite>::new(CursorOrderBy {
+            order: DoneThenTitle,
+            id_col: todo_members::id,
+            reverse: false,
+        });
+        pretty_assertions::assert_eq!(
+            stmt.stmt().replace("\"", "'"),
+            r#"'done' DESC, 'title' ASC, 'Todo'.'id' ASC"#
+        );
+
+        let stmt = StatementBuilder::<Sqlite>::new(CursorOrderBy {
+            order: DoneThenTitle,
+            id_col: todo_members::id,
+            reverse: true,
+        });
+        pretty_assertions::assert_eq!(
+            stmt.stmt().replace("\"", "'"),
+            r#"'done' ASC, 'title' DESC, 'Todo'.'id' DESC"#
+        );
+    }
+}
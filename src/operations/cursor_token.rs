@@ -0,0 +1,136 @@
+//! opaque cursors handed to clients in place of `Cursor`s.
+//!
+//! a token is `v1.<payload>.<signature>`, both parts base64url without
+//! padding, and the signature is HMAC-SHA256 of `v1.<payload>` keyed with a
+//! secret only the server knows, so clients can't forge or edit cursors.
+//! bump `VERSION` whenever the payload changes shape.
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+const VERSION: &str = "v1";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CursorTokenError {
+    Malformed,
+    UnsupportedVersion,
+    BadSignature,
+}
+
+fn mac(secret: &[u8]) -> Hmac<Sha256> {
+    Hmac::<Sha256>::new_from_slice(secret).expect("hmac accepts keys of any length")
+}
+
+/// wraps `payload` in a token signed with `secret`
+pub fn sign(secret: &[u8], payload: &[u8]) -> String {
+    let signed = format!("{VERSION}.{}", URL_SAFE_NO_PAD.encode(payload));
+
+    let mut mac = mac(secret);
+    mac.update(signed.as_bytes());
+    let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+
+    format!("{signed}.{signature}")
+}
+
+/// the payload of a token signed with `secret`
+pub fn verify(secret: &[u8], token: &str) -> Result<Vec<u8>, CursorTokenError> {
+    let (signed, signature) = token.rsplit_once('.').ok_or(CursorTokenError::Malformed)?;
+    let (version, payload) = signed.split_once('.').ok_or(CursorTokenError::Malformed)?;
+    if version != VERSION {
+        return Err(CursorTokenError::UnsupportedVersion);
+    }
+
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature)
+        .map_err(|_| CursorTokenError::Malformed)?;
+    let mut mac = mac(secret);
+    mac.update(signed.as_bytes());
+    mac.verify_slice(&signature)
+        .map_err(|_| CursorTokenError::BadSignature)?;
+
+    URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|_| CursorTokenError::Malformed)
+}
+
+#[cfg(feature = "serde")]
+mod cursor_as_token {
+    use serde::{Serialize, de::DeserializeOwned};
+
+    use super::{CursorTokenError, sign, verify};
+    use crate::operations::cursor::Cursor;
+
+    impl<Item> Cursor<Item>
+    where
+        Item: Serialize,
+    {
+        /// `next_item` or `prev_item` as a token clients can only hand back
+        pub fn to_token(&self, secret: &[u8]) -> String {
+            let payload = serde_json::to_vec(self).expect("cursors serialize to json");
+            sign(secret, &payload)
+        }
+    }
+
+    impl<Item> Cursor<Item>
+    where
+        Item: DeserializeOwned,
+    {
+        pub fn from_token(secret: &[u8], token: &str) -> Result<Self, CursorTokenError> {
+            let payload = verify(secret, token)?;
+            serde_json::from_slice(&payload).map_err(|_| CursorTokenError::Malformed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CursorTokenError, sign, verify};
+
+    #[test]
+    fn round_trip_and_tamper() {
+        let token = sign(b"secret", br#"{"id":4}"#);
+        pretty_assertions::assert_eq!(verify(b"secret", &token).unwrap(), br#"{"id":4}"#);
+
+        pretty_assertions::assert_eq!(
+            verify(b"other_secret", &token).unwrap_err(),
+            CursorTokenError::BadSignature
+        );
+
+        // an edited payload keeps the old signature
+        let (_, signature) = token.rsplit_once('.').unwrap();
+        let edited = sign(b"secret", br#"{"id":5}"#);
+        let (edited, _) = edited.rsplit_once('.').unwrap();
+        pretty_assertions::assert_eq!(
+            verify(b"secret", &format!("{edited}.{signature}")).unwrap_err(),
+            CursorTokenError::BadSignature
+        );
+
+        pretty_assertions::assert_eq!(
+            verify(b"secret", &token.replacen("v1", "v2", 1)).unwrap_err(),
+            CursorTokenError::UnsupportedVersion
+        );
+        pretty_assertions::assert_eq!(
+            verify(b"secret", "not a token").unwrap_err(),
+            CursorTokenError::Malformed
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn cursor_token() {
+        use crate::operations::cursor::Cursor;
+
+        let cursor = Cursor::Before((4_i64, Some("home".to_string())));
+        let token = cursor.to_token(b"secret");
+
+        pretty_assertions::assert_eq!(
+            Cursor::<(i64, Option<String>)>::from_token(b"secret", &token).unwrap(),
+            cursor
+        );
+        pretty_assertions::assert_eq!(
+            Cursor::<(i64, Option<String>)>::from_token(b"other_secret", &token).unwrap_err(),
+            CursorTokenError::BadSignature
+        );
+    }
+}
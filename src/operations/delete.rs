@@ -4,14 +4,17 @@ use crate::{
     execute::Executable,
     fix_executor::ExecutorTrait,
     from_row::{FromRowAlias, FromRowData},
+    links::soft_delete::expressions::SetDeletedAt,
     operations::{
         LinkedOutput, Operation, OperationOutput,
         operations_expressions_crossover::{ExpressionsForOperation, TableExpressions},
     },
     sqlx_query_builder::{
-        Expression, ManyExpressions, StatementBuilder, basic_expressions::ManyFlat,
-        statements::delete_statement::DeleteStatement,
+        Expression, ManyExpressions, StatementBuilder,
+        basic_expressions::{ColumnIsNull, ManyFlat},
+        statements::{delete_statement::DeleteStatement, update_statement::UpdateStatement},
     },
+    statement_cache::DELETE,
 };
 
 pub struct Delete<Base, Wheres, Links> {
@@ -204,29 +207,42 @@ where
 
             let id = self.base.id();
 
-            let (stmt, args) = StatementBuilder::<S>::new(DeleteStatement {
-                table_name: self.base.table_name_pascal_case(),
-                wheres: ManyFlat((
-                    self.wheres,
-                    link.wheres(link_data.wheres, pre_op_split_wheres),
-                )),
-                returning: ManyFlat((
-                    id.identifier(),
-                    self.base.identifier(),
-                    link.delete_return_expression(),
-                )),
-            })
-            .unwrap();
+            let wheres = ManyFlat((
+                self.wheres,
+                link.wheres(link_data.wheres, pre_op_split_wheres),
+            ));
+            let returning = ManyFlat((
+                id.identifier(),
+                self.base.identifier(),
+                link.delete_return_expression(),
+            ));
+
+            // records of a soft deleted collection are only marked as deleted
+            let (stmt, args) = match self.base.soft_delete() {
+                true => StatementBuilder::<S>::new(UpdateStatement {
+                    table_name: self.base.table_name_pascal_case(),
+                    values: SetDeletedAt { deleted: true },
+                    wheres: ManyFlat((
+                        wheres,
+                        ColumnIsNull {
+                            col: ("deleted_at",),
+                        },
+                    )),
+                    returning,
+                })
+                .unwrap(),
+                false => StatementBuilder::<S>::new(DeleteStatement {
+                    table_name: self.base.table_name_pascal_case(),
+                    wheres,
+                    returning,
+                })
+                .unwrap(),
+            };
 
             let link_from_row = link.from_row();
 
-            let mut res = S::fetch_all_mapped(
-                &mut *pool,
-                Executable {
-                    string: &stmt,
-                    arguments: args,
-                },
-                |row| {
+            let mut res =
+                S::fetch_all_mapped(&mut *pool, Executable::cached(&DELETE, &stmt, args), |row| {
                     let id = id.no_alias(&row).unwrap();
                     let attributes = self.base.no_alias(&row).unwrap();
                     let links = link_from_row.no_alias(&row).unwrap();
@@ -234,11 +250,11 @@ where
                         id,
                         attributes,
                         links,
+                        computed: (),
                     }
-                },
-            )
-            .await
-            .unwrap();
+                })
+                .await
+                .unwrap();
 
             if res.len() == 1 {
                 let first = res.pop().unwrap();
@@ -247,6 +263,7 @@ where
                     id: first.id,
                     attributes: first.attributes,
                     links,
+                    computed: (),
                 }];
             } else {
                 res.into_iter()
@@ -256,6 +273,7 @@ where
                             id: each.id,
                             attributes: each.attributes,
                             links,
+                            computed: (),
                         }
                     })
                     .collect()
@@ -313,7 +331,8 @@ mod test {
                     done: true,
                     description: Some(String::from("description_2")),
                 },
-                links: ()
+                links: (),
+                computed: (),
             }]
         );
 
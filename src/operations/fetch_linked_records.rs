@@ -200,6 +200,7 @@ where
             group_by: (),
             order: (),
             limit: (),
+            offset: (),
         })
         .unwrap();
 
@@ -208,6 +209,7 @@ where
             Executable {
                 string: &stmt,
                 arguments: args,
+                persistent: false,
             },
         )
         .await
@@ -373,6 +375,7 @@ where
             group_by: (),
             order: (),
             limit: (),
+            offset: (),
         })
         .unwrap();
 
@@ -381,6 +384,7 @@ where
             Executable {
                 string: &stmt,
                 arguments: args,
+                persistent: false,
             },
         )
         .await
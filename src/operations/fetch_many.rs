@@ -4,19 +4,21 @@ use crate::{
     execute::Executable,
     fix_executor::ExecutorTrait,
     from_row::{FromRowAlias, FromRowData, RowPreAliased},
+    links::soft_delete::expressions::NotDeleted,
     operations::{
         LinkedOutput, Operation, OperationOutput,
-        operations_expressions_crossover::{
-            ExpressionsForOperation, OnInsert, SelfPrescribedInsert,
-        },
+        cursor::{Cursor, CursorCondition, CursorOrder, CursorOrderBy},
+        link_order_by::{AliasedExpression, CountAll},
+        operations_expressions_crossover::ExpressionsForOperation,
     },
     sqlx_query_builder::{
-        ManyExpressions, StatementBuilder,
-        basic_expressions::{Bind, ManyColumnsLargerOrEqual, ManyFlat},
+        Expression, ManyExpressions, StatementBuilder,
+        basic_expressions::{Bind, ManyFlat},
         statements::select_statement::SelectStatement,
     },
+    statement_cache::FETCH_MANY,
 };
-use sqlx::{Encode, Type};
+use sqlx::{ColumnIndex, Decode, Encode, Row, Type};
 
 pub trait LinkFetch {
     type SelectItems;
@@ -28,6 +30,12 @@ pub trait LinkFetch {
     type Wheres;
     fn where_expressions(&self) -> Self::Wheres;
 
+    /// keep the deleted records of a soft deleted base, see
+    /// `links::soft_delete`
+    fn include_deleted(&self) -> bool {
+        false
+    }
+
     type Op;
     type OpInput;
 
@@ -173,43 +181,148 @@ mod std_impls {
     }
 }
 
-pub struct FetchMany<From, Links, Wheres, Order, FirstItem> {
+pub struct FetchMany<From, Links, Wheres, Order, CursorItem, Computed = ()> {
     pub base: From,
     pub wheres: Wheres,
     pub links: Links,
     pub cursor_order_by: Order,
-    pub cursor_first_item: Option<FirstItem>,
-    pub limit: i64,
+    pub pagination: Pagination<CursorItem>,
+    /// extra select items decoded into `LinkedOutput::computed`, see
+    /// `operations::computed::Computed`
+    pub computed: Computed,
+}
+
+pub enum Pagination<CursorItem> {
+    Cursor {
+        cursor: Option<Cursor<CursorItem>>,
+        limit: i64,
+    },
+    /// pages are counted from zero, `next_item` and `prev_item` are never
+    /// set in this mode, build it with `Pagination::offset`
+    Offset(OffsetPage),
+}
+
+/// a page that passed `Pagination::offset`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct OffsetPage {
+    page: i64,
+    page_size: i64,
+    offset: i64,
+    count_total: bool,
+}
+
+impl OffsetPage {
+    pub fn page(&self) -> i64 {
+        self.page
+    }
+    pub fn page_size(&self) -> i64 {
+        self.page_size
+    }
+    /// count every record matching the filters into `total`
+    pub fn count_total(&self) -> bool {
+        self.count_total
+    }
+}
+
+/// an offset page that can't be fetched: `page` is negative, `page_size`
+/// isn't positive, or the page starts past the last representable offset
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidPage {
+    pub page: i64,
+    pub page_size: i64,
+}
+
+/// a `limit` or `page_size` outside of `0..=max_page_size`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PageTooLarge {
+    pub page_size: i64,
+    pub max_page_size: i64,
+}
+
+impl<CursorItem> Pagination<CursorItem> {
+    pub fn offset(page: i64, page_size: i64, count_total: bool) -> Result<Self, InvalidPage> {
+        match (page >= 0 && page_size > 0)
+            .then(|| page.checked_mul(page_size))
+            .flatten()
+        {
+            Some(offset) => Ok(Pagination::Offset(OffsetPage {
+                page,
+                page_size,
+                offset,
+                count_total,
+            })),
+            None => Err(InvalidPage { page, page_size }),
+        }
+    }
+
+    pub fn page_size(&self) -> i64 {
+        match self {
+            Pagination::Cursor { limit, .. } => *limit,
+            Pagination::Offset(page) => page.page_size,
+        }
+    }
+
+    /// rejects pages larger than `max_page_size`, for sizes that come from
+    /// clients
+    pub fn at_most(self, max_page_size: i64) -> Result<Self, PageTooLarge> {
+        let page_size = self.page_size();
+        match (0..=max_page_size).contains(&page_size) {
+            true => Ok(self),
+            false => Err(PageTooLarge {
+                page_size,
+                max_page_size,
+            }),
+        }
+    }
 }
 
+/// `next_item` is passed back as `Cursor::After`, and `prev_item` as
+/// `Cursor::Before`.
+///
+/// `prev_item` of a page fetched forward is set whenever the page didn't
+/// start from the beginning, it may lead to an empty page when the cursor
+/// didn't point to an existing record.
+///
+/// `total` is only counted for `Pagination::Offset`, with a separate
+/// `COUNT(*)` query so it's set on pages past the last record too
 #[derive(Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ManyOutput<T, Next> {
     pub items: Vec<T>,
     pub next_item: Option<Next>,
+    pub prev_item: Option<Next>,
+    pub total: Option<i64>,
 }
 
-impl<B, L, W, O, F> OperationOutput for FetchMany<B, L, W, O, (<B::Id as CollectionId>::IdData, F)>
+impl<B, L, W, O, Cm> OperationOutput
+    for FetchMany<B, L, W, O, (<B::Id as CollectionId>::IdData, O::RData), Cm>
 where
     B: Collection,
     L: LinkFetch,
     O: FromRowData,
+    Cm: FromRowData,
 {
     type Output = ManyOutput<
-        LinkedOutput<<B::Id as CollectionId>::IdData, B::OutputData, L::Output>,
+        LinkedOutput<<B::Id as CollectionId>::IdData, B::OutputData, L::Output, Cm::RData>,
         (<B::Id as CollectionId>::IdData, O::RData),
     >;
 }
 
-impl<S, Base, Links, Wheres, OrderBy, First> Operation<S>
-    for FetchMany<Base, Links, Wheres, OrderBy, (<Base::Id as CollectionId>::IdData, First)>
+impl<S, Base, Links, Wheres, OrderBy, Computed> Operation<S>
+    for FetchMany<
+        Base,
+        Links,
+        Wheres,
+        OrderBy,
+        (<Base::Id as CollectionId>::IdData, OrderBy::RData),
+        Computed,
+    >
 where
     S: DatabaseExt,
     S: ExecutorTrait,
     Base: Send,
     OrderBy: Send,
-    First: Send,
-    Wheres: Send,
+    Wheres: Send + Clone,
     Wheres: for<'q> ManyExpressions<'q, S>,
     Links: Send + LinkFetch<Output: Send>,
     Links::Wheres: for<'q> ManyExpressions<'q, S>,
@@ -227,55 +340,119 @@ where
     Base: for<'r> FromRowAlias<'r, S::Row>,
     Base::Id: FromRowData<RData = <Base::Id as CollectionId>::IdData>,
     Base::Id: for<'r> FromRowAlias<'r, S::Row>,
-    Base::Id: CollectionId<IdData: Send + for<'q> Encode<'q, S> + Type<S>>,
+    Base::Id: CollectionId<IdData: Send + Clone + 'static + for<'q> Encode<'q, S> + Type<S>>,
     Base::Id: ExpressionsForOperation<
             ScopedAliased: for<'q> ManyExpressions<'q, S>,
-            Scoped: for<'q> ManyExpressions<'q, S>,
-        >,
+            Scoped: for<'q> Expression<'q, S>,
+        > + 'static,
     // Base::Id: Scoped<Scoped: for<'q> Expression<'q, S>>,
     // Base::Id: Aliased<Aliased: for<'q> Expression<'q, S>>,
     Links: LinkFetch<Output: Send>,
-    i64: for<'q> Encode<'q, S> + Type<S>,
-    OrderBy: Send + Clone,
-    // OrderBy: Scoped<Scoped: for<'q> ManyExpressions<'q, S>>,
-    OrderBy: ExpressionsForOperation<Scoped: for<'q> ManyExpressions<'q, S>>,
-    OrderBy: for<'r> FromRowAlias<'r, S::Row, RData: Send>,
-    First: Send,
-    First: SelfPrescribedInsert<
-            InsertValue: Send + for<'q> ManyExpressions<'q, S>,
-            InsertId: Send + for<'q> ManyExpressions<'q, S>,
-        >,
+    i64: for<'q> Encode<'q, S> + for<'r> Decode<'r, S> + Type<S>,
+    for<'a> &'a str: ColumnIndex<S::Row>,
+    OrderBy: Send + Clone + 'static,
+    OrderBy: ExpressionsForOperation<ScopedAliased: for<'q> ManyExpressions<'q, S>>,
+    OrderBy: for<'q> CursorOrder<'q, S>,
+    OrderBy: for<'r> FromRowAlias<'r, S::Row, RData: Send + Clone + 'static>,
+    Computed: Send,
+    Computed: ExpressionsForOperation<ScopedAliased: for<'q> ManyExpressions<'q, S>>,
+    Computed: for<'r> FromRowAlias<'r, S::Row, RData: Send>,
 {
     async fn exec_operation(self, pool: &mut S::Connection) -> Self::Output {
         // let db = S::singleton();
         let id = self.base.id();
         let link_items = self.links.non_aggregating_select_items();
+
+        let (cursor, limit, offset, count_total) = match self.pagination {
+            Pagination::Cursor { cursor, limit } => (cursor, Some(limit), None, false),
+            Pagination::Offset(page) => (
+                None,
+                None,
+                Some((page.offset, page.page_size)),
+                page.count_total,
+            ),
+        };
+
+        let total = match count_total {
+            true => {
+                let (stmt, arg) = StatementBuilder::<S>::new(SelectStatement {
+                    select_items: AliasedExpression {
+                        expression: CountAll,
+                        alias: ("total",),
+                    },
+                    from: self.base.table_name().to_string(),
+                    joins: self.links.non_duplicating_join_expressions(),
+                    group_by: (),
+                    order: (),
+                    wheres: ManyFlat((
+                        self.wheres.clone(),
+                        self.links.where_expressions(),
+                        NotDeleted::of(&self.base, &self.links, self.base.table_name().to_string()),
+                    )),
+                    limit: (),
+                    offset: (),
+                })
+                .unwrap();
+
+                tracing::info!(sql_stmt = %stmt, "fetch many total");
+
+                let row = S::fetch_optional(&mut *pool, Executable::cached(&FETCH_MANY, &stmt, arg))
+                    .await
+                    .unwrap()
+                    .expect("COUNT(*) returns a row");
+                Some(row.try_get::<i64, _>("total").unwrap())
+            }
+            false => None,
+        };
+
+        // a page before the cursor is fetched in reverse then flipped back
+        let reverse = matches!(cursor, Some(Cursor::Before(_)));
+        let (condition, cursor_item) = match cursor {
+            Some(Cursor::After(item)) => (Some((item, false)), None),
+            Some(Cursor::Before(item)) => (Some((item.clone(), true)), Some(item)),
+            None => (None, None),
+        };
+        let has_cursor = condition.is_some();
+
         let query_builder = StatementBuilder::<S>::new(SelectStatement {
             select_items: ManyFlat((
                 id.scoped_aliased("i"),
                 self.base.scoped_aliased("b"),
                 link_items.scoped_aliased("l"),
+                ManyFlat((
+                    self.cursor_order_by.scoped_aliased("o"),
+                    self.computed.scoped_aliased("c"),
+                )),
             )),
             from: self.base.table_name().to_string(),
             joins: self.links.non_duplicating_join_expressions(),
             group_by: (),
-            order: self.cursor_order_by.scoped(),
+            order: CursorOrderBy {
+                order: self.cursor_order_by.clone(),
+                id_col: self.base.id(),
+                reverse,
+            },
             wheres: ManyFlat((
                 self.wheres,
                 self.links.where_expressions(),
-                self.cursor_first_item
-                    .map(|(id, first)| {
-                        let (idents, values) = first.on_insert();
-                        // let idents = first.scoped();
-                        // let first = first.on_insert(());
-                        ManyColumnsLargerOrEqual {
-                            ids: ManyFlat((idents, self.base.id().scoped())),
-                            values: ManyFlat((values, Bind(id))),
-                        }
+                NotDeleted::of(&self.base, &self.links, self.base.table_name().to_string()),
+                condition
+                    .map(|((id, values), before)| CursorCondition {
+                        order: self.cursor_order_by.clone(),
+                        id_col: self.base.id(),
+                        id,
+                        values,
+                        before,
                     })
-                    .unwrap(),
+                    .into_iter()
+                    .collect::<Vec<_>>(),
             )),
-            limit: Bind(self.limit + 1),
+            limit: match (limit, offset) {
+                (Some(limit), _) => Bind(limit + 1),
+                (None, Some((_, page_size))) => Bind(page_size),
+                (None, None) => unreachable!("pagination is either by cursor or by offset"),
+            },
+            offset: offset.map(|(offset, _)| Bind(offset)),
         });
 
         let (stmt, arg) = query_builder.unwrap();
@@ -284,26 +461,44 @@ where
 
         let mut s = S::fetch_all(
             &mut *pool,
-            Executable {
-                string: &stmt,
-                arguments: arg,
-            },
+            Executable::cached(&FETCH_MANY, &stmt, arg),
         )
         .await
         .unwrap();
 
-        let has_more = if s.len() == (self.limit + 1) as usize {
-            let last = s
-                .pop()
-                .expect("bug: len is usize + 1, should have last item to pop");
-            let next = self
-                .cursor_order_by
-                .pre_alias(RowPreAliased::new(&last, "b"))
-                .unwrap();
-            let id = id.pre_alias(RowPreAliased::new(&last, "i")).unwrap();
-            Some((id, next))
-        } else {
-            None
+        let (next_item, prev_item) = {
+            let cursor_item_of = |row: &S::Row| {
+                let next = self
+                    .cursor_order_by
+                    .pre_alias(RowPreAliased::new(row, "o"))
+                    .unwrap();
+                let id = id.pre_alias(RowPreAliased::new(row, "i")).unwrap();
+                (id, next)
+            };
+
+            let has_more = if limit.is_some_and(|limit| s.len() == (limit + 1) as usize) {
+                let last = s
+                    .pop()
+                    .expect("bug: len is usize + 1, should have last item to pop");
+                Some(cursor_item_of(&last))
+            } else {
+                None
+            };
+
+            if reverse {
+                s.reverse();
+                let prev = match has_more {
+                    Some(_) => s.first().map(cursor_item_of),
+                    None => None,
+                };
+                (cursor_item, prev)
+            } else {
+                let prev = match has_cursor {
+                    true => s.first().map(cursor_item_of),
+                    false => None,
+                };
+                (has_more, prev)
+            }
         };
 
         let mut input = self.links.operation_initialize_input();
@@ -318,6 +513,7 @@ where
                     id,
                     attributes: self.base.pre_alias(RowPreAliased::new(&e, "b")).unwrap(),
                     links: link,
+                    computed: self.computed.pre_alias(RowPreAliased::new(&e, "c")).unwrap(),
                 };
             })
             .collect::<Vec<_>>();
@@ -334,12 +530,15 @@ where
                 id: e.id,
                 attributes: e.attributes,
                 links: self.links.take_many(e.links, &mut po),
+                computed: e.computed,
             })
             .collect::<Vec<_>>();
 
         ManyOutput {
             items: all,
-            next_item: has_more,
+            next_item,
+            prev_item,
+            total,
         }
     }
 }
@@ -352,8 +551,8 @@ mod test {
         connect_in_memory::ConnectInMemory,
         operations::{
             LinkedOutput, Operation,
-            fetch_many::{FetchMany, ManyOutput},
-            operations_expressions_crossover::NamedBind,
+            cursor::{Cursor, Desc},
+            fetch_many::{FetchMany, InvalidPage, ManyOutput, PageTooLarge, Pagination},
         },
         test_module::{Todo, TodoHandler, todo_members},
     };
@@ -390,9 +589,11 @@ mod test {
                 wheres: (),
                 links: (),
                 cursor_order_by: todo_members::title,
-                // cursor_first_item: None::<(i64, ())>,
-                cursor_first_item: Some((4, todo_members::title::bind(String::from("non_unique")))),
-                limit: 2,
+                pagination: Pagination::Cursor {
+                    cursor: Some(Cursor::After((4, String::from("non_unique")))),
+                    limit: 2,
+                },
+                computed: (),
             },
             &mut conn,
         )
@@ -410,6 +611,7 @@ mod test {
                             description: Some("description_4".to_string()),
                         },
                         links: (),
+                        computed: (),
                     },
                     LinkedOutput {
                         id: 2,
@@ -419,10 +621,246 @@ mod test {
                             description: Some("description_2".to_string()),
                         },
                         links: (),
+                        computed: (),
                     },
                 ],
                 next_item: Some((6, String::from("sixth_todo"))),
+                prev_item: Some((4, String::from("non_unique"))),
+                total: None,
             }
         );
+
+        let output = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: TodoHandler,
+                wheres: (),
+                links: (),
+                cursor_order_by: todo_members::title,
+                pagination: Pagination::Cursor {
+                    cursor: output.prev_item.map(Cursor::Before),
+                    limit: 2,
+                },
+                computed: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            output.items.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![5, 1]
+        );
+        pretty_assertions::assert_eq!(output.next_item, Some((4, String::from("non_unique"))));
+        pretty_assertions::assert_eq!(output.prev_item, None);
+    }
+
+    #[tokio::test]
+    async fn descending() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        query(
+            "
+        CREATE TABLE Todo (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            done BOOLEAN NOT NULL,
+            description TEXT
+        );
+
+        INSERT INTO Todo (title, done, description) VALUES
+            ('a', true, NULL),
+            ('b', false, NULL),
+            ('b', true, NULL),
+            ('c', false, NULL),
+            ('d', true, NULL);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let output = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: TodoHandler,
+                wheres: (),
+                links: (),
+                cursor_order_by: Desc(todo_members::title),
+                pagination: Pagination::Cursor {
+                    cursor: None,
+                    limit: 2,
+                },
+                computed: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            output.items.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![5, 4]
+        );
+        pretty_assertions::assert_eq!(output.next_item, Some((3, String::from("b"))));
+        pretty_assertions::assert_eq!(output.prev_item, None);
+
+        let output = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: TodoHandler,
+                wheres: (),
+                links: (),
+                cursor_order_by: Desc(todo_members::title),
+                pagination: Pagination::Cursor {
+                    cursor: output.next_item.map(Cursor::After),
+                    limit: 2,
+                },
+                computed: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            output.items.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![3, 2]
+        );
+        pretty_assertions::assert_eq!(output.next_item, Some((1, String::from("a"))));
+        pretty_assertions::assert_eq!(output.prev_item, Some((3, String::from("b"))));
+
+        let output = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: TodoHandler,
+                wheres: (),
+                links: (),
+                cursor_order_by: Desc(todo_members::title),
+                pagination: Pagination::Cursor {
+                    cursor: output.prev_item.map(Cursor::Before),
+                    limit: 2,
+                },
+                computed: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            output.items.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![5, 4]
+        );
+        pretty_assertions::assert_eq!(output.next_item, Some((3, String::from("b"))));
+        pretty_assertions::assert_eq!(output.prev_item, None);
+    }
+
+    #[tokio::test]
+    async fn offset() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        query(
+            "
+        CREATE TABLE Todo (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            done BOOLEAN NOT NULL,
+            description TEXT
+        );
+
+        INSERT INTO Todo (title, done, description) VALUES
+            ('a', true, NULL),
+            ('b', false, NULL),
+            ('b', true, NULL),
+            ('c', false, NULL),
+            ('d', true, NULL);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let output = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: TodoHandler,
+                wheres: (),
+                links: (),
+                cursor_order_by: todo_members::title,
+                pagination: Pagination::offset(1, 2, true).unwrap(),
+                computed: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            output.items.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+        pretty_assertions::assert_eq!(output.total, Some(5));
+        pretty_assertions::assert_eq!(output.next_item, None);
+        pretty_assertions::assert_eq!(output.prev_item, None);
+
+        let output = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: TodoHandler,
+                wheres: (),
+                links: (),
+                cursor_order_by: todo_members::title,
+                pagination: Pagination::offset(2, 2, false).unwrap(),
+                computed: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            output.items.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![5]
+        );
+        pretty_assertions::assert_eq!(output.total, None);
+
+        let output = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: TodoHandler,
+                wheres: (),
+                links: (),
+                cursor_order_by: todo_members::title,
+                pagination: Pagination::offset(3, 2, true).unwrap(),
+                computed: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(output.items, vec![]);
+        pretty_assertions::assert_eq!(output.total, Some(5));
+
+        for (page, page_size) in [(0, 0), (1, -2), (-1, 2), (i64::MAX, 2)] {
+            pretty_assertions::assert_eq!(
+                Pagination::<()>::offset(page, page_size, false).err(),
+                Some(InvalidPage { page, page_size })
+            );
+        }
+
+        pretty_assertions::assert_eq!(
+            Pagination::<()>::offset(0, 20, false).unwrap().at_most(10).err(),
+            Some(PageTooLarge {
+                page_size: 20,
+                max_page_size: 10
+            })
+        );
+        pretty_assertions::assert_eq!(
+            Pagination::<()>::Cursor {
+                cursor: None,
+                limit: -1
+            }
+            .at_most(10)
+            .err(),
+            Some(PageTooLarge {
+                page_size: -1,
+                max_page_size: 10
+            })
+        );
+        assert!(
+            Pagination::<()>::offset(0, 10, false)
+                .unwrap()
+                .at_most(10)
+                .is_ok()
+        );
     }
 }
@@ -4,6 +4,7 @@ use crate::{
     execute::Executable,
     fix_executor::ExecutorTrait,
     from_row::{FromRowAlias, RowPreAliased},
+    links::soft_delete::expressions::NotDeleted,
     operations::{
         LinkedOutput, Operation, OperationOutput,
         fetch_many::LinkFetch,
@@ -13,6 +14,7 @@ use crate::{
         Expression, ManyExpressions, StatementBuilder, basic_expressions::ManyFlat,
         statements::select_statement::SelectStatement,
     },
+    statement_cache::FETCH_ONE,
 };
 
 pub struct FetchOne<Base, Links, Wheres> {
@@ -78,22 +80,21 @@ where
                 )),
                 from: self.base.table_name_pascal_case(),
                 joins: self.links.non_duplicating_join_expressions(),
-                wheres: ManyFlat((self.wheres, self.links.where_expressions())),
+                wheres: ManyFlat((
+                    self.wheres,
+                    self.links.where_expressions(),
+                    NotDeleted::of(&self.base, &self.links, self.base.table_name_pascal_case()),
+                )),
                 group_by: (),
                 order: (),
                 limit: (),
+                offset: (),
             })
             .unwrap();
 
-            let row = S::fetch_optional(
-                &mut *pool,
-                Executable {
-                    string: &stmt,
-                    arguments: args,
-                },
-            )
-            .await
-            .unwrap()?;
+            let row = S::fetch_optional(&mut *pool, Executable::cached(&FETCH_ONE, &stmt, args))
+                .await
+                .unwrap()?;
 
             let id = id.pre_alias(RowPreAliased::new(&row, "i")).unwrap();
             let attributes = self.base.pre_alias(RowPreAliased::new(&row, "b")).unwrap();
@@ -109,6 +110,7 @@ where
                 id,
                 attributes,
                 links: self.links.take_once(link_items, op),
+                computed: (),
             })
         }
     }
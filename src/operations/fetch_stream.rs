@@ -0,0 +1,310 @@
+use crate::{
+    collections::{Collection, CollectionId},
+    database_extention::DatabaseExt,
+    execute::Executable,
+    fix_executor::ExecutorTrait,
+    from_row::{FromRowAlias, FromRowData, RowPreAliased},
+    links::soft_delete::expressions::NotDeleted,
+    operations::{
+        LinkedOutput, Operation, cursor::CursorOrderBy, fetch_many::LinkFetch,
+        operations_expressions_crossover::ExpressionsForOperation,
+    },
+    sqlx_query_builder::{
+        Expression, ManyExpressions, StatementBuilder,
+        basic_expressions::{Bind, ColumnGreaterThan, ManyFlat},
+        statements::select_statement::SelectStatement,
+    },
+    statement_cache::FETCH_STREAM,
+};
+use futures::{Stream, StreamExt, TryStreamExt, stream};
+use sqlx::{Encode, Type};
+
+/// all records matching `wheres` ordered by id, without loading them all at once.
+///
+/// records are read `batch_size` at a time, each batch is one query read
+/// through `ExecutorTrait::fetch_stream` then its links are fetched together,
+/// the next batch starts after the last id of the previous one, a
+/// `batch_size` that isn't positive yields a single
+/// `sqlx::Error::InvalidArgument`
+pub struct FetchStream<Base, Links, Wheres> {
+    pub base: Base,
+    pub wheres: Wheres,
+    pub links: Links,
+    pub batch_size: i64,
+}
+
+pub type FetchStreamItem<Base, Links> = LinkedOutput<
+    <<Base as Collection>::Id as CollectionId>::IdData,
+    <Base as Collection>::OutputData,
+    <Links as LinkFetch>::Output,
+>;
+
+struct State<'c, S: sqlx::Database, Base, Links, Wheres, IdData> {
+    op: FetchStream<Base, Links, Wheres>,
+    conn: &'c mut S::Connection,
+    after: Option<IdData>,
+}
+
+impl<Base, Links, Wheres> FetchStream<Base, Links, Wheres> {
+    pub fn exec_stream<'c, S>(
+        self,
+        conn: &'c mut S::Connection,
+    ) -> impl Stream<Item = Result<FetchStreamItem<Base, Links>, sqlx::Error>> + Send + 'c
+    where
+        S: DatabaseExt,
+        S: ExecutorTrait,
+        Base: Send + 'c,
+        Links: Send + 'c,
+        Wheres: Send + Clone + 'c,
+        Wheres: for<'q> ManyExpressions<'q, S>,
+        Links: LinkFetch<Output: Send>,
+        Links::Wheres: for<'q> ManyExpressions<'q, S>,
+        Links::SelectItems:
+            Send + ExpressionsForOperation<ScopedAliased: for<'q> ManyExpressions<'q, S>>,
+        Links::SelectItems: for<'r> FromRowAlias<'r, S::Row, RData: Send>,
+        Links::Join: for<'q> ManyExpressions<'q, S>,
+        Links::Op: Operation<S>,
+        Links::OpInput: Send,
+        Base: Collection<OutputData: Send, Id: Send>,
+        Base: ExpressionsForOperation<ScopedAliased: for<'q> ManyExpressions<'q, S>>,
+        Base: FromRowData<RData = Base::OutputData>,
+        Base: for<'r> FromRowAlias<'r, S::Row>,
+        Base::Id: FromRowData<RData = <Base::Id as CollectionId>::IdData>,
+        Base::Id: for<'r> FromRowAlias<'r, S::Row>,
+        Base::Id: CollectionId<IdData: Send + Clone + 'static + for<'q> Encode<'q, S> + Type<S>>,
+        Base::Id: ExpressionsForOperation<
+                ScopedAliased: for<'q> ManyExpressions<'q, S>,
+                Scoped: for<'q> Expression<'q, S>,
+            > + 'static,
+        i64: for<'q> Encode<'q, S> + Type<S>,
+    {
+        let batch_size = self.batch_size;
+        let state = (batch_size > 0).then(|| State::<S, _, _, _, _> {
+            op: self,
+            conn,
+            after: None,
+        });
+        let invalid = state.is_none().then(|| {
+            sqlx::Error::InvalidArgument(format!("batch_size must be positive, got {batch_size}"))
+        });
+
+        stream::iter(invalid.map(Err)).chain(
+            stream::unfold(state, |state| async move {
+                let mut state = state?;
+                let batch = next_batch(&mut state).await;
+                let full = batch
+                    .as_ref()
+                    .is_ok_and(|batch| batch.len() as i64 == state.op.batch_size);
+                let batch = match batch {
+                    Ok(batch) => stream::iter(batch).map(Ok).left_stream(),
+                    Err(err) => stream::once(async move { Err(err) }).right_stream(),
+                };
+                // a short batch is the last one, an error ends the stream too
+                Some((batch, full.then_some(state)))
+            })
+            .flatten(),
+        )
+    }
+}
+
+async fn next_batch<S, Base, Links, Wheres>(
+    state: &mut State<'_, S, Base, Links, Wheres, <Base::Id as CollectionId>::IdData>,
+) -> Result<Vec<FetchStreamItem<Base, Links>>, sqlx::Error>
+where
+    S: DatabaseExt,
+    S: ExecutorTrait,
+    Wheres: Clone,
+    Wheres: for<'q> ManyExpressions<'q, S>,
+    Links: LinkFetch<Output: Send>,
+    Links::Wheres: for<'q> ManyExpressions<'q, S>,
+    Links::SelectItems: ExpressionsForOperation<ScopedAliased: for<'q> ManyExpressions<'q, S>>,
+    Links::SelectItems: for<'r> FromRowAlias<'r, S::Row>,
+    Links::Join: for<'q> ManyExpressions<'q, S>,
+    Links::Op: Operation<S>,
+    Base: Collection,
+    Base: ExpressionsForOperation<ScopedAliased: for<'q> ManyExpressions<'q, S>>,
+    Base: FromRowData<RData = Base::OutputData>,
+    Base: for<'r> FromRowAlias<'r, S::Row>,
+    Base::Id: FromRowData<RData = <Base::Id as CollectionId>::IdData>,
+    Base::Id: for<'r> FromRowAlias<'r, S::Row>,
+    Base::Id: CollectionId<IdData: Clone + 'static + for<'q> Encode<'q, S> + Type<S>>,
+    Base::Id: ExpressionsForOperation<
+            ScopedAliased: for<'q> ManyExpressions<'q, S>,
+            Scoped: for<'q> Expression<'q, S>,
+        > + 'static,
+    i64: for<'q> Encode<'q, S> + Type<S>,
+{
+    // fields of `state` are borrowed anew after each await, so the
+    // future stays `Send` without requiring `Sync` of the operation
+    let id = state.op.base.id();
+    let link_items = state.op.links.non_aggregating_select_items();
+
+    let query_builder = StatementBuilder::<S>::new(SelectStatement {
+        select_items: ManyFlat((
+            id.scoped_aliased("i"),
+            state.op.base.scoped_aliased("b"),
+            link_items.scoped_aliased("l"),
+        )),
+        from: state.op.base.table_name().to_string(),
+        joins: state.op.links.non_duplicating_join_expressions(),
+        group_by: (),
+        order: CursorOrderBy {
+            order: (),
+            id_col: state.op.base.id(),
+            reverse: false,
+        },
+        wheres: ManyFlat((
+            state.op.wheres.clone(),
+            state.op.links.where_expressions(),
+            NotDeleted::of(
+                &state.op.base,
+                &state.op.links,
+                state.op.base.table_name().to_string(),
+            ),
+            state
+                .after
+                .clone()
+                .map(|after| ColumnGreaterThan {
+                    col: id.scoped(),
+                    gt: after,
+                })
+                .into_iter()
+                .collect::<Vec<_>>(),
+        )),
+        limit: Bind(state.op.batch_size),
+        offset: (),
+    });
+
+    let (stmt, arg) = query_builder.unwrap();
+
+    tracing::info!(sql_stmt = %stmt, "fetch stream batch");
+
+    let mut input = state.op.links.operation_initialize_input();
+    let mut batch = Vec::new();
+
+    let mut rows = S::fetch_stream(
+        &mut *state.conn,
+        Executable::cached(&FETCH_STREAM, &stmt, arg),
+    );
+
+    // each row is decoded as soon as it is read
+    while let Some(row) = rows.try_next().await? {
+        let id = id.pre_alias(RowPreAliased::new(&row, "i")).unwrap();
+        let link = link_items.pre_alias(RowPreAliased::new(&row, "l")).unwrap();
+        state.op.links.operation_fix_on_many(&link, &mut input);
+        batch.push(LinkedOutput {
+            id,
+            attributes: state
+                .op
+                .base
+                .pre_alias(RowPreAliased::new(&row, "b"))
+                .unwrap(),
+            links: link,
+            computed: (),
+        });
+    }
+    drop(rows);
+
+    let mut po = state
+        .op
+        .links
+        .operation_construct(input)
+        .exec_operation(&mut *state.conn)
+        .await;
+
+    state.after = batch.last().map(|last| last.id.clone());
+
+    Ok(batch
+        .into_iter()
+        .map(|e| LinkedOutput {
+            id: e.id,
+            attributes: e.attributes,
+            links: state.op.links.take_many(e.links, &mut po),
+            computed: (),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use futures::{StreamExt, TryStreamExt};
+    use sqlx::{Sqlite, query};
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        operations::{LinkedOutput, fetch_stream::FetchStream},
+        test_module::{Todo, TodoHandler},
+    };
+
+    #[tokio::test]
+    async fn main() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        query(
+            "
+        CREATE TABLE Todo (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            done BOOLEAN NOT NULL,
+            description TEXT
+        );
+
+        INSERT INTO Todo (title, done, description) VALUES
+            ('a', true, NULL),
+            ('b', false, NULL),
+            ('c', true, NULL),
+            ('d', false, NULL),
+            ('e', true, NULL);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let items = FetchStream {
+            base: TodoHandler,
+            wheres: (),
+            links: (),
+            batch_size: 2,
+        }
+        .exec_stream::<Sqlite>(&mut conn)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+
+        pretty_assertions::assert_eq!(
+            items,
+            ["a", "b", "c", "d", "e"]
+                .into_iter()
+                .enumerate()
+                .map(|(index, title)| LinkedOutput {
+                    id: index as i64 + 1,
+                    attributes: Todo {
+                        title: title.to_string(),
+                        done: index % 2 == 0,
+                        description: None,
+                    },
+                    links: (),
+                    computed: (),
+                })
+                .collect::<Vec<_>>()
+        );
+
+        for batch_size in [0, -1] {
+            let items = FetchStream {
+                base: TodoHandler,
+                wheres: (),
+                links: (),
+                batch_size,
+            }
+            .exec_stream::<Sqlite>(&mut conn)
+            .collect::<Vec<_>>()
+            .await;
+
+            assert!(matches!(
+                items.as_slice(),
+                [Err(sqlx::Error::InvalidArgument(_))]
+            ));
+        }
+    }
+}
@@ -0,0 +1,439 @@
+//! hooks around the writes of a collection.
+//!
+//! `Hooked` wraps an `InsertOne`, `Update` or `Delete` and runs the hooks of
+//! its base in the same transaction as the write (a savepoint when the
+//! connection is already in one). a hook returning an error rolls back the
+//! write and everything the hooks did before it.
+
+use sqlx::{Connection, Database, Transaction};
+
+use crate::{
+    collections::{Collection, CollectionId},
+    operations::{
+        LinkedOutput, Operation, OperationOutput,
+        delete::{Delete, DeleteLink, DeleteLinkSplit},
+        insert::{ConstraintViolation, InsertLinkConsumeData, InsertOne, InsertOneLink},
+        update::{Update, UpdateLink, UpdateLinkSplit},
+    },
+    sqlx_query_builder::statements::insert_statement::One,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum HookError {
+    /// returned by a hook, with the reason
+    Aborted(String),
+    Constraint(ConstraintViolation),
+    /// beginning, committing or rolling back the transaction failed
+    Transaction(String),
+}
+
+impl HookError {
+    fn transaction(error: sqlx::Error) -> Self {
+        HookError::Transaction(error.to_string())
+    }
+}
+
+impl From<ConstraintViolation> for HookError {
+    fn from(value: ConstraintViolation) -> Self {
+        HookError::Constraint(value)
+    }
+}
+
+/// every hook defaults to doing nothing
+pub trait CollectionHooks<S: Database>: Collection {
+    fn before_insert(
+        &self,
+        _data: &mut Self::InputData,
+        _conn: &mut S::Connection,
+    ) -> impl Future<Output = Result<(), HookError>> + Send {
+        async { Ok(()) }
+    }
+
+    fn after_insert(
+        &self,
+        _id: &<Self::Id as CollectionId>::IdData,
+        _attributes: &Self::OutputData,
+        _conn: &mut S::Connection,
+    ) -> impl Future<Output = Result<(), HookError>> + Send {
+        async { Ok(()) }
+    }
+
+    fn before_update(
+        &self,
+        _partial: &mut Self::UpdateData,
+        _conn: &mut S::Connection,
+    ) -> impl Future<Output = Result<(), HookError>> + Send {
+        async { Ok(()) }
+    }
+
+    /// called once for each deleted record
+    fn after_delete(
+        &self,
+        _id: &<Self::Id as CollectionId>::IdData,
+        _attributes: &Self::OutputData,
+        _conn: &mut S::Connection,
+    ) -> impl Future<Output = Result<(), HookError>> + Send {
+        async { Ok(()) }
+    }
+}
+
+pub struct Hooked<Op>(pub Op);
+
+/// commits `tx` when `output` is ok, rolls it back otherwise
+async fn finish<S: Database, T>(
+    tx: Transaction<'_, S>,
+    output: Result<T, HookError>,
+) -> Result<T, HookError> {
+    match output {
+        Ok(output) => {
+            tx.commit().await.map_err(HookError::transaction)?;
+            Ok(output)
+        }
+        Err(error) => {
+            tx.rollback().await.map_err(HookError::transaction)?;
+            Err(error)
+        }
+    }
+}
+
+impl<Id, Base, PreL, L> OperationOutput for Hooked<InsertOne<Id, Base, One<Base::InputData>, PreL>>
+where
+    PreL: InsertLinkConsumeData<Link = L>,
+    L: InsertOneLink,
+    Base: Collection,
+{
+    type Output = Result<
+        LinkedOutput<<Base::Id as CollectionId>::IdData, Base::OutputData, L::Output>,
+        HookError,
+    >;
+}
+
+impl<S, Id, Base, PreL, L> Operation<S> for Hooked<InsertOne<Id, Base, One<Base::InputData>, PreL>>
+where
+    S: Database,
+    PreL: InsertLinkConsumeData<Link = L>,
+    L: InsertOneLink<Output: Send>,
+    Base: CollectionHooks<S> + Clone + Send + Sync,
+    Base::InputData: Send,
+    Base::OutputData: Send,
+    <Base::Id as CollectionId>::IdData: Send,
+    InsertOne<Id, Base, One<Base::InputData>, PreL>: Operation<
+            S,
+            Output = Result<
+                LinkedOutput<<Base::Id as CollectionId>::IdData, Base::OutputData, L::Output>,
+                ConstraintViolation,
+            >,
+        >,
+{
+    fn exec_operation(self, pool: &mut S::Connection) -> impl Future<Output = Self::Output> + Send
+    where
+        S: Database,
+        Self: Sized,
+    {
+        async move {
+            let mut op = self.0;
+            let base = op.handler.clone();
+            let mut tx = pool.begin().await.map_err(HookError::transaction)?;
+
+            let output = async {
+                base.before_insert(&mut op.data.0, &mut tx).await?;
+                let output = op.exec_operation(&mut tx).await?;
+                base.after_insert(&output.id, &output.attributes, &mut tx)
+                    .await?;
+                Ok(output)
+            }
+            .await;
+
+            finish(tx, output).await
+        }
+    }
+}
+
+impl<Base, Wheres, PreL, L> OperationOutput for Hooked<Update<Base, Base::UpdateData, Wheres, PreL>>
+where
+    PreL: UpdateLinkSplit<Link = L>,
+    L: UpdateLink,
+    Base: Collection,
+{
+    type Output = Result<
+        Vec<LinkedOutput<<Base::Id as CollectionId>::IdData, Base::OutputData, L::Output>>,
+        HookError,
+    >;
+}
+
+impl<S, Base, Wheres, PreL, L> Operation<S> for Hooked<Update<Base, Base::UpdateData, Wheres, PreL>>
+where
+    S: Database,
+    PreL: UpdateLinkSplit<Link = L>,
+    L: UpdateLink<Output: Send>,
+    Base: CollectionHooks<S> + Clone + Send + Sync,
+    Base::UpdateData: Send,
+    Base::OutputData: Send,
+    <Base::Id as CollectionId>::IdData: Send,
+    Update<Base, Base::UpdateData, Wheres, PreL>: Operation<
+            S,
+            Output = Result<
+                Vec<LinkedOutput<<Base::Id as CollectionId>::IdData, Base::OutputData, L::Output>>,
+                ConstraintViolation,
+            >,
+        >,
+{
+    fn exec_operation(self, pool: &mut S::Connection) -> impl Future<Output = Self::Output> + Send
+    where
+        S: Database,
+        Self: Sized,
+    {
+        async move {
+            let mut op = self.0;
+            let base = op.base.clone();
+            let mut tx = pool.begin().await.map_err(HookError::transaction)?;
+
+            let output = async {
+                base.before_update(&mut op.partial, &mut tx).await?;
+                Ok(op.exec_operation(&mut tx).await?)
+            }
+            .await;
+
+            finish(tx, output).await
+        }
+    }
+}
+
+impl<Base, Wheres, PreL, L> OperationOutput for Hooked<Delete<Base, Wheres, PreL>>
+where
+    PreL: DeleteLinkSplit<Link = L>,
+    L: DeleteLink,
+    Base: Collection,
+{
+    type Output = Result<
+        Vec<LinkedOutput<<Base::Id as CollectionId>::IdData, Base::OutputData, L::Output>>,
+        HookError,
+    >;
+}
+
+impl<S, Base, Wheres, PreL, L> Operation<S> for Hooked<Delete<Base, Wheres, PreL>>
+where
+    S: Database,
+    PreL: DeleteLinkSplit<Link = L>,
+    L: DeleteLink<Output: Send + Sync>,
+    Base: CollectionHooks<S> + Clone + Send + Sync,
+    Base::OutputData: Send + Sync,
+    <Base::Id as CollectionId>::IdData: Send + Sync,
+    Delete<Base, Wheres, PreL>: Operation<
+            S,
+            Output = Vec<
+                LinkedOutput<<Base::Id as CollectionId>::IdData, Base::OutputData, L::Output>,
+            >,
+        >,
+{
+    fn exec_operation(self, pool: &mut S::Connection) -> impl Future<Output = Self::Output> + Send
+    where
+        S: Database,
+        Self: Sized,
+    {
+        async move {
+            let base = self.0.base.clone();
+            let mut tx = pool.begin().await.map_err(HookError::transaction)?;
+
+            let output = async {
+                let output = self.0.exec_operation(&mut tx).await;
+                for deleted in output.iter() {
+                    base.after_delete(&deleted.id, &deleted.attributes, &mut tx)
+                        .await?;
+                }
+                Ok(output)
+            }
+            .await;
+
+            finish(tx, output).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sqlx::{Sqlite, SqliteConnection};
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        operations::{
+            LinkedOutput, Operation,
+            delete::Delete,
+            hooks::{CollectionHooks, HookError, Hooked},
+            insert::InsertOne,
+            insert_id_mode::AutoGenerate,
+        },
+        sqlx_query_builder::{
+            basic_expressions::{ColumnEqual, ScopedColumn},
+            statements::insert_statement::One,
+        },
+        test_module::{Todo, TodoHandler},
+    };
+
+    impl CollectionHooks<Sqlite> for TodoHandler {
+        async fn before_insert(
+            &self,
+            data: &mut Todo,
+            _: &mut SqliteConnection,
+        ) -> Result<(), HookError> {
+            if data.title.is_empty() {
+                return Err(HookError::Aborted(String::from("title is empty")));
+            }
+            data.description
+                .get_or_insert_with(|| String::from("set by hook"));
+            Ok(())
+        }
+
+        async fn after_insert(
+            &self,
+            id: &i64,
+            attributes: &Todo,
+            conn: &mut SqliteConnection,
+        ) -> Result<(), HookError> {
+            sqlx::query("INSERT INTO Log (entry) VALUES ($1);")
+                .bind(format!("inserted {id}"))
+                .execute(&mut *conn)
+                .await
+                .unwrap();
+            if attributes.title == "undo" {
+                return Err(HookError::Aborted(String::from("undone")));
+            }
+            Ok(())
+        }
+
+        async fn after_delete(
+            &self,
+            id: &i64,
+            _: &Todo,
+            conn: &mut SqliteConnection,
+        ) -> Result<(), HookError> {
+            sqlx::query("INSERT INTO Log (entry) VALUES ($1);")
+                .bind(format!("deleted {id}"))
+                .execute(&mut *conn)
+                .await
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn main() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        sqlx::query(
+            "
+        CREATE TABLE Todo (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            done BOOLEAN NOT NULL,
+            description TEXT
+        );
+        CREATE TABLE Log (
+            entry TEXT NOT NULL
+        );
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let aborted = Operation::<Sqlite>::exec_operation(
+            Hooked(InsertOne {
+                id: AutoGenerate,
+                data: One(Todo {
+                    title: String::new(),
+                    done: false,
+                    description: None,
+                }),
+                handler: TodoHandler,
+                links: (),
+            }),
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            aborted,
+            Err(HookError::Aborted(String::from("title is empty")))
+        );
+
+        let undone = Operation::<Sqlite>::exec_operation(
+            Hooked(InsertOne {
+                id: AutoGenerate,
+                data: One(Todo {
+                    title: String::from("undo"),
+                    done: false,
+                    description: None,
+                }),
+                handler: TodoHandler,
+                links: (),
+            }),
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(undone, Err(HookError::Aborted(String::from("undone"))));
+
+        let inserted = Operation::<Sqlite>::exec_operation(
+            Hooked(InsertOne {
+                id: AutoGenerate,
+                data: One(Todo {
+                    title: String::from("todo"),
+                    done: false,
+                    description: None,
+                }),
+                handler: TodoHandler,
+                links: (),
+            }),
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            inserted,
+            Ok(LinkedOutput {
+                id: 1,
+                attributes: Todo {
+                    title: String::from("todo"),
+                    done: false,
+                    description: Some(String::from("set by hook")),
+                },
+                links: (),
+                computed: (),
+            })
+        );
+
+        let deleted = Operation::<Sqlite>::exec_operation(
+            Hooked(Delete {
+                base: TodoHandler,
+                wheres: ColumnEqual {
+                    col: ScopedColumn {
+                        table: ("Todo",),
+                        col: ("id",),
+                    },
+                    eq: 1,
+                },
+                links: (),
+            }),
+            &mut conn,
+        )
+        .await
+        .unwrap();
+
+        pretty_assertions::assert_eq!(deleted.len(), 1);
+
+        let log: Vec<String> = sqlx::query_scalar("SELECT entry FROM Log;")
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+
+        pretty_assertions::assert_eq!(log, vec!["inserted 1", "deleted 1"]);
+
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM Todo;")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+
+        pretty_assertions::assert_eq!(remaining, 0);
+    }
+}
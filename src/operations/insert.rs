@@ -6,7 +6,9 @@ use crate::{
     from_row::{FromRowAlias, FromRowData},
     operations::{LinkedOutput, Operation, OperationOutput, operations_expressions_crossover::{ExpressionsForOperation, OnInsert, TableExpressions}},
     sqlx_query_builder::{Expression, IsOpExpression, ManyExpressions, PossibleExpression, StatementBuilder, basic_expressions::ManyFlat, statements::insert_statement::{InsertStatement, One}},
+    statement_cache::INSERT_ONE,
 };
+use sqlx::error::ErrorKind;
 
 pub trait InsertLinkConsumeData {
     type Link: InsertOneLink;
@@ -202,13 +204,64 @@ pub struct InsertOne<Id, Handler, Data, Links> {
 
 // pub use crate::operations::insert_one_links::{InsertLinkConsumeErased, InsertLinks};
 
+/// the database rejected the written record
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct ConstraintViolation(pub Option<String>);
+pub struct ConstraintViolation {
+    pub kind: ConstraintKind,
+    /// the violated constraint, its name when it has one (`CONSTRAINT
+    /// <name> CHECK (..)`), otherwise whatever the database reports
+    pub constraint: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConstraintKind {
+    Check,
+    Unique,
+    ForeignKey,
+    NotNull,
+    /// `RAISE(ABORT, <message>)` in a trigger, `constraint` is the message
+    Trigger,
+    /// raised by a link rather than the database
+    Unknown,
+}
+
+impl ConstraintViolation {
+    /// `None` when `error` isn't a constraint violation
+    pub fn from_database_error(error: &dyn sqlx::error::DatabaseError) -> Option<Self> {
+        let kind = match error.kind() {
+            ErrorKind::CheckViolation => ConstraintKind::Check,
+            ErrorKind::UniqueViolation => ConstraintKind::Unique,
+            ErrorKind::ForeignKeyViolation => ConstraintKind::ForeignKey,
+            ErrorKind::NotNullViolation => ConstraintKind::NotNull,
+            // sqlx has no kind for SQLITE_CONSTRAINT_TRIGGER
+            _ if error.code().as_deref() == Some("1811") => {
+                return Some(Self {
+                    kind: ConstraintKind::Trigger,
+                    constraint: Some(error.message().to_string()),
+                });
+            }
+            _ => return None,
+        };
+        // sqlite doesn't report the constraint apart from the message:
+        // "CHECK constraint failed: <name>"
+        let constraint = error.constraint().map(|c| c.to_string()).or_else(|| {
+            error
+                .message()
+                .split_once("constraint failed: ")
+                .map(|(_, constraint)| constraint.to_string())
+        });
+        Some(Self { kind, constraint })
+    }
+}
 
 impl From<()> for ConstraintViolation {
     fn from(_: ()) -> Self {
-        ConstraintViolation(None)
+        ConstraintViolation {
+            kind: ConstraintKind::Unknown,
+            constraint: None,
+        }
     }
 }
 
@@ -304,18 +357,12 @@ where
 
             let row = S::fetch_optional(
                 &mut *pool,
-                Executable {
-                    string: &stmt,
-                    arguments: arg,
-                },
+                Executable::cached(&INSERT_ONE, &stmt, arg),
             )
             .await
             .map_err(|e| {
-                if let Some(e) = e.as_database_error() {
-                    if e.is_check_violation() || e.is_unique_violation() || e.is_foreign_key_violation() {
-                        return ConstraintViolation(e.constraint().map(|c| c.to_string()));
-                    }
-                    
+                if let Some(violation) = e.as_database_error().and_then(ConstraintViolation::from_database_error) {
+                    return violation;
                 } 
                     tracing::error!(sqlx_error = ?e, "bug: must clear all sqlx errors, hard to know where this error was originated!");
                     panic!()
@@ -338,6 +385,7 @@ where
                 id,
                 attributes,
                 links,
+                computed: (),
             })
         }
     }
@@ -396,7 +444,8 @@ mod test {
                     done: false,
                     description: None,
                 },
-                links: ()
+                links: (),
+                computed: (),
             })
         );
     }
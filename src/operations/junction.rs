@@ -107,6 +107,7 @@ where
             Executable {
                 string: &stmt,
                 arguments: args,
+                persistent: false,
             },
         )
         .await
@@ -177,6 +178,7 @@ where
             Executable {
                 string: &stmt,
                 arguments: args,
+                persistent: false,
             },
         )
         .await
@@ -302,6 +304,7 @@ where
             group_by: (),
             order: (),
             limit: (),
+            offset: (),
         })
         .unwrap();
 
@@ -310,6 +313,7 @@ where
             Executable {
                 string: &stmt,
                 arguments: args,
+                persistent: false,
             },
         )
         .await
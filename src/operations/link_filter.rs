@@ -0,0 +1,499 @@
+use crate::{
+    database_extention::DatabaseExt,
+    sqlx_query_builder::{
+        Expression, IsOpExpression, ManyExpressions, OpExpression, PossibleExpression,
+        StatementBuilder,
+    },
+};
+
+/// links that can be reached from a correlated subquery, this is what
+/// `Exists`, `Any` and `All` filters are built on:
+///
+/// `EXISTS (SELECT 1 FROM <from> <joins> WHERE <correlate> AND ..)`
+pub trait LinkFilter {
+    type From;
+    fn filter_from(&self) -> Self::From;
+
+    type Joins;
+    fn filter_joins(&self) -> Self::Joins;
+
+    /// condition that ties the subquery to the row of the outer statement
+    type Correlate;
+    fn filter_correlate(&self) -> Self::Correlate;
+}
+
+/// at least one record is linked
+#[derive(Clone)]
+pub struct Exists<Link> {
+    pub link: Link,
+}
+
+/// at least one linked record passes all `filters`
+#[derive(Clone)]
+pub struct Any<Link, Filters> {
+    pub link: Link,
+    pub filters: Filters,
+}
+
+/// every linked record passes all `filters`, records with no links pass as well
+///
+/// a linked record that makes `filters` NULL doesn't pass
+#[derive(Clone)]
+pub struct All<Link, Filters> {
+    pub link: Link,
+    pub filters: Filters,
+}
+
+fn subquery_start<'q, S, Link>(link: &Link, ctx: &mut StatementBuilder<'q, S>)
+where
+    S: DatabaseExt,
+    Link: LinkFilter,
+    Link::From: Expression<'q, S>,
+    Link::Joins: ManyExpressions<'q, S>,
+    Link::Correlate: Expression<'q, S>,
+{
+    ctx.syntax("SELECT 1");
+    subquery_from(link, ctx);
+}
+
+/// ` FROM <from> <joins> WHERE <correlate>`, shared with link order by
+pub(crate) fn subquery_from<'q, S, Link>(link: &Link, ctx: &mut StatementBuilder<'q, S>)
+where
+    S: DatabaseExt,
+    Link: LinkFilter,
+    Link::From: Expression<'q, S>,
+    Link::Joins: ManyExpressions<'q, S>,
+    Link::Correlate: Expression<'q, S>,
+{
+    ctx.syntax(" FROM ");
+    Expression::expression(link.filter_from(), ctx);
+    link.filter_joins().expression(" ", " ", ctx);
+    ctx.syntax(" WHERE ");
+    Expression::expression(link.filter_correlate(), ctx);
+}
+
+impl<Link> OpExpression for Exists<Link> {}
+
+impl<'q, S, Link> Expression<'q, S> for Exists<Link>
+where
+    S: DatabaseExt,
+    Link: LinkFilter + 'q,
+    Link::From: Expression<'q, S>,
+    Link::Joins: ManyExpressions<'q, S>,
+    Link::Correlate: Expression<'q, S>,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("EXISTS (");
+        subquery_start(&self.link, ctx);
+        ctx.syntax(")");
+    }
+}
+
+impl<Link, Filters> OpExpression for Any<Link, Filters> {}
+
+impl<'q, S, Link, Filters> Expression<'q, S> for Any<Link, Filters>
+where
+    S: DatabaseExt,
+    Link: LinkFilter + 'q,
+    Link::From: Expression<'q, S>,
+    Link::Joins: ManyExpressions<'q, S>,
+    Link::Correlate: Expression<'q, S>,
+    Filters: ManyExpressions<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("EXISTS (");
+        subquery_start(&self.link, ctx);
+        self.filters.expression(" AND ", " AND ", ctx);
+        ctx.syntax(")");
+    }
+}
+
+impl<Link, Filters> IsOpExpression for All<Link, Filters>
+where
+    Filters: IsOpExpression,
+{
+    fn is_op(&self) -> bool {
+        self.filters.is_op()
+    }
+}
+
+impl<'q, S, Link, Filters> PossibleExpression<'q, S> for All<Link, Filters>
+where
+    S: DatabaseExt,
+    Link: LinkFilter + 'q,
+    Link::From: Expression<'q, S>,
+    Link::Joins: ManyExpressions<'q, S>,
+    Link::Correlate: Expression<'q, S>,
+    Filters: ManyExpressions<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        if self.is_op() {
+            ctx.syntax("NOT EXISTS (");
+            subquery_start(&self.link, ctx);
+            ctx.syntax(" AND (");
+            self.filters.expression("", " AND ", ctx);
+            ctx.syntax(") IS NOT TRUE)");
+        }
+    }
+
+    fn expression_starting(self, start: &'static str, ctx: &mut StatementBuilder<'q, S>) {
+        if self.is_op() {
+            ctx.syntax(start);
+            PossibleExpression::expression(self, ctx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sqlx::{Sqlite, query};
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        links::{
+            DefaultRelationKey, relation_many_to_many::ManyToMany,
+            relation_optional_to_many::OptionalToMany,
+        },
+        operations::{
+            LinkedOutput, Operation,
+            fetch_many::{FetchMany, ManyOutput, Pagination},
+            link_filter::{All, Any, Exists},
+        },
+        sqlx_query_builder::{
+            PossibleExpression, StatementBuilder,
+            basic_expressions::{ColumnEqual, PossibleImplMany, ScopedColumn},
+        },
+        test_module::{CategoryHandler, TagHandler, Todo, TodoHandler, todo_members},
+    };
+
+    const TODO_CATEGORY: OptionalToMany<DefaultRelationKey, TodoHandler, CategoryHandler> =
+        OptionalToMany {
+            fk_unique_id: DefaultRelationKey,
+            from: TodoHandler,
+            to: CategoryHandler,
+        };
+
+    const TODO_TAGS: ManyToMany<DefaultRelationKey, TodoHandler, TagHandler> = ManyToMany {
+        relation_key: DefaultRelationKey,
+        from: TodoHandler,
+        to: TagHandler,
+    };
+
+    fn title_eq(
+        title: &str,
+    ) -> ColumnEqual<ScopedColumn<(&'static str,), (&'static str,)>, String> {
+        ColumnEqual {
+            col: ScopedColumn {
+                table: ("Category",),
+                col: ("title",),
+            },
+            eq: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn statements() {
+        let stmt = StatementBuilder::<Sqlite>::new(Exists {
+            link: TODO_CATEGORY,
+        });
+        pretty_assertions::assert_eq!(
+            stmt.stmt().replace("\"", "'"),
+            r#"EXISTS (SELECT 1 FROM 'Category' WHERE 'Category'.'id' = 'Todo'.'fk_category_def')"#
+        );
+
+        let stmt = StatementBuilder::<Sqlite>::new(Any {
+            link: TODO_CATEGORY,
+            filters: vec![title_eq("work")],
+        });
+        pretty_assertions::assert_eq!(
+            stmt.stmt().replace("\"", "'"),
+            r#"EXISTS (SELECT 1 FROM 'Category' WHERE 'Category'.'id' = 'Todo'.'fk_category_def' AND 'Category'.'title' = $1)"#
+        );
+
+        let stmt = StatementBuilder::<Sqlite>::new(Exists { link: TODO_TAGS });
+        pretty_assertions::assert_eq!(
+            stmt.stmt().replace("\"", "'"),
+            r#"EXISTS (SELECT 1 FROM 'ct_todotag_def' INNER JOIN 'Tag' ON 'ct_todotag_def'.'tag_id' = 'Tag'.'id' WHERE 'ct_todotag_def'.'todo_id' = 'Todo'.'id')"#
+        );
+
+        let mut stmt = StatementBuilder::<Sqlite>::default();
+        PossibleExpression::expression(
+            All {
+                link: TODO_CATEGORY,
+                filters: [title_eq("work"), title_eq("home")],
+            },
+            &mut stmt,
+        );
+        pretty_assertions::assert_eq!(
+            stmt.stmt().replace("\"", "'"),
+            r#"NOT EXISTS (SELECT 1 FROM 'Category' WHERE 'Category'.'id' = 'Todo'.'fk_category_def' AND ('Category'.'title' = $1 AND 'Category'.'title' = $2) IS NOT TRUE)"#
+        );
+
+        let mut stmt = StatementBuilder::<Sqlite>::default();
+        PossibleExpression::expression(
+            All {
+                link: TODO_CATEGORY,
+                filters: Vec::<ColumnEqual<ScopedColumn<(&str,), (&str,)>, String>>::new(),
+            },
+            &mut stmt,
+        );
+        pretty_assertions::assert_eq!(stmt.stmt(), "");
+    }
+
+    #[tokio::test]
+    async fn main() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        query(
+            "
+        CREATE TABLE Category (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            note TEXT
+        );
+
+        CREATE TABLE Todo (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            done BOOLEAN NOT NULL,
+            description TEXT,
+            fk_category_def INTEGER REFERENCES Category(id)
+        );
+
+        INSERT INTO Category (title, note) VALUES ('work', NULL), ('home', 'chores');
+
+        INSERT INTO Todo (title, done, description, fk_category_def) VALUES
+            ('first_todo', true, NULL, 1),
+            ('second_todo', false, NULL, 2),
+            ('third_todo', true, NULL, NULL);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let output = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: TodoHandler,
+                wheres: Any {
+                    link: TODO_CATEGORY,
+                    filters: vec![title_eq("work")],
+                },
+                links: (),
+                cursor_order_by: todo_members::title,
+                pagination: Pagination::Cursor {
+                    cursor: None,
+                    limit: 10,
+                },
+                computed: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            output,
+            ManyOutput {
+                items: vec![LinkedOutput {
+                    id: 1,
+                    attributes: Todo {
+                        title: "first_todo".to_string(),
+                        done: true,
+                        description: None,
+                    },
+                    links: (),
+                    computed: (),
+                }],
+                next_item: None,
+                prev_item: None,
+                total: None,
+            }
+        );
+
+        let output = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: TodoHandler,
+                wheres: PossibleImplMany(All {
+                    link: TODO_CATEGORY,
+                    filters: vec![title_eq("home")],
+                }),
+                links: (),
+                cursor_order_by: todo_members::title,
+                pagination: Pagination::Cursor {
+                    cursor: None,
+                    limit: 10,
+                },
+                computed: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            output
+                .items
+                .into_iter()
+                .map(|e| e.attributes.title)
+                .collect::<Vec<_>>(),
+            vec!["second_todo".to_string(), "third_todo".to_string()]
+        );
+
+        // a NULL note is neither equal nor different to 'chores', so the
+        // todo of 'work' doesn't pass
+        let output = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: TodoHandler,
+                wheres: PossibleImplMany(All {
+                    link: TODO_CATEGORY,
+                    filters: vec![ColumnEqual {
+                        col: ScopedColumn {
+                            table: ("Category",),
+                            col: ("note",),
+                        },
+                        eq: "chores".to_string(),
+                    }],
+                }),
+                links: (),
+                cursor_order_by: todo_members::title,
+                pagination: Pagination::Cursor {
+                    cursor: None,
+                    limit: 10,
+                },
+                computed: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            output
+                .items
+                .into_iter()
+                .map(|e| e.attributes.title)
+                .collect::<Vec<_>>(),
+            vec!["second_todo".to_string(), "third_todo".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn many_to_many() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        query(
+            "
+        CREATE TABLE Tag (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL
+        );
+
+        CREATE TABLE Todo (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            done BOOLEAN NOT NULL,
+            description TEXT
+        );
+
+        CREATE TABLE ct_todotag_def (
+            todo_id INTEGER NOT NULL REFERENCES Todo(id),
+            tag_id INTEGER NOT NULL REFERENCES Tag(id),
+            PRIMARY KEY (todo_id, tag_id)
+        );
+
+        INSERT INTO Tag (title) VALUES ('urgent'), ('home');
+
+        INSERT INTO Todo (title, done, description) VALUES
+            ('first_todo', true, NULL),
+            ('second_todo', false, NULL),
+            ('third_todo', true, NULL);
+
+        INSERT INTO ct_todotag_def (todo_id, tag_id) VALUES (1, 1), (1, 2), (2, 2);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let tag_title_eq = |title: &str| ColumnEqual {
+            col: ScopedColumn {
+                table: ("Tag",),
+                col: ("title",),
+            },
+            eq: title.to_string(),
+        };
+
+        let titles = |output: ManyOutput<LinkedOutput<i64, Todo, (), ()>, _>| {
+            output
+                .items
+                .into_iter()
+                .map(|e| e.attributes.title)
+                .collect::<Vec<_>>()
+        };
+
+        let output = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: TodoHandler,
+                wheres: Exists { link: TODO_TAGS },
+                links: (),
+                cursor_order_by: todo_members::title,
+                pagination: Pagination::Cursor {
+                    cursor: None,
+                    limit: 10,
+                },
+                computed: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            titles(output),
+            vec!["first_todo".to_string(), "second_todo".to_string()]
+        );
+
+        let output = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: TodoHandler,
+                wheres: Any {
+                    link: TODO_TAGS,
+                    filters: vec![tag_title_eq("urgent")],
+                },
+                links: (),
+                cursor_order_by: todo_members::title,
+                pagination: Pagination::Cursor {
+                    cursor: None,
+                    limit: 10,
+                },
+                computed: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(titles(output), vec!["first_todo".to_string()]);
+
+        // the todo with no tag passes as well
+        let output = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: TodoHandler,
+                wheres: PossibleImplMany(All {
+                    link: TODO_TAGS,
+                    filters: vec![tag_title_eq("home")],
+                }),
+                links: (),
+                cursor_order_by: todo_members::title,
+                pagination: Pagination::Cursor {
+                    cursor: None,
+                    limit: 10,
+                },
+                computed: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            titles(output),
+            vec!["second_todo".to_string(), "third_todo".to_string()]
+        );
+    }
+}
@@ -0,0 +1,498 @@
+use sqlx::{ColumnIndex, Decode, Encode, Row, Type};
+
+use crate::{
+    database_extention::DatabaseExt,
+    from_row::{
+        FromRowAlias, FromRowData, FromRowError, RowPostAliased, RowPreAliased, RowTwoAliased,
+    },
+    operations::{
+        cursor::CursorOrder,
+        link_filter::{LinkFilter, subquery_from},
+        operations_expressions_crossover::ExpressionsForOperation,
+    },
+    sqlx_query_builder::{
+        Expression, ManyExpressions, OpExpression, SanitizeManyTupleSpec, StatementBuilder,
+    },
+    tuple_trait::Tuple,
+};
+
+/// order by a column of a linked record, meant for links that resolve to
+/// at most one record like `OptionalToMany`.
+///
+/// records with no linked record sort as `NULL`, before every other record,
+/// so the value is `None` for them
+#[derive(Clone)]
+pub struct LinkColumn<Link, Col> {
+    pub link: Link,
+    pub col: Col,
+}
+
+/// order by the number of linked records
+#[derive(Clone)]
+pub struct LinkCount<Link> {
+    pub link: Link,
+}
+
+/// `(SELECT <select> FROM <from> <joins> WHERE <correlate>)`
+#[derive(Clone)]
+pub struct LinkSubquery<Link, Select> {
+    pub link: Link,
+    pub select: Select,
+}
+
+#[derive(Clone)]
+pub struct CountAll;
+
+#[derive(Clone)]
+pub struct AliasedExpression<E, A> {
+    pub expression: E,
+    pub alias: A,
+}
+
+impl<Link, Select> OpExpression for LinkSubquery<Link, Select> {}
+
+impl<'q, S, Link, Select> Expression<'q, S> for LinkSubquery<Link, Select>
+where
+    S: DatabaseExt,
+    Link: LinkFilter + 'q,
+    Link::From: Expression<'q, S>,
+    Link::Joins: ManyExpressions<'q, S>,
+    Link::Correlate: Expression<'q, S>,
+    Select: Expression<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("(SELECT ");
+        self.select.expression(ctx);
+        subquery_from(&self.link, ctx);
+        ctx.syntax(")");
+    }
+}
+
+impl OpExpression for CountAll {}
+
+impl<'q, S> Expression<'q, S> for CountAll
+where
+    S: DatabaseExt,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("COUNT(*)");
+    }
+}
+
+impl<E, A> OpExpression for AliasedExpression<E, A> {}
+
+impl<'q, S, E, A> Expression<'q, S> for AliasedExpression<E, A>
+where
+    S: DatabaseExt,
+    E: Expression<'q, S> + 'q,
+    A: 'q + for<'s> Tuple<SanitizeManyTupleSpec<'s, 'q, S>>,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        self.expression.expression(ctx);
+        ctx.syntax(" AS ");
+        ctx.sanitize_many(self.alias);
+    }
+}
+
+impl<Link, Col> ExpressionsForOperation for LinkColumn<Link, Col>
+where
+    Link: Clone,
+    Col: ExpressionsForOperation,
+{
+    type Identifier = Col::Identifier;
+    fn identifier(&self) -> Self::Identifier {
+        self.col.identifier()
+    }
+
+    type Scoped = LinkSubquery<Link, Col::Scoped>;
+    fn scoped(&self) -> Self::Scoped {
+        LinkSubquery {
+            link: self.link.clone(),
+            select: self.col.scoped(),
+        }
+    }
+
+    type ScopedAliased = AliasedExpression<Self::Scoped, (&'static str, Col::Identifier)>;
+    fn scoped_aliased(&self, alias: &'static str) -> Self::ScopedAliased {
+        AliasedExpression {
+            expression: self.scoped(),
+            alias: (alias, self.col.identifier()),
+        }
+    }
+
+    type NumScopedAliased = AliasedExpression<Self::Scoped, (&'static str, usize, Col::Identifier)>;
+    fn num_scoped_aliased(&self, num: usize, alias: &'static str) -> Self::NumScopedAliased {
+        AliasedExpression {
+            expression: self.scoped(),
+            alias: (alias, num, self.col.identifier()),
+        }
+    }
+}
+
+impl<Link> ExpressionsForOperation for LinkCount<Link>
+where
+    Link: Clone,
+{
+    type Identifier = &'static str;
+    fn identifier(&self) -> Self::Identifier {
+        "link_count"
+    }
+
+    type Scoped = LinkSubquery<Link, CountAll>;
+    fn scoped(&self) -> Self::Scoped {
+        LinkSubquery {
+            link: self.link.clone(),
+            select: CountAll,
+        }
+    }
+
+    type ScopedAliased = AliasedExpression<Self::Scoped, (&'static str, &'static str)>;
+    fn scoped_aliased(&self, alias: &'static str) -> Self::ScopedAliased {
+        AliasedExpression {
+            expression: self.scoped(),
+            alias: (alias, "link_count"),
+        }
+    }
+
+    type NumScopedAliased = AliasedExpression<Self::Scoped, (&'static str, usize, &'static str)>;
+    fn num_scoped_aliased(&self, num: usize, alias: &'static str) -> Self::NumScopedAliased {
+        AliasedExpression {
+            expression: self.scoped(),
+            alias: (alias, num, "link_count"),
+        }
+    }
+}
+
+impl<'q, S, Link, Col> CursorOrder<'q, S> for LinkColumn<Link, Col>
+where
+    S: DatabaseExt,
+    Link: LinkFilter + Clone + 'q,
+    Link::From: Expression<'q, S>,
+    Link::Joins: ManyExpressions<'q, S>,
+    Link::Correlate: Expression<'q, S>,
+    Col: CursorOrder<'q, S> + ExpressionsForOperation<Scoped: Expression<'q, S> + 'q>,
+{
+    fn keys_len(&self) -> usize {
+        1
+    }
+    fn descending(&self, _: usize) -> bool {
+        false
+    }
+    fn nullable(&self, _: usize) -> bool {
+        true
+    }
+    fn key(&self, _: usize, ctx: &mut StatementBuilder<'q, S>) {
+        Expression::expression(self.scoped(), ctx)
+    }
+    fn value(&self, value: &Self::RData, _: usize, ctx: &mut StatementBuilder<'q, S>) {
+        match value {
+            Some(value) => self.col.value(value, 0, ctx),
+            None => ctx.syntax("NULL"),
+        }
+    }
+}
+
+impl<'q, S, Link> CursorOrder<'q, S> for LinkCount<Link>
+where
+    S: DatabaseExt,
+    Link: LinkFilter + Clone + 'q,
+    Link::From: Expression<'q, S>,
+    Link::Joins: ManyExpressions<'q, S>,
+    Link::Correlate: Expression<'q, S>,
+    i64: Encode<'q, S> + Type<S>,
+{
+    fn keys_len(&self) -> usize {
+        1
+    }
+    fn descending(&self, _: usize) -> bool {
+        false
+    }
+    fn nullable(&self, _: usize) -> bool {
+        false
+    }
+    fn key(&self, _: usize, ctx: &mut StatementBuilder<'q, S>) {
+        Expression::expression(self.scoped(), ctx)
+    }
+    fn value(&self, value: &Self::RData, _: usize, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.bind(*value)
+    }
+}
+
+impl<Link, Col> FromRowData for LinkColumn<Link, Col>
+where
+    Col: FromRowData,
+{
+    type RData = Option<Col::RData>;
+}
+
+/// decoded by the identifier of `col`, as `scoped_aliased` names it
+impl<'r, R, Link, Col> FromRowAlias<'r, R> for LinkColumn<Link, Col>
+where
+    R: Row + 'r,
+    Col: FromRowData + ExpressionsForOperation<Identifier: AsRef<str>>,
+    Col::RData: Type<R::Database> + Decode<'r, R::Database>,
+    for<'q> &'q str: ColumnIndex<R>,
+{
+    fn no_alias(&self, row: &'r R) -> Result<Self::RData, FromRowError> {
+        Ok(row.try_get(self.col.identifier().as_ref())?)
+    }
+    fn pre_alias(&self, row: RowPreAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+        Ok(row.try_get(self.col.identifier().as_ref())?)
+    }
+    fn post_alias(&self, row: RowPostAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+        Ok(row.try_get(self.col.identifier().as_ref())?)
+    }
+    fn two_alias(&self, row: RowTwoAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+        Ok(row.try_get(self.col.identifier().as_ref())?)
+    }
+}
+
+impl<Link> FromRowData for LinkCount<Link> {
+    type RData = i64;
+}
+
+impl<'r, R, Link> FromRowAlias<'r, R> for LinkCount<Link>
+where
+    R: Row + 'r,
+    i64: Type<R::Database> + Decode<'r, R::Database>,
+    for<'q> &'q str: ColumnIndex<R>,
+{
+    fn no_alias(&self, row: &'r R) -> Result<Self::RData, FromRowError> {
+        Ok(row.try_get("link_count")?)
+    }
+    fn pre_alias(&self, row: RowPreAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+        Ok(row.try_get("link_count")?)
+    }
+    fn post_alias(&self, row: RowPostAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+        Ok(row.try_get("link_count")?)
+    }
+    fn two_alias(&self, row: RowTwoAliased<'r, R>) -> Result<Self::RData, FromRowError> {
+        Ok(row.try_get("link_count")?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sqlx::{Sqlite, query};
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        links::{
+            DefaultRelationKey, relation_optional_to_many::OptionalToMany,
+            relation_optional_to_many_inverse::OptionalToManyInverse,
+        },
+        operations::{
+            LinkedOutput, Operation,
+            cursor::Cursor,
+            fetch_many::{FetchMany, ManyOutput, Pagination},
+            link_order_by::{LinkColumn, LinkCount},
+            operations_expressions_crossover::ExpressionsForOperation,
+        },
+        sqlx_query_builder::StatementBuilder,
+        test_module::{Category, CategoryHandler, TodoHandler, category_members},
+    };
+
+    const CATEGORY_OF_TODO: OptionalToMany<DefaultRelationKey, TodoHandler, CategoryHandler> =
+        OptionalToMany {
+            fk_unique_id: DefaultRelationKey,
+            from: TodoHandler,
+            to: CategoryHandler,
+        };
+
+    const TODOS_OF_CATEGORY: OptionalToManyInverse<
+        DefaultRelationKey,
+        CategoryHandler,
+        TodoHandler,
+    > = OptionalToManyInverse {
+        fk_unique_id: DefaultRelationKey,
+        from: CategoryHandler,
+        to: TodoHandler,
+    };
+
+    #[test]
+    fn statements() {
+        let link_column = LinkColumn {
+            link: CATEGORY_OF_TODO,
+            col: category_members::title,
+        };
+
+        let stmt = StatementBuilder::<Sqlite>::new(link_column.scoped_aliased("o"));
+        pretty_assertions::assert_eq!(
+            stmt.stmt().replace("\"", "'"),
+            r#"(SELECT 'Category'.'title' FROM 'Category' WHERE 'Category'.'id' = 'Todo'.'fk_category_def') AS 'otitle'"#
+        );
+
+        let stmt = StatementBuilder::<Sqlite>::new(
+            LinkCount {
+                link: TODOS_OF_CATEGORY,
+            }
+            .scoped(),
+        );
+        pretty_assertions::assert_eq!(
+            stmt.stmt().replace("\"", "'"),
+            r#"(SELECT COUNT(*) FROM 'Todo' WHERE 'Todo'.'fk_category_def' = 'Category'.'id')"#
+        );
+    }
+
+    async fn setup() -> sqlx::SqliteConnection {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        query(
+            "
+        CREATE TABLE Category (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL
+        );
+
+        CREATE TABLE Todo (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            done BOOLEAN NOT NULL,
+            description TEXT,
+            fk_category_def INTEGER REFERENCES Category(id)
+        );
+
+        INSERT INTO Category (title) VALUES ('work'), ('home'), ('empty');
+
+        INSERT INTO Todo (title, done, description, fk_category_def) VALUES
+            ('first_todo', true, NULL, 1),
+            ('second_todo', false, NULL, 2),
+            ('third_todo', true, NULL, 1),
+            ('fourth_todo', true, NULL, 2),
+            ('fifth_todo', false, NULL, 2),
+            ('sixth_todo', false, NULL, NULL);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        conn
+    }
+
+    #[tokio::test]
+    async fn order_by_link_column() {
+        let mut conn = setup().await;
+
+        let category_title = LinkColumn {
+            link: CATEGORY_OF_TODO,
+            col: category_members::title,
+        };
+        let page = |cursor| FetchMany {
+            base: TodoHandler,
+            wheres: (),
+            links: (),
+            cursor_order_by: category_title.clone(),
+            pagination: Pagination::Cursor { cursor, limit: 2 },
+            computed: (),
+        };
+
+        // the todo with no category sorts first
+        let output = Operation::<Sqlite>::exec_operation(page(None), &mut conn).await;
+
+        pretty_assertions::assert_eq!(
+            output.items.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![6, 2]
+        );
+        pretty_assertions::assert_eq!(output.next_item, Some((4, Some("home".to_string()))));
+
+        let output = Operation::<Sqlite>::exec_operation(
+            page(output.next_item.map(Cursor::After)),
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            output.items.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![4, 5]
+        );
+        pretty_assertions::assert_eq!(output.next_item, Some((1, Some("work".to_string()))));
+        pretty_assertions::assert_eq!(output.prev_item, Some((4, Some("home".to_string()))));
+
+        let output = Operation::<Sqlite>::exec_operation(
+            page(output.prev_item.map(Cursor::Before)),
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            output.items.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![6, 2]
+        );
+        pretty_assertions::assert_eq!(output.next_item, Some((4, Some("home".to_string()))));
+        pretty_assertions::assert_eq!(output.prev_item, None);
+
+        // a cursor can point to a record with no category
+        let output =
+            Operation::<Sqlite>::exec_operation(page(Some(Cursor::After((6, None)))), &mut conn)
+                .await;
+
+        pretty_assertions::assert_eq!(
+            output.items.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![6, 2]
+        );
+
+        let output = Operation::<Sqlite>::exec_operation(
+            page(Some(Cursor::Before((2, Some("home".to_string()))))),
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            output.items.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![6]
+        );
+    }
+
+    #[tokio::test]
+    async fn order_by_link_count() {
+        let mut conn = setup().await;
+
+        let todo_count = LinkCount {
+            link: TODOS_OF_CATEGORY,
+        };
+
+        let output = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: CategoryHandler,
+                wheres: (),
+                links: (),
+                cursor_order_by: todo_count.clone(),
+                pagination: Pagination::Cursor {
+                    cursor: Some(Cursor::After((0, 1))),
+                    limit: 10,
+                },
+                computed: (),
+            },
+            &mut conn,
+        )
+        .await;
+
+        pretty_assertions::assert_eq!(
+            output,
+            ManyOutput {
+                items: vec![
+                    LinkedOutput {
+                        id: 1,
+                        attributes: Category {
+                            title: "work".to_string(),
+                        },
+                        links: (),
+                        computed: (),
+                    },
+                    LinkedOutput {
+                        id: 2,
+                        attributes: Category {
+                            title: "home".to_string(),
+                        },
+                        links: (),
+                        computed: (),
+                    },
+                ],
+                next_item: None,
+                prev_item: Some((1, 2)),
+                total: None,
+            }
+        );
+    }
+}
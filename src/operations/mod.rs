@@ -2,11 +2,18 @@ use sqlx::Database;
 
 // pub mod delete_by_id;
 // pub mod delete_one;
+pub mod computed;
+pub mod cursor;
+pub mod cursor_token;
 pub mod delete;
 // pub mod fetch_linked_records;
 pub mod fetch_many;
 pub mod fetch_one;
+pub mod fetch_stream;
+pub mod hooks;
 pub mod insert;
+pub mod link_filter;
+pub mod link_order_by;
 // pub mod insert_one_links;
 // pub mod junction;
 // pub mod v1_insert_one;
@@ -100,7 +107,6 @@ pub mod operations_expressions_crossover {
         }
     }
 
-    #[claw_ql_macros::skip]
     mod std_impls {
         use crate::operations::operations_expressions_crossover::ExpressionsForOperation;
 
@@ -210,10 +216,13 @@ pub mod operations_expressions_crossover {
 
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct LinkedOutput<Id, C, L> {
+pub struct LinkedOutput<Id, C, L, Computed = ()> {
     pub id: Id,
     pub attributes: C,
     pub links: L,
+    /// select items computed alongside the record, see `FetchMany::computed`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub computed: Computed,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -241,8 +250,8 @@ pub struct IdOutput<Id> {
     pub id: Id,
 }
 
-impl<I, C, L> From<LinkedOutput<I, C, L>> for CollectionOutput<I, C> {
-    fn from(value: LinkedOutput<I, C, L>) -> Self {
+impl<I, C, L, Cm> From<LinkedOutput<I, C, L, Cm>> for CollectionOutput<I, C> {
+    fn from(value: LinkedOutput<I, C, L, Cm>) -> Self {
         CollectionOutput {
             id: value.id,
             attributes: value.attributes,
@@ -250,8 +259,8 @@ impl<I, C, L> From<LinkedOutput<I, C, L>> for CollectionOutput<I, C> {
     }
 }
 
-impl<I, C, L> From<LinkedOutput<I, C, L>> for IdOutput<I> {
-    fn from(value: LinkedOutput<I, C, L>) -> Self {
+impl<I, C, L, Cm> From<LinkedOutput<I, C, L, Cm>> for IdOutput<I> {
+    fn from(value: LinkedOutput<I, C, L, Cm>) -> Self {
         IdOutput { id: value.id }
     }
 }
@@ -665,11 +674,14 @@ mod gen_serde_impls {
         str: Serialize<F>,
         Vec<T>: Serialize<F>,
         Option<Next>: Serialize<F>,
+        Option<i64>: Serialize<F>,
     {
         fn serialize(&self, ctx: &mut F) {
             let mut object = ctx.serialize_start();
             ctx.serialize_pair(&mut object, "items", &self.items);
             ctx.serialize_pair(&mut object, "next_item", &self.next_item);
+            ctx.serialize_pair(&mut object, "prev_item", &self.prev_item);
+            ctx.serialize_pair(&mut object, "total", &self.total);
             ctx.serialize_end(object);
         }
     }
@@ -900,6 +912,7 @@ pub mod execute_expression {
                 Executable {
                     string: stmt.as_str(),
                     arguments: arg,
+                    persistent: false,
                 },
             )
             .await
@@ -10,6 +10,7 @@ use crate::{
     sqlx_query_builder::{
         Expression, IsOpExpression, ManyExpressions, StatementBuilder, basic_expressions::ManyFlat, statements::update_statement::UpdateStatement,
     },
+    statement_cache::UPDATE,
 };
 
 pub struct Update<Base, Partial, Wheres, Links> {
@@ -272,17 +273,12 @@ where
 
             let res = S::fetch_all(
                 &mut *pool,
-                Executable {
-                    string: &stmt,
-                    arguments: args,
-                },
+                Executable::cached(&UPDATE, &stmt, args),
             )
             .await
             .map_err(|e| {
-                if let Some(db) = e.as_database_error() {
-                    if db.is_check_violation() || db.is_unique_violation() || db.is_foreign_key_violation() {
-                        return ConstraintViolation(db.constraint().map(|c| c.to_string()));
-                    }
+                if let Some(violation) = e.as_database_error().and_then(ConstraintViolation::from_database_error) {
+                    return violation;
                 }
             
                 tracing::error!(sqlx_error = ?e, "bug: must clear all sqlx errors, hard to know where this error was originated!");
@@ -299,6 +295,7 @@ where
                     id,
                     attributes,
                     links: (),
+                    computed: (),
                 }
             })
             .collect::<Vec<_>>();
@@ -317,6 +314,7 @@ where
                     id: e.id,
                     attributes: e.attributes,
                     links: self_link.take(f, &mut poo, &mut pre_op_split_take),
+                    computed: (),
                 })
                 .collect())
         }
@@ -379,7 +377,8 @@ mod test {
                     done: true,
                     description: Some("description_2".to_string()),
                 },
-                links: ()
+                links: (),
+                computed: (),
             }]
         );
 
@@ -114,6 +114,7 @@ where
             group_by: (),
             order: (),
             limit: (),
+            offset: (),
         });
 
         let stmt = query_builder.stmt().to_string();
@@ -145,6 +146,7 @@ stmt: {stmt}",
                 .pre_alias(pre_alias(&s, "b"))
                 .expect("bug: sqlx errors should ruled out by claw_ql"),
             links: link_spec.take(sub_op, inner),
+            computed: (),
         })
     }
 }
@@ -318,6 +320,7 @@ mod test {
                         (2, 'second_todo', false, NULL, 1);
                         ",
                 arguments: Default::default(),
+                persistent: false,
             },
         )
         .await
@@ -354,6 +357,7 @@ mod test {
                         title: "category_1".to_string()
                     }
                 }),
+                computed: (),
             })
         );
     }
@@ -176,6 +176,7 @@ where
             id,
             attributes: (),
             links: self.links.output(s),
+            computed: (),
         }
     }
 }
@@ -200,6 +200,7 @@ where
             id,
             attributes: self.0.handler.no_alias(&first_row).unwrap(),
             links: self.0.links.output(post_op),
+            computed: (),
         })
     }
 }
@@ -142,6 +142,7 @@ where
                 Executable {
                     string: &stmt,
                     arguments: arg,
+                    persistent: false,
                 },
             )
             .await
@@ -165,6 +166,7 @@ where
                 id,
                 attributes,
                 links,
+                computed: (),
             }
         }
     }
@@ -222,7 +224,8 @@ mod test {
                     done: false,
                     description: None,
                 },
-                links: ()
+                links: (),
+                computed: (),
             }
         );
 
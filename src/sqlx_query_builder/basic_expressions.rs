@@ -4,6 +4,7 @@ use sqlx::Database;
 
 use crate::{
     database_extention::DatabaseExt,
+    is_null::IsNull,
     sqlx_query_builder::{
         Expression, IsOpExpression, ManyExpressions, OpExpression, PossibleExpression,
         SanitizeManyTupleSpec, StatementBuilder,
@@ -333,7 +334,9 @@ column_compare!(ColumnGreaterThan, gt, " > ");
 column_compare!(ColumnGreaterThanOrEqual, ge, " >= ");
 column_compare!(ColumnLessThan, lt, " < ");
 column_compare!(ColumnLessThanOrEqual, le, " <= ");
-column_compare!(ColumnContains, like, " LIKE ");
+// `like` is a pattern, use `ColumnContainsText` and co to match text as is
+column_compare!(ColumnLike, like, " LIKE ");
+
 
 column_is!(ColumnIsNotNull, " IS NOT NULL");
 column_is!(ColumnIsNull, " IS NULL");
@@ -347,12 +350,10 @@ pub struct ColumnIn<Col, V> {
     pub values: V,
 }
 
-impl<Col, V> IsOpExpression for ColumnIn<Col, V>
-where
-    V: IsOpExpression,
-{
+// an empty list matches nothing rather than dropping the filter
+impl<Col, V> IsOpExpression for ColumnIn<Col, V> {
     fn is_op(&self) -> bool {
-        self.values.is_op()
+        true
     }
 }
 
@@ -363,11 +364,12 @@ where
     V: ManyExpressions<'q, S> + 'q,
 {
     fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
-        if self.is_op() {
-            ctx.syntax("(");
+        if self.values.is_op() {
             self.col.expression(ctx);
-            self.values.expression("IN (", ", ", ctx);
+            self.values.expression(" IN (", ", ", ctx);
             ctx.syntax(")");
+        } else {
+            ctx.syntax("1 = 0");
         }
     }
 
@@ -375,12 +377,153 @@ where
     where
         S: DatabaseExt,
     {
-        if self.is_op() {
-            ctx.syntax(start);
-            self.col.expression(ctx);
-            self.values.expression("IN (", ", ", ctx);
-            ctx.syntax(")");
+        ctx.syntax(start);
+        self.expression(ctx);
+    }
+}
+
+/// `<col> BETWEEN <low> AND <high>`, both ends included
+#[derive(Clone)]
+pub struct ColumnBetween<Col, Val> {
+    pub col: Col,
+    pub low: Val,
+    pub high: Val,
+}
+
+impl<Col, Val> OpExpression for ColumnBetween<Col, Val> {}
+
+impl<'q, S, Col, Val> Expression<'q, S> for ColumnBetween<Col, Val>
+where
+    S: DatabaseExt,
+    Col: Expression<'q, S> + 'q,
+    Val: 'q + Encode<'q, S> + Type<S>,
+{
+    fn expression(self, arg: &mut StatementBuilder<'q, S>) {
+        self.col.expression(arg);
+        arg.syntax(" BETWEEN ");
+        arg.bind(self.low);
+        arg.syntax(" AND ");
+        arg.bind(self.high);
+    }
+}
+
+/// `LOWER(<col>) = LOWER(<eq>)`
+#[derive(Clone)]
+pub struct ColumnEqualIgnoreCase<Col, Val> {
+    pub col: Col,
+    pub eq: Val,
+}
+
+impl<Col, Val> OpExpression for ColumnEqualIgnoreCase<Col, Val> {}
+
+impl<'q, S, Col, Val> Expression<'q, S> for ColumnEqualIgnoreCase<Col, Val>
+where
+    S: DatabaseExt,
+    Col: Expression<'q, S> + 'q,
+    Val: 'q + Encode<'q, S> + Type<S>,
+{
+    fn expression(self, arg: &mut StatementBuilder<'q, S>) {
+        arg.syntax("LOWER(");
+        self.col.expression(arg);
+        arg.syntax(") = LOWER(");
+        arg.bind(self.eq);
+        arg.syntax(")");
+    }
+}
+
+/// `NOT (<expression>)`
+#[derive(Clone)]
+pub struct Not<E>(pub E);
+
+impl<E> OpExpression for Not<E> {}
+
+impl<'q, S, E> Expression<'q, S> for Not<E>
+where
+    S: DatabaseExt,
+    E: Expression<'q, S> + 'q,
+{
+    fn expression(self, arg: &mut StatementBuilder<'q, S>) {
+        arg.syntax("NOT (");
+        self.0.expression(arg);
+        arg.syntax(")");
+    }
+}
+
+/// escapes `%`, `_` and `\` so `text` is matched as is by a `LIKE` with
+/// `ESCAPE '\'`
+pub fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
         }
+        escaped.push(c);
+    }
+    escaped
+}
+
+macro_rules! column_like_escaped {
+    ($name:ident, $before:literal, $after:literal) => {
+        #[derive(Clone)]
+        pub struct $name<Col, Val> {
+            pub col: Col,
+            pub val: Val,
+        }
+
+        impl<Col, Val> OpExpression for $name<Col, Val> {}
+
+        impl<'q, S, Col, Val> Expression<'q, S> for $name<Col, Val>
+        where
+            S: DatabaseExt,
+            Col: Expression<'q, S> + 'q,
+            Val: 'q + AsRef<str>,
+            String: 'q + Encode<'q, S> + Type<S>,
+        {
+            fn expression(self, arg: &mut StatementBuilder<'q, S>) {
+                self.col.expression(arg);
+                arg.syntax(" LIKE ");
+                arg.bind(format!(
+                    concat!($before, "{}", $after),
+                    escape_like(self.val.as_ref())
+                ));
+                arg.syntax(" ESCAPE '\\'");
+            }
+        }
+    };
+}
+
+column_like_escaped!(ColumnContainsText, "%", "%");
+column_like_escaped!(ColumnStartsWith, "", "%");
+column_like_escaped!(ColumnEndsWith, "%", "");
+
+/// `like` used to be a raw pattern, it is now matched as is like
+/// `ColumnContainsText` does
+#[deprecated(note = "use `ColumnContainsText`, or `ColumnLike` for patterns")]
+#[derive(Clone)]
+pub struct ColumnContains<Col, Val> {
+    pub col: Col,
+    pub like: Val,
+}
+
+#[allow(deprecated)]
+impl<Col, Val> OpExpression for ColumnContains<Col, Val> {}
+
+#[allow(deprecated)]
+impl<'q, S, Col, Val> Expression<'q, S> for ColumnContains<Col, Val>
+where
+    S: DatabaseExt,
+    Col: Expression<'q, S> + 'q,
+    Val: 'q + AsRef<str>,
+    String: 'q + Encode<'q, S> + Type<S>,
+{
+    fn expression(self, arg: &mut StatementBuilder<'q, S>) {
+        Expression::expression(
+            ColumnContainsText {
+                col: self.col,
+                val: self.like,
+            },
+            arg,
+        )
     }
 }
 
@@ -409,6 +552,57 @@ where
     }
 }
 
+/// compare two columns without binding, used to correlate a subquery
+/// with the outer statement
+#[derive(Clone)]
+pub struct ColumnEqualColumn<Col, Other> {
+    pub col: Col,
+    pub other: Other,
+}
+
+impl<Col, Other> OpExpression for ColumnEqualColumn<Col, Other> {}
+
+impl<'q, S, Col, Other> Expression<'q, S> for ColumnEqualColumn<Col, Other>
+where
+    S: DatabaseExt,
+    Col: Expression<'q, S> + 'q,
+    Other: Expression<'q, S> + 'q,
+{
+    fn expression(self, arg: &mut StatementBuilder<'q, S>) {
+        self.col.expression(arg);
+        arg.syntax(" = ");
+        self.other.expression(arg);
+    }
+}
+
+/// `<name> <type> [NOT NULL] <constraints>`, `NOT NULL` unless `T` is
+/// an `Option`
+pub struct ColumnDefinition<Name, T, Constraints> {
+    pub name: Name,
+    pub ty: PhantomData<T>,
+    pub constraints: Constraints,
+}
+
+impl<Name, T, Constraints> OpExpression for ColumnDefinition<Name, T, Constraints> {}
+
+impl<'q, S, Name, T, Constraints> Expression<'q, S> for ColumnDefinition<Name, T, Constraints>
+where
+    S: DatabaseExt,
+    Name: Expression<'q, S> + 'q,
+    T: IsNull + Type<S> + 'q,
+    Constraints: ManyExpressions<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        self.name.expression(ctx);
+        ctx.syntax(" ");
+        ctx.type_as_syntax::<T>();
+        if !T::is_null() {
+            ctx.syntax(" NOT NULL");
+        }
+        self.constraints.expression(" ", " ", ctx);
+    }
+}
+
 // pub mod tuple_many_expressions {
 //     use crate::{
 //         database_extention::DatabaseExt,
@@ -605,9 +799,49 @@ where
     }
 }
 
+impl<T0, T1, T2, T3> IsOpExpression for ManyFlat<(T0, T1, T2, T3)>
+where
+    T0: IsOpExpression,
+    T1: IsOpExpression,
+    T2: IsOpExpression,
+    T3: IsOpExpression,
+{
+    fn is_op(&self) -> bool {
+        self.0.0.is_op() || self.0.1.is_op() || self.0.2.is_op() || self.0.3.is_op()
+    }
+}
+
+impl<'s, T0, T1, T2, T3, S> ManyExpressions<'s, S> for ManyFlat<(T0, T1, T2, T3)>
+where
+    T0: ManyExpressions<'s, S>,
+    T1: ManyExpressions<'s, S>,
+    T2: ManyExpressions<'s, S>,
+    T3: ManyExpressions<'s, S>,
+{
+    fn expression(self, start: &'static str, join: &'static str, ctx: &mut StatementBuilder<'s, S>)
+    where
+        S: DatabaseExt,
+    {
+        let (t0, t1, t2, t3) = self.0;
+        let first_three = ManyFlat((t0, t1, t2));
+        let need_start = !first_three.is_op();
+        first_three.expression(start, join, ctx);
+
+        match (t3.is_op(), need_start) {
+            (false, _) => {}
+            (true, true) => t3.expression(start, join, ctx),
+            (true, false) => t3.expression(join, join, ctx),
+        };
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::sqlx_query_builder::basic_expressions::ManyFlat;
+    use crate::connect_in_memory::ConnectInMemory;
+    use crate::sqlx_query_builder::basic_expressions::{
+        Bind, ColumnBetween, ColumnContainsText, ColumnEndsWith, ColumnEqualIgnoreCase, ColumnIn,
+        ColumnStartsWith, ManyFlat, Not, PossibleImplExpression,
+    };
     use crate::sqlx_query_builder::{Expression, ManyExpressions, OpExpression, StatementBuilder};
     use sqlx::Sqlite;
 
@@ -675,6 +909,120 @@ mod tests {
             "START 'id', 'email', 'name', 'age', 'job', 'job_description'"
         );
     }
+
+    #[test]
+    fn filters() {
+        let stmt = StatementBuilder::<Sqlite>::new(Not(ColumnBetween {
+            col: "done",
+            low: 1,
+            high: 3,
+        }));
+        pretty_assertions::assert_eq!(
+            stmt.stmt().replace("\"", "'"),
+            "NOT ('done' BETWEEN $1 AND $2)"
+        );
+
+        let stmt = StatementBuilder::<Sqlite>::new(
+            PossibleImplExpression::new(ColumnIn {
+                col: "id",
+                values: ManyFlat(vec![Bind(1), Bind(2)]),
+            })
+            .unwrap(),
+        );
+        pretty_assertions::assert_eq!(stmt.stmt().replace("\"", "'"), "'id' IN ($1, $2)");
+
+        let stmt = StatementBuilder::<Sqlite>::new(
+            PossibleImplExpression::new(ColumnIn {
+                col: "id",
+                values: ManyFlat(Vec::<Bind<i64>>::new()),
+            })
+            .unwrap(),
+        );
+        pretty_assertions::assert_eq!(stmt.stmt(), "1 = 0");
+
+        let stmt = StatementBuilder::<Sqlite>::new(ColumnEqualIgnoreCase {
+            col: "title",
+            eq: "Milk",
+        });
+        pretty_assertions::assert_eq!(stmt.stmt().replace("\"", "'"), "LOWER('title') = LOWER($1)");
+    }
+
+    #[tokio::test]
+    async fn like_matches_text_as_is() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        sqlx::query(
+            "
+        CREATE TABLE Todo (title TEXT NOT NULL);
+        INSERT INTO Todo (title) VALUES ('100% done'), ('1000 done'), ('a_b'), ('axb');
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        async fn titles<'q>(
+            wheres: impl Expression<'q, Sqlite>,
+            conn: &mut sqlx::SqliteConnection,
+        ) -> Vec<String> {
+            let (stmt, args) = StatementBuilder::<Sqlite>::new(wheres).unwrap();
+            sqlx::query_scalar_with(&format!("SELECT title FROM Todo WHERE {stmt}"), args)
+                .fetch_all(conn)
+                .await
+                .unwrap()
+        }
+
+        let found = titles(
+            ColumnContainsText {
+                col: "title",
+                val: "0%",
+            },
+            &mut conn,
+        )
+        .await;
+        pretty_assertions::assert_eq!(found, vec!["100% done"]);
+
+        #[allow(deprecated)]
+        let found = titles(
+            super::ColumnContains {
+                col: "title",
+                like: "_b",
+            },
+            &mut conn,
+        )
+        .await;
+        pretty_assertions::assert_eq!(found, vec!["a_b"]);
+
+        let found = titles(
+            ColumnStartsWith {
+                col: "title",
+                val: "a_",
+            },
+            &mut conn,
+        )
+        .await;
+        pretty_assertions::assert_eq!(found, vec!["a_b"]);
+
+        let found = titles(
+            ColumnEndsWith {
+                col: "title",
+                val: "_b",
+            },
+            &mut conn,
+        )
+        .await;
+        pretty_assertions::assert_eq!(found, vec!["a_b"]);
+
+        let found = titles(
+            ColumnEqualIgnoreCase {
+                col: "title",
+                eq: "AXB",
+            },
+            &mut conn,
+        )
+        .await;
+        pretty_assertions::assert_eq!(found, vec!["axb"]);
+    }
 }
 
 #[claw_ql_macros::skip]
@@ -0,0 +1,348 @@
+//! constraints of a column or a table
+//!
+//! they end up in the schema where the database rejects parameters, so
+//! values are inlined with `Literal` and hand written SQL goes in `Sql`
+
+use std::{ops::Not, sync::Arc};
+
+use crate::{
+    database_extention::DatabaseExt,
+    sqlx_query_builder::{Expression, ManyExpressions, OpExpression, StatementBuilder},
+};
+
+/// a value that can be written into a statement as is
+pub trait SqlLiteral {
+    fn push_literal(&self, into: &mut String);
+}
+
+macro_rules! number_literal {
+    ($($ty:ty)*) => {
+        $(impl SqlLiteral for $ty {
+            fn push_literal(&self, into: &mut String) {
+                into.push_str(&self.to_string());
+            }
+        })*
+    };
+}
+
+number_literal!(i32 i64 u32 usize);
+
+impl SqlLiteral for f64 {
+    #[track_caller]
+    fn push_literal(&self, into: &mut String) {
+        if self.is_finite().not() {
+            panic!("{self} has no literal")
+        }
+        // `Debug` keeps the decimal point of whole numbers
+        into.push_str(&format!("{self:?}"));
+    }
+}
+
+impl SqlLiteral for bool {
+    fn push_literal(&self, into: &mut String) {
+        into.push_str(if *self { "1" } else { "0" });
+    }
+}
+
+impl SqlLiteral for str {
+    fn push_literal(&self, into: &mut String) {
+        into.push('\'');
+        into.push_str(&self.replace('\'', "''"));
+        into.push('\'');
+    }
+}
+
+impl SqlLiteral for String {
+    fn push_literal(&self, into: &mut String) {
+        self.as_str().push_literal(into)
+    }
+}
+
+impl SqlLiteral for Arc<str> {
+    fn push_literal(&self, into: &mut String) {
+        self.as_ref().push_literal(into)
+    }
+}
+
+impl<T: SqlLiteral + ?Sized> SqlLiteral for &T {
+    fn push_literal(&self, into: &mut String) {
+        (**self).push_literal(into)
+    }
+}
+
+/// a value inlined into the statement instead of being bound
+#[derive(Clone)]
+pub struct Literal<T>(pub T);
+
+impl<T> OpExpression for Literal<T> {}
+
+impl<'q, S, T> Expression<'q, S> for Literal<T>
+where
+    S: DatabaseExt,
+    T: SqlLiteral + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        self.0.push_literal(&mut ctx.stmt);
+    }
+}
+
+/// SQL written by the developer, pushed as is
+#[derive(Clone, Copy)]
+pub struct Sql(pub &'static str);
+
+impl OpExpression for Sql {}
+
+impl<'q, S> Expression<'q, S> for Sql
+where
+    S: DatabaseExt,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax(self.0);
+    }
+}
+
+/// `CHECK (<conditions>)`, many conditions are joined by `AND`
+#[derive(Clone)]
+pub struct Check<Conditions>(pub Conditions);
+
+impl<C> OpExpression for Check<C> {}
+
+impl<'q, S, C> Expression<'q, S> for Check<C>
+where
+    S: DatabaseExt,
+    C: ManyExpressions<'q, S> + 'q,
+{
+    #[track_caller]
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        if self.0.is_op().not() {
+            panic!("check without a condition")
+        }
+        self.0.expression("CHECK (", " AND ", ctx);
+        ctx.syntax(")");
+    }
+}
+
+/// `DEFAULT (<value>)`, the value is either a `Literal` or an expression
+/// the database evaluates on every insert
+#[derive(Clone)]
+pub struct Default<Value>(pub Value);
+
+impl<V> OpExpression for Default<V> {}
+
+impl<'q, S, V> Expression<'q, S> for Default<V>
+where
+    S: DatabaseExt,
+    V: Expression<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("DEFAULT (");
+        self.0.expression(ctx);
+        ctx.syntax(")");
+    }
+}
+
+/// `CONSTRAINT <name> <constraint>`, the name is what
+/// `ConstraintViolation::constraint` reports
+#[derive(Clone)]
+pub struct Named<Name, Constraint> {
+    pub name: Name,
+    pub constraint: Constraint,
+}
+
+impl<N, C> OpExpression for Named<N, C> {}
+
+impl<'q, S, N, C> Expression<'q, S> for Named<N, C>
+where
+    S: DatabaseExt,
+    N: Expression<'q, S> + 'q,
+    C: Expression<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("CONSTRAINT ");
+        self.name.expression(ctx);
+        ctx.syntax(" ");
+        self.constraint.expression(ctx);
+    }
+}
+
+/// `<col_def> <constraints>`, adds constraints to a column definition that
+/// doesn't take any
+#[derive(Clone)]
+pub struct ConstrainedColumn<ColDef, Constraints> {
+    pub col_def: ColDef,
+    pub constraints: Constraints,
+}
+
+impl<D, C> OpExpression for ConstrainedColumn<D, C> {}
+
+impl<'q, S, D, C> Expression<'q, S> for ConstrainedColumn<D, C>
+where
+    S: DatabaseExt,
+    D: Expression<'q, S> + 'q,
+    C: ManyExpressions<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        self.col_def.expression(ctx);
+        self.constraints.expression(" ", " ", ctx);
+    }
+}
+
+/// `REFERENCES <table>(<column>) <ons>`, `ons` being actions like
+/// `OnDelete`
+#[derive(Clone)]
+pub struct References<Table, Column, Ons> {
+    pub table: Table,
+    pub column: Column,
+    pub ons: Ons,
+}
+
+impl<T, C, O> OpExpression for References<T, C, O> {}
+
+impl<'q, S, T, C, O> Expression<'q, S> for References<T, C, O>
+where
+    S: DatabaseExt,
+    T: Expression<'q, S> + 'q,
+    C: Expression<'q, S> + 'q,
+    O: ManyExpressions<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("REFERENCES ");
+        self.table.expression(ctx);
+        ctx.syntax("(");
+        self.column.expression(ctx);
+        ctx.syntax(")");
+        self.ons.expression(" ", " ", ctx);
+    }
+}
+
+/// `ON DELETE <action>`, `CASCADE`, `SET NULL`..
+#[derive(Clone)]
+pub struct OnDelete(pub &'static str);
+
+impl OpExpression for OnDelete {}
+
+impl<'q, S> Expression<'q, S> for OnDelete
+where
+    S: DatabaseExt,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("ON DELETE ");
+        ctx.syntax(self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::marker::PhantomData;
+
+    use sqlx::Sqlite;
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        database_extention::DatabaseExt,
+        operations::insert::{ConstraintKind, ConstraintViolation},
+        sqlx_query_builder::{
+            StatementBuilder,
+            basic_expressions::{ColumnDefinition, ManyFlat},
+            column_constraints::{Check, ConstrainedColumn, Default, Literal, Named, Sql},
+            computed_expressions::{GreaterThan, InList, Length},
+            statements::create_table_statement::{CreateTable, expressions::create_table},
+        },
+    };
+
+    #[tokio::test]
+    async fn checks_and_defaults() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        let stmt = StatementBuilder::<Sqlite>::new_no_data(CreateTable {
+            init: create_table,
+            name: "Todo",
+            col_defs: ManyFlat((
+                Sqlite::id_on_create_table_expression(),
+                ColumnDefinition {
+                    name: "title",
+                    ty: PhantomData::<String>,
+                    constraints: Named {
+                        name: "title_not_empty",
+                        constraint: Check(GreaterThan(Length("title"), Literal(0))),
+                    },
+                },
+                ColumnDefinition {
+                    name: "status",
+                    ty: PhantomData::<String>,
+                    constraints: ManyFlat((
+                        Default(Literal("it's new")),
+                        Check(InList {
+                            expression: "status",
+                            values: [Literal("it's new"), Literal("done")],
+                        }),
+                    )),
+                },
+                ConstrainedColumn {
+                    col_def: ColumnDefinition {
+                        name: "score",
+                        ty: PhantomData::<Option<f64>>,
+                        constraints: (),
+                    },
+                    constraints: ManyFlat((
+                        Default(Literal(0.0)),
+                        Named {
+                            name: "score_range",
+                            constraint: Check([Sql("\"score\" >= 0"), Sql("\"score\" <= 10")]),
+                        },
+                    )),
+                },
+            )),
+        })
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            stmt.replace("\"", "`"),
+            "CREATE TABLE `Todo` (\
+            `id` INTEGER PRIMARY KEY AUTOINCREMENT, \
+            `title` TEXT NOT NULL CONSTRAINT `title_not_empty` CHECK (LENGTH(`title`) > 0), \
+            `status` TEXT NOT NULL DEFAULT ('it''s new') CHECK (`status` IN ('it''s new', 'done')), \
+            `score` REAL DEFAULT (0.0) CONSTRAINT `score_range` CHECK (`score` >= 0 AND `score` <= 10));"
+        );
+        sqlx::query(&stmt).execute(&mut conn).await.unwrap();
+
+        sqlx::query("INSERT INTO Todo (title) VALUES ('first')")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        let found: (String, String, f64) = sqlx::query_as("SELECT title, status, score FROM Todo")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        pretty_assertions::assert_eq!(found, ("first".to_string(), "it's new".to_string(), 0.0));
+
+        let mut violations = vec![];
+        for stmt in [
+            "INSERT INTO Todo (title) VALUES ('')",
+            "INSERT INTO Todo (title, score) VALUES ('second', 11)",
+            "INSERT INTO Todo (title, status) VALUES (NULL, 'done')",
+        ] {
+            let error = sqlx::query(stmt).execute(&mut conn).await.unwrap_err();
+            violations.push(ConstraintViolation::from_database_error(
+                error.as_database_error().unwrap(),
+            ));
+        }
+
+        pretty_assertions::assert_eq!(
+            violations,
+            vec![
+                Some(ConstraintViolation {
+                    kind: ConstraintKind::Check,
+                    constraint: Some("title_not_empty".to_string()),
+                }),
+                Some(ConstraintViolation {
+                    kind: ConstraintKind::Check,
+                    constraint: Some("score_range".to_string()),
+                }),
+                Some(ConstraintViolation {
+                    kind: ConstraintKind::NotNull,
+                    constraint: Some("Todo.title".to_string()),
+                }),
+            ]
+        );
+    }
+}
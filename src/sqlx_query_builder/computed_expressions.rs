@@ -0,0 +1,308 @@
+//! expressions computed by the database rather than read from a column:
+//! window functions, arithmetic and `CASE`
+
+use std::ops::Not;
+
+use crate::{
+    database_extention::DatabaseExt,
+    sqlx_query_builder::{
+        Expression, ManyExpressions, OpExpression, PossibleExpression, StatementBuilder,
+    },
+};
+
+/// `<function> OVER (PARTITION BY <partition_by> ORDER BY <order_by>)`,
+/// both clauses are omitted when `()`
+#[derive(Clone)]
+pub struct Over<Function, PartitionBy, OrderBy> {
+    pub function: Function,
+    pub partition_by: PartitionBy,
+    pub order_by: OrderBy,
+}
+
+impl<F, P, O> OpExpression for Over<F, P, O> {}
+
+impl<'q, S, F, P, O> Expression<'q, S> for Over<F, P, O>
+where
+    S: DatabaseExt,
+    F: Expression<'q, S> + 'q,
+    P: ManyExpressions<'q, S> + 'q,
+    O: ManyExpressions<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        self.function.expression(ctx);
+        ctx.syntax(" OVER (");
+        let partitioned = self.partition_by.is_op();
+        self.partition_by.expression("PARTITION BY ", ", ", ctx);
+        if self.order_by.is_op() {
+            if partitioned {
+                ctx.syntax(" ");
+            }
+            self.order_by.expression("ORDER BY ", ", ", ctx);
+        }
+        ctx.syntax(")");
+    }
+}
+
+/// `<expression> DESC`, meant for the `order_by` of `Over`
+#[derive(Clone)]
+pub struct Descending<E>(pub E);
+
+impl<E> OpExpression for Descending<E> {}
+
+impl<'q, S, E> Expression<'q, S> for Descending<E>
+where
+    S: DatabaseExt,
+    E: Expression<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        self.0.expression(ctx);
+        ctx.syntax(" DESC");
+    }
+}
+
+macro_rules! window_function {
+    ($name:ident, $syntax:literal) => {
+        #[derive(Clone)]
+        pub struct $name;
+
+        impl OpExpression for $name {}
+
+        impl<'q, S> Expression<'q, S> for $name
+        where
+            S: DatabaseExt,
+        {
+            fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+                ctx.syntax($syntax);
+            }
+        }
+    };
+}
+
+window_function!(RowNumber, "ROW_NUMBER()");
+window_function!(Rank, "RANK()");
+window_function!(DenseRank, "DENSE_RANK()");
+
+macro_rules! offset_function {
+    ($name:ident, $syntax:literal) => {
+        /// the value of `expression` `offset` rows away, `NULL` when there
+        /// is no such row
+        #[derive(Clone)]
+        pub struct $name<E> {
+            pub expression: E,
+            pub offset: usize,
+        }
+
+        impl<E> OpExpression for $name<E> {}
+
+        impl<'q, S, E> Expression<'q, S> for $name<E>
+        where
+            S: DatabaseExt,
+            E: Expression<'q, S> + 'q,
+        {
+            fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+                ctx.syntax($syntax);
+                self.expression.expression(ctx);
+                ctx.syntax(", ");
+                // a number can't inject anything
+                ctx.stmt.push_str(&self.offset.to_string());
+                ctx.syntax(")");
+            }
+        }
+    };
+}
+
+offset_function!(Lag, "LAG(");
+offset_function!(Lead, "LEAD(");
+
+macro_rules! arithmetic {
+    ($name:ident, $op:literal) => {
+        #[derive(Clone)]
+        pub struct $name<Lhs, Rhs>(pub Lhs, pub Rhs);
+
+        impl<Lhs, Rhs> OpExpression for $name<Lhs, Rhs> {}
+
+        impl<'q, S, Lhs, Rhs> Expression<'q, S> for $name<Lhs, Rhs>
+        where
+            S: DatabaseExt,
+            Lhs: Expression<'q, S> + 'q,
+            Rhs: Expression<'q, S> + 'q,
+        {
+            fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+                ctx.syntax("(");
+                self.0.expression(ctx);
+                ctx.syntax($op);
+                self.1.expression(ctx);
+                ctx.syntax(")");
+            }
+        }
+    };
+}
+
+arithmetic!(Add, " + ");
+arithmetic!(Subtract, " - ");
+arithmetic!(Multiply, " * ");
+arithmetic!(Divide, " / ");
+
+/// `WHEN <condition> THEN <then>`, one branch of `Case`
+#[derive(Clone)]
+pub struct When<Condition, Then> {
+    pub condition: Condition,
+    pub then: Then,
+}
+
+impl<C, T> OpExpression for When<C, T> {}
+
+impl<'q, S, C, T> Expression<'q, S> for When<C, T>
+where
+    S: DatabaseExt,
+    C: Expression<'q, S> + 'q,
+    T: Expression<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("WHEN ");
+        self.condition.expression(ctx);
+        ctx.syntax(" THEN ");
+        self.then.expression(ctx);
+    }
+}
+
+/// `CASE <whens> ELSE <otherwise> END`, `ELSE` is omitted when
+/// `otherwise` is `()` so unmatched rows are `NULL`
+#[derive(Clone)]
+pub struct Case<Whens, Otherwise> {
+    pub whens: Whens,
+    pub otherwise: Otherwise,
+}
+
+impl<W, O> OpExpression for Case<W, O> {}
+
+impl<'q, S, W, O> Expression<'q, S> for Case<W, O>
+where
+    S: DatabaseExt,
+    W: ManyExpressions<'q, S> + 'q,
+    O: PossibleExpression<'q, S> + 'q,
+{
+    #[track_caller]
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        if self.whens.is_op().not() {
+            panic!("case without a when")
+        }
+        ctx.syntax("CASE ");
+        self.whens.expression("", " ", ctx);
+        self.otherwise.expression_starting(" ELSE ", ctx);
+        ctx.syntax(" END");
+    }
+}
+
+/// `LOWER(<expression>)`, also usable as the column of an index to back
+/// case-insensitive lookups
+#[derive(Clone)]
+pub struct Lower<E>(pub E);
+
+impl<E> OpExpression for Lower<E> {}
+
+impl<'q, S, E> Expression<'q, S> for Lower<E>
+where
+    S: DatabaseExt,
+    E: Expression<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("LOWER(");
+        self.0.expression(ctx);
+        ctx.syntax(")");
+    }
+}
+
+/// `LENGTH(<expression>)`, characters of a text or bytes of a blob
+#[derive(Clone)]
+pub struct Length<E>(pub E);
+
+impl<E> OpExpression for Length<E> {}
+
+impl<'q, S, E> Expression<'q, S> for Length<E>
+where
+    S: DatabaseExt,
+    E: Expression<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("LENGTH(");
+        self.0.expression(ctx);
+        ctx.syntax(")");
+    }
+}
+
+macro_rules! comparison {
+    ($name:ident, $op:literal) => {
+        /// compares two expressions without binding either, unlike the
+        /// `Column*` filters of `basic_expressions`
+        #[derive(Clone)]
+        pub struct $name<Lhs, Rhs>(pub Lhs, pub Rhs);
+
+        impl<Lhs, Rhs> OpExpression for $name<Lhs, Rhs> {}
+
+        impl<'q, S, Lhs, Rhs> Expression<'q, S> for $name<Lhs, Rhs>
+        where
+            S: DatabaseExt,
+            Lhs: Expression<'q, S> + 'q,
+            Rhs: Expression<'q, S> + 'q,
+        {
+            fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+                self.0.expression(ctx);
+                ctx.syntax($op);
+                self.1.expression(ctx);
+            }
+        }
+    };
+}
+
+comparison!(Equal, " = ");
+comparison!(NotEqual, " <> ");
+comparison!(GreaterThan, " > ");
+comparison!(GreaterOrEqual, " >= ");
+comparison!(LessThan, " < ");
+comparison!(LessOrEqual, " <= ");
+
+/// `<expression> IN (<values>)`, an empty list matches nothing like
+/// `ColumnIn` does
+#[derive(Clone)]
+pub struct InList<E, Values> {
+    pub expression: E,
+    pub values: Values,
+}
+
+impl<E, V> OpExpression for InList<E, V> {}
+
+impl<'q, S, E, V> Expression<'q, S> for InList<E, V>
+where
+    S: DatabaseExt,
+    E: Expression<'q, S> + 'q,
+    V: ManyExpressions<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        if self.values.is_op().not() {
+            ctx.syntax("1 = 0");
+            return;
+        }
+        self.expression.expression(ctx);
+        self.values.expression(" IN (", ", ", ctx);
+        ctx.syntax(")");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::Sqlite;
+
+    use crate::sqlx_query_builder::{
+        StatementBuilder, column_constraints::Literal, computed_expressions::InList,
+    };
+
+    #[test]
+    fn empty_in_list() {
+        let stmt = StatementBuilder::<Sqlite>::new_no_data(InList {
+            expression: "status",
+            values: Vec::<Literal<&str>>::new(),
+        });
+        pretty_assertions::assert_eq!(stmt.as_deref(), Some("1 = 0"));
+    }
+}
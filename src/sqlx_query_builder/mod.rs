@@ -5,6 +5,8 @@ use sqlx::{Encode, Type};
 
 use crate::database_extention::DatabaseExt;
 pub mod basic_expressions;
+pub mod column_constraints;
+pub mod computed_expressions;
 pub mod statements;
 pub mod std_impls;
 pub mod trait_objects;
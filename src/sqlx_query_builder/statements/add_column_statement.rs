@@ -12,11 +12,14 @@ pub struct DropColumn<Table, Name> {
 
 impl<Table, ColDef> OpExpression for AddColumn<Table, ColDef> {}
 
+impl<Table, Name> OpExpression for DropColumn<Table, Name> {}
+
 mod impl_for_sqlx_fo {
     use crate::{
         database_extention::DatabaseExt,
         sqlx_query_builder::{
-            Expression, StatementBuilder, statements::add_column_statement::AddColumn,
+            Expression, StatementBuilder,
+            statements::add_column_statement::{AddColumn, DropColumn},
         },
     };
     use sqlx::Database;
@@ -35,4 +38,19 @@ mod impl_for_sqlx_fo {
             ctx.syntax(";");
         }
     }
+
+    impl<'q, S, Table, Name> Expression<'q, S> for DropColumn<Table, Name>
+    where
+        S: Database + DatabaseExt,
+        Table: Expression<'q, S> + 'q,
+        Name: Expression<'q, S> + 'q,
+    {
+        fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+            ctx.syntax("ALTER TABLE ");
+            self.table.expression(ctx);
+            ctx.syntax(" DROP COLUMN ");
+            self.col_name.expression(ctx);
+            ctx.syntax(";");
+        }
+    }
 }
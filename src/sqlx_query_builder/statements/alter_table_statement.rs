@@ -0,0 +1,520 @@
+use std::ops::Not;
+
+use sqlx::{Connection, Row, Sqlite, SqliteConnection};
+
+use crate::{
+    database_extention::DatabaseExt,
+    sqlx_query_builder::{
+        Expression, ManyExpressions, OpExpression, StatementBuilder, statements::Inverse,
+    },
+};
+
+/// `ALTER TABLE <table> RENAME TO <to>;`
+#[derive(Clone)]
+pub struct RenameTable<Table, To> {
+    pub table: Table,
+    pub to: To,
+}
+
+impl<Table, To> OpExpression for RenameTable<Table, To> {}
+
+impl<'q, S, Table, To> Expression<'q, S> for RenameTable<Table, To>
+where
+    S: DatabaseExt,
+    Table: Expression<'q, S> + 'q,
+    To: Expression<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("ALTER TABLE ");
+        self.table.expression(ctx);
+        ctx.syntax(" RENAME TO ");
+        self.to.expression(ctx);
+        ctx.syntax(";");
+    }
+}
+
+impl<Table: Clone, To: Clone> Inverse for RenameTable<Table, To> {
+    type InverseStatement = RenameTable<To, Table>;
+    fn inverse(&self) -> Self::InverseStatement {
+        RenameTable {
+            table: self.to.clone(),
+            to: self.table.clone(),
+        }
+    }
+}
+
+/// `ALTER TABLE <table> RENAME COLUMN <col_name> TO <to>;`
+#[derive(Clone)]
+pub struct RenameColumn<Table, Name, To> {
+    pub table: Table,
+    pub col_name: Name,
+    pub to: To,
+}
+
+impl<Table, Name, To> OpExpression for RenameColumn<Table, Name, To> {}
+
+impl<'q, S, Table, Name, To> Expression<'q, S> for RenameColumn<Table, Name, To>
+where
+    S: DatabaseExt,
+    Table: Expression<'q, S> + 'q,
+    Name: Expression<'q, S> + 'q,
+    To: Expression<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("ALTER TABLE ");
+        self.table.expression(ctx);
+        ctx.syntax(" RENAME COLUMN ");
+        self.col_name.expression(ctx);
+        ctx.syntax(" TO ");
+        self.to.expression(ctx);
+        ctx.syntax(";");
+    }
+}
+
+impl<Table: Clone, Name: Clone, To: Clone> Inverse for RenameColumn<Table, Name, To> {
+    type InverseStatement = RenameColumn<Table, To, Name>;
+    fn inverse(&self) -> Self::InverseStatement {
+        RenameColumn {
+            table: self.table.clone(),
+            col_name: self.to.clone(),
+            to: self.col_name.clone(),
+        }
+    }
+}
+
+/// changes sqlite can't make with `ALTER TABLE`: the type or nullability
+/// of a column, adding or dropping a constraint..
+///
+/// `col_defs` is the whole definition of the table after the change, like
+/// `CreateTable::col_defs`, and `columns` are the ones copied over from the
+/// old table. renders sqlite's table rebuild:
+///
+/// ```sql
+/// CREATE TABLE "meta_rebuild_<table>" (<col_defs>);
+/// INSERT INTO "meta_rebuild_<table>" (<columns>) SELECT <columns> FROM "<table>";
+/// DROP TABLE "<table>";
+/// ALTER TABLE "meta_rebuild_<table>" RENAME TO "<table>";
+/// ```
+///
+/// foreign keys have to be off so dropping the old table doesn't cascade to
+/// the records referencing it, `RebuildTable::execute` takes care of that.
+/// indexes and triggers of the old table are dropped with it and have to be
+/// created again
+pub struct RebuildTable<Table, ColDefs, Columns> {
+    pub table: Table,
+    pub col_defs: ColDefs,
+    pub columns: Columns,
+}
+
+impl<Table, ColDefs, Columns> OpExpression for RebuildTable<Table, ColDefs, Columns> {}
+
+impl<'q, Table, ColDefs, Columns> Expression<'q, Sqlite> for RebuildTable<Table, ColDefs, Columns>
+where
+    Table: AsRef<str> + 'q,
+    ColDefs: ManyExpressions<'q, Sqlite> + 'q,
+    Columns: ManyExpressions<'q, Sqlite> + Clone + 'q,
+{
+    #[track_caller]
+    fn expression(self, ctx: &mut StatementBuilder<'q, Sqlite>) {
+        if self.col_defs.is_op().not() {
+            panic!("rebuilding a table without columns")
+        }
+        let table = self.table.as_ref();
+        let rebuilt = ("meta_rebuild_", table);
+
+        ctx.syntax("CREATE TABLE ");
+        ctx.sanitize_many(rebuilt);
+        self.col_defs.expression(" (", ", ", ctx);
+        ctx.syntax("); INSERT INTO ");
+        ctx.sanitize_many(rebuilt);
+        self.columns.clone().expression(" (", ", ", ctx);
+        ctx.syntax(")");
+        self.columns.expression(" SELECT ", ", ", ctx);
+        ctx.syntax(" FROM ");
+        ctx.sanitize(table);
+        ctx.syntax("; DROP TABLE ");
+        ctx.sanitize(table);
+        ctx.syntax("; ALTER TABLE ");
+        ctx.sanitize_many(rebuilt);
+        ctx.syntax(" RENAME TO ");
+        ctx.sanitize(table);
+        ctx.syntax(";");
+    }
+}
+
+/// a row of `PRAGMA foreign_key_check`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ForeignKeyViolation {
+    pub table: String,
+    pub rowid: Option<i64>,
+    pub parent: String,
+}
+
+#[derive(Debug)]
+pub enum RebuildTableError {
+    /// foreign keys are on and can't be turned off inside a transaction
+    InTransaction,
+    /// the rebuilt table left records referencing rows that don't exist
+    ForeignKeyViolations(Vec<ForeignKeyViolation>),
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for RebuildTableError {
+    fn from(value: sqlx::Error) -> Self {
+        RebuildTableError::Database(value)
+    }
+}
+
+impl<Table, ColDefs, Columns> RebuildTable<Table, ColDefs, Columns>
+where
+    Self: for<'q> Expression<'q, Sqlite>,
+{
+    /// turns foreign keys off, rebuilds the table in a transaction that is
+    /// rolled back unless `PRAGMA foreign_key_check` passes, then sets
+    /// foreign keys back to what they were, whether the rebuild failed or not
+    pub async fn execute(self, conn: &mut SqliteConnection) -> Result<(), RebuildTableError> {
+        let stmt = StatementBuilder::<Sqlite>::new_no_data(self)
+            .expect("a table rebuild doesn't bind values");
+
+        let foreign_keys: bool = sqlx::query_scalar("PRAGMA foreign_keys")
+            .fetch_one(&mut *conn)
+            .await?;
+        if foreign_keys {
+            sqlx::query("PRAGMA foreign_keys = OFF")
+                .execute(&mut *conn)
+                .await?;
+            let still_on: bool = sqlx::query_scalar("PRAGMA foreign_keys")
+                .fetch_one(&mut *conn)
+                .await?;
+            if still_on {
+                return Err(RebuildTableError::InTransaction);
+            }
+        }
+
+        let output = rebuild(&stmt, &mut *conn).await;
+
+        if foreign_keys {
+            sqlx::query("PRAGMA foreign_keys = ON")
+                .execute(&mut *conn)
+                .await?;
+        }
+        output
+    }
+}
+
+async fn rebuild(stmt: &str, conn: &mut SqliteConnection) -> Result<(), RebuildTableError> {
+    let mut tx = conn.begin().await?;
+
+    let output = async {
+        sqlx::query(stmt).execute(&mut *tx).await?;
+
+        let violations = sqlx::query("PRAGMA foreign_key_check")
+            .fetch_all(&mut *tx)
+            .await?;
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(RebuildTableError::ForeignKeyViolations(
+                violations
+                    .into_iter()
+                    .map(|row| ForeignKeyViolation {
+                        table: row.get(0),
+                        rowid: row.get(1),
+                        parent: row.get(2),
+                    })
+                    .collect(),
+            )),
+        }
+    }
+    .await;
+
+    match output.is_ok() {
+        true => tx.commit().await?,
+        false => tx.rollback().await?,
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::marker::PhantomData;
+
+    use sqlx::{Connection, Sqlite};
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        database_extention::DatabaseExt,
+        sqlx_query_builder::{
+            StatementBuilder,
+            basic_expressions::{ColumnDefinition, ManyFlat},
+            column_constraints::Sql,
+            statements::{
+                Inverse,
+                add_column_statement::DropColumn,
+                alter_table_statement::{
+                    ForeignKeyViolation, RebuildTable, RebuildTableError, RenameColumn, RenameTable,
+                },
+            },
+        },
+    };
+
+    #[tokio::test]
+    async fn rename_and_drop() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        sqlx::query(
+            "
+        CREATE TABLE Todo (id INTEGER PRIMARY KEY, title TEXT NOT NULL, obsolete TEXT);
+        INSERT INTO Todo (id, title, obsolete) VALUES (1, 'first', NULL);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let rename_table = RenameTable {
+            table: "Todo",
+            to: "Task",
+        };
+        let rename_column = RenameColumn {
+            table: "Task",
+            col_name: "title",
+            to: "name",
+        };
+        let drop_column = DropColumn {
+            table: "Task",
+            col_name: "obsolete",
+        };
+
+        let stmts = [
+            StatementBuilder::<Sqlite>::new_no_data(rename_table.clone()).unwrap(),
+            StatementBuilder::<Sqlite>::new_no_data(rename_column.clone()).unwrap(),
+            StatementBuilder::<Sqlite>::new_no_data(drop_column).unwrap(),
+        ];
+        pretty_assertions::assert_eq!(
+            stmts
+                .iter()
+                .map(|e| e.replace("\"", "'"))
+                .collect::<Vec<_>>(),
+            vec![
+                "ALTER TABLE 'Todo' RENAME TO 'Task';",
+                "ALTER TABLE 'Task' RENAME COLUMN 'title' TO 'name';",
+                "ALTER TABLE 'Task' DROP COLUMN 'obsolete';",
+            ]
+        );
+        for stmt in stmts {
+            sqlx::query(&stmt).execute(&mut conn).await.unwrap();
+        }
+
+        let found: (i64, String) = sqlx::query_as("SELECT * FROM Task")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        pretty_assertions::assert_eq!(found, (1, "first".to_string()));
+
+        for stmt in [
+            StatementBuilder::<Sqlite>::new_no_data(rename_column.inverse()).unwrap(),
+            StatementBuilder::<Sqlite>::new_no_data(rename_table.inverse()).unwrap(),
+        ] {
+            sqlx::query(&stmt).execute(&mut conn).await.unwrap();
+        }
+
+        let found: (i64, String) = sqlx::query_as("SELECT id, title FROM Todo")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        pretty_assertions::assert_eq!(found, (1, "first".to_string()));
+    }
+
+    #[tokio::test]
+    async fn rebuild_keeps_records_and_references() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        sqlx::query(
+            "
+        PRAGMA foreign_keys = ON;
+        CREATE TABLE Todo (id INTEGER PRIMARY KEY, title TEXT NOT NULL, done TEXT NOT NULL, obsolete TEXT);
+        CREATE TABLE Comment (
+            id INTEGER PRIMARY KEY,
+            todo_id INTEGER REFERENCES Todo(id) ON DELETE CASCADE
+        );
+        INSERT INTO Todo (id, title, done, obsolete) VALUES (1, 'first', '0', NULL), (2, 'second', '1', 'x');
+        INSERT INTO Comment (id, todo_id) VALUES (1, 1), (2, 2);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        // `title` becomes optional, `done` an integer and `obsolete` is left out
+        let rebuild = || RebuildTable {
+            table: "Todo",
+            col_defs: ManyFlat((
+                Sqlite::id_on_create_table_expression(),
+                ColumnDefinition {
+                    name: "title",
+                    ty: PhantomData::<Option<String>>,
+                    constraints: (),
+                },
+                ColumnDefinition {
+                    name: "done",
+                    ty: PhantomData::<i64>,
+                    constraints: (),
+                },
+            )),
+            columns: ["id", "title", "done"],
+        };
+        let stmt = StatementBuilder::<Sqlite>::new_no_data(rebuild()).unwrap();
+        pretty_assertions::assert_eq!(
+            stmt.replace("\"", "'"),
+            "CREATE TABLE 'meta_rebuild_Todo' ('id' INTEGER PRIMARY KEY AUTOINCREMENT, 'title' TEXT, 'done' INTEGER NOT NULL); \
+            INSERT INTO 'meta_rebuild_Todo' ('id', 'title', 'done') SELECT 'id', 'title', 'done' FROM 'Todo'; \
+            DROP TABLE 'Todo'; \
+            ALTER TABLE 'meta_rebuild_Todo' RENAME TO 'Todo';"
+        );
+        rebuild().execute(&mut conn).await.unwrap();
+
+        let foreign_keys: bool = sqlx::query_scalar("PRAGMA foreign_keys")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert!(foreign_keys);
+
+        sqlx::query("INSERT INTO Todo (id, title, done) VALUES (3, NULL, 1)")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        let todos: Vec<(i64, Option<String>, i64)> =
+            sqlx::query_as("SELECT id, title, done FROM Todo ORDER BY id")
+                .fetch_all(&mut conn)
+                .await
+                .unwrap();
+        pretty_assertions::assert_eq!(
+            todos,
+            vec![
+                (1, Some("first".to_string()), 0),
+                (2, Some("second".to_string()), 1),
+                (3, None, 1),
+            ]
+        );
+
+        // dropping the old table didn't cascade and the references point
+        // to the new one
+        let comments: Vec<(i64, i64)> =
+            sqlx::query_as("SELECT id, todo_id FROM Comment ORDER BY id")
+                .fetch_all(&mut conn)
+                .await
+                .unwrap();
+        pretty_assertions::assert_eq!(comments, vec![(1, 1), (2, 2)]);
+
+        sqlx::query("DELETE FROM Todo WHERE id = 1")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        let comments: Vec<i64> = sqlx::query_scalar("SELECT id FROM Comment")
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+        pretty_assertions::assert_eq!(comments, vec![2]);
+    }
+
+    #[tokio::test]
+    async fn failed_rebuild_rolls_back() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        sqlx::query(
+            "
+        PRAGMA foreign_keys = ON;
+        CREATE TABLE Todo (id INTEGER PRIMARY KEY, title TEXT);
+        CREATE TABLE Archived (id INTEGER PRIMARY KEY);
+        CREATE TABLE Comment (
+            id INTEGER PRIMARY KEY,
+            todo_id INTEGER REFERENCES Todo(id) ON DELETE CASCADE
+        );
+        INSERT INTO Todo (id, title) VALUES (1, 'first'), (2, NULL);
+        INSERT INTO Comment (id, todo_id) VALUES (1, 1), (2, 2);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        async fn schema(conn: &mut sqlx::SqliteConnection) -> (Vec<String>, bool) {
+            let tables = sqlx::query_scalar("SELECT sql FROM sqlite_master ORDER BY name")
+                .fetch_all(&mut *conn)
+                .await
+                .unwrap();
+            let foreign_keys = sqlx::query_scalar("PRAGMA foreign_keys")
+                .fetch_one(&mut *conn)
+                .await
+                .unwrap();
+            (tables, foreign_keys)
+        }
+        let before = schema(&mut conn).await;
+
+        // a record has no title
+        let not_null = RebuildTable {
+            table: "Todo",
+            col_defs: ManyFlat((
+                Sqlite::id_on_create_table_expression(),
+                ColumnDefinition {
+                    name: "title",
+                    ty: PhantomData::<String>,
+                    constraints: (),
+                },
+            )),
+            columns: ["id", "title"],
+        }
+        .execute(&mut conn)
+        .await;
+        assert!(matches!(not_null, Err(RebuildTableError::Database(_))));
+        pretty_assertions::assert_eq!(schema(&mut conn).await, before);
+
+        // comments would reference archived todos that don't exist
+        let dangling = RebuildTable {
+            table: "Comment",
+            col_defs: ManyFlat((
+                Sqlite::id_on_create_table_expression(),
+                ColumnDefinition {
+                    name: "todo_id",
+                    ty: PhantomData::<Option<i64>>,
+                    constraints: Sql("REFERENCES \"Archived\"(\"id\")"),
+                },
+            )),
+            columns: ["id", "todo_id"],
+        }
+        .execute(&mut conn)
+        .await;
+        match dangling {
+            Err(RebuildTableError::ForeignKeyViolations(violations)) => {
+                pretty_assertions::assert_eq!(
+                    violations,
+                    [1, 2]
+                        .map(|rowid| ForeignKeyViolation {
+                            table: String::from("Comment"),
+                            rowid: Some(rowid),
+                            parent: String::from("Archived"),
+                        })
+                        .to_vec()
+                )
+            }
+            other => panic!("expected foreign key violations, got {other:?}"),
+        }
+        pretty_assertions::assert_eq!(schema(&mut conn).await, before);
+
+        // foreign keys can't be turned off inside a transaction
+        let mut tx = conn.begin().await.unwrap();
+        let in_transaction = RebuildTable {
+            table: "Todo",
+            col_defs: ManyFlat((Sqlite::id_on_create_table_expression(),)),
+            columns: ["id"],
+        }
+        .execute(&mut tx)
+        .await;
+        assert!(matches!(
+            in_transaction,
+            Err(RebuildTableError::InTransaction)
+        ));
+        tx.rollback().await.unwrap();
+        pretty_assertions::assert_eq!(schema(&mut conn).await, before);
+    }
+}
@@ -0,0 +1,304 @@
+use crate::{
+    database_extention::DatabaseExt,
+    sqlx_query_builder::{
+        Expression, ManyExpressions, OpExpression, PossibleExpression, StatementBuilder,
+        basic_expressions::ManyFlat,
+        statements::select_statement::{SelectStatement, Subquery},
+    },
+};
+
+/// number of columns a select item renders, `None` when it is only known
+/// at runtime (like a `Vec`)
+pub trait SelectColumns {
+    const COLUMNS: Option<usize>;
+}
+
+impl<T: OpExpression> SelectColumns for T {
+    const COLUMNS: Option<usize> = Some(1);
+}
+
+impl<T> SelectColumns for Vec<T> {
+    const COLUMNS: Option<usize> = None;
+}
+
+impl SelectColumns for () {
+    const COLUMNS: Option<usize> = Some(0);
+}
+
+impl<T> SelectColumns for ManyFlat<Vec<T>> {
+    const COLUMNS: Option<usize> = None;
+}
+
+const fn add_columns(lhs: Option<usize>, rhs: Option<usize>) -> Option<usize> {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => Some(lhs + rhs),
+        _ => None,
+    }
+}
+
+const fn columns_match(lhs: Option<usize>, rhs: Option<usize>) -> bool {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => lhs == rhs,
+        _ => true,
+    }
+}
+
+impl<T0: SelectColumns> SelectColumns for ManyFlat<(T0,)> {
+    const COLUMNS: Option<usize> = T0::COLUMNS;
+}
+
+impl<T0: SelectColumns, T1: SelectColumns> SelectColumns for ManyFlat<(T0, T1)> {
+    const COLUMNS: Option<usize> = add_columns(T0::COLUMNS, T1::COLUMNS);
+}
+
+impl<T0: SelectColumns, T1: SelectColumns, T2: SelectColumns> SelectColumns
+    for ManyFlat<(T0, T1, T2)>
+{
+    const COLUMNS: Option<usize> = add_columns(add_columns(T0::COLUMNS, T1::COLUMNS), T2::COLUMNS);
+}
+
+impl<T0: SelectColumns, T1: SelectColumns, T2: SelectColumns, T3: SelectColumns> SelectColumns
+    for ManyFlat<(T0, T1, T2, T3)>
+{
+    const COLUMNS: Option<usize> = add_columns(
+        add_columns(T0::COLUMNS, T1::COLUMNS),
+        add_columns(T2::COLUMNS, T3::COLUMNS),
+    );
+}
+
+/// a select without `ORDER BY`, `LIMIT` or `OFFSET`, these go to the
+/// `CompoundSelect` instead
+pub trait SimpleSelect {}
+
+impl<SelectItems, From, Joins, Wheres, GroupBy> SimpleSelect
+    for SelectStatement<SelectItems, From, Joins, Wheres, GroupBy, (), (), ()>
+{
+}
+
+/// one side of a compound select, rendered without the trailing `;`
+pub trait CompoundMember<'q, S>: 'q {
+    const COLUMNS: Option<usize>;
+    fn member(self, ctx: &mut StatementBuilder<'q, S>)
+    where
+        S: DatabaseExt;
+}
+
+impl<'q, S, SelectItems, From, Joins, Wheres, GroupBy> CompoundMember<'q, S>
+    for SelectStatement<SelectItems, From, Joins, Wheres, GroupBy, (), (), ()>
+where
+    S: DatabaseExt,
+    SelectItems: SelectColumns + ManyExpressions<'q, S> + 'q,
+    From: Expression<'q, S> + 'q,
+    Joins: ManyExpressions<'q, S> + 'q,
+    Wheres: ManyExpressions<'q, S> + 'q,
+    GroupBy: ManyExpressions<'q, S> + 'q,
+{
+    const COLUMNS: Option<usize> = SelectItems::COLUMNS;
+    #[track_caller]
+    fn member(self, ctx: &mut StatementBuilder<'q, S>) {
+        Subquery(self).body(ctx);
+    }
+}
+
+macro_rules! compound {
+    ($name:ident, $op:literal) => {
+        #[doc = concat!("`<lhs>", $op, "<rhs>`, chain more selects by nesting in `lhs`")]
+        #[derive(Clone)]
+        pub struct $name<Lhs, Rhs>(pub Lhs, pub Rhs);
+
+        impl<'q, S, Lhs, Rhs> CompoundMember<'q, S> for $name<Lhs, Rhs>
+        where
+            S: DatabaseExt,
+            Lhs: CompoundMember<'q, S>,
+            Rhs: CompoundMember<'q, S> + SimpleSelect,
+        {
+            const COLUMNS: Option<usize> = match Lhs::COLUMNS {
+                Some(columns) => Some(columns),
+                None => Rhs::COLUMNS,
+            };
+            #[track_caller]
+            fn member(self, ctx: &mut StatementBuilder<'q, S>) {
+                const {
+                    assert!(
+                        columns_match(Lhs::COLUMNS, Rhs::COLUMNS),
+                        "both sides of a compound select should have the same number of columns"
+                    )
+                };
+                self.0.member(ctx);
+                ctx.syntax($op);
+                self.1.member(ctx);
+            }
+        }
+    };
+}
+
+compound!(Union, " UNION ");
+compound!(UnionAll, " UNION ALL ");
+compound!(Intersect, " INTERSECT ");
+compound!(Except, " EXCEPT ");
+
+/// `<compound> ORDER BY <order> LIMIT <limit> OFFSET <offset>;`, the order
+/// applies to the combined result so it refers to the result columns
+#[derive(Clone)]
+pub struct CompoundSelect<Compound, Order, Limit, Offset> {
+    pub compound: Compound,
+    pub order: Order,
+    pub limit: Limit,
+    /// rendered only after a `LIMIT`
+    pub offset: Offset,
+}
+
+impl<Compound, Order, Limit, Offset> CompoundSelect<Compound, Order, Limit, Offset> {
+    fn body<'q, S>(self, ctx: &mut StatementBuilder<'q, S>)
+    where
+        S: DatabaseExt,
+        Compound: CompoundMember<'q, S>,
+        Order: ManyExpressions<'q, S> + 'q,
+        Limit: PossibleExpression<'q, S> + 'q,
+        Offset: PossibleExpression<'q, S> + 'q,
+    {
+        self.compound.member(ctx);
+        self.order.expression(" ORDER BY ", ", ", ctx);
+        self.limit.expression_starting(" LIMIT ", ctx);
+        self.offset.expression_starting(" OFFSET ", ctx);
+    }
+}
+
+impl<Compound, Order, Limit, Offset> OpExpression
+    for CompoundSelect<Compound, Order, Limit, Offset>
+{
+}
+
+impl<'q, S, Compound, Order, Limit, Offset> Expression<'q, S>
+    for CompoundSelect<Compound, Order, Limit, Offset>
+where
+    S: DatabaseExt,
+    Compound: CompoundMember<'q, S>,
+    Order: ManyExpressions<'q, S> + 'q,
+    Limit: PossibleExpression<'q, S> + 'q,
+    Offset: PossibleExpression<'q, S> + 'q,
+{
+    #[track_caller]
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        self.body(ctx);
+        ctx.syntax(";");
+    }
+}
+
+impl<'q, S, Compound, Order, Limit, Offset> Expression<'q, S>
+    for Subquery<CompoundSelect<Compound, Order, Limit, Offset>>
+where
+    S: DatabaseExt,
+    Compound: CompoundMember<'q, S>,
+    Order: ManyExpressions<'q, S> + 'q,
+    Limit: PossibleExpression<'q, S> + 'q,
+    Offset: PossibleExpression<'q, S> + 'q,
+{
+    #[track_caller]
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("(");
+        self.0.body(ctx);
+        ctx.syntax(")");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::Sqlite;
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        sqlx_query_builder::{
+            StatementBuilder,
+            basic_expressions::{Bind, ColumnEqual, ColumnLessThan},
+            computed_expressions::Descending,
+            statements::{
+                compound_select::{CompoundSelect, Except, Union},
+                select_statement::SelectStatement,
+            },
+        },
+    };
+
+    fn select<SelectItems, From, Wheres>(
+        select_items: SelectItems,
+        from: From,
+        wheres: Wheres,
+    ) -> SelectStatement<SelectItems, From, (), Wheres, (), (), (), ()> {
+        SelectStatement {
+            select_items,
+            from,
+            joins: (),
+            wheres,
+            group_by: (),
+            order: (),
+            limit: (),
+            offset: (),
+        }
+    }
+
+    #[tokio::test]
+    async fn union_and_except() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        sqlx::query(
+            "
+        CREATE TABLE Todo (id INTEGER PRIMARY KEY, title TEXT NOT NULL, done BOOLEAN NOT NULL);
+        INSERT INTO Todo (id, title, done) VALUES (1, 'first', 0), (2, 'second', 1), (3, 'third', 0);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let (stmt, args) = StatementBuilder::<Sqlite>::new(CompoundSelect {
+            compound: Union(
+                select(
+                    "id",
+                    "Todo",
+                    ColumnEqual {
+                        col: "done",
+                        eq: true,
+                    },
+                ),
+                select("id", "Todo", ColumnLessThan { col: "id", lt: 2 }),
+            ),
+            order: Descending("id"),
+            limit: Bind(5),
+            offset: (),
+        })
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            stmt.replace("\"", "'"),
+            "SELECT 'id' FROM 'Todo' WHERE 'done' = $1 UNION SELECT 'id' FROM 'Todo' WHERE 'id' < $2 ORDER BY 'id' DESC LIMIT $3;"
+        );
+        let found: Vec<i64> = sqlx::query_scalar_with(&stmt, args)
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+        pretty_assertions::assert_eq!(found, vec![2, 1]);
+
+        // records in the first select but not in the second
+        let (stmt, args) = StatementBuilder::<Sqlite>::new(CompoundSelect {
+            compound: Except(
+                select("id", "Todo", ()),
+                select(
+                    "id",
+                    "Todo",
+                    ColumnEqual {
+                        col: "done",
+                        eq: true,
+                    },
+                ),
+            ),
+            order: "id",
+            limit: (),
+            offset: (),
+        })
+        .unwrap();
+        let found: Vec<i64> = sqlx::query_scalar_with(&stmt, args)
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+        pretty_assertions::assert_eq!(found, vec![1, 3]);
+    }
+}
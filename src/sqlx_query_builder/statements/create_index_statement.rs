@@ -0,0 +1,185 @@
+use std::ops::Not;
+
+use crate::{
+    database_extention::DatabaseExt,
+    sqlx_query_builder::{Expression, ManyExpressions, OpExpression, StatementBuilder},
+};
+
+/// `CREATE [UNIQUE] INDEX [IF NOT EXISTS] <name> ON <table> (<columns>) [WHERE <wheres>];`
+///
+/// `columns` can hold expressions (`Lower("title")`, `Descending("id")`..)
+/// beside plain column names, and a non-empty `wheres` makes it a partial
+/// index
+///
+/// the database rejects parameters in schema statements, so every part has
+/// to render without binds, build it with `StatementBuilder::new_no_data`
+pub struct CreateIndex<Name, Table, Columns, Wheres> {
+    pub unique: bool,
+    pub if_not_exists: bool,
+    pub name: Name,
+    pub table: Table,
+    pub columns: Columns,
+    pub wheres: Wheres,
+}
+
+impl<Name, Table, Columns, Wheres> OpExpression for CreateIndex<Name, Table, Columns, Wheres> {}
+
+impl<'q, S, Name, Table, Columns, Wheres> Expression<'q, S>
+    for CreateIndex<Name, Table, Columns, Wheres>
+where
+    S: DatabaseExt,
+    Name: Expression<'q, S> + 'q,
+    Table: Expression<'q, S> + 'q,
+    Columns: ManyExpressions<'q, S> + 'q,
+    Wheres: ManyExpressions<'q, S> + 'q,
+{
+    #[track_caller]
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        if self.columns.is_op().not() {
+            panic!("index without columns")
+        }
+        ctx.syntax("CREATE ");
+        if self.unique {
+            ctx.syntax("UNIQUE ");
+        }
+        ctx.syntax("INDEX ");
+        if self.if_not_exists {
+            ctx.syntax("IF NOT EXISTS ");
+        }
+        self.name.expression(ctx);
+        ctx.syntax(" ON ");
+        self.table.expression(ctx);
+        self.columns.expression(" (", ", ", ctx);
+        ctx.syntax(")");
+        self.wheres.expression(" WHERE ", " AND ", ctx);
+        ctx.syntax(";");
+    }
+}
+
+/// `DROP INDEX [IF EXISTS] <name>;`
+pub struct DropIndex<Name> {
+    pub if_exists: bool,
+    pub name: Name,
+}
+
+impl<Name> OpExpression for DropIndex<Name> {}
+
+impl<'q, S, Name> Expression<'q, S> for DropIndex<Name>
+where
+    S: DatabaseExt,
+    Name: Expression<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("DROP INDEX ");
+        if self.if_exists {
+            ctx.syntax("IF EXISTS ");
+        }
+        self.name.expression(ctx);
+        ctx.syntax(";");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::Sqlite;
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        sqlx_query_builder::{
+            StatementBuilder,
+            basic_expressions::ColumnIsNotNull,
+            computed_expressions::{Descending, Lower},
+            statements::create_index_statement::{CreateIndex, DropIndex},
+        },
+    };
+
+    #[tokio::test]
+    async fn create_and_drop_indexes() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        sqlx::query(
+            "
+        CREATE TABLE Todo (id INTEGER PRIMARY KEY, title TEXT NOT NULL, done BOOLEAN NOT NULL, description TEXT);
+        INSERT INTO Todo (id, title, done, description) VALUES (1, 'First', 0, NULL), (2, 'second', 1, 'two');
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let unique = StatementBuilder::<Sqlite>::new_no_data(CreateIndex {
+            unique: true,
+            if_not_exists: false,
+            name: "todo_title_unique",
+            table: "Todo",
+            columns: Lower("title"),
+            wheres: (),
+        })
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            unique.replace("\"", "'"),
+            "CREATE UNIQUE INDEX 'todo_title_unique' ON 'Todo' (LOWER('title'));"
+        );
+
+        let partial = StatementBuilder::<Sqlite>::new_no_data(CreateIndex {
+            unique: false,
+            if_not_exists: true,
+            name: "todo_described",
+            table: "Todo",
+            columns: [Descending("done"), Descending("id")],
+            wheres: ColumnIsNotNull {
+                col: ("description",),
+            },
+        })
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            partial.replace("\"", "'"),
+            "CREATE INDEX IF NOT EXISTS 'todo_described' ON 'Todo' ('done' DESC, 'id' DESC) WHERE 'description' IS NOT NULL;"
+        );
+
+        sqlx::query(&unique).execute(&mut conn).await.unwrap();
+        sqlx::query(&partial).execute(&mut conn).await.unwrap();
+        // the same statement again is a no-op
+        sqlx::query(&partial).execute(&mut conn).await.unwrap();
+
+        // the index is on the lowered title
+        let duplicate = sqlx::query("INSERT INTO Todo (title, done) VALUES ('first', 0)")
+            .execute(&mut conn)
+            .await;
+        assert!(duplicate.is_err());
+
+        let indexes = || {
+            sqlx::query_scalar::<_, String>(
+                "SELECT name FROM sqlite_master WHERE type = 'index' AND tbl_name = 'Todo' ORDER BY name",
+            )
+        };
+        pretty_assertions::assert_eq!(
+            indexes().fetch_all(&mut conn).await.unwrap(),
+            vec![
+                "todo_described".to_string(),
+                "todo_title_unique".to_string()
+            ]
+        );
+
+        let drop = StatementBuilder::<Sqlite>::new_no_data(DropIndex {
+            if_exists: true,
+            name: "todo_title_unique",
+        })
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            drop.replace("\"", "'"),
+            "DROP INDEX IF EXISTS 'todo_title_unique';"
+        );
+        sqlx::query(&drop).execute(&mut conn).await.unwrap();
+        sqlx::query(&drop).execute(&mut conn).await.unwrap();
+
+        pretty_assertions::assert_eq!(
+            indexes().fetch_all(&mut conn).await.unwrap(),
+            vec!["todo_described".to_string()]
+        );
+        sqlx::query("INSERT INTO Todo (title, done) VALUES ('first', 0)")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{
+    database_extention::DatabaseExt,
+    sqlx_query_builder::{Expression, OpExpression, StatementBuilder},
+};
+
+/// `CREATE VIEW [IF NOT EXISTS] <name> AS <select>;`
+///
+/// `select` is a complete statement, a `SelectStatement` or a
+/// `CompoundSelect`, it renders the trailing `;`
+///
+/// the database rejects parameters in schema statements, so the select has
+/// to render without binds, build it with `StatementBuilder::new_no_data`
+pub struct CreateView<Name, Select> {
+    pub if_not_exists: bool,
+    pub name: Name,
+    pub select: Select,
+}
+
+impl<Name, Select> OpExpression for CreateView<Name, Select> {}
+
+impl<'q, S, Name, Select> Expression<'q, S> for CreateView<Name, Select>
+where
+    S: DatabaseExt,
+    Name: Expression<'q, S> + 'q,
+    Select: Expression<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("CREATE VIEW ");
+        if self.if_not_exists {
+            ctx.syntax("IF NOT EXISTS ");
+        }
+        self.name.expression(ctx);
+        ctx.syntax(" AS ");
+        self.select.expression(ctx);
+    }
+}
+
+/// `DROP VIEW [IF EXISTS] <name>;`
+pub struct DropView<Name> {
+    pub if_exists: bool,
+    pub name: Name,
+}
+
+impl<Name> OpExpression for DropView<Name> {}
+
+impl<'q, S, Name> Expression<'q, S> for DropView<Name>
+where
+    S: DatabaseExt,
+    Name: Expression<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        ctx.syntax("DROP VIEW ");
+        if self.if_exists {
+            ctx.syntax("IF EXISTS ");
+        }
+        self.name.expression(ctx);
+        ctx.syntax(";");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::Sqlite;
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        sqlx_query_builder::{
+            StatementBuilder,
+            basic_expressions::ColumnIsNotNull,
+            statements::{
+                create_view_statement::{CreateView, DropView},
+                select_statement::SelectStatement,
+            },
+        },
+    };
+
+    #[tokio::test]
+    async fn create_and_drop_views() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        sqlx::query(
+            "
+        CREATE TABLE Todo (id INTEGER PRIMARY KEY, title TEXT NOT NULL, done BOOLEAN NOT NULL, description TEXT);
+        INSERT INTO Todo (id, title, done, description) VALUES (1, 'first', 0, NULL), (2, 'second', 1, 'two');
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let create = StatementBuilder::<Sqlite>::new_no_data(CreateView {
+            if_not_exists: true,
+            name: "DescribedTodo",
+            select: SelectStatement {
+                select_items: ["id", "title"],
+                from: "Todo",
+                joins: (),
+                wheres: ColumnIsNotNull {
+                    col: ("description",),
+                },
+                group_by: (),
+                order: (),
+                limit: (),
+                offset: (),
+            },
+        })
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            create.replace("\"", "'"),
+            "CREATE VIEW IF NOT EXISTS 'DescribedTodo' AS SELECT 'id', 'title' FROM 'Todo' WHERE 'description' IS NOT NULL;"
+        );
+
+        sqlx::query(&create).execute(&mut conn).await.unwrap();
+        // the same statement again is a no-op
+        sqlx::query(&create).execute(&mut conn).await.unwrap();
+
+        let found: Vec<(i64, String)> = sqlx::query_as("SELECT id, title FROM DescribedTodo")
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+        pretty_assertions::assert_eq!(found, vec![(2, "second".to_string())]);
+
+        let drop = StatementBuilder::<Sqlite>::new_no_data(DropView {
+            if_exists: true,
+            name: "DescribedTodo",
+        })
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            drop.replace("\"", "'"),
+            "DROP VIEW IF EXISTS 'DescribedTodo';"
+        );
+        sqlx::query(&drop).execute(&mut conn).await.unwrap();
+        sqlx::query(&drop).execute(&mut conn).await.unwrap();
+
+        assert!(
+            sqlx::query("SELECT id FROM DescribedTodo")
+                .fetch_all(&mut conn)
+                .await
+                .is_err()
+        );
+    }
+}
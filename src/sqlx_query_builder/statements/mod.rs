@@ -1,13 +1,37 @@
-use crate::sqlx_query_builder::OpExpression;
+use crate::{
+    database_extention::DatabaseExt,
+    sqlx_query_builder::{Expression, ManyExpressions, OpExpression, StatementBuilder},
+};
 
 pub mod add_column_statement;
+pub mod alter_table_statement;
+pub mod compound_select;
+pub mod create_index_statement;
 pub mod create_table_statement;
+pub mod create_view_statement;
 pub mod delete_statement;
 pub mod insert_statement;
 pub mod select_statement;
 pub mod update_statement;
+pub mod with_statement;
 
 pub trait Inverse {
     type InverseStatement;
     fn inverse(&self) -> Self::InverseStatement;
 }
+
+/// statements run one after the other, usually a `ManyFlat` of them,
+/// separated by spaces
+pub struct Batch<T>(pub T);
+
+impl<T> OpExpression for Batch<T> {}
+
+impl<'q, S, T> Expression<'q, S> for Batch<T>
+where
+    S: DatabaseExt,
+    T: ManyExpressions<'q, S> + 'q,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        self.0.expression("", " ", ctx);
+    }
+}
@@ -3,11 +3,14 @@ use std::ops::Not;
 use crate::{
     database_extention::DatabaseExt,
     sqlx_query_builder::{
-        Expression, ManyExpressions, OpExpression, PossibleExpression, StatementBuilder,
+        Expression, ManyExpressions, OpExpression, PossibleExpression, SanitizeManyTupleSpec,
+        StatementBuilder,
     },
+    tuple_trait::Tuple,
 };
 
-pub struct SelectStatement<SelectItems, From, Joins, Wheres, GroupBy, Order, Limit> {
+#[derive(Clone)]
+pub struct SelectStatement<SelectItems, From, Joins, Wheres, GroupBy, Order, Limit, Offset> {
     pub select_items: SelectItems,
     pub from: From,
     pub joins: Joins,
@@ -15,15 +18,17 @@ pub struct SelectStatement<SelectItems, From, Joins, Wheres, GroupBy, Order, Lim
     pub group_by: GroupBy,
     pub order: Order,
     pub limit: Limit,
+    /// rendered only after a `LIMIT`
+    pub offset: Offset,
 }
 
-impl<SelectItems, From, Joins, GroupBy, Wheres, Limit, Order> OpExpression
-    for SelectStatement<SelectItems, From, Joins, GroupBy, Wheres, Limit, Order>
+impl<SelectItems, From, Joins, GroupBy, Wheres, Limit, Order, Offset> OpExpression
+    for SelectStatement<SelectItems, From, Joins, GroupBy, Wheres, Limit, Order, Offset>
 {
 }
 
-impl<'q, S, SelectItems, From, Joins, Wheres, Limit, Order, GroupBy> Expression<'q, S>
-    for SelectStatement<SelectItems, From, Joins, Wheres, GroupBy, Order, Limit>
+impl<'q, S, SelectItems, From, Joins, Wheres, Limit, Order, GroupBy, Offset> Expression<'q, S>
+    for SelectStatement<SelectItems, From, Joins, Wheres, GroupBy, Order, Limit, Offset>
 where
     SelectItems: ManyExpressions<'q, S> + 'q,
     From: Expression<'q, S> + 'q,
@@ -32,26 +37,325 @@ where
     Wheres: ManyExpressions<'q, S> + 'q,
     Limit: PossibleExpression<'q, S> + 'q,
     Order: ManyExpressions<'q, S> + 'q,
+    Offset: PossibleExpression<'q, S> + 'q,
 {
     #[track_caller]
     fn expression(self, ctx: &mut StatementBuilder<'q, S>)
     where
         S: DatabaseExt,
     {
+        Subquery(self).body(ctx);
+        ctx.syntax(";");
+    }
+}
+
+/// a select statement nested in another statement, renders `(SELECT ..)`
+/// without the trailing `;`
+///
+/// binds are numbered in the order they are rendered, so they continue
+/// from the outer statement
+pub struct Subquery<Select>(pub Select);
+
+impl<SelectItems, From, Joins, Wheres, GroupBy, Order, Limit, Offset>
+    Subquery<SelectStatement<SelectItems, From, Joins, Wheres, GroupBy, Order, Limit, Offset>>
+{
+    #[track_caller]
+    pub(crate) fn body<'q, S>(self, ctx: &mut StatementBuilder<'q, S>)
+    where
+        S: DatabaseExt,
+        SelectItems: ManyExpressions<'q, S> + 'q,
+        From: Expression<'q, S> + 'q,
+        Joins: ManyExpressions<'q, S> + 'q,
+        GroupBy: ManyExpressions<'q, S> + 'q,
+        Wheres: ManyExpressions<'q, S> + 'q,
+        Limit: PossibleExpression<'q, S> + 'q,
+        Order: ManyExpressions<'q, S> + 'q,
+        Offset: PossibleExpression<'q, S> + 'q,
+    {
+        let this = self.0;
         ctx.syntax("SELECT ");
-        if self.select_items.is_op().not() {
+        if this.select_items.is_op().not() {
             panic!("empty select item")
         }
-        self.select_items.expression("", ", ", ctx);
+        this.select_items.expression("", ", ", ctx);
 
         ctx.syntax(" FROM ");
-        self.from.expression(ctx);
-        self.joins.expression(" ", ", ", ctx);
-        self.wheres.expression(" WHERE ", " AND ", ctx);
-        self.group_by.expression(" GROUP BY ", ", ", ctx);
-        self.order.expression(" ORDER BY ", ", ", ctx);
+        this.from.expression(ctx);
+        this.joins.expression(" ", ", ", ctx);
+        this.wheres.expression(" WHERE ", " AND ", ctx);
+        this.group_by.expression(" GROUP BY ", ", ", ctx);
+        this.order.expression(" ORDER BY ", ", ", ctx);
 
-        self.limit.expression_starting(" LIMIT ", ctx);
-        ctx.syntax(";");
+        this.limit.expression_starting(" LIMIT ", ctx);
+        this.offset.expression_starting(" OFFSET ", ctx);
+    }
+}
+
+impl<Select> OpExpression for Subquery<Select> {}
+
+impl<'q, S, SelectItems, From, Joins, Wheres, Limit, Order, GroupBy, Offset> Expression<'q, S>
+    for Subquery<SelectStatement<SelectItems, From, Joins, Wheres, GroupBy, Order, Limit, Offset>>
+where
+    SelectItems: ManyExpressions<'q, S> + 'q,
+    From: Expression<'q, S> + 'q,
+    Joins: ManyExpressions<'q, S> + 'q,
+    GroupBy: ManyExpressions<'q, S> + 'q,
+    Wheres: ManyExpressions<'q, S> + 'q,
+    Limit: PossibleExpression<'q, S> + 'q,
+    Order: ManyExpressions<'q, S> + 'q,
+    Offset: PossibleExpression<'q, S> + 'q,
+{
+    #[track_caller]
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>)
+    where
+        S: DatabaseExt,
+    {
+        ctx.syntax("(");
+        self.body(ctx);
+        ctx.syntax(")");
+    }
+}
+
+/// scalar subquery as a select item, `(SELECT ..) AS <alias>`
+#[derive(Clone)]
+pub struct AliasedSubquery<Select, A> {
+    pub select: Select,
+    pub alias: A,
+}
+
+impl<Select, A> OpExpression for AliasedSubquery<Select, A> {}
+
+impl<'q, S, Select, A> Expression<'q, S> for AliasedSubquery<Select, A>
+where
+    S: DatabaseExt,
+    Subquery<Select>: Expression<'q, S>,
+    A: 'q + for<'s> Tuple<SanitizeManyTupleSpec<'s, 'q, S>>,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>)
+    where
+        S: DatabaseExt,
+    {
+        Expression::expression(Subquery(self.select), ctx);
+        ctx.syntax(" AS ");
+        ctx.sanitize_many(self.alias);
+    }
+}
+
+/// `<col> IN (SELECT ..)`
+#[derive(Clone)]
+pub struct InSubquery<Col, Select> {
+    pub col: Col,
+    pub select: Select,
+}
+
+impl<Col, Select> OpExpression for InSubquery<Col, Select> {}
+
+impl<'q, S, Col, Select> Expression<'q, S> for InSubquery<Col, Select>
+where
+    S: DatabaseExt,
+    Col: Expression<'q, S> + 'q,
+    Subquery<Select>: Expression<'q, S>,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>)
+    where
+        S: DatabaseExt,
+    {
+        Expression::expression(self.col, ctx);
+        ctx.syntax(" IN ");
+        Expression::expression(Subquery(self.select), ctx);
+    }
+}
+
+/// `EXISTS (SELECT ..)`
+#[derive(Clone)]
+pub struct Exists<Select>(pub Select);
+
+impl<Select> OpExpression for Exists<Select> {}
+
+impl<'q, S, Select> Expression<'q, S> for Exists<Select>
+where
+    S: DatabaseExt,
+    Subquery<Select>: Expression<'q, S>,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>)
+    where
+        S: DatabaseExt,
+    {
+        ctx.syntax("EXISTS ");
+        Expression::expression(Subquery(self.0), ctx);
+    }
+}
+
+/// `NOT EXISTS (SELECT ..)`, useful for anti-joins
+#[derive(Clone)]
+pub struct NotExists<Select>(pub Select);
+
+impl<Select> OpExpression for NotExists<Select> {}
+
+impl<'q, S, Select> Expression<'q, S> for NotExists<Select>
+where
+    S: DatabaseExt,
+    Subquery<Select>: Expression<'q, S>,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>)
+    where
+        S: DatabaseExt,
+    {
+        ctx.syntax("NOT EXISTS ");
+        Expression::expression(Subquery(self.0), ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::Sqlite;
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        sqlx_query_builder::{
+            StatementBuilder,
+            basic_expressions::{ColumnEqual, ColumnEqualColumn, ScopedColumn},
+            statements::select_statement::{
+                AliasedSubquery, Exists, InSubquery, NotExists, SelectStatement,
+            },
+            trait_objects::BoxedExpression,
+        },
+    };
+
+    fn select<SelectItems, From, Wheres>(
+        select_items: SelectItems,
+        from: From,
+        wheres: Wheres,
+    ) -> SelectStatement<SelectItems, From, (), Wheres, (), (), (), ()> {
+        SelectStatement {
+            select_items,
+            from,
+            joins: (),
+            wheres,
+            group_by: (),
+            order: (),
+            limit: (),
+            offset: (),
+        }
+    }
+
+    #[tokio::test]
+    async fn subqueries() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        sqlx::query(
+            "
+        CREATE TABLE Todo (id INTEGER PRIMARY KEY, title TEXT NOT NULL);
+        CREATE TABLE Tag (todo_id INTEGER NOT NULL, name TEXT NOT NULL);
+        INSERT INTO Todo (id, title) VALUES (1, 'first'), (2, 'second'), (3, 'third');
+        INSERT INTO Tag (todo_id, name) VALUES (1, 'home'), (1, 'work'), (3, 'work');
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let tagged = |tag: &'static str| {
+            select(
+                "todo_id",
+                "Tag",
+                [
+                    Box::new(ColumnEqual {
+                        col: "name",
+                        eq: tag.to_string(),
+                    }) as Box<dyn BoxedExpression<Sqlite> + Send>,
+                    Box::new(ColumnEqualColumn {
+                        col: ScopedColumn {
+                            table: ("Tag",),
+                            col: ("todo_id",),
+                        },
+                        other: ScopedColumn {
+                            table: ("Todo",),
+                            col: ("id",),
+                        },
+                    }),
+                ],
+            )
+        };
+
+        // binds inside the subquery continue the numbering of the outer statement
+        let (stmt, args) = StatementBuilder::<Sqlite>::new(select(
+            "title",
+            "Todo",
+            [
+                Box::new(ColumnEqual {
+                    col: "title",
+                    eq: "third".to_string(),
+                }) as Box<dyn BoxedExpression<Sqlite> + Send>,
+                Box::new(Exists(tagged("work"))),
+            ],
+        ))
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            stmt.replace("\"", "'"),
+            "SELECT 'title' FROM 'Todo' WHERE 'title' = $1 AND EXISTS (SELECT 'todo_id' FROM 'Tag' WHERE 'name' = $2 AND 'Tag'.'todo_id' = 'Todo'.'id');"
+        );
+        let found: Vec<String> = sqlx::query_scalar_with(&stmt, args)
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+        pretty_assertions::assert_eq!(found, vec!["third"]);
+
+        let (stmt, args) =
+            StatementBuilder::<Sqlite>::new(select("title", "Todo", NotExists(tagged("work"))))
+                .unwrap();
+        let found: Vec<String> = sqlx::query_scalar_with(&stmt, args)
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+        pretty_assertions::assert_eq!(found, vec!["second"]);
+
+        let (stmt, args) = StatementBuilder::<Sqlite>::new(select(
+            "title",
+            "Todo",
+            InSubquery {
+                col: "id",
+                select: select(
+                    "todo_id",
+                    "Tag",
+                    ColumnEqual {
+                        col: "name",
+                        eq: "home",
+                    },
+                ),
+            },
+        ))
+        .unwrap();
+        let found: Vec<String> = sqlx::query_scalar_with(&stmt, args)
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+        pretty_assertions::assert_eq!(found, vec!["first"]);
+
+        let (stmt, args) = StatementBuilder::<Sqlite>::new(select(
+            AliasedSubquery {
+                select: select(
+                    "name",
+                    "Tag",
+                    ColumnEqual {
+                        col: "todo_id",
+                        eq: 3,
+                    },
+                ),
+                alias: ("tag",),
+            },
+            "Todo",
+            ColumnEqual { col: "id", eq: 1 },
+        ))
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            stmt.replace("\"", "'"),
+            "SELECT (SELECT 'name' FROM 'Tag' WHERE 'todo_id' = $1) AS 'tag' FROM 'Todo' WHERE 'id' = $2;"
+        );
+        let tag: String = sqlx::query_scalar_with(&stmt, args)
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        pretty_assertions::assert_eq!(tag, "work");
     }
 }
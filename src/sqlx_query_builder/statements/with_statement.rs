@@ -0,0 +1,288 @@
+use std::ops::Not;
+
+use crate::{
+    database_extention::DatabaseExt,
+    sqlx_query_builder::{
+        Expression, ManyExpressions, OpExpression, StatementBuilder,
+        statements::select_statement::Subquery,
+    },
+};
+
+/// `WITH [RECURSIVE] <ctes> <body>`
+///
+/// `body` is a complete statement (`SelectStatement`, `UpdateStatement`,
+/// `DeleteStatement`..) that can refer to the ctes by name
+pub struct WithStatement<Ctes, Body> {
+    pub recursive: bool,
+    pub ctes: Ctes,
+    pub body: Body,
+}
+
+impl<Ctes, Body> OpExpression for WithStatement<Ctes, Body> {}
+
+impl<'q, S, Ctes, Body> Expression<'q, S> for WithStatement<Ctes, Body>
+where
+    S: DatabaseExt,
+    Ctes: ManyExpressions<'q, S> + 'q,
+    Body: Expression<'q, S> + 'q,
+{
+    #[track_caller]
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        if self.ctes.is_op().not() {
+            panic!("empty with statement")
+        }
+        ctx.syntax("WITH ");
+        if self.recursive {
+            ctx.syntax("RECURSIVE ");
+        }
+        self.ctes.expression("", ", ", ctx);
+        ctx.syntax(" ");
+        self.body.expression(ctx);
+    }
+}
+
+/// `<name> [(<columns>)] AS (<query>)`, one named query of a `WithStatement`
+#[derive(Clone)]
+pub struct Cte<Name, Columns, Query> {
+    pub name: Name,
+    /// optional, when `()` the columns are named after the query's
+    pub columns: Columns,
+    pub query: Query,
+}
+
+impl<Name, Columns, Query> OpExpression for Cte<Name, Columns, Query> {}
+
+impl<'q, S, Name, Columns, Query> Expression<'q, S> for Cte<Name, Columns, Query>
+where
+    S: DatabaseExt,
+    Name: Expression<'q, S> + 'q,
+    Columns: ManyExpressions<'q, S> + 'q,
+    Query: 'q,
+    Subquery<Query>: Expression<'q, S>,
+{
+    fn expression(self, ctx: &mut StatementBuilder<'q, S>) {
+        self.name.expression(ctx);
+        if self.columns.is_op() {
+            self.columns.expression(" (", ", ", ctx);
+            ctx.syntax(")");
+        }
+        ctx.syntax(" AS ");
+        Expression::expression(Subquery(self.query), ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::Sqlite;
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        sqlx_query_builder::{
+            StatementBuilder,
+            basic_expressions::{Bind, ColumnEqual, ColumnLessThan, UpdatingColumn},
+            computed_expressions::Add,
+            statements::{
+                compound_select::{CompoundSelect, UnionAll},
+                delete_statement::DeleteStatement,
+                select_statement::{InSubquery, SelectStatement},
+                update_statement::UpdateStatement,
+                with_statement::{Cte, WithStatement},
+            },
+        },
+    };
+
+    fn select<SelectItems, From, Wheres>(
+        select_items: SelectItems,
+        from: From,
+        wheres: Wheres,
+    ) -> SelectStatement<SelectItems, From, (), Wheres, (), (), (), ()> {
+        SelectStatement {
+            select_items,
+            from,
+            joins: (),
+            wheres,
+            group_by: (),
+            order: (),
+            limit: (),
+            offset: (),
+        }
+    }
+
+    #[tokio::test]
+    async fn with_select_update_and_delete() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        sqlx::query(
+            "
+        CREATE TABLE Todo (id INTEGER PRIMARY KEY, title TEXT NOT NULL, done BOOLEAN NOT NULL);
+        INSERT INTO Todo (id, title, done) VALUES (1, 'first', 0), (2, 'second', 1), (3, 'third', 0);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let pending = || Cte {
+            name: "pending",
+            columns: vec!["pending_id"],
+            query: select(
+                "id",
+                "Todo",
+                ColumnEqual {
+                    col: "done",
+                    eq: false,
+                },
+            ),
+        };
+
+        let (stmt, args) = StatementBuilder::<Sqlite>::new(WithStatement {
+            recursive: false,
+            ctes: pending(),
+            body: select(
+                "pending_id",
+                "pending",
+                ColumnLessThan {
+                    col: "pending_id",
+                    lt: 3,
+                },
+            ),
+        })
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            stmt.replace("\"", "'"),
+            "WITH 'pending' ('pending_id') AS (SELECT 'id' FROM 'Todo' WHERE 'done' = $1) SELECT 'pending_id' FROM 'pending' WHERE 'pending_id' < $2;"
+        );
+        let found: Vec<i64> = sqlx::query_scalar_with(&stmt, args)
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+        pretty_assertions::assert_eq!(found, vec![1]);
+
+        let (stmt, args) = StatementBuilder::<Sqlite>::new(WithStatement {
+            recursive: false,
+            ctes: pending(),
+            body: UpdateStatement {
+                table_name: "Todo",
+                values: UpdatingColumn {
+                    col: ("title",),
+                    set: Some("pending"),
+                },
+                wheres: InSubquery {
+                    col: "id",
+                    select: select("pending_id", "pending", ()),
+                },
+                returning: (),
+            },
+        })
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            stmt.replace("\"", "'"),
+            "WITH 'pending' ('pending_id') AS (SELECT 'id' FROM 'Todo' WHERE 'done' = $1) UPDATE 'Todo' SET 'title' = $2 WHERE 'id' IN (SELECT 'pending_id' FROM 'pending');"
+        );
+        sqlx::query_with(&stmt, args)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let (stmt, args) = StatementBuilder::<Sqlite>::new(WithStatement {
+            recursive: false,
+            ctes: [
+                Cte {
+                    name: "done",
+                    columns: (),
+                    query: select(
+                        "id",
+                        "Todo",
+                        ColumnEqual {
+                            col: "done",
+                            eq: true,
+                        },
+                    ),
+                },
+                Cte {
+                    name: "pending",
+                    columns: (),
+                    query: select(
+                        "id",
+                        "Todo",
+                        ColumnEqual {
+                            col: "done",
+                            eq: false,
+                        },
+                    ),
+                },
+            ],
+            body: DeleteStatement {
+                table_name: "Todo",
+                wheres: InSubquery {
+                    col: "id",
+                    select: select("id", "done", ()),
+                },
+                returning: (),
+            },
+        })
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            stmt.replace("\"", "'"),
+            "WITH 'done' AS (SELECT 'id' FROM 'Todo' WHERE 'done' = $1), 'pending' AS (SELECT 'id' FROM 'Todo' WHERE 'done' = $2) DELETE FROM 'Todo' WHERE 'id' IN (SELECT 'id' FROM 'done');"
+        );
+        sqlx::query_with(&stmt, args)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let left: Vec<(i64, String)> = sqlx::query_as("SELECT id, title FROM Todo")
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+        pretty_assertions::assert_eq!(
+            left,
+            vec![(1, "pending".to_string()), (3, "pending".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn recursive_cte() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        sqlx::query(
+            "
+        CREATE TABLE Todo (id INTEGER PRIMARY KEY, title TEXT NOT NULL, done BOOLEAN NOT NULL);
+        INSERT INTO Todo (id, title, done) VALUES (1, 'first', 0), (2, 'second', 1);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        // the ids following the first todo, whether or not a todo has them
+        let (stmt, args) = StatementBuilder::<Sqlite>::new(WithStatement {
+            recursive: true,
+            ctes: Cte {
+                name: "seq",
+                columns: vec!["x"],
+                query: CompoundSelect {
+                    compound: UnionAll(
+                        select("id", "Todo", ColumnEqual { col: "id", eq: 1 }),
+                        select(Add("x", Bind(1)), "seq", ColumnLessThan { col: "x", lt: 4 }),
+                    ),
+                    order: (),
+                    limit: (),
+                    offset: (),
+                },
+            },
+            body: select("x", "seq", ()),
+        })
+        .unwrap();
+        pretty_assertions::assert_eq!(
+            stmt.replace("\"", "'"),
+            "WITH RECURSIVE 'seq' ('x') AS (SELECT 'id' FROM 'Todo' WHERE 'id' = $1 UNION ALL SELECT ('x' + $2) FROM 'seq' WHERE 'x' < $3) SELECT 'x' FROM 'seq';"
+        );
+
+        let found: Vec<i64> = sqlx::query_scalar_with(&stmt, args)
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+        pretty_assertions::assert_eq!(found, vec![1, 2, 3, 4]);
+    }
+}
@@ -0,0 +1,279 @@
+//! which generated statements are kept prepared on the connection.
+//!
+//! sqlx prepares a statement on every call unless `Execute::persistent` is
+//! set, then the connection keeps it in its own statement cache, bounded by
+//! `statement_cache_capacity` of the connect options and keyed by the SQL.
+//! operations whose SQL only depends on their types persist by default.
+//! `FETCH_MANY` (cursor or offset pages, `Vec` wheres), `UPDATE` (kept fields
+//! are left out of the `SET`) and `DYNAMIC` render a different statement
+//! from call to call, so they don't, and one-off statements don't push the
+//! others out of the connection's cache.
+//!
+//! persistence is set per operation, for every connection, with
+//! `CachedOperation::set_persistent`.
+//!
+//! every operation also remembers the shapes of its last `SHAPE_CAPACITY`
+//! statements, keyed by their SQL, and counts how often a statement had been
+//! seen before. this is not the cache of any connection, a high hit rate in
+//! `CachedOperation::metrics` says that the operation repeats its SQL and
+//! persisting it pays off.
+
+use std::{
+    collections::{BTreeSet, VecDeque},
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+};
+
+/// the statement cache of a sqlite connection holds 100 statements
+pub const SHAPE_CAPACITY: usize = 100;
+
+/// the statements of an operation and whether they persist
+#[derive(Debug)]
+pub struct CachedOperation {
+    name: &'static str,
+    default: bool,
+    persistent: AtomicBool,
+    shapes: Mutex<Shapes>,
+    lookups: AtomicU64,
+    hits: AtomicU64,
+    evictions: AtomicU64,
+}
+
+/// the hashes of the last `SHAPE_CAPACITY` statements, oldest first
+#[derive(Debug)]
+struct Shapes {
+    seen: BTreeSet<u64>,
+    order: VecDeque<u64>,
+}
+
+/// how often an operation rendered a statement it had seen before
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeMetrics {
+    pub operation: &'static str,
+    pub lookups: u64,
+    pub hits: u64,
+    pub evictions: u64,
+    /// distinct statements currently remembered
+    pub shapes: usize,
+}
+
+impl ShapeMetrics {
+    /// `hits / lookups`, `0.0` before the first statement
+    pub fn hit_rate(&self) -> f64 {
+        if self.lookups == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / self.lookups as f64
+    }
+}
+
+impl CachedOperation {
+    pub const fn new(name: &'static str, persistent: bool) -> Self {
+        Self {
+            name,
+            default: persistent,
+            persistent: AtomicBool::new(persistent),
+            shapes: Mutex::new(Shapes {
+                seen: BTreeSet::new(),
+                order: VecDeque::new(),
+            }),
+            lookups: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn persistent(&self) -> bool {
+        self.persistent.load(Ordering::Relaxed)
+    }
+
+    /// overrides the default persistence, `None` restores it
+    pub fn set_persistent(&self, persistent: Option<bool>) {
+        self.persistent
+            .store(persistent.unwrap_or(self.default), Ordering::Relaxed);
+    }
+
+    /// remembers the shape of `sql`, true if it was already there
+    pub fn record(&self, sql: &str) -> bool {
+        let mut hasher = DefaultHasher::new();
+        sql.hash(&mut hasher);
+        let key = hasher.finish();
+
+        self.lookups.fetch_add(1, Ordering::Relaxed);
+
+        let mut shapes = self.shapes.lock().unwrap_or_else(|e| e.into_inner());
+        if shapes.seen.contains(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return true;
+        }
+
+        shapes.seen.insert(key);
+        shapes.order.push_back(key);
+        if shapes.order.len() > SHAPE_CAPACITY {
+            if let Some(oldest) = shapes.order.pop_front() {
+                shapes.seen.remove(&oldest);
+            }
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+        false
+    }
+
+    pub fn metrics(&self) -> ShapeMetrics {
+        let shapes = self
+            .shapes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .order
+            .len();
+        ShapeMetrics {
+            operation: self.name,
+            lookups: self.lookups.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            shapes,
+        }
+    }
+
+    /// forgets the remembered shapes and zeroes the metrics
+    pub fn reset_metrics(&self) {
+        let mut shapes = self.shapes.lock().unwrap_or_else(|e| e.into_inner());
+        shapes.seen.clear();
+        shapes.order.clear();
+        self.lookups.store(0, Ordering::Relaxed);
+        self.hits.store(0, Ordering::Relaxed);
+        self.evictions.store(0, Ordering::Relaxed);
+    }
+}
+
+pub static FETCH_ONE: CachedOperation = CachedOperation::new("fetch_one", true);
+/// the SQL depends on the kind of page and on runtime sized wheres
+pub static FETCH_MANY: CachedOperation = CachedOperation::new("fetch_many", false);
+pub static FETCH_STREAM: CachedOperation = CachedOperation::new("fetch_stream", true);
+pub static INSERT_ONE: CachedOperation = CachedOperation::new("insert_one", true);
+/// the `SET` leaves out kept fields
+pub static UPDATE: CachedOperation = CachedOperation::new("update", false);
+pub static DELETE: CachedOperation = CachedOperation::new("delete", true);
+/// `links::soft_delete::operations::Restore`
+pub static SOFT_DELETE: CachedOperation = CachedOperation::new("soft_delete", true);
+/// the versions stamped by `Audited` and read by `FetchHistory`
+pub static AUDIT_LOG: CachedOperation = CachedOperation::new("audit_log", true);
+pub static VERSIONED: CachedOperation = CachedOperation::new("versioned", true);
+/// SQL whose shape is built from runtime input
+pub static DYNAMIC: CachedOperation = CachedOperation::new("dynamic", false);
+
+/// the metrics of every operation above
+pub fn metrics() -> Vec<ShapeMetrics> {
+    [
+        &FETCH_ONE,
+        &FETCH_MANY,
+        &FETCH_STREAM,
+        &INSERT_ONE,
+        &UPDATE,
+        &DELETE,
+        &SOFT_DELETE,
+        &AUDIT_LOG,
+        &VERSIONED,
+        &DYNAMIC,
+    ]
+    .into_iter()
+    .map(CachedOperation::metrics)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Not;
+
+    use sqlx::{Connection, Executor, Sqlite, sqlite::SqliteArguments};
+
+    use super::{CachedOperation, DYNAMIC, FETCH_MANY, FETCH_ONE, SHAPE_CAPACITY, ShapeMetrics};
+    use crate::{connect_in_memory::ConnectInMemory, execute::Executable};
+
+    #[tokio::test]
+    async fn persistent_statements_stay_on_the_connection() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        assert!(FETCH_ONE.persistent());
+        assert!(FETCH_MANY.persistent().not());
+        assert!(DYNAMIC.persistent().not());
+
+        for sql in ["SELECT 1;", "SELECT 1;"] {
+            conn.execute(Executable::cached(
+                &FETCH_ONE,
+                sql,
+                SqliteArguments::default(),
+            ))
+            .await
+            .unwrap();
+        }
+        conn.execute(Executable::cached(
+            &DYNAMIC,
+            "SELECT 2;",
+            SqliteArguments::default(),
+        ))
+        .await
+        .unwrap();
+
+        // only the typed statement, once
+        pretty_assertions::assert_eq!(conn.cached_statements_size(), 1);
+    }
+
+    #[test]
+    fn override_persistence() {
+        static OPERATION: CachedOperation = CachedOperation::new("test", false);
+
+        OPERATION.set_persistent(Some(true));
+        assert!(OPERATION.persistent());
+        OPERATION.set_persistent(None);
+        assert!(OPERATION.persistent().not());
+    }
+
+    #[test]
+    fn shape_hit_rate() {
+        static OPERATION: CachedOperation = CachedOperation::new("test", false);
+
+        assert!(OPERATION.record("SELECT 1;").not());
+        assert!(OPERATION.record("SELECT 1;"));
+        assert!(OPERATION.record("SELECT 2;").not());
+        assert!(OPERATION.record("SELECT 1;"));
+
+        let metrics = OPERATION.metrics();
+        pretty_assertions::assert_eq!(
+            metrics,
+            ShapeMetrics {
+                operation: "test",
+                lookups: 4,
+                hits: 2,
+                evictions: 0,
+                shapes: 2,
+            }
+        );
+        pretty_assertions::assert_eq!(metrics.hit_rate(), 0.5);
+    }
+
+    #[test]
+    fn shapes_are_bounded() {
+        static OPERATION: CachedOperation = CachedOperation::new("test", false);
+
+        for i in 0..=SHAPE_CAPACITY {
+            OPERATION.record(&format!("SELECT {i};"));
+        }
+
+        let metrics = OPERATION.metrics();
+        pretty_assertions::assert_eq!(metrics.shapes, SHAPE_CAPACITY);
+        pretty_assertions::assert_eq!(metrics.evictions, 1);
+        // the oldest shape was evicted, the newest is still there
+        assert!(OPERATION.record("SELECT 0;").not());
+        assert!(OPERATION.record(&format!("SELECT {SHAPE_CAPACITY};")));
+
+        OPERATION.reset_metrics();
+        pretty_assertions::assert_eq!(OPERATION.metrics().lookups, 0);
+    }
+}
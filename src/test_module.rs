@@ -1,6 +1,20 @@
 macro_rules! define_collection {
     (struct $pascal_case:ident $size:literal {$(
         $member:ident: $type:ty,
+    )*}) => {
+        define_collection!(@soft_delete false struct $pascal_case $size {$(
+            $member: $type,
+        )*});
+    };
+    (#[soft_delete] struct $pascal_case:ident $size:literal {$(
+        $member:ident: $type:ty,
+    )*}) => {
+        define_collection!(@soft_delete true struct $pascal_case $size {$(
+            $member: $type,
+        )*});
+    };
+    (@soft_delete $soft_delete:literal struct $pascal_case:ident $size:literal {$(
+        $member:ident: $type:ty,
     )*}) => {
         const _: ()  ={
             if $size == 0 {
@@ -90,6 +104,9 @@ macro_rules! define_collection {
                 fn id(&self) -> Self::Id {
                     SingleIncremintalInt(stringify!($pascal_case))
                 }
+                fn soft_delete(&self) -> bool {
+                    $soft_delete
+                }
             }
         };
 
@@ -330,10 +347,13 @@ macro_rules! define_collection {
                 {
                     ctx.syntax(start);
 
+                    let mut sep = "";
                     $(
+                        ctx.syntax(sep);
                         ctx.bind(self.$member);
-                        ctx.syntax(join);
+                        sep = join;
                     )*
+                    let _ = sep;
                 }
             }
         };
@@ -380,14 +400,17 @@ macro_rules! define_collection {
                 {
                     if self.is_op() { ctx.syntax(start); }
 
+                    let mut sep = "";
                     $(
                         if let Update::Set(value) = self.$member {
+                            ctx.syntax(sep);
                             ctx.syntax(stringify!($member));
                             ctx.syntax(" = ");
                             ctx.bind(value);
-                            ctx.syntax(join);
+                            sep = join;
                         }
                     )*
+                    let _ = sep;
                 }
             }
         };
@@ -457,6 +480,28 @@ macro_rules! define_collection {
                     }
                 }
 
+                impl<'q, S> crate::operations::cursor::CursorOrder<'q, S> for $member
+                where
+                    S: crate::database_extention::DatabaseExt,
+                    $type: sqlx::Encode<'q, S> + sqlx::Type<S> + Clone + crate::is_null::IsNull + 'q,
+                {
+                    fn keys_len(&self) -> usize {
+                        1
+                    }
+                    fn descending(&self, _: usize) -> bool {
+                        false
+                    }
+                    fn nullable(&self, _: usize) -> bool {
+                        <$type as crate::is_null::IsNull>::is_null()
+                    }
+                    fn key(&self, _: usize, ctx: &mut crate::sqlx_query_builder::StatementBuilder<'q, S>) {
+                        crate::sqlx_query_builder::Expression::expression(self.scoped(), ctx)
+                    }
+                    fn value(&self, value: &$type, _: usize, ctx: &mut crate::sqlx_query_builder::StatementBuilder<'q, S>) {
+                        ctx.bind(value.clone())
+                    }
+                }
+
                 impl ExpressionsForOperation for $member {
                     type Identifier = &'static str;
                     fn identifier(&self) -> Self::Identifier {
@@ -543,3 +588,16 @@ define_collection!(
         title: String,
     }
 );
+
+define_collection!(
+    struct OpenTodo 1 {
+        title: String,
+    }
+);
+
+define_collection!(
+    #[soft_delete]
+    struct Note 1 {
+        title: String,
+    }
+);
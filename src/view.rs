@@ -0,0 +1,344 @@
+//! read-only collections backed by a `CREATE VIEW`
+//!
+//! a `View` reads like its `base` collection, so `FetchOne` and `FetchMany`
+//! work on it as is, but its id has no `Identifier` expression and it takes
+//! `ReadOnly` as input, so `Insert`, `Update` and `Delete` don't compile.
+//!
+//! the base collection names the view and its columns, so `select` has to
+//! return an `id` column and every column of the base by name.
+
+use crate::{
+    collections::{Collection, CollectionId},
+    from_row::{
+        FromRowAlias, FromRowData, FromRowError, RowPostAliased, RowPreAliased, RowTwoAliased,
+    },
+    on_migrate::OnMigrate,
+    operations::operations_expressions_crossover::{ExpressionsForOperation, TableExpressions},
+    sqlx_query_builder::statements::create_view_statement::CreateView,
+};
+
+#[derive(Clone, Debug)]
+pub struct View<Base, Select> {
+    pub base: Base,
+    /// a `SelectStatement` or a `CompoundSelect` without binds
+    pub select: Select,
+}
+
+/// the input of writes to a view and the identifier of its id, it isn't
+/// an expression so no write operation accepts it
+///
+/// ```compile_fail
+/// # use claw_ql::{operations::{Operation, insert::InsertOne, insert_id_mode::AutoGenerate}, sqlx_query_builder::statements::insert_statement::One, test_module::OpenTodoHandler, view::{ReadOnly, View}};
+/// # async fn insert(view: View<OpenTodoHandler, ()>, conn: &mut sqlx::SqliteConnection) {
+/// Operation::<sqlx::Sqlite>::exec_operation(
+///     InsertOne { id: AutoGenerate, data: One(ReadOnly), handler: view, links: () },
+///     conn,
+/// )
+/// .await;
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// # use claw_ql::{operations::{Operation, update::Update}, test_module::OpenTodoHandler, view::{ReadOnly, View}};
+/// # async fn update(view: View<OpenTodoHandler, ()>, conn: &mut sqlx::SqliteConnection) {
+/// Operation::<sqlx::Sqlite>::exec_operation(
+///     Update { base: view, partial: ReadOnly, wheres: (), links: () },
+///     conn,
+/// )
+/// .await;
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// # use claw_ql::{operations::{Operation, delete::Delete}, test_module::OpenTodoHandler, view::View};
+/// # async fn delete(view: View<OpenTodoHandler, ()>, conn: &mut sqlx::SqliteConnection) {
+/// Operation::<sqlx::Sqlite>::exec_operation(Delete { base: view, wheres: (), links: () }, conn)
+///     .await;
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReadOnly;
+
+/// the id of a view, reads like `Id` but can't be written
+#[derive(Clone, Debug)]
+pub struct ReadOnlyId<Id>(pub Id);
+
+impl<Base, Select> Collection for View<Base, Select>
+where
+    Base: Collection,
+{
+    fn table_name(&self) -> &str {
+        self.base.table_name()
+    }
+    fn table_name_lower_case(&self) -> &str {
+        self.base.table_name_lower_case()
+    }
+    type InputData = ReadOnly;
+    type UpdateData = ReadOnly;
+    type OutputData = Base::OutputData;
+    type Id = ReadOnlyId<Base::Id>;
+    fn id(&self) -> Self::Id {
+        ReadOnlyId(self.base.id())
+    }
+}
+
+impl<Base, Select> FromRowData for View<Base, Select>
+where
+    Base: FromRowData,
+{
+    type RData = Base::RData;
+}
+
+impl<'r, R, Base, Select> FromRowAlias<'r, R> for View<Base, Select>
+where
+    Base: FromRowAlias<'r, R>,
+{
+    fn no_alias(&self, row: &'r R) -> Result<Self::RData, FromRowError> {
+        self.base.no_alias(row)
+    }
+    fn pre_alias(&self, row: RowPreAliased<'r, R>) -> Result<Self::RData, FromRowError>
+    where
+        R: sqlx::Row,
+    {
+        self.base.pre_alias(row)
+    }
+    fn post_alias(&self, row: RowPostAliased<'r, R>) -> Result<Self::RData, FromRowError>
+    where
+        R: sqlx::Row,
+    {
+        self.base.post_alias(row)
+    }
+    fn two_alias(&self, row: RowTwoAliased<'r, R>) -> Result<Self::RData, FromRowError>
+    where
+        R: sqlx::Row,
+    {
+        self.base.two_alias(row)
+    }
+}
+
+impl<Base, Select> ExpressionsForOperation for View<Base, Select>
+where
+    Base: ExpressionsForOperation,
+{
+    type Identifier = Base::Identifier;
+    fn identifier(&self) -> Self::Identifier {
+        self.base.identifier()
+    }
+    type Scoped = Base::Scoped;
+    fn scoped(&self) -> Self::Scoped {
+        self.base.scoped()
+    }
+    type ScopedAliased = Base::ScopedAliased;
+    fn scoped_aliased(&self, alias: &'static str) -> Self::ScopedAliased {
+        self.base.scoped_aliased(alias)
+    }
+    type NumScopedAliased = Base::NumScopedAliased;
+    fn num_scoped_aliased(&self, num: usize, alias: &'static str) -> Self::NumScopedAliased {
+        self.base.num_scoped_aliased(num, alias)
+    }
+}
+
+/// migrates the view, not the table of `base`
+impl<Base, Select> TableExpressions for View<Base, Select>
+where
+    Base: TableExpressions,
+    Select: Clone,
+{
+    type SnakeCase = Base::SnakeCase;
+    type PascalCase = Base::PascalCase;
+    fn table_name_snake_case(&self) -> Self::SnakeCase {
+        self.base.table_name_snake_case()
+    }
+    fn table_name_pascal_case(&self) -> Self::PascalCase {
+        self.base.table_name_pascal_case()
+    }
+    type Migrate = CreateView<Base::PascalCase, Select>;
+    fn migrate(&self) -> Self::Migrate {
+        CreateView {
+            if_not_exists: false,
+            name: self.base.table_name_pascal_case(),
+            select: self.select.clone(),
+        }
+    }
+}
+
+impl<Base, Select> OnMigrate for View<Base, Select>
+where
+    Base: TableExpressions,
+    Select: Clone,
+{
+    type Statements = CreateView<Base::PascalCase, Select>;
+
+    fn statments(&self) -> Self::Statements {
+        self.migrate()
+    }
+}
+
+impl<Id> CollectionId for ReadOnlyId<Id>
+where
+    Id: CollectionId,
+{
+    type IdData = Id::IdData;
+}
+
+impl<Id> FromRowData for ReadOnlyId<Id>
+where
+    Id: FromRowData,
+{
+    type RData = Id::RData;
+}
+
+impl<'r, R, Id> FromRowAlias<'r, R> for ReadOnlyId<Id>
+where
+    Id: FromRowAlias<'r, R>,
+{
+    fn no_alias(&self, row: &'r R) -> Result<Self::RData, FromRowError> {
+        self.0.no_alias(row)
+    }
+    fn pre_alias(&self, row: RowPreAliased<'r, R>) -> Result<Self::RData, FromRowError>
+    where
+        R: sqlx::Row,
+    {
+        self.0.pre_alias(row)
+    }
+    fn post_alias(&self, row: RowPostAliased<'r, R>) -> Result<Self::RData, FromRowError>
+    where
+        R: sqlx::Row,
+    {
+        self.0.post_alias(row)
+    }
+    fn two_alias(&self, row: RowTwoAliased<'r, R>) -> Result<Self::RData, FromRowError>
+    where
+        R: sqlx::Row,
+    {
+        self.0.two_alias(row)
+    }
+}
+
+impl<Id> ExpressionsForOperation for ReadOnlyId<Id>
+where
+    Id: ExpressionsForOperation,
+{
+    type Identifier = ReadOnly;
+    fn identifier(&self) -> Self::Identifier {
+        ReadOnly
+    }
+    type Scoped = Id::Scoped;
+    fn scoped(&self) -> Self::Scoped {
+        self.0.scoped()
+    }
+    type ScopedAliased = Id::ScopedAliased;
+    fn scoped_aliased(&self, alias: &'static str) -> Self::ScopedAliased {
+        self.0.scoped_aliased(alias)
+    }
+    type NumScopedAliased = Id::NumScopedAliased;
+    fn num_scoped_aliased(&self, num: usize, alias: &'static str) -> Self::NumScopedAliased {
+        self.0.num_scoped_aliased(num, alias)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::Sqlite;
+
+    use crate::{
+        connect_in_memory::ConnectInMemory,
+        on_migrate::OnMigrate,
+        operations::{
+            Operation,
+            fetch_many::{FetchMany, Pagination},
+            fetch_one::FetchOne,
+            operations_expressions_crossover::TableExpressions,
+        },
+        sqlx_query_builder::{
+            StatementBuilder, basic_expressions::ColumnEqual, column_constraints::Literal,
+            computed_expressions::Equal, statements::select_statement::SelectStatement,
+        },
+        test_module::{OpenTodo, OpenTodoHandler, open_todo_members},
+        view::View,
+    };
+
+    #[tokio::test]
+    async fn read_a_view() {
+        let mut conn = Sqlite::in_memory_connection().await;
+
+        sqlx::query(
+            "
+        CREATE TABLE Todo (id INTEGER PRIMARY KEY, title TEXT NOT NULL, done BOOLEAN NOT NULL, description TEXT);
+        INSERT INTO Todo (id, title, done, description) VALUES (1, 'first', 0, NULL), (2, 'second', 1, NULL), (3, 'third', 0, NULL);
+    ",
+        )
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+        let view = View {
+            base: OpenTodoHandler,
+            select: SelectStatement {
+                select_items: ["id", "title"],
+                from: "Todo",
+                joins: (),
+                wheres: Equal("done", Literal(false)),
+                group_by: (),
+                order: (),
+                limit: (),
+                offset: (),
+            },
+        };
+
+        let migration = StatementBuilder::<Sqlite>::new_no_data(view.statments()).unwrap();
+        pretty_assertions::assert_eq!(
+            migration,
+            r#"CREATE VIEW "OpenTodo" AS SELECT "id", "title" FROM "Todo" WHERE "done" = 0;"#
+        );
+        pretty_assertions::assert_eq!(
+            StatementBuilder::<Sqlite>::new_no_data(view.migrate()),
+            Some(migration.clone())
+        );
+        sqlx::query(&migration).execute(&mut conn).await.unwrap();
+
+        let one = Operation::<Sqlite>::exec_operation(
+            FetchOne {
+                base: view.clone(),
+                links: (),
+                wheres: ColumnEqual {
+                    col: "id",
+                    eq: 3_i64,
+                },
+            },
+            &mut conn,
+        )
+        .await
+        .unwrap();
+        pretty_assertions::assert_eq!(one.id, 3);
+        pretty_assertions::assert_eq!(
+            one.attributes,
+            OpenTodo {
+                title: "third".to_string()
+            }
+        );
+
+        let many = Operation::<Sqlite>::exec_operation(
+            FetchMany {
+                base: view,
+                wheres: (),
+                links: (),
+                cursor_order_by: open_todo_members::title,
+                pagination: Pagination::Cursor {
+                    cursor: None,
+                    limit: 10,
+                },
+                computed: (),
+            },
+            &mut conn,
+        )
+        .await;
+        pretty_assertions::assert_eq!(
+            many.items
+                .into_iter()
+                .map(|item| (item.id, item.attributes.title))
+                .collect::<Vec<_>>(),
+            vec![(1, "first".to_string()), (3, "third".to_string())]
+        );
+    }
+}
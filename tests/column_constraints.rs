@@ -0,0 +1,42 @@
+use claw_ql::{
+    connect_in_memory::ConnectInMemory,
+    on_migrate::OnMigrate,
+    operations::insert::{ConstraintKind, ConstraintViolation},
+    sqlx_query_builder::StatementBuilder,
+};
+use claw_ql_macros::{Collection, OnMigrate};
+use sqlx::Sqlite;
+
+#[derive(Collection, OnMigrate, Debug, PartialEq)]
+pub struct Note {
+    #[column(check = "LENGTH(\"title\") > 0")]
+    pub title: String,
+    #[column(default = "0")]
+    pub priority: i64,
+}
+
+#[tokio::test]
+async fn derived_check() {
+    let mut conn = Sqlite::in_memory_connection().await;
+
+    let migration = StatementBuilder::<Sqlite>::new_no_data(note.statments()).unwrap();
+    sqlx::query(&migration).execute(&mut conn).await.unwrap();
+
+    sqlx::query("INSERT INTO Note (title) VALUES ('first')")
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    let error = sqlx::query("INSERT INTO Note (title) VALUES ('')")
+        .execute(&mut conn)
+        .await
+        .unwrap_err();
+
+    pretty_assertions::assert_eq!(
+        ConstraintViolation::from_database_error(error.as_database_error().unwrap()),
+        Some(ConstraintViolation {
+            kind: ConstraintKind::Check,
+            constraint: Some("check_title".to_string()),
+        })
+    );
+}
@@ -6,7 +6,8 @@ use claw_ql::{
     links::{Link, update_links::SetId},
     operations::{
         CollectionOutput, LinkedOutput, Operation,
-        fetch_many::{FetchMany, ManyOutput},
+        cursor::Cursor,
+        fetch_many::{FetchMany, ManyOutput, Pagination},
         v1_insert_one::InsertOne,
     },
     test_module::{self, Category, Todo, category, todo_members},
@@ -57,10 +58,13 @@ async fn fetch_many() {
         wheres: (),
         links: { <category as Link<test_module::todo>>::spec(category) },
         cursor_order_by: todo_members::title,
-        cursor_first_item: Some((4, {
-            todo_members::title.on_insert(String::from("non_unique"))
-        })),
-        limit: 2,
+        pagination: Pagination::Cursor {
+            cursor: Some(Cursor::After((4, {
+                todo_members::title.on_insert(String::from("non_unique"))
+            }))),
+            limit: 2,
+        },
+        computed: (),
     };
 
     // any compile time error beyond this point is a bug
@@ -79,6 +83,7 @@ async fn fetch_many() {
                         description: Some("description_4".to_string()),
                     },
                     links: None,
+                    computed: (),
                 },
                 LinkedOutput {
                     id: 2,
@@ -93,9 +98,12 @@ async fn fetch_many() {
                             title: "category_3".to_string()
                         }
                     }),
+                    computed: (),
                 },
             ],
             next_item: Some((6, String::from("sixth_todo"))),
+            prev_item: Some((4, String::from("non_unique"))),
+            total: None,
         }
     );
 }
@@ -156,7 +164,8 @@ async fn insert_ops() {
                 done: true,
                 description: Some("description_1".to_string()),
             },
-            links: 1
+            links: 1,
+            computed: (),
         }
     );
 }
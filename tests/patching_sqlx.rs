@@ -33,6 +33,7 @@ async fn main() {
         Executable {
             string: "SELECT * FROM users",
             arguments: Default::default(),
+            persistent: false,
         },
     )
     .await
@@ -16,7 +16,8 @@ mod test {
         connect_in_memory::ConnectInMemory,
         operations::{
             LinkedOutput, Operation, SafeOperation,
-            fetch_many_cursor_multi_col::{FetchMany, ManyOutput},
+            cursor::Cursor,
+            fetch_many::{FetchMany, ManyOutput, Pagination},
         },
         test_module::{self, Todo, todo_members},
     };
@@ -52,8 +53,11 @@ mod test {
             wheres: (),
             links: (),
             cursor_order_by: todo_members::title,
-            cursor_first_item: Some((4, String::from("non_unique"))),
-            limit: 2,
+            pagination: Pagination::Cursor {
+                cursor: Some(Cursor::After((4, String::from("non_unique")))),
+                limit: 2,
+            },
+            computed: (),
         }
         .safety_check()
         .unwrap();
@@ -72,6 +76,7 @@ mod test {
                             description: Some("description_4".to_string()),
                         },
                         links: (),
+                        computed: (),
                     },
                     LinkedOutput {
                         id: 2,
@@ -81,9 +86,12 @@ mod test {
                             description: Some("description_2".to_string()),
                         },
                         links: (),
+                        computed: (),
                     },
                 ],
                 next_item: Some((6, String::from("sixth_todo"))),
+                prev_item: Some((4, String::from("non_unique"))),
+                total: None,
             }
         );
     }
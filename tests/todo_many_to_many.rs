@@ -138,6 +138,7 @@ INSERT INTO StudentCourse (student_id, course_id) VALUES
                     },
                 },
             ],),
+            computed: (),
         },)
     );
 
@@ -156,6 +157,7 @@ INSERT INTO StudentCourse (student_id, course_id) VALUES
             links: (
                 CountResult(2), // there are two students enroled in CS101
             ),
+            computed: (),
         },)
     );
 }
@@ -5,7 +5,7 @@ use claw_ql::{
     on_migrate::OnMigrate,
     operations::{
         LinkedOutput, Operation,
-        fetch_many::{FetchMany, ManyOutput},
+        fetch_many::{FetchMany, ManyOutput, Pagination},
     },
     sqlx_query_builder::StatementBuilder,
     test_module::{self, Todo},
@@ -51,8 +51,11 @@ async fn test_fetch_many() {
                 collection: test_module::todo,
             },
             cursor_order_by: (),
-            cursor_first_item: None::<(i64, ())>,
-            limit: 10,
+            pagination: Pagination::<(i64, ())>::Cursor {
+                cursor: None,
+                limit: 10,
+            },
+            computed: (),
         },
         &mut conn,
     )
@@ -72,7 +75,8 @@ async fn test_fetch_many() {
                     links: TimestampOutput {
                         created_at: "created_at_test".to_string(),
                         updated_at: "updated_at_test".to_string(),
-                    }
+                    },
+                    computed: (),
                 },
                 LinkedOutput {
                     id: 2,
@@ -84,7 +88,8 @@ async fn test_fetch_many() {
                     links: TimestampOutput {
                         created_at: "created_at_test_2".to_string(),
                         updated_at: "updated_at_test_2".to_string(),
-                    }
+                    },
+                    computed: (),
                 },
                 LinkedOutput {
                     id: 3,
@@ -96,10 +101,13 @@ async fn test_fetch_many() {
                     links: TimestampOutput {
                         created_at: "created_at_test_3".to_string(),
                         updated_at: "updated_at_test_3".to_string(),
-                    }
+                    },
+                    computed: (),
                 },
             ],
-            next_item: None
+            next_item: None,
+            prev_item: None,
+            total: None,
         }
     );
 }